@@ -0,0 +1,139 @@
+//! ASCII-art export of the 2D render: the image is averaged down to a grid of
+//! character cells and each cell's luminance picks a character from a ramp.
+
+use eframe::egui;
+use sim_core::nudge::Nudge;
+use sim_core::Color;
+
+/// Height of a terminal character cell relative to its width. Cells are
+/// taller than wide, so each one covers this many times more image rows than
+/// columns, or the art comes out stretched vertically.
+const CELL_ASPECT: f32 = 2.0;
+
+/// Darkest to brightest
+const DEFAULT_RAMP: &str = " .:-=+*#%@";
+
+pub struct AsciiSettings {
+    /// Characters per line
+    pub columns: usize,
+    /// Characters from darkest to brightest; at least two are used
+    pub ramp: String,
+    /// Brightest pixels get the first character, for dark text on a light
+    /// background
+    pub invert: bool,
+}
+
+impl Default for AsciiSettings {
+    fn default() -> Self {
+        Self {
+            columns: 100,
+            ramp: DEFAULT_RAMP.to_string(),
+            invert: false,
+        }
+    }
+}
+
+/// Convert a `width`x`height` image to lines of text. Each character is the
+/// mean luminance of the pixels under its cell.
+pub fn to_ascii(pixels: &[Color], width: usize, height: usize, settings: &AsciiSettings) -> String {
+    let mut ramp: Vec<char> = settings.ramp.chars().collect();
+    if ramp.len() < 2 {
+        ramp = DEFAULT_RAMP.chars().collect();
+    }
+    if settings.invert {
+        ramp.reverse();
+    }
+    if width == 0 || height == 0 || pixels.len() < width * height {
+        return String::new();
+    }
+
+    let columns = settings.columns.clamp(1, width);
+    let cell_width = width as f32 / columns as f32;
+    let rows = ((height as f32 / (cell_width * CELL_ASPECT)).round() as usize).clamp(1, height);
+    let cell_height = height as f32 / rows as f32;
+
+    let mut text = String::with_capacity((columns + 1) * rows);
+    for row in 0..rows {
+        let y0 = (row as f32 * cell_height) as usize;
+        let y1 = (((row + 1) as f32 * cell_height) as usize).clamp(y0 + 1, height);
+        for column in 0..columns {
+            let x0 = (column as f32 * cell_width) as usize;
+            let x1 = (((column + 1) as f32 * cell_width) as usize).clamp(x0 + 1, width);
+
+            let mut sum = 0.0;
+            for y in y0..y1 {
+                for c in &pixels[y * width + x0..y * width + x1] {
+                    sum += luminance(*c);
+                }
+            }
+            let mean = sum / ((y1 - y0) * (x1 - x0)) as f32;
+            let index = (mean * (ramp.len() - 1) as f32).round() as usize;
+            text.push(ramp[index.min(ramp.len() - 1)]);
+        }
+        text.push('\n');
+    }
+    text
+}
+
+/// Relative luminance (Rec. 709 weights), 0..1
+fn luminance(c: Color) -> f32 {
+    (0.2126 * c.r as f32 + 0.7152 * c.g as f32 + 0.0722 * c.b as f32) / 255.0
+}
+
+/// Export panel state
+#[derive(Default)]
+pub struct AsciiExport {
+    pub settings: AsciiSettings,
+    status: Option<String>,
+}
+
+impl AsciiExport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Settings plus copy/save buttons for the given render
+    pub fn ui(&mut self, ui: &mut egui::Ui, pixels: &[Color], width: usize, height: usize) {
+        egui::CollapsingHeader::new("🔤 ASCII Art")
+            .show(ui, |ui| {
+                ui.add(egui::Slider::new(&mut self.settings.columns, 20..=300)
+                    .nudge(ui, 1.0)
+                    .text("Width (chars)"));
+                ui.horizontal(|ui| {
+                    ui.label("Ramp:");
+                    ui.text_edit_singleline(&mut self.settings.ramp)
+                        .on_hover_text("Characters from darkest to brightest");
+                    if ui.button("Default").clicked() {
+                        self.settings.ramp = DEFAULT_RAMP.to_string();
+                    }
+                });
+                ui.checkbox(&mut self.settings.invert, "Invert (for light backgrounds)");
+
+                ui.horizontal(|ui| {
+                    if ui.button("📋 Copy").clicked() {
+                        let text = to_ascii(pixels, width, height, &self.settings);
+                        self.status = Some(format!("Copied {} lines", text.lines().count()));
+                        ui.output_mut(|o| o.copied_text = text);
+                    }
+
+                    #[cfg(not(target_arch = "wasm32"))]
+                    if ui.button("💾 Save…").clicked() {
+                        if let Some(path) = rfd::FileDialog::new()
+                            .add_filter("Text", &["txt"])
+                            .set_file_name("render.txt")
+                            .save_file() {
+                            let text = to_ascii(pixels, width, height, &self.settings);
+                            self.status = Some(match std::fs::write(&path, text) {
+                                Ok(()) => format!("Saved {}", path.display()),
+                                Err(e) => format!("Could not save: {e}"),
+                            });
+                        }
+                    }
+                });
+
+                if let Some(status) = &self.status {
+                    ui.label(status);
+                }
+            });
+    }
+}
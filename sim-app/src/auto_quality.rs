@@ -0,0 +1,125 @@
+//! Auto-quality: watches how much CPU time frames take and trades render
+//! detail for frame rate to stay near a target fps. The app applies the
+//! adjustments to the 2D render scale or the 3D point cap.
+
+use eframe::egui;
+use sim_core::nudge::Nudge;
+use sim_core::readout;
+use std::ops::RangeInclusive;
+
+/// Bounds auto-quality keeps the 2D render scale within
+pub const SCALE_RANGE: RangeInclusive<f32> = 0.25..=1.0;
+
+/// Bounds auto-quality keeps the 3D point cap within
+pub const POINTS_RANGE: RangeInclusive<usize> = 500..=20_000;
+
+/// Weight of each new frame in the frame-time average
+const SMOOTHING: f32 = 0.1;
+
+/// Quality drops once the average is this far over budget, and rises once
+/// it is this far under. Adjustments aim for the budget, so they land
+/// inside the band instead of bouncing between its edges.
+const OVER_BUDGET: f32 = 1.1;
+const UNDER_BUDGET: f32 = 0.7;
+
+/// Frames sampled after an adjustment before the next one, so the average
+/// reflects the new quality
+const SETTLE_FRAMES: u32 = 20;
+
+/// Bounds on the render-cost factor of a single adjustment. Cuts may be big
+/// to get out of a stutter quickly; raises are small.
+const MAX_CUT: f32 = 0.6;
+const MAX_RAISE: f32 = 1.15;
+
+pub struct AutoQuality {
+    pub enabled: bool,
+    pub target_fps: f32,
+    /// Average frame time in seconds since the last adjustment
+    average: Option<f32>,
+    settle: u32,
+    /// What the samples are being taken of; switching sims starts over
+    source: String,
+}
+
+impl Default for AutoQuality {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            target_fps: 60.0,
+            average: None,
+            settle: 0,
+            source: String::new(),
+        }
+    }
+}
+
+impl AutoQuality {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Take the CPU time of a frame that rendered `source`. Returns the
+    /// factor to scale render cost by when it's time to adjust.
+    pub fn sample(&mut self, source: &str, seconds: f32) -> Option<f32> {
+        if !self.enabled || !seconds.is_finite() || seconds <= 0.0 {
+            return None;
+        }
+        if source != self.source {
+            self.source = source.to_string();
+            self.average = None;
+            self.settle = SETTLE_FRAMES;
+        }
+
+        let average = match self.average {
+            Some(average) => average + (seconds - average) * SMOOTHING,
+            None => seconds,
+        };
+        self.average = Some(average);
+        if self.settle > 0 {
+            self.settle -= 1;
+            return None;
+        }
+
+        let budget = 1.0 / self.target_fps.max(1.0);
+        let factor = if average > budget * OVER_BUDGET {
+            (budget / average).max(MAX_CUT)
+        } else if average < budget * UNDER_BUDGET {
+            (budget / average).min(MAX_RAISE)
+        } else {
+            return None;
+        };
+
+        self.average = None;
+        self.settle = SETTLE_FRAMES;
+        Some(factor)
+    }
+
+    /// Average frame time in milliseconds, once there is one
+    pub fn frame_ms(&self) -> Option<f32> {
+        self.average.map(|seconds| seconds * 1000.0)
+    }
+
+    /// Toggle and target; `detail` describes the setting being driven
+    pub fn ui(&mut self, ui: &mut egui::Ui, detail: &str) {
+        egui::CollapsingHeader::new("⚡ Auto Quality")
+            .show(ui, |ui| {
+                if ui.checkbox(&mut self.enabled, "Adapt Detail to Frame Rate")
+                    .on_hover_text("Lower or raise the render detail to keep frames within the target time. Turn off to set it by hand.")
+                    .changed() {
+                    self.average = None;
+                    self.settle = 0;
+                }
+
+                ui.add_enabled_ui(self.enabled, |ui| {
+                    ui.add(egui::Slider::new(&mut self.target_fps, 20.0..=144.0)
+                        .nudge(ui, 1.0)
+                        .suffix(" fps")
+                        .text("Target"));
+                    let frame = self.frame_ms()
+                        .map_or_else(|| "measuring…".to_string(), |ms| readout::millis(ms, 1));
+                    ui.label(format!("Frame: {frame} (budget {})", readout::millis(1000.0 / self.target_fps.max(1.0), 1)));
+                    ui.label(detail);
+                });
+            });
+    }
+}
@@ -0,0 +1,166 @@
+//! Parameter automation: record every change to the active sim's parameters
+//! with its sim-clock timestamp, then replay the changes at the same times.
+//! Parameters are read and written through `save_state`/`load_state`, so any
+//! sim with a state map can be automated, field by field.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// One parameter change
+#[derive(Clone, Serialize, Deserialize)]
+pub struct AutomationEvent {
+    /// Sim-clock seconds since the recording started
+    pub time: f64,
+    /// Key in the sim's `save_state` object
+    pub field: String,
+    pub value: Value,
+}
+
+/// A recorded automation, saved in scene files
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct AutomationTrack {
+    /// `name()` of the sim the track was recorded on
+    pub simulation: String,
+    /// In time order. Every field's starting value is recorded at time 0,
+    /// so a replay begins from the same parameters as the recording.
+    pub events: Vec<AutomationEvent>,
+    /// Length of the recording, including any still time at the end
+    pub duration: f64,
+}
+
+impl AutomationTrack {
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+}
+
+enum Mode {
+    Idle,
+    /// Clock time the recording started at, and the state last seen
+    Recording { start: f64, last: Map<String, Value> },
+    /// Clock time the replay started at, and the next event to apply
+    Playing { start: f64, next: usize },
+}
+
+pub struct Automation {
+    pub track: AutomationTrack,
+    /// Start the replay over when it reaches the end
+    pub loop_playback: bool,
+    mode: Mode,
+}
+
+impl Default for Automation {
+    fn default() -> Self {
+        Self {
+            track: AutomationTrack::default(),
+            loop_playback: false,
+            mode: Mode::Idle,
+        }
+    }
+}
+
+impl Automation {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_recording(&self) -> bool {
+        matches!(self.mode, Mode::Recording { .. })
+    }
+
+    pub fn is_playing(&self) -> bool {
+        matches!(self.mode, Mode::Playing { .. })
+    }
+
+    /// Seconds into the recording or replay, if one is running
+    pub fn elapsed(&self, now: f64) -> Option<f64> {
+        match self.mode {
+            Mode::Idle => None,
+            Mode::Recording { start, .. } | Mode::Playing { start, .. } => Some(now - start),
+        }
+    }
+
+    /// Start a new track on `simulation`, whose current parameters are
+    /// `state`. Returns false if the sim has no state map to record.
+    pub fn start_recording(&mut self, simulation: &str, state: &Value, now: f64) -> bool {
+        let Some(fields) = state.as_object() else {
+            return false;
+        };
+        if fields.is_empty() {
+            return false;
+        }
+
+        self.track = AutomationTrack {
+            simulation: simulation.to_string(),
+            events: Vec::new(),
+            duration: 0.0,
+        };
+        self.mode = Mode::Recording { start: now, last: Map::new() };
+        self.record(state, now);
+        true
+    }
+
+    /// Add an event for each field of `state` that changed since the last
+    /// call. Called once per frame while recording.
+    pub fn record(&mut self, state: &Value, now: f64) {
+        let Mode::Recording { start, last } = &mut self.mode else {
+            return;
+        };
+        let Some(fields) = state.as_object() else {
+            return;
+        };
+
+        let time = now - *start;
+        for (field, value) in fields {
+            if last.get(field) != Some(value) {
+                self.track.events.push(AutomationEvent {
+                    time,
+                    field: field.clone(),
+                    value: value.clone(),
+                });
+                last.insert(field.clone(), value.clone());
+            }
+        }
+        self.track.duration = time;
+    }
+
+    /// Replay the track from the beginning
+    pub fn start_playback(&mut self, now: f64) {
+        if !self.track.is_empty() {
+            self.mode = Mode::Playing { start: now, next: 0 };
+        }
+    }
+
+    pub fn stop(&mut self) {
+        self.mode = Mode::Idle;
+    }
+
+    /// Every change whose timestamp has been reached since the last call,
+    /// merged into one state object for `load_state` (a later change to the
+    /// same field wins). Several events can land in one frame, or none, so
+    /// the replay keeps to the recorded times at any frame rate.
+    pub fn due(&mut self, now: f64) -> Option<Value> {
+        let Mode::Playing { start, next } = &mut self.mode else {
+            return None;
+        };
+
+        let time = now - *start;
+        let mut changes = Map::new();
+        while let Some(event) = self.track.events.get(*next).filter(|e| e.time <= time) {
+            changes.insert(event.field.clone(), event.value.clone());
+            *next += 1;
+        }
+
+        if *next >= self.track.events.len() && time >= self.track.duration {
+            if self.loop_playback {
+                // The time-0 events restore the starting values on the next call
+                *start = now;
+                *next = 0;
+            } else {
+                self.mode = Mode::Idle;
+            }
+        }
+
+        (!changes.is_empty()).then_some(Value::Object(changes))
+    }
+}
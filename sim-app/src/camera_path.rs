@@ -0,0 +1,245 @@
+//! Keyframed 3D camera flythroughs. Keyframes pin the viewer's rotation and
+//! zoom at given times; in between, each value follows a Catmull-Rom spline
+//! so the camera moves smoothly through every keyframe instead of turning
+//! sharply at them.
+
+use crate::viewer_3d::Viewer3D;
+use eframe::egui;
+use sim_core::nudge::Nudge;
+use std::f32::consts::TAU;
+
+/// Default spacing between keyframes added from the current view
+const KEYFRAME_SPACING: f32 = 2.0;
+
+#[derive(Clone, Copy)]
+pub struct Keyframe {
+    /// Seconds from the start of the path
+    pub time: f32,
+    /// Unwrapped angle: may leave 0..TAU so the path turns the short way
+    pub rotation_x: f32,
+    pub rotation_y: f32,
+    pub zoom: f32,
+}
+
+impl Keyframe {
+    fn values(&self) -> [f32; 3] {
+        [self.rotation_x, self.rotation_y, self.zoom]
+    }
+}
+
+/// What drives the path's playhead
+#[derive(Clone, Copy, PartialEq)]
+pub enum Playback {
+    Stopped,
+    /// Real time, for checking the path
+    Preview,
+    /// Video frames, one per frame written
+    Render,
+}
+
+pub struct CameraPath {
+    /// Sorted by time
+    pub keyframes: Vec<Keyframe>,
+    pub playback: Playback,
+    /// Playhead, in seconds
+    pub time: f32,
+}
+
+impl Default for CameraPath {
+    fn default() -> Self {
+        Self {
+            keyframes: Vec::new(),
+            playback: Playback::Stopped,
+            time: 0.0,
+        }
+    }
+}
+
+impl CameraPath {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Time of the last keyframe
+    pub fn duration(&self) -> f32 {
+        self.keyframes.last().map(|k| k.time).unwrap_or(0.0)
+    }
+
+    /// Whether there's anything to play
+    pub fn is_playable(&self) -> bool {
+        self.keyframes.len() >= 2 && self.duration() > 0.0
+    }
+
+    /// Add the viewer's current camera after the last keyframe. Angles are
+    /// shifted by whole turns to the nearest equivalent of the previous
+    /// keyframe, so e.g. 6.2 -> 0.1 rad continues forward instead of
+    /// spinning back almost a full turn.
+    pub fn add_keyframe(&mut self, viewer: &Viewer3D) {
+        let mut key = Keyframe {
+            time: self.keyframes.last().map(|k| k.time + KEYFRAME_SPACING).unwrap_or(0.0),
+            rotation_x: viewer.rotation_x,
+            rotation_y: viewer.rotation_y,
+            zoom: viewer.zoom,
+        };
+        if let Some(prev) = self.keyframes.last() {
+            key.rotation_x = nearest_turn(key.rotation_x, prev.rotation_x);
+            key.rotation_y = nearest_turn(key.rotation_y, prev.rotation_y);
+        }
+        self.keyframes.push(key);
+    }
+
+    /// Camera `[rotation_x, rotation_y, zoom]` at time `t`, clamped to the
+    /// ends of the path
+    pub fn sample(&self, t: f32) -> Option<[f32; 3]> {
+        let keys = &self.keyframes;
+        let first = keys.first()?;
+        let last = keys.last()?;
+        if keys.len() == 1 || t <= first.time {
+            return Some(first.values());
+        }
+        if t >= last.time {
+            return Some(last.values());
+        }
+
+        let i = keys.windows(2).position(|w| t < w[1].time).unwrap_or(keys.len() - 2);
+        let (k1, k2) = (&keys[i], &keys[i + 1]);
+        let h = (k2.time - k1.time).max(f32::EPSILON);
+        let s = (t - k1.time) / h;
+
+        // Cubic Hermite with Catmull-Rom tangents, scaled for uneven
+        // keyframe spacing so speed stays continuous across keyframes
+        let tangent = |j: usize, c: usize| -> f32 {
+            let prev = &keys[j.saturating_sub(1)];
+            let next = &keys[(j + 1).min(keys.len() - 1)];
+            let dt = (next.time - prev.time).max(f32::EPSILON);
+            (next.values()[c] - prev.values()[c]) / dt
+        };
+        let h00 = 2.0 * s * s * s - 3.0 * s * s + 1.0;
+        let h10 = s * s * s - 2.0 * s * s + s;
+        let h01 = -2.0 * s * s * s + 3.0 * s * s;
+        let h11 = s * s * s - s * s;
+
+        let mut out = [0.0; 3];
+        for (c, value) in out.iter_mut().enumerate() {
+            *value = h00 * k1.values()[c]
+                + h10 * h * tangent(i, c)
+                + h01 * k2.values()[c]
+                + h11 * h * tangent(i + 1, c);
+        }
+        Some(out)
+    }
+
+    /// Move the viewer's camera to the path at the playhead
+    pub fn apply(&self, viewer: &mut Viewer3D) {
+        if let Some([rotation_x, rotation_y, zoom]) = self.sample(self.time) {
+            viewer.set_rotation(rotation_x, rotation_y);
+            viewer.zoom = zoom.clamp(0.5, 5.0);
+        }
+    }
+
+    /// Start playing from the beginning. Auto-rotate is turned off, since it
+    /// would fight the path.
+    pub fn play(&mut self, playback: Playback, viewer: &mut Viewer3D) {
+        self.playback = playback;
+        self.time = 0.0;
+        viewer.auto_rotate = false;
+    }
+
+    /// Advance a real-time preview by `dt`, stopping at the end
+    pub fn tick(&mut self, dt: f32) {
+        if self.playback == Playback::Preview {
+            self.time += dt;
+            if self.time >= self.duration() {
+                self.time = self.duration();
+                self.playback = Playback::Stopped;
+            }
+        }
+    }
+
+    /// Keyframe list and preview controls. Returns true when "Render Path"
+    /// was clicked; the caller starts the recording.
+    pub fn ui(&mut self, ui: &mut egui::Ui, viewer: &mut Viewer3D, can_render: bool) -> bool {
+        let mut render = false;
+
+        egui::CollapsingHeader::new("🎥 Camera Path")
+            .show(ui, |ui| {
+                if ui.button("➕ Add Keyframe (Current View)").clicked() {
+                    self.add_keyframe(viewer);
+                }
+
+                let mut remove = None;
+                let mut go_to = None;
+                for (i, key) in self.keyframes.iter_mut().enumerate() {
+                    ui.horizontal(|ui| {
+                        ui.add(egui::DragValue::new(&mut key.time)
+                            .speed(0.05)
+                            .range(0.0..=600.0)
+                            .suffix(" s"));
+                        ui.label(format!("x {:.2}  y {:.2}  zoom {:.2}",
+                            key.rotation_x.rem_euclid(TAU), key.rotation_y.rem_euclid(TAU), key.zoom));
+                        if ui.small_button("👁").on_hover_text("Show this keyframe").clicked() {
+                            go_to = Some(i);
+                        }
+                        if ui.small_button("🗑").clicked() {
+                            remove = Some(i);
+                        }
+                    });
+                }
+                if let Some(i) = go_to {
+                    self.time = self.keyframes[i].time;
+                    viewer.auto_rotate = false;
+                    self.apply(viewer);
+                }
+                if let Some(i) = remove {
+                    self.keyframes.remove(i);
+                }
+                self.keyframes.sort_by(|a, b| a.time.total_cmp(&b.time));
+
+                if self.keyframes.is_empty() {
+                    ui.label("Add keyframes from different views to build a path");
+                    return;
+                }
+
+                ui.horizontal(|ui| {
+                    ui.add_enabled_ui(self.is_playable() && self.playback != Playback::Render, |ui| {
+                        if self.playback == Playback::Preview {
+                            if ui.button("⏹ Stop").clicked() {
+                                self.playback = Playback::Stopped;
+                            }
+                        } else if ui.button("▶ Preview").clicked() {
+                            self.play(Playback::Preview, viewer);
+                        }
+                    });
+
+                    if can_render {
+                        let enabled = self.is_playable() && self.playback == Playback::Stopped;
+                        if ui.add_enabled(enabled, egui::Button::new("🎬 Render Path"))
+                            .on_hover_text("Record the path with the Video Export settings")
+                            .clicked() {
+                            render = true;
+                        }
+                    }
+
+                    if ui.button("Clear").clicked() {
+                        self.keyframes.clear();
+                        self.playback = Playback::Stopped;
+                    }
+                });
+
+                let duration = self.duration();
+                if ui.add(egui::Slider::new(&mut self.time, 0.0..=duration.max(0.01))
+                    .nudge(ui, 0.05)
+                    .text("Time (s)")).changed() {
+                    viewer.auto_rotate = false;
+                    self.apply(viewer);
+                }
+            });
+
+        render
+    }
+}
+
+/// `angle` shifted by whole turns to be within half a turn of `reference`
+fn nearest_turn(angle: f32, reference: f32) -> f32 {
+    angle + ((reference - angle) / TAU).round() * TAU
+}
@@ -0,0 +1,143 @@
+//! Colorblindness simulation for the 2D view, to check how a render reads
+//! to viewers with dichromatic vision. Each pixel is decoded to linear RGB,
+//! taken to LMS cone space, has the missing cone's response rebuilt from the
+//! other two, and is taken back. This is the Viénot, Brettel & Mollon (1999)
+//! method: their RGB-to-LMS matrix, with white and blue unchanged for the
+//! red-green types and white and red unchanged for tritanopia.
+
+use crate::compositing::{linear_to_srgb, srgb_to_linear_table};
+use eframe::egui;
+use sim_core::Color;
+
+type Matrix = [[f32; 3]; 3];
+
+/// Linear RGB to LMS cone responses
+const RGB_TO_LMS: Matrix = [
+    [17.8824, 43.5161, 4.11935],
+    [3.45565, 27.1554, 3.86714],
+    [0.0299566, 0.184309, 1.46709],
+];
+
+#[derive(Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+pub enum ColorVision {
+    Normal,
+    Protanopia,
+    Deuteranopia,
+    Tritanopia,
+}
+
+impl ColorVision {
+    pub fn all() -> Vec<ColorVision> {
+        vec![
+            ColorVision::Normal,
+            ColorVision::Protanopia,
+            ColorVision::Deuteranopia,
+            ColorVision::Tritanopia,
+        ]
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            ColorVision::Normal => "None",
+            ColorVision::Protanopia => "Protanopia",
+            ColorVision::Deuteranopia => "Deuteranopia",
+            ColorVision::Tritanopia => "Tritanopia",
+        }
+    }
+
+    pub fn description(&self) -> &str {
+        match self {
+            ColorVision::Normal => "Show the render unchanged",
+            ColorVision::Protanopia => "No long-wavelength (red) cones; reds look dark and merge with greens",
+            ColorVision::Deuteranopia => "No medium-wavelength (green) cones; reds and greens merge",
+            ColorVision::Tritanopia => "No short-wavelength (blue) cones; blues and greens merge, yellows turn pink",
+        }
+    }
+
+    /// The cone that is missing and a primary whose appearance doesn't
+    /// change. Dichromats see white and the anchor as everyone else does.
+    fn missing_cone(&self) -> Option<(usize, [f32; 3])> {
+        match self {
+            ColorVision::Normal => None,
+            ColorVision::Protanopia => Some((0, [0.0, 0.0, 1.0])),
+            ColorVision::Deuteranopia => Some((1, [0.0, 0.0, 1.0])),
+            ColorVision::Tritanopia => Some((2, [1.0, 0.0, 0.0])),
+        }
+    }
+
+    /// LMS to LMS: the missing cone's response rebuilt as the mix of the
+    /// other two that is exact for white and the anchor primary
+    fn projection(&self) -> Matrix {
+        let mut projection = [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]];
+        let Some((lost, anchor)) = self.missing_cone() else {
+            return projection;
+        };
+
+        let lms = |rgb: [f32; 3]| RGB_TO_LMS.map(|row| row[0] * rgb[0] + row[1] * rgb[1] + row[2] * rgb[2]);
+        let (white, anchor) = (lms([1.0, 1.0, 1.0]), lms(anchor));
+        let (p, q) = ((lost + 1) % 3, (lost + 2) % 3);
+        let determinant = white[p] * anchor[q] - white[q] * anchor[p];
+        projection[lost] = [0.0; 3];
+        projection[lost][p] = (white[lost] * anchor[q] - white[q] * anchor[lost]) / determinant;
+        projection[lost][q] = (white[p] * anchor[lost] - white[lost] * anchor[p]) / determinant;
+        projection
+    }
+
+    /// The whole simulation as one linear RGB matrix
+    fn matrix(&self) -> Matrix {
+        multiply(&invert(&RGB_TO_LMS), &multiply(&self.projection(), &RGB_TO_LMS))
+    }
+
+    /// Show `pixels` as this kind of vision sees them, in place. `Normal`
+    /// leaves them untouched.
+    pub fn apply(&self, pixels: &mut [Color]) {
+        if *self == ColorVision::Normal {
+            return;
+        }
+
+        let m = self.matrix();
+        let decode = srgb_to_linear_table();
+        for pixel in pixels {
+            let rgb = [decode[pixel.r as usize], decode[pixel.g as usize], decode[pixel.b as usize]];
+            let [r, g, b] = m.map(|row| linear_to_srgb(row[0] * rgb[0] + row[1] * rgb[1] + row[2] * rgb[2]));
+            *pixel = Color::from_rgb(r, g, b);
+        }
+    }
+
+    /// Mode selector; returns true if it changed
+    pub fn ui(&mut self, ui: &mut egui::Ui) -> bool {
+        let mut changed = false;
+
+        egui::CollapsingHeader::new("👁 Color Vision")
+            .show(ui, |ui| {
+                egui::ComboBox::from_label("Simulate")
+                    .selected_text(self.name())
+                    .show_ui(ui, |ui| {
+                        for vision in ColorVision::all() {
+                            changed |= ui.selectable_value(self, vision, vision.name())
+                                .on_hover_text(vision.description())
+                                .changed();
+                        }
+                    });
+                ui.label(egui::RichText::new(self.description()).weak());
+            });
+
+        changed
+    }
+}
+
+fn multiply(a: &Matrix, b: &Matrix) -> Matrix {
+    std::array::from_fn(|i| std::array::from_fn(|j| (0..3).map(|k| a[i][k] * b[k][j]).sum()))
+}
+
+/// Inverse by cofactors; only used on the fixed, well-conditioned RGB-to-LMS
+/// matrix
+fn invert(m: &Matrix) -> Matrix {
+    let cofactor = |i: usize, j: usize| {
+        let (r0, r1) = ((i + 1) % 3, (i + 2) % 3);
+        let (c0, c1) = ((j + 1) % 3, (j + 2) % 3);
+        m[r0][c0] * m[r1][c1] - m[r0][c1] * m[r1][c0]
+    };
+    let determinant: f32 = (0..3).map(|j| m[0][j] * cofactor(0, j)).sum();
+    std::array::from_fn(|i| std::array::from_fn(|j| cofactor(j, i) / determinant))
+}
@@ -0,0 +1,185 @@
+//! Two-layer compositing for the 2D view: a second sim is rendered at the
+//! same size as the main one and blended over it pixel by pixel. Blending
+//! happens on linear light rather than on the sRGB-encoded bytes, so e.g.
+//! Add behaves like two lights shining on the same spot.
+
+use eframe::egui;
+use sim_core::nudge::Nudge;
+use sim_core::Color;
+use std::sync::OnceLock;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum BlendMode {
+    Normal,
+    Add,
+    Multiply,
+    Screen,
+    Overlay,
+}
+
+impl BlendMode {
+    pub fn all() -> Vec<BlendMode> {
+        vec![
+            BlendMode::Normal,
+            BlendMode::Add,
+            BlendMode::Multiply,
+            BlendMode::Screen,
+            BlendMode::Overlay,
+        ]
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            BlendMode::Normal => "Normal",
+            BlendMode::Add => "Add",
+            BlendMode::Multiply => "Multiply",
+            BlendMode::Screen => "Screen",
+            BlendMode::Overlay => "Overlay",
+        }
+    }
+
+    /// Blend one linear channel of the layer `b` onto the base `a`, both
+    /// in 0..1
+    fn apply(&self, a: f32, b: f32) -> f32 {
+        match self {
+            BlendMode::Normal => b,
+            BlendMode::Add => (a + b).min(1.0),
+            BlendMode::Multiply => a * b,
+            BlendMode::Screen => 1.0 - (1.0 - a) * (1.0 - b),
+            BlendMode::Overlay => {
+                if a < 0.5 {
+                    2.0 * a * b
+                } else {
+                    1.0 - 2.0 * (1.0 - a) * (1.0 - b)
+                }
+            }
+        }
+    }
+}
+
+pub struct Compositing {
+    pub enabled: bool,
+    /// Index of the layer sim in the app's 2D sim list
+    pub layer: usize,
+    pub mode: BlendMode,
+    /// How much of the blended result replaces the base, 0..1
+    pub opacity: f32,
+}
+
+impl Default for Compositing {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            layer: 0,
+            mode: BlendMode::Screen,
+            opacity: 0.5,
+        }
+    }
+}
+
+impl Compositing {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The layer sim's index while compositing over sim `primary`. A sim
+    /// can't be layered over itself, so that case is off.
+    pub fn active_layer(&self, primary: usize) -> Option<usize> {
+        (self.enabled && self.layer != primary).then_some(self.layer)
+    }
+
+    /// Settings; `names` are the 2D sims in list order. Returns true if
+    /// anything changed.
+    pub fn ui(&mut self, ui: &mut egui::Ui, names: &[&str], primary: usize) -> bool {
+        let mut changed = false;
+
+        egui::CollapsingHeader::new("🎭 Layer Blend")
+            .show(ui, |ui| {
+                changed |= ui.checkbox(&mut self.enabled, "Blend a Second Sim")
+                    .on_hover_text("Render another 2D sim at the same size and blend it over this one")
+                    .changed();
+
+                ui.add_enabled_ui(self.enabled, |ui| {
+                    let selected = names.get(self.layer).copied().unwrap_or("");
+                    egui::ComboBox::from_label("Layer")
+                        .selected_text(selected)
+                        .show_ui(ui, |ui| {
+                            for (i, name) in names.iter().enumerate().filter(|(i, _)| *i != primary) {
+                                changed |= ui.selectable_value(&mut self.layer, i, *name).changed();
+                            }
+                        });
+                    if self.layer == primary {
+                        ui.label("Pick a different sim to blend");
+                    }
+
+                    egui::ComboBox::from_label("Blend Mode")
+                        .selected_text(self.mode.name())
+                        .show_ui(ui, |ui| {
+                            for mode in BlendMode::all() {
+                                changed |= ui.selectable_value(&mut self.mode, mode, mode.name()).changed();
+                            }
+                        });
+
+                    changed |= ui.add(egui::Slider::new(&mut self.opacity, 0.0..=1.0)
+                        .nudge(ui, 0.01)
+                        .text("Opacity")).changed();
+                });
+            });
+
+        changed
+    }
+
+    /// Blend `layer` over `base` in place. Both are the same size; a
+    /// mismatched layer is ignored.
+    pub fn blend(&self, base: &mut [Color], layer: &[Color]) {
+        if layer.len() != base.len() {
+            return;
+        }
+
+        let decode = srgb_to_linear_table();
+        let opacity = self.opacity.clamp(0.0, 1.0);
+        let mix = |a: u8, b: u8| {
+            let (a, b) = (decode[a as usize], decode[b as usize]);
+            linear_to_srgb(a + (self.mode.apply(a, b) - a) * opacity)
+        };
+
+        for (base, layer) in base.iter_mut().zip(layer) {
+            *base = Color::from_rgb(mix(base.r, layer.r), mix(base.g, layer.g), mix(base.b, layer.b));
+        }
+    }
+}
+
+/// The sRGB decoding of every byte value
+pub(crate) fn srgb_to_linear_table() -> &'static [f32; 256] {
+    static TABLE: OnceLock<[f32; 256]> = OnceLock::new();
+    TABLE.get_or_init(|| {
+        std::array::from_fn(|i| {
+            let c = i as f32 / 255.0;
+            if c <= 0.04045 {
+                c / 12.92
+            } else {
+                ((c + 0.055) / 1.055).powf(2.4)
+            }
+        })
+    })
+}
+
+/// Steps in the linear-to-sRGB table; fine enough that every byte value is
+/// reachable and decoding then encoding a byte gives it back unchanged
+const ENCODE_STEPS: usize = 4096;
+
+pub(crate) fn linear_to_srgb(c: f32) -> u8 {
+    static TABLE: OnceLock<[u8; ENCODE_STEPS]> = OnceLock::new();
+    let table = TABLE.get_or_init(|| {
+        std::array::from_fn(|i| {
+            let c = i as f32 / (ENCODE_STEPS - 1) as f32;
+            let encoded = if c <= 0.0031308 {
+                c * 12.92
+            } else {
+                1.055 * c.powf(1.0 / 2.4) - 0.055
+            };
+            (encoded * 255.0).round() as u8
+        })
+    });
+    table[(c.clamp(0.0, 1.0) * (ENCODE_STEPS - 1) as f32).round() as usize]
+}
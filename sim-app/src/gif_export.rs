@@ -0,0 +1,214 @@
+//! Animated GIF recording of the 2D render. Frames are kept in memory as
+//! rendered and only encoded when the recording stops, each with its own
+//! quantized local palette, so sims whose colors drift over time don't band
+//! against a palette picked from the first frame.
+
+use eframe::egui;
+use sim_core::nudge::Nudge;
+use sim_core::readout;
+use sim_core::Color;
+
+/// Memory the buffered frames may take before recording stops early,
+/// whatever the frame count asks for
+const MAX_BYTES: usize = 512 * 1024 * 1024;
+
+/// NeuQuant sampling factor for the per-frame palettes, 1 (best) to 30
+/// (fastest)
+const QUANTIZE_SPEED: i32 = 10;
+
+/// GIF dimensions are 16-bit
+const MAX_SIDE: usize = u16::MAX as usize;
+
+pub struct GifSettings {
+    /// Frames to record before stopping on its own
+    pub frames: u32,
+    /// Time each frame is shown, in milliseconds. GIFs store hundredths of
+    /// a second, so it is rounded to 10 ms.
+    pub delay_ms: u32,
+}
+
+impl Default for GifSettings {
+    fn default() -> Self {
+        Self {
+            frames: 90,
+            delay_ms: 40,
+        }
+    }
+}
+
+/// Frames of a finished recording, all `width`x`height`
+pub struct GifFrames {
+    pub width: usize,
+    pub height: usize,
+    /// Per-frame delay in hundredths of a second
+    pub delay: u16,
+    pub frames: Vec<Vec<Color>>,
+    /// Stopped early because the frames reached the memory cap
+    pub limit_hit: bool,
+}
+
+impl GifFrames {
+    /// Warning to show with the save result, when the recording was cut
+    /// short
+    pub fn warning(&self) -> Option<String> {
+        self.limit_hit.then(|| format!("Stopped at the {} MB memory limit after {} frames",
+            MAX_BYTES / (1024 * 1024), readout::count(self.frames.len())))
+    }
+
+    /// Encode as a looping animated GIF
+    pub fn encode(&self) -> Result<Vec<u8>, gif::EncodingError> {
+        let (width, height) = (self.width as u16, self.height as u16);
+        let mut encoder = gif::Encoder::new(Vec::new(), width, height, &[])?;
+        encoder.set_repeat(gif::Repeat::Infinite)?;
+
+        for colors in &self.frames {
+            let rgb: Vec<u8> = colors.iter().flat_map(|c| [c.r, c.g, c.b]).collect();
+            let mut frame = gif::Frame::from_rgb_speed(width, height, &rgb, QUANTIZE_SPEED);
+            frame.delay = self.delay;
+            encoder.write_frame(&frame)?;
+        }
+        Ok(encoder.into_inner()?)
+    }
+}
+
+/// GIF panel state: settings, the frames of the recording in progress and
+/// the last result
+#[derive(Default)]
+pub struct GifRecorder {
+    pub settings: GifSettings,
+    /// Frames so far while recording; the first frame locks the size
+    recording: Option<GifFrames>,
+    /// Target frame count of the recording in progress
+    target: u32,
+    /// A stopped recording waiting to be saved
+    finished: Option<GifFrames>,
+    status: Option<String>,
+}
+
+impl GifRecorder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.recording.is_some()
+    }
+
+    /// Fixed sim step while recording, so the GIF plays at real speed
+    /// however long each frame takes to render
+    pub fn frame_dt(&self) -> Option<f32> {
+        self.recording.as_ref().map(|r| r.delay as f32 / 100.0)
+    }
+
+    pub fn start(&mut self) {
+        self.recording = Some(GifFrames {
+            width: 0,
+            height: 0,
+            delay: (self.settings.delay_ms / 10).clamp(1, u16::MAX as u32) as u16,
+            frames: Vec::new(),
+            limit_hit: false,
+        });
+        self.target = self.settings.frames.max(1);
+        self.finished = None;
+        self.status = None;
+    }
+
+    /// End the recording and hand what was captured to `take_finished`
+    pub fn stop(&mut self) {
+        if let Some(recording) = self.recording.take() {
+            if recording.frames.is_empty() {
+                self.status = Some("Nothing was recorded".to_string());
+            } else {
+                self.finished = Some(recording);
+            }
+        }
+    }
+
+    /// Add a rendered frame. The size is locked by the first one; later
+    /// frames of another size are cropped or padded with `background`.
+    /// Stops at the target count, or early with a warning once the frames
+    /// would outgrow the memory cap.
+    pub fn push_frame(&mut self, colors: &[Color], width: usize, height: usize, background: Color) {
+        let Some(recording) = &mut self.recording else {
+            return;
+        };
+
+        if recording.frames.is_empty() {
+            recording.width = width.min(MAX_SIDE);
+            recording.height = height.min(MAX_SIDE);
+        }
+        let (w, h) = (recording.width, recording.height);
+        if w == 0 || h == 0 {
+            return;
+        }
+
+        let frame_bytes = w * h * std::mem::size_of::<Color>();
+        if (recording.frames.len() + 1) * frame_bytes > MAX_BYTES {
+            recording.limit_hit = true;
+            self.stop();
+            return;
+        }
+
+        let mut frame = vec![background; w * h];
+        for y in 0..h.min(height) {
+            let row = y * width;
+            let len = w.min(width);
+            if let Some(src) = colors.get(row..row + len) {
+                frame[y * w..y * w + len].copy_from_slice(src);
+            }
+        }
+        recording.frames.push(frame);
+
+        if recording.frames.len() as u32 >= self.target {
+            self.stop();
+        }
+    }
+
+    /// A stopped recording, once, for the app to encode and save
+    pub fn take_finished(&mut self) -> Option<GifFrames> {
+        self.finished.take()
+    }
+
+    /// Show the outcome of saving under the controls
+    pub fn set_status(&mut self, status: String) {
+        self.status = Some(status);
+    }
+
+    /// Collapsible settings and the record toggle
+    pub fn ui(&mut self, ui: &mut egui::Ui) {
+        egui::CollapsingHeader::new("🎞 GIF Recording")
+            .show(ui, |ui| {
+                if let Some(recording) = &self.recording {
+                    ui.label(format!("Recording {}x{}: frame {} of {}",
+                        recording.width, recording.height,
+                        readout::count(recording.frames.len()), readout::count(self.target)));
+                    ui.add(egui::ProgressBar::new(recording.frames.len() as f32 / self.target as f32).show_percentage());
+                    if ui.button("⏹ Stop").on_hover_text("Stop early and save the frames so far").clicked() {
+                        self.stop();
+                    }
+                    return;
+                }
+
+                ui.add(egui::Slider::new(&mut self.settings.frames, 30..=300)
+                    .nudge(ui, 1.0)
+                    .text("Frames"));
+                ui.add(egui::Slider::new(&mut self.settings.delay_ms, 20..=200)
+                    .nudge(ui, 10.0)
+                    .step_by(10.0)
+                    .suffix(" ms")
+                    .text("Frame Delay"));
+                ui.label(format!("Length: {}",
+                    readout::seconds(self.settings.frames as f32 * self.settings.delay_ms as f32 / 1000.0, 1)));
+
+                if ui.button("⏺ Record GIF")
+                    .on_hover_text("Capture every rendered frame, then save them as a looping GIF")
+                    .clicked() {
+                    self.start();
+                }
+
+                if let Some(status) = &self.status {
+                    ui.label(status);
+                }
+            });
+    }
+}
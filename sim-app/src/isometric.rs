@@ -0,0 +1,194 @@
+//! Isometric voxel view for grid sims: every cell of the sim's scalar field
+//! becomes a column standing on a 2:1 isometric floor, as tall as its value
+//! and colored like the cell in the normal render. Columns are drawn back to
+//! front so nearer ones hide the ones behind them.
+
+use eframe::egui;
+use serde_json::{json, Value};
+use sim_core::nudge::Nudge;
+use sim_core::state::{read, read_clamped};
+use sim_core::Color;
+use std::collections::HashSet;
+
+/// Brightness of the top, left and right faces, as if lit from the upper
+/// left
+const TOP_SHADE: f32 = 1.0;
+const LEFT_SHADE: f32 = 0.72;
+const RIGHT_SHADE: f32 = 0.5;
+
+/// How far empty floor tiles are lifted towards white, so the floor still
+/// shows on a background of the same color
+const FLOOR_TINT: f32 = 0.08;
+
+pub struct IsometricView {
+    /// Names of the sims the view is switched on for
+    enabled: HashSet<String>,
+    /// Height of the tallest column above a unit cube, in tile widths
+    pub relief: f32,
+}
+
+impl Default for IsometricView {
+    fn default() -> Self {
+        Self {
+            enabled: HashSet::new(),
+            relief: 1.5,
+        }
+    }
+}
+
+impl IsometricView {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_enabled(&self, simulation: &str) -> bool {
+        self.enabled.contains(simulation)
+    }
+
+    /// For scene files
+    pub fn state(&self) -> Value {
+        let mut enabled: Vec<&String> = self.enabled.iter().collect();
+        enabled.sort();
+        json!({ "enabled": enabled, "relief": self.relief })
+    }
+
+    pub fn load_state(&mut self, state: &Value) {
+        read(state, "enabled", &mut self.enabled);
+        read_clamped(state, "relief", &mut self.relief, 0.0..=6.0);
+    }
+
+    /// Toggle for `simulation`, which may or may not have a scalar field.
+    /// Returns true if anything changed.
+    pub fn ui(&mut self, ui: &mut egui::Ui, simulation: &str, available: bool) -> bool {
+        let mut changed = false;
+
+        egui::CollapsingHeader::new("🧊 Isometric Voxels")
+            .show(ui, |ui| {
+                let mut on = self.is_enabled(simulation);
+                if ui.add_enabled(available, egui::Checkbox::new(&mut on, "Enable for this simulation"))
+                    .on_hover_text("Draw each grid cell as a column whose height follows the sim's scalar field")
+                    .changed() {
+                    if on {
+                        self.enabled.insert(simulation.to_string());
+                    } else {
+                        self.enabled.remove(simulation);
+                    }
+                    changed = true;
+                }
+                if !available {
+                    ui.label("This simulation has no height field");
+                }
+
+                ui.add_enabled_ui(on && available, |ui| {
+                    changed |= ui.add(egui::Slider::new(&mut self.relief, 0.0..=6.0)
+                        .nudge(ui, 0.05)
+                        .text("Relief")).changed();
+                    ui.label("Painting is off in this view");
+                });
+            });
+
+        changed
+    }
+
+    /// Draw the `field_width`x`field_height` field as columns into a
+    /// `width`x`height` image. `colors` is the flat render at that size;
+    /// each column takes the color under its cell's center.
+    #[allow(clippy::too_many_arguments)]
+    pub fn render(
+        &self,
+        field: &[f32],
+        field_width: usize,
+        field_height: usize,
+        colors: &[Color],
+        width: usize,
+        height: usize,
+        background: Color,
+    ) -> Vec<Color> {
+        let mut pixels = vec![background; width * height];
+        if field_width == 0 || field_height == 0 || field.len() < field_width * field_height
+            || width == 0 || height == 0 || colors.len() < width * height {
+            return pixels;
+        }
+
+        let max = field.iter()
+            .filter(|v| v.is_finite())
+            .fold(0.0f32, |hi, &v| hi.max(v));
+        let relief = self.relief.max(0.0);
+
+        // The floor is a diamond (field_width + field_height) half-tiles
+        // across; leave room above it for a unit cube plus the full relief
+        let diagonal = (field_width + field_height) as f32;
+        let tile_width = (2.0 * width as f32 / diagonal)
+            .min(height as f32 / (diagonal / 4.0 + 0.5 + relief));
+        let tile_height = tile_width / 2.0;
+        let top = (height as f32 - tile_width * (diagonal / 4.0 + 0.5 + relief)) / 2.0;
+        let origin_x = width as f32 / 2.0 + (field_height as f32 - field_width as f32) * tile_width / 4.0;
+        let origin_y = top + tile_height + relief * tile_width;
+
+        // Back to front: cells on the same i + j diagonal never overlap
+        for diagonal_index in 0..field_width + field_height - 1 {
+            let first = diagonal_index.saturating_sub(field_height - 1);
+            for i in first..=diagonal_index.min(field_width - 1) {
+                let j = diagonal_index - i;
+                let value = field[j * field_width + i];
+                let column = if value.is_finite() && value > 0.0 {
+                    tile_height + relief * tile_width * value / max
+                } else {
+                    0.0
+                };
+
+                let sample_x = ((i as f32 + 0.5) * width as f32 / field_width as f32) as usize;
+                let sample_y = ((j as f32 + 0.5) * height as f32 / field_height as f32) as usize;
+                let mut color = colors[sample_y.min(height - 1) * width + sample_x.min(width - 1)];
+                if column == 0.0 {
+                    color = Color::lerp(color, Color::WHITE, FLOOR_TINT);
+                }
+
+                let x = origin_x + (i as f32 - j as f32) * tile_width / 2.0;
+                let y = origin_y + (i + j) as f32 * tile_height / 2.0;
+                draw_column(&mut pixels, width, height, [x, y], tile_width, column, color);
+            }
+        }
+
+        pixels
+    }
+}
+
+/// One column whose floor tile has its top corner at `corner`: the top face
+/// raised by `column` pixels, and the two visible side faces below it
+fn draw_column(pixels: &mut [Color], width: usize, height: usize, corner: [f32; 2], tile_width: f32, column: f32, color: Color) {
+    let half = tile_width / 2.0;
+    let x0 = ((corner[0] - half).floor() as i64).max(0);
+    let x1 = ((corner[0] + half).ceil() as i64).min(width as i64);
+
+    for x in x0..x1 {
+        let dx = x as f32 + 0.5 - corner[0];
+        if dx.abs() >= half {
+            continue;
+        }
+        // Upper and lower edges of the floor diamond in this pixel column
+        let upper = corner[1] + dx.abs() / 2.0;
+        let lower = corner[1] + half - dx.abs() / 2.0;
+
+        let side = if dx < 0.0 { LEFT_SHADE } else { RIGHT_SHADE };
+        fill_span(pixels, width, height, x as usize, lower - column, lower, shade(color, side));
+        fill_span(pixels, width, height, x as usize, upper - column, lower - column, shade(color, TOP_SHADE));
+    }
+}
+
+/// Pixels of column `x` whose centers lie in `top..bottom`
+fn fill_span(pixels: &mut [Color], width: usize, height: usize, x: usize, top: f32, bottom: f32, color: Color) {
+    let y0 = ((top - 0.5).ceil() as i64).max(0);
+    let y1 = ((bottom - 0.5).ceil() as i64).min(height as i64);
+    for y in y0..y1 {
+        pixels[y as usize * width + x] = color;
+    }
+}
+
+fn shade(c: Color, factor: f32) -> Color {
+    Color::from_rgb(
+        (c.r as f32 * factor) as u8,
+        (c.g as f32 * factor) as u8,
+        (c.b as f32 * factor) as u8,
+    )
+}
@@ -3,14 +3,48 @@
 //! This library exports the main application components for use by both
 //! the native desktop app and the WebAssembly web app.
 
+pub mod ascii_art;
+pub mod auto_quality;
+pub mod automation;
+pub mod camera_path;
+pub mod color_vision;
+pub mod compositing;
+pub mod gif_export;
+pub mod isometric;
+pub mod palette_animation;
+pub mod persistence;
+pub mod plane_overlay;
+pub mod render_budget;
+pub mod scene;
+pub mod screensaver;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod video;
 pub mod viewer_2d;
 pub mod viewer_3d;
 
 use eframe::egui;
+use scene::{Scene, SCENE_VERSION};
+use screensaver::{PlaylistEntry, Screensaver};
 use sim_core::*;
+use sim_core::nudge::Nudge;
+
+/// Upper bound on the frame dt seen by sims. A stall (window drag, tab switch)
+/// would otherwise hand physics sims one huge integration step.
+const MAX_FRAME_DT: f32 = 1.0 / 30.0;
+
+/// dt used for the first frame after the window regains focus, so sims
+/// don't integrate the whole time spent in the background in one step
+const RESUME_DT: f32 = 1.0 / 60.0;
+
+/// How long toast messages stay on screen, in seconds
+const TOAST_SECONDS: f64 = 4.0;
+
+/// Hides and restores the control panel. Ignored while a text field has
+/// focus, so it can still be typed.
+const HIDE_UI_KEY: egui::Key = egui::Key::H;
 
 /// Simulation type selector
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
 pub enum SimulationType {
     TwoD,
     ThreeD,
@@ -25,76 +59,33 @@ pub struct ComplexSystemsApp {
     pub simulations_3d: Vec<Simulation3DBox>,
     pub viewer_2d: viewer_2d::Viewer2D,
     pub viewer_3d: viewer_3d::Viewer3D,
+    pub pause_on_blur: bool,
+    /// Black panels, white text and bright outlines on every widget
+    pub high_contrast: bool,
+    /// Sim time shared by every sim; pausing it freezes all of them
+    pub clock: clock::SimClock,
+    pub screensaver: Screensaver,
+    #[cfg(not(target_arch = "wasm32"))]
+    pub video: video::VideoExport,
+    pub camera_path: camera_path::CameraPath,
+    pub palette_animation: palette_animation::PaletteAnimation,
+    pub automation: automation::Automation,
+    pub auto_quality: auto_quality::AutoQuality,
+    /// Result of the last scene save/load, shown under the buttons
+    scene_status: Option<String>,
+    /// Text field for pasting settings, while it is open
+    settings_paste: Option<String>,
+    /// Transient message and the `input.time` it disappears at
+    toast: Option<(String, f64)>,
+    was_focused: bool,
+    /// Control panel hidden so the view fills the window
+    pub ui_hidden: bool,
 }
 
 impl ComplexSystemsApp {
     pub fn new(_cc: &eframe::CreationContext<'_>) -> Self {
-        let simulations_2d: Vec<Simulation2DBox> = vec![
-            // Fractals
-            Box::new(mandelbrot::Mandelbrot::new()),
-            Box::new(julia::Julia::new()),
-            Box::new(burning_ship::BurningShip::new()),
-
-            // Cellular Systems & Emergent Complexity
-            Box::new(game_of_life::GameOfLife::new()),
-            Box::new(cellular_automaton::CellularAutomaton::new(30)),
-            Box::new(langtons_ant::LangtonsAnt::new()),
-            Box::new(cyclic_ca::CyclicCA::new()),
-
-            // Growth & Self-Organization
-            Box::new(dla::DLA::new()),
-            Box::new(sandpile::Sandpile::new()),
-
-            // Animated Simulations
-            Box::new(double_pendulum::DoublePendulum::new()),
-            Box::new(reaction_diffusion::ReactionDiffusion::new()),
-            Box::new(lissajous::LissajousCurves::new()),
-            Box::new(wave_interference::WaveInterference::new()),
-
-            // Generative Patterns
-            Box::new(generative::KochSnowflake::new()),
-            Box::new(generative::Phyllotaxis::new()),
-            Box::new(generative::PerlinFlow::new()),
-            Box::new(generative::Boids::new()),
-            Box::new(generative::DeJongAttractor::new()),
-            Box::new(generative::CliffordAttractor::new()),
-
-            // Complex Emergent Simulations
-            Box::new(slime_mold::SlimeMold::new()),
-            Box::new(falling_sand::FallingSand::new()),
-        ];
-
-        let simulations_3d: Vec<Simulation3DBox> = vec![
-            // Stunning 3D Visualizations
-            Box::new(dna_helix::DNAHelix::new()),
-            Box::new(torus_knot::TorusKnot::new()),
-            Box::new(galaxy_spiral::GalaxySpiral::new()),
-
-            // Enhanced Particle Systems
-            Box::new(particle_attractor_3d::ParticleAttractor3D::new()),
-            Box::new(boids_3d::Boids3D::new()),
-
-            // Classic Attractors
-            Box::new(lorenz::LorenzAttractor::new()),
-            Box::new(rossler::RosslerAttractor::new()),
-
-            // Additional Chaotic Attractors
-            Box::new(aizawa::AizawaAttractor::new()),
-            Box::new(halvorsen::HalvorsenAttractor::new()),
-            Box::new(dadras::DadrasAttractor::new()),
-            Box::new(thomas::ThomasAttractor::new()),
-            Box::new(chen::ChenAttractor::new()),
-
-            // Diverse Particle Simulations
-            Box::new(nbody_gravity::NBodyGravity::new()),
-            Box::new(fluid_sph::FluidSPH::new()),
-            Box::new(magnetic_field::MagneticField::new()),
-
-            // Radical 3D Animations
-            Box::new(vortex_turbulence::VortexTurbulence::new()),
-            Box::new(lightning_bolt::LightningBolt::new()),
-            Box::new(fractal_tree_3d::FractalTree3D::new()),
-        ];
+        let simulations_2d = registry::simulations_2d();
+        let simulations_3d = registry::simulations_3d();
 
         Self {
             sim_type: SimulationType::TwoD,
@@ -104,22 +95,900 @@ impl ComplexSystemsApp {
             simulations_3d,
             viewer_2d: viewer_2d::Viewer2D::new(),
             viewer_3d: viewer_3d::Viewer3D::new(),
+            pause_on_blur: true,
+            high_contrast: false,
+            clock: clock::SimClock::new(),
+            screensaver: Screensaver::new(),
+            #[cfg(not(target_arch = "wasm32"))]
+            video: video::VideoExport::new(),
+            camera_path: camera_path::CameraPath::new(),
+            palette_animation: palette_animation::PaletteAnimation::new(),
+            automation: automation::Automation::new(),
+            auto_quality: auto_quality::AutoQuality::new(),
+            scene_status: None,
+            settings_paste: None,
+            toast: None,
+            was_focused: true,
+            ui_hidden: false,
+        }
+    }
+
+    /// Switch to a playlist entry: select the sim by name, apply its preset
+    /// and put the view back to its defaults. Unknown sim names are skipped.
+    fn show_playlist_entry(&mut self, entry: &PlaylistEntry) {
+        match entry.sim_type {
+            SimulationType::TwoD => {
+                let Some(index) = self.simulations_2d.iter().position(|s| s.name() == entry.sim_name) else {
+                    return;
+                };
+                self.sim_type = SimulationType::TwoD;
+                self.sim_2d_index = index;
+                if let Some(preset) = &entry.preset {
+                    self.simulations_2d[index].load_preset(preset);
+                }
+                self.viewer_2d.reset_view();
+            }
+            SimulationType::ThreeD => {
+                let Some(index) = self.simulations_3d.iter().position(|s| s.name() == entry.sim_name) else {
+                    return;
+                };
+                self.sim_type = SimulationType::ThreeD;
+                self.sim_3d_index = index;
+                let sim = &mut self.simulations_3d[index];
+                sim.reset();
+                if let Some(preset) = &entry.preset {
+                    sim.load_preset(preset);
+                }
+                self.viewer_3d.reset_view();
+            }
+        }
+    }
+
+    /// Hand a click on the 2D view to the sim, and switch to the sim it
+    /// answers with (Mandelbrot opening the Julia set of the clicked point)
+    fn follow_plane_click(&mut self, ctx: &egui::Context) {
+        let Some((u, v)) = self.viewer_2d.take_plane_click() else {
+            return;
+        };
+        if self.sim_type != SimulationType::TwoD {
+            return;
+        }
+        let Some((name, state)) = self.simulations_2d[self.sim_2d_index].plane_click(u, v) else {
+            return;
+        };
+        let Some(index) = self.simulations_2d.iter().position(|s| s.name() == name) else {
+            return;
+        };
+
+        self.sim_2d_index = index;
+        self.simulations_2d[index].load_state(&state);
+        self.viewer_2d.reset_view();
+        self.show_toast(ctx, format!("{name}: c = {u:.4} {} {:.4}i", if v < 0.0 { '-' } else { '+' }, v.abs()));
+    }
+
+    /// Snapshot the active sim, its parameters and the matching viewer
+    pub fn capture_scene(&self) -> Scene {
+        let (simulation, params, view) = match self.sim_type {
+            SimulationType::TwoD => {
+                let sim = &self.simulations_2d[self.sim_2d_index];
+                (sim.name().to_string(), sim.save_state(), self.viewer_2d.view_state())
+            }
+            SimulationType::ThreeD => {
+                let sim = &self.simulations_3d[self.sim_3d_index];
+                (sim.name().to_string(), sim.save_state(), self.viewer_3d.view_state())
+            }
+        };
+
+        Scene {
+            version: SCENE_VERSION,
+            sim_type: self.sim_type,
+            simulation,
+            params,
+            view,
+            automation: (!self.automation.track.is_empty()).then(|| self.automation.track.clone()),
+        }
+    }
+
+    /// Select the scene's sim by name and restore its parameters and view.
+    /// Leaves the app untouched if the sim isn't registered.
+    pub fn apply_scene(&mut self, scene: &Scene) -> Result<(), String> {
+        let not_found = || format!("Unknown simulation \"{}\"", scene.simulation);
+
+        match scene.sim_type {
+            SimulationType::TwoD => {
+                let index = self.simulations_2d.iter()
+                    .position(|s| s.name() == scene.simulation)
+                    .ok_or_else(not_found)?;
+                self.sim_2d_index = index;
+                self.simulations_2d[index].load_state(&scene.params);
+                self.viewer_2d.load_view_state(&scene.view);
+            }
+            SimulationType::ThreeD => {
+                let index = self.simulations_3d.iter()
+                    .position(|s| s.name() == scene.simulation)
+                    .ok_or_else(not_found)?;
+                self.sim_3d_index = index;
+                self.simulations_3d[index].load_state(&scene.params);
+                self.viewer_3d.load_view_state(&scene.view);
+            }
+        }
+
+        if let Some(track) = &scene.automation {
+            self.automation.stop();
+            self.automation.track = track.clone();
+        }
+
+        self.sim_type = scene.sim_type;
+        self.screensaver.stop();
+        Ok(())
+    }
+
+    /// Name and `save_state` of the sim being shown
+    fn active_sim_state(&self) -> (String, serde_json::Value) {
+        match self.sim_type {
+            SimulationType::TwoD => {
+                let sim = &self.simulations_2d[self.sim_2d_index];
+                (sim.name().to_string(), sim.save_state())
+            }
+            SimulationType::ThreeD => {
+                let sim = &self.simulations_3d[self.sim_3d_index];
+                (sim.name().to_string(), sim.save_state())
+            }
+        }
+    }
+
+    /// Apply the automation changes due this frame to the sim the track was
+    /// recorded on, whether or not it's the one being shown
+    fn play_automation(&mut self) {
+        let Some(changes) = self.automation.due(self.clock.time()) else {
+            return;
+        };
+
+        let name = &self.automation.track.simulation;
+        if let Some(sim) = self.simulations_2d.iter_mut().find(|s| s.name() == name) {
+            sim.load_state(&changes);
+            self.viewer_2d.needs_update = true;
+        } else if let Some(sim) = self.simulations_3d.iter_mut().find(|s| s.name() == name) {
+            sim.load_state(&changes);
+        }
+    }
+
+    /// Record this frame's parameter changes, once the UI has made them
+    fn record_automation(&mut self) {
+        if !self.automation.is_recording() {
+            return;
+        }
+        let (name, state) = self.active_sim_state();
+        if name == self.automation.track.simulation {
+            self.automation.record(&state, self.clock.time());
+        }
+    }
+
+    /// Hand last frame's CPU time to auto-quality and apply any adjustment
+    /// to the active viewer
+    fn adapt_quality(&mut self, seconds: f32) {
+        match self.sim_type {
+            SimulationType::TwoD => {
+                if !self.viewer_2d.rendered() || self.viewer_2d.is_capturing() {
+                    return;
+                }
+                let name = self.simulations_2d[self.sim_2d_index].name();
+                if let Some(factor) = self.auto_quality.sample(name, seconds) {
+                    // Render cost goes with the pixel count, the square of the scale
+                    let range = auto_quality::SCALE_RANGE;
+                    let scale = (self.viewer_2d.scale * factor.sqrt()).clamp(*range.start(), *range.end());
+                    if scale != self.viewer_2d.scale {
+                        self.viewer_2d.scale = scale;
+                        self.viewer_2d.needs_update = true;
+                    }
+                }
+            }
+            SimulationType::ThreeD => {
+                if self.viewer_3d.capture_size.is_some() {
+                    return;
+                }
+                let name = self.simulations_3d[self.sim_3d_index].name();
+                if let Some(factor) = self.auto_quality.sample(name, seconds) {
+                    let range = auto_quality::POINTS_RANGE;
+                    self.viewer_3d.max_points = ((self.viewer_3d.max_points as f32 * factor) as usize)
+                        .clamp(*range.start(), *range.end());
+                }
+            }
         }
     }
+
+    fn automation_ui(&mut self, ui: &mut egui::Ui) {
+        egui::CollapsingHeader::new("🎚 Automation")
+            .show(ui, |ui| {
+                let now = self.clock.time();
+                let track = &self.automation.track;
+
+                if self.automation.is_recording() {
+                    ui.label(format!("Recording {}: {}, {} changes",
+                        track.simulation, readout::seconds(self.automation.elapsed(now).unwrap_or(0.0), 1), readout::count(track.events.len())));
+                    if ui.button("⏹ Stop Recording").clicked() {
+                        self.automation.stop();
+                    }
+                    return;
+                }
+
+                if self.automation.is_playing() {
+                    ui.label(format!("Playing {}: {} of {}",
+                        track.simulation, readout::seconds(self.automation.elapsed(now).unwrap_or(0.0), 1), readout::seconds(track.duration, 1)));
+                    if ui.button("⏹ Stop").clicked() {
+                        self.automation.stop();
+                    }
+                    return;
+                }
+
+                if track.is_empty() {
+                    ui.label("Record to capture every parameter change with its time");
+                } else {
+                    ui.label(format!("Track: {} ({}, {} changes)",
+                        track.simulation, readout::seconds(track.duration, 1), readout::count(track.events.len())));
+                }
+
+                ui.horizontal(|ui| {
+                    if ui.button("⏺ Record").clicked() {
+                        let (name, state) = self.active_sim_state();
+                        if !self.automation.start_recording(&name, &state, now) {
+                            self.show_toast(ui.ctx(), format!("{name} has no parameters to record"));
+                        }
+                    }
+                    if ui.add_enabled(!self.automation.track.is_empty(), egui::Button::new("▶ Play")).clicked() {
+                        self.automation.start_playback(now);
+                    }
+                    ui.checkbox(&mut self.automation.loop_playback, "Loop");
+                });
+                ui.label("Saved with the scene");
+            });
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn save_scene_dialog(&mut self) {
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("Scene", &["json"])
+            .set_file_name("scene.json")
+            .save_file() else {
+            return;
+        };
+
+        self.scene_status = Some(match std::fs::write(&path, self.capture_scene().to_json()) {
+            Ok(()) => format!("Saved {}", path.display()),
+            Err(e) => format!("Could not save scene: {e}"),
+        });
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn load_scene_dialog(&mut self) {
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("Scene", &["json"])
+            .pick_file() else {
+            return;
+        };
+
+        let result = std::fs::read_to_string(&path)
+            .map_err(|e| format!("Could not read scene: {e}"))
+            .and_then(|text| Scene::from_json(&text))
+            .and_then(|scene| self.apply_scene(&scene));
+
+        self.scene_status = Some(match result {
+            Ok(()) => format!("Loaded {}", path.display()),
+            Err(e) => e,
+        });
+    }
+
+    /// Write the current scene into the page's URL fragment and return the
+    /// full link
+    #[cfg(target_arch = "wasm32")]
+    fn share_url(&self) -> Option<String> {
+        let location = web_sys::window()?.location();
+        location.set_hash(&self.capture_scene().to_fragment()).ok()?;
+        location.href().ok()
+    }
+
+    /// Load a dropped `.json` file: a full scene if it has one's shape,
+    /// otherwise a parameter object for the active sim. Returns the message
+    /// to show either way.
+    fn load_dropped_file(&mut self, file: &egui::DroppedFile) -> Result<String, String> {
+        let name = file.path.as_ref()
+            .and_then(|p| p.file_name())
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_else(|| file.name.clone());
+        if !name.to_lowercase().ends_with(".json") {
+            return Err(format!("{name}: only .json scene or preset files can be dropped"));
+        }
+
+        // Web drops carry the bytes; native drops carry a path
+        let bytes = match (&file.bytes, &file.path) {
+            (Some(bytes), _) => bytes.to_vec(),
+            (None, Some(path)) => std::fs::read(path).map_err(|e| format!("{name}: {e}"))?,
+            (None, None) => return Err(format!("{name}: file contents unavailable")),
+        };
+        let text = String::from_utf8(bytes).map_err(|_| format!("{name}: not a text file"))?;
+        let value: serde_json::Value = serde_json::from_str(&text)
+            .map_err(|e| format!("{name}: invalid JSON ({e})"))?;
+
+        if value.get("simulation").is_some() {
+            let scene = Scene::from_json(&text).map_err(|e| format!("{name}: {e}"))?;
+            self.apply_scene(&scene).map_err(|e| format!("{name}: {e}"))?;
+            return Ok(format!("Loaded scene {name}"));
+        }
+
+        if !value.is_object() {
+            return Err(format!("{name}: expected a scene or parameter object"));
+        }
+        let sim_name = self.load_active_sim_state(&value);
+        Ok(format!("Applied {name} to {sim_name}"))
+    }
+
+    /// `load_state` on the sim being shown; returns its name
+    fn load_active_sim_state(&mut self, state: &serde_json::Value) -> String {
+        match self.sim_type {
+            SimulationType::TwoD => {
+                let sim = &mut self.simulations_2d[self.sim_2d_index];
+                sim.load_state(state);
+                self.viewer_2d.needs_update = true;
+                sim.name().to_string()
+            }
+            SimulationType::ThreeD => {
+                let sim = &mut self.simulations_3d[self.sim_3d_index];
+                sim.load_state(state);
+                sim.name().to_string()
+            }
+        }
+    }
+
+    /// Copy the active sim's parameters to the clipboard as JSON, or paste
+    /// them back. Pasting goes through a text field, since egui only sees
+    /// the clipboard when the user pastes into one.
+    fn settings_clipboard_ui(&mut self, ui: &mut egui::Ui) {
+        let (name, state) = self.active_sim_state();
+        let supported = !state.is_null();
+        let mut opened = false;
+
+        ui.horizontal(|ui| {
+            if ui.add_enabled(supported, egui::Button::new("📋 Copy Settings"))
+                .on_hover_text("Copy this simulation's parameters as JSON")
+                .on_disabled_hover_text(format!("{name} has no parameters to copy"))
+                .clicked() {
+                let json = serde_json::to_string_pretty(&state).unwrap_or_default();
+                ui.output_mut(|o| o.copied_text = json);
+                self.show_toast(ui.ctx(), format!("Copied {name} settings"));
+            }
+            let pasting = self.settings_paste.is_some();
+            if ui.add_enabled(supported, egui::SelectableLabel::new(pasting, "📥 Paste Settings"))
+                .on_hover_text("Apply parameters copied from this simulation")
+                .on_disabled_hover_text(format!("{name} has no parameters to paste"))
+                .clicked() {
+                self.settings_paste = if pasting { None } else { Some(String::new()) };
+                opened = !pasting;
+            }
+        });
+
+        let Some(text) = &mut self.settings_paste else {
+            return;
+        };
+        let response = ui.add(egui::TextEdit::singleline(text)
+            .hint_text("Press Ctrl+V here")
+            .desired_width(f32::INFINITY));
+        if opened {
+            response.request_focus();
+        }
+        if !response.changed() || text.trim().is_empty() {
+            return;
+        }
+
+        let message = match serde_json::from_str::<serde_json::Value>(text) {
+            Ok(value) if value.is_object() => {
+                self.settings_paste = None;
+                let sim_name = self.load_active_sim_state(&value);
+                format!("Applied settings to {sim_name}")
+            }
+            Ok(_) => "Pasted text is not a settings object".to_string(),
+            Err(e) => format!("Pasted text is not valid JSON ({e})"),
+        };
+        self.show_toast(ui.ctx(), message);
+    }
+
+    /// Hide or restore the control panel; hiding says how to get it back,
+    /// since the button that did it is gone with the panel
+    fn toggle_ui_hidden(&mut self, ctx: &egui::Context) {
+        self.ui_hidden = !self.ui_hidden;
+        if self.ui_hidden {
+            self.show_toast(ctx, format!("Controls hidden. Press {} to show them again", HIDE_UI_KEY.name()));
+        }
+    }
+
+    fn show_toast(&mut self, ctx: &egui::Context, message: String) {
+        self.toast = Some((message, ctx.input(|i| i.time) + TOAST_SECONDS));
+    }
+
+    /// Save a palette exported from a color scheme picker
+    #[cfg(not(target_arch = "wasm32"))]
+    fn save_palette_export(&mut self, ctx: &egui::Context) {
+        let Some(request) = palette_export::take_save_request(ctx) else {
+            return;
+        };
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("Palette", &[request.extension.as_str()])
+            .set_file_name(&request.file_name)
+            .save_file() else {
+            return;
+        };
+
+        let message = match std::fs::write(&path, request.contents) {
+            Ok(()) => format!("Saved {}", path.display()),
+            Err(e) => format!("Could not save palette: {e}"),
+        };
+        self.show_toast(ctx, message);
+    }
+
+    /// Re-render the 2D sim at the current resolution and save it as a PNG
+    #[cfg(not(target_arch = "wasm32"))]
+    fn save_2d_png(&mut self, ctx: &egui::Context) {
+        let simulation = self.simulations_2d[self.sim_2d_index].as_ref();
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("PNG image", &["png"])
+            .set_file_name(format!("{}.png", simulation.name().to_lowercase().replace(' ', "_")))
+            .save_file() else {
+            return;
+        };
+
+        let (width, height) = self.viewer_2d.image_size();
+        let message = match self.viewer_2d.export_png(simulation, &path) {
+            Ok(()) => format!("Saved {width}×{height} PNG to {}", path.display()),
+            Err(e) => format!("Could not save PNG: {e}"),
+        };
+        self.show_toast(ctx, message);
+    }
+
+    /// Re-render the 2D sim at the current resolution and download it as a
+    /// PNG
+    #[cfg(target_arch = "wasm32")]
+    fn save_2d_png(&mut self, ctx: &egui::Context) {
+        let simulation = self.simulations_2d[self.sim_2d_index].as_ref();
+        let name = format!("{}.png", simulation.name().to_lowercase().replace(' ', "_"));
+        let message = match self.viewer_2d.encode_png(simulation) {
+            Ok(png) => match download(&name, &png, "image/png") {
+                Some(()) => format!("Downloaded {name}"),
+                None => "Could not start the download".to_string(),
+            },
+            Err(e) => format!("Could not encode PNG: {e}"),
+        };
+        self.show_toast(ctx, message);
+    }
+
+    /// Encode a finished GIF recording and save it; the outcome is shown
+    /// under the recording controls
+    #[cfg(not(target_arch = "wasm32"))]
+    fn save_gif(&mut self, recording: gif_export::GifFrames) {
+        let name = self.simulations_2d[self.sim_2d_index].name().to_lowercase().replace(' ', "_");
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("Animated GIF", &["gif"])
+            .set_file_name(format!("{name}.gif"))
+            .save_file() else {
+            self.viewer_2d.gif.set_status("Recording discarded".to_string());
+            return;
+        };
+
+        let status = match recording.encode() {
+            Ok(bytes) => match std::fs::write(&path, bytes) {
+                Ok(()) => format!("Saved {} frames to {}", readout::count(recording.frames.len()), path.display()),
+                Err(e) => format!("Could not save GIF: {e}"),
+            },
+            Err(e) => format!("Could not encode GIF: {e}"),
+        };
+        self.viewer_2d.gif.set_status(match recording.warning() {
+            Some(warning) => format!("{warning}. {status}"),
+            None => status,
+        });
+    }
+
+    /// Encode a finished GIF recording and download it
+    #[cfg(target_arch = "wasm32")]
+    fn save_gif(&mut self, recording: gif_export::GifFrames) {
+        let name = format!("{}.gif", self.simulations_2d[self.sim_2d_index].name().to_lowercase().replace(' ', "_"));
+        let status = match recording.encode() {
+            Ok(bytes) => match download(&name, &bytes, "image/gif") {
+                Some(()) => format!("Downloaded {name} ({} frames)", readout::count(recording.frames.len())),
+                None => "Could not start the download".to_string(),
+            },
+            Err(e) => format!("Could not encode GIF: {e}"),
+        };
+        self.viewer_2d.gif.set_status(match recording.warning() {
+            Some(warning) => format!("{warning}. {status}"),
+            None => status,
+        });
+    }
+
+    /// Render the 3D view offscreen at the export size and save it as a PNG
+    #[cfg(not(target_arch = "wasm32"))]
+    fn save_3d_png(&mut self, ctx: &egui::Context) {
+        let simulation = self.simulations_3d[self.sim_3d_index].as_ref();
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("PNG image", &["png"])
+            .set_file_name(format!("{}.png", simulation.name().to_lowercase().replace(' ', "_")))
+            .save_file() else {
+            return;
+        };
+
+        let (width, height) = self.viewer_3d.png_size;
+        let pixels = self.viewer_3d.render_to_buffer(simulation, width, height);
+        let bytes = pixels.iter().flat_map(|c| [c.r(), c.g(), c.b()]).collect();
+        let message = match image::RgbImage::from_raw(width as u32, height as u32, bytes)
+            .map(|image| image.save(&path)) {
+            Some(Ok(())) => format!("Saved {width}×{height} PNG to {}", path.display()),
+            Some(Err(e)) => format!("Could not save PNG: {e}"),
+            None => "Could not save PNG: nothing rendered".to_string(),
+        };
+        self.show_toast(ctx, message);
+    }
+
+    /// Render the turntable sprite sheet and save it as one PNG
+    #[cfg(not(target_arch = "wasm32"))]
+    fn save_turntable(&mut self, ctx: &egui::Context) {
+        let simulation = self.simulations_3d[self.sim_3d_index].as_ref();
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("PNG image", &["png"])
+            .set_file_name(format!("{}_turntable.png", simulation.name().to_lowercase().replace(' ', "_")))
+            .save_file() else {
+            return;
+        };
+
+        let frames = self.viewer_3d.turntable_frames;
+        let (pixels, width, height) = self.viewer_3d.render_turntable(simulation);
+        let bytes = pixels.iter().flat_map(|c| [c.r(), c.g(), c.b()]).collect();
+        let message = match image::RgbImage::from_raw(width as u32, height as u32, bytes)
+            .map(|image| image.save(&path)) {
+            Some(Ok(())) => format!("Saved {frames}-frame turntable ({width}×{height}) to {}", path.display()),
+            Some(Err(e)) => format!("Could not save sprite sheet: {e}"),
+            None => "Could not save sprite sheet: nothing rendered".to_string(),
+        };
+        self.show_toast(ctx, message);
+    }
+
+    /// Dim the window while files hover over it, and load any that land
+    fn handle_file_drop(&mut self, ctx: &egui::Context) {
+        if ctx.input(|i| !i.raw.hovered_files.is_empty()) {
+            let painter = ctx.layer_painter(egui::LayerId::new(egui::Order::Foreground, egui::Id::new("file_drop")));
+            let screen = ctx.screen_rect();
+            painter.rect_filled(screen, 0.0, egui::Color32::from_black_alpha(160));
+            painter.text(
+                screen.center(),
+                egui::Align2::CENTER_CENTER,
+                "Drop a scene or preset file to load it",
+                egui::FontId::proportional(22.0),
+                egui::Color32::WHITE,
+            );
+        }
+
+        let dropped = ctx.input(|i| i.raw.dropped_files.clone());
+        for file in &dropped {
+            let message = match self.load_dropped_file(file) {
+                Ok(message) | Err(message) => message,
+            };
+            self.show_toast(ctx, message);
+        }
+    }
+
+    fn toast_ui(&mut self, ctx: &egui::Context) {
+        let Some((message, until)) = &self.toast else {
+            return;
+        };
+        if ctx.input(|i| i.time) > *until {
+            self.toast = None;
+            return;
+        }
+
+        egui::Area::new(egui::Id::new("toast"))
+            .anchor(egui::Align2::CENTER_BOTTOM, egui::vec2(0.0, -24.0))
+            .order(egui::Order::Foreground)
+            .interactable(false)
+            .show(ctx, |ui| {
+                egui::Frame::popup(ui.style()).show(ui, |ui| {
+                    ui.label(message.as_str());
+                });
+            });
+    }
+
+    fn scene_ui(&mut self, ui: &mut egui::Ui) {
+        egui::CollapsingHeader::new("💾 Scene")
+            .show(ui, |ui| {
+                #[cfg(not(target_arch = "wasm32"))]
+                ui.horizontal(|ui| {
+                    if ui.button("Save Scene").clicked() {
+                        self.save_scene_dialog();
+                    }
+                    if ui.button("Load Scene").clicked() {
+                        self.load_scene_dialog();
+                    }
+                });
+
+                #[cfg(target_arch = "wasm32")]
+                if ui.button("🔗 Copy Shareable URL")
+                    .on_hover_text("Put this scene in the page URL and copy the link")
+                    .clicked() {
+                    self.scene_status = Some(match self.share_url() {
+                        Some(url) => {
+                            ui.output_mut(|o| o.copied_text = url);
+                            "Link copied to clipboard".to_string()
+                        }
+                        None => "Could not update the page URL".to_string(),
+                    });
+                }
+
+                if let Some(status) = &self.scene_status {
+                    ui.label(status);
+                }
+            });
+    }
+
+    /// Start recording the camera path through the video exporter, one
+    /// video frame per path step
+    fn render_camera_path(&mut self) {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let frames = (self.camera_path.duration() * self.video.settings.fps as f32).ceil() as u32 + 1;
+            if self.video.start(frames, self.viewer_3d.image_size()).is_ok() {
+                self.camera_path.play(camera_path::Playback::Render, &mut self.viewer_3d);
+            }
+        }
+    }
+
+    /// Move the 3D camera along the path while it plays. A render follows
+    /// the frames written, so the path and the video stay in lockstep.
+    fn drive_camera_path(&mut self) {
+        match self.camera_path.playback {
+            camera_path::Playback::Stopped => return,
+            camera_path::Playback::Preview => self.camera_path.tick(self.clock.dt()),
+            camera_path::Playback::Render => {
+                #[cfg(not(target_arch = "wasm32"))]
+                match self.video.frames_written() {
+                    Some(written) => {
+                        self.camera_path.time = written as f32 / self.video.settings.fps.max(1) as f32;
+                    }
+                    None => self.camera_path.playback = camera_path::Playback::Stopped,
+                };
+            }
+        }
+        self.camera_path.apply(&mut self.viewer_3d);
+    }
+
+    /// Sim step per frame while a video or GIF is being recorded
+    fn recording_dt(&self) -> Option<f32> {
+        let gif = self.viewer_2d.gif.frame_dt().filter(|_| self.sim_type == SimulationType::TwoD);
+        #[cfg(not(target_arch = "wasm32"))]
+        return self.video.frame_dt().or(gif);
+        #[cfg(target_arch = "wasm32")]
+        gif
+    }
+
+    fn time_ui(&mut self, ui: &mut egui::Ui) {
+        egui::CollapsingHeader::new("⏯ Time")
+            .default_open(true)
+            .show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    let label = if self.clock.paused { "▶ Resume" } else { "⏸ Pause" };
+                    if ui.button(label).clicked() {
+                        self.clock.paused = !self.clock.paused;
+                    }
+                    ui.label(format!("t = {}", readout::seconds(self.clock.time(), 1)));
+                });
+
+                ui.horizontal(|ui| {
+                    ui.add(egui::Slider::new(&mut self.clock.time_scale, 0.1..=4.0)
+                        .nudge(ui, 0.05)
+                        .logarithmic(true)
+                        .text("Time Scale"));
+                    if ui.button("1×").clicked() {
+                        self.clock.time_scale = 1.0;
+                    }
+                });
+            });
+    }
+
+    fn screensaver_ui(&mut self, ui: &mut egui::Ui) {
+        egui::CollapsingHeader::new("🖥 Screensaver")
+            .show(ui, |ui| {
+                if let Some(entry) = self.screensaver.current() {
+                    ui.label(format!("Showing: {} ({} left)", entry.sim_name, readout::seconds(self.screensaver.remaining(), 0)));
+                    ui.label("Move the mouse or press any key to exit");
+                } else if ui.button("▶ Start Screensaver").clicked() {
+                    if let Some(entry) = self.screensaver.start().cloned() {
+                        self.show_playlist_entry(&entry);
+                    }
+                }
+
+                ui.add(egui::Slider::new(&mut self.screensaver.duration_scale, 0.25..=4.0)
+                    .nudge(ui, 0.02)
+                    .text("Duration Scale"));
+
+                ui.label("Playlist:");
+                for entry in &self.screensaver.playlist {
+                    let preset = entry.preset.as_deref().unwrap_or("current settings");
+                    ui.label(format!("  {} - {} ({})", entry.sim_name, preset,
+                        readout::seconds(entry.duration * self.screensaver.duration_scale, 0)));
+                }
+            });
+    }
+}
+
+/// The main sim, mutably, and a different sim to blend over it
+fn main_and_layer(sims: &mut [Simulation2DBox], main: usize, layer: Option<usize>) -> (&mut Simulation2DBox, Option<&dyn Simulation2D>) {
+    match layer {
+        Some(layer) if layer < main => {
+            let (before, rest) = sims.split_at_mut(main);
+            (&mut rest[0], Some(before[layer].as_ref()))
+        }
+        Some(layer) if layer > main && layer < sims.len() => {
+            let (rest, after) = sims.split_at_mut(layer);
+            (&mut rest[main], Some(after[0].as_ref()))
+        }
+        _ => (&mut sims[main], None),
+    }
+}
+
+/// Hand `bytes` to the browser as a file download named `name`, through a
+/// temporary object URL on a clicked link
+#[cfg(target_arch = "wasm32")]
+fn download(name: &str, bytes: &[u8], mime: &str) -> Option<()> {
+    use wasm_bindgen::JsCast;
+
+    let document = web_sys::window()?.document()?;
+    let parts = js_sys::Array::of1(&js_sys::Uint8Array::from(bytes));
+    let options = web_sys::BlobPropertyBag::new();
+    options.set_type(mime);
+    let blob = web_sys::Blob::new_with_u8_array_sequence_and_options(&parts, &options).ok()?;
+    let url = web_sys::Url::create_object_url_with_blob(&blob).ok()?;
+
+    let link = document.create_element("a").ok()?.dyn_into::<web_sys::HtmlAnchorElement>().ok()?;
+    link.set_href(&url);
+    link.set_download(name);
+    link.click();
+    web_sys::Url::revoke_object_url(&url).ok()
+}
+
+/// Read-only panel with the sim's governing equations, if it provides them
+fn equations_ui(ui: &mut egui::Ui, equations: Option<&str>) {
+    let Some(equations) = equations else {
+        return;
+    };
+    egui::CollapsingHeader::new("📖 Equations")
+        .show(ui, |ui| {
+            ui.label(egui::RichText::new(equations).monospace());
+        });
+}
+
+/// Any pointer movement, click, scroll, touch or key press this frame
+fn has_user_input(ctx: &egui::Context) -> bool {
+    ctx.input(|i| {
+        i.pointer.delta() != egui::Vec2::ZERO
+            || i.events.iter().any(|e| matches!(e,
+                egui::Event::Key { .. }
+                | egui::Event::Text(_)
+                | egui::Event::PointerButton { .. }
+                | egui::Event::MouseWheel { .. }
+                | egui::Event::Touch { .. }))
+    })
+}
+
+/// Switch the UI between egui's own themes and a high-contrast one. Both
+/// light and dark get it, so a system theme change doesn't undo it.
+fn apply_theme(ctx: &egui::Context, high_contrast: bool) {
+    for theme in [egui::Theme::Dark, egui::Theme::Light] {
+        let visuals = if high_contrast { high_contrast_visuals() } else { theme.default_visuals() };
+        ctx.set_visuals_of(theme, visuals);
+    }
+}
+
+fn high_contrast_visuals() -> egui::Visuals {
+    let white = egui::Color32::WHITE;
+    let black = egui::Color32::BLACK;
+    let highlight = egui::Color32::from_rgb(255, 220, 0);
+
+    let mut visuals = egui::Visuals::dark();
+    visuals.override_text_color = Some(white);
+    visuals.panel_fill = black;
+    visuals.window_fill = black;
+    visuals.extreme_bg_color = black;
+    visuals.faint_bg_color = egui::Color32::from_gray(24);
+    visuals.hyperlink_color = highlight;
+    visuals.selection.bg_fill = egui::Color32::from_rgb(110, 90, 0);
+    visuals.selection.stroke = egui::Stroke::new(1.5, highlight);
+
+    let widgets = &mut visuals.widgets;
+    for (state, outline) in [
+        (&mut widgets.noninteractive, egui::Color32::from_gray(140)),
+        (&mut widgets.inactive, white),
+        (&mut widgets.hovered, highlight),
+        (&mut widgets.active, highlight),
+        (&mut widgets.open, highlight),
+    ] {
+        state.bg_stroke = egui::Stroke::new(1.0, outline);
+        state.fg_stroke.color = white;
+    }
+    widgets.inactive.bg_fill = black;
+    widgets.inactive.weak_bg_fill = black;
+    widgets.hovered.bg_fill = egui::Color32::from_gray(40);
+    widgets.hovered.weak_bg_fill = egui::Color32::from_gray(40);
+    widgets.active.bg_fill = egui::Color32::from_gray(64);
+    widgets.active.weak_bg_fill = egui::Color32::from_gray(64);
+    visuals
 }
 
 impl eframe::App for ComplexSystemsApp {
-    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
-        ctx.request_repaint();
+    fn update(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
+        let focused = ctx.input(|i| i.focused);
+        // A recording keeps going in the background
+        let blurred = self.pause_on_blur && !focused && self.recording_dt().is_none();
+
+        if blurred {
+            // Stop the repaint loop and freeze dt-driven sims until focus returns
+            ctx.input_mut(|i| i.stable_dt = 0.0);
+        } else {
+            if !self.was_focused {
+                ctx.input_mut(|i| i.stable_dt = i.stable_dt.min(RESUME_DT));
+            }
+            ctx.request_repaint();
+        }
+        self.was_focused = focused;
+
+        // The clock is advanced from the clamped frame dt, so the clamps cover
+        // both 3D `step` calls and the 2D accumulators
+        ctx.input_mut(|i| i.stable_dt = i.stable_dt.min(MAX_FRAME_DT));
+        // While recording, every frame is one video frame of sim time no
+        // matter how long it took to render
+        if let Some(dt) = self.recording_dt() {
+            ctx.input_mut(|i| i.stable_dt = dt);
+        }
+        self.clock.advance(ctx.input(|i| i.stable_dt));
+        self.clock.publish(ctx);
+
+        // A new phase only recolors: escape-time fractals reuse their cached
+        // iterations, so cycling them doesn't re-iterate
+        self.palette_animation.advance(self.clock.dt());
+        let palette_phase = self.palette_animation.phase();
+        if palette_phase != self.viewer_2d.palette_phase {
+            self.viewer_2d.palette_phase = palette_phase;
+            self.viewer_2d.needs_update = true;
+        }
+
+        if self.screensaver.is_active() {
+            if self.screensaver.accepts_input() && has_user_input(ctx) {
+                self.screensaver.stop();
+            } else if let Some(entry) = self.screensaver.tick(ctx.input(|i| i.stable_dt)).cloned() {
+                self.show_playlist_entry(&entry);
+            }
+        }
+
+        self.handle_file_drop(ctx);
+        self.play_automation();
+        if let Some(seconds) = frame.info().cpu_usage {
+            self.adapt_quality(seconds);
+            self.viewer_2d.record_frame_time(seconds);
+        }
+
+        if !ctx.wants_keyboard_input() && ctx.input_mut(|i| i.consume_key(egui::Modifiers::NONE, HIDE_UI_KEY)) {
+            self.toggle_ui_hidden(ctx);
+        }
 
         egui::SidePanel::left("control_panel")
             .min_width(400.0)
             .max_width(550.0)
             .resizable(true)
-            .show(ctx, |ui| {
+            .show_animated(ctx, !self.ui_hidden, |ui| {
                 ui.vertical_centered(|ui| {
                     ui.heading("Complex Systems Visualizer");
                 });
+                ui.horizontal(|ui| {
+                    if ui.button("⛶ Hide Controls")
+                        .on_hover_text(format!("Let the view fill the window. Press {} to hide or show the controls at any time.", HIDE_UI_KEY.name()))
+                        .clicked() {
+                        self.toggle_ui_hidden(ui.ctx());
+                    }
+                    ui.label(egui::RichText::new(format!("or press {}", HIDE_UI_KEY.name())).weak());
+                });
                 ui.separator();
 
                 ui.horizontal(|ui| {
@@ -132,6 +1001,32 @@ impl eframe::App for ComplexSystemsApp {
                     }
                 });
 
+                ui.checkbox(&mut self.pause_on_blur, "Pause When Unfocused")
+                    .on_hover_text("Stop rendering while the window is in the background to save power");
+                if ui.checkbox(&mut self.high_contrast, "High Contrast Controls")
+                    .on_hover_text("White text and outlines on black, with yellow highlights")
+                    .changed() {
+                    apply_theme(ui.ctx(), self.high_contrast);
+                }
+                egui::CollapsingHeader::new("🔢 Number Format")
+                    .show(ui, |ui| {
+                        readout::NumberStyle::ui(ui);
+                    });
+
+                self.time_ui(ui);
+                self.palette_animation.ui(ui);
+                #[cfg(not(target_arch = "wasm32"))]
+                {
+                    let canvas_size = match self.sim_type {
+                        SimulationType::TwoD => self.viewer_2d.image_size(),
+                        SimulationType::ThreeD => self.viewer_3d.image_size(),
+                    };
+                    self.video.ui(ui, canvas_size);
+                }
+                self.screensaver_ui(ui);
+                self.scene_ui(ui);
+                self.automation_ui(ui);
+
                 ui.separator();
 
                 // Global scale/zoom controls
@@ -139,8 +1034,11 @@ impl eframe::App for ComplexSystemsApp {
                     SimulationType::TwoD => {
                         ui.horizontal(|ui| {
                             ui.label("Pattern Detail:");
-                            if ui.add(egui::Slider::new(&mut self.viewer_2d.scale, 0.25..=2.0)
-                                .text("Scale")).changed() {
+                            if ui.add_enabled(!self.auto_quality.enabled, egui::Slider::new(&mut self.viewer_2d.scale, 0.25..=2.0)
+                                .nudge(ui, 0.01)
+                                .text("Scale"))
+                                .on_disabled_hover_text("Set by Auto Quality; turn it off to set the scale by hand")
+                                .changed() {
                                 // Clamp value to ensure it stays within valid range
                                 self.viewer_2d.scale = self.viewer_2d.scale.clamp(0.25, 2.0);
                                 self.viewer_2d.needs_update = true;
@@ -148,7 +1046,7 @@ impl eframe::App for ComplexSystemsApp {
                                 self.viewer_2d.pan_x = 0.0;
                                 self.viewer_2d.pan_y = 0.0;
                             }
-                            if ui.button("Reset Scale").clicked() {
+                            if ui.add_enabled(!self.auto_quality.enabled, egui::Button::new("Reset Scale")).clicked() {
                                 self.viewer_2d.scale = 1.0;
                                 self.viewer_2d.needs_update = true;
                                 self.viewer_2d.pan_x = 0.0;
@@ -159,24 +1057,52 @@ impl eframe::App for ComplexSystemsApp {
                                 self.viewer_2d.pan_y = 0.0;
                             }
                         });
+                        let sim = &mut self.simulations_2d[self.sim_2d_index];
+                        if sim.supports_auto_fit() && ui.button("🎯 Auto-Fit")
+                            .on_hover_text("Center and zoom so the pattern fills the canvas")
+                            .clicked() {
+                            let (width, height) = self.viewer_2d.image_size();
+                            sim.auto_fit(width, height);
+                            self.viewer_2d.needs_update = true;
+                        }
                         ui.label(format!("Resolution: {}x{} pixels",
                             (800.0 * self.viewer_2d.scale) as i32,
                             (600.0 * self.viewer_2d.scale) as i32));
+                        if ui.button("💾 Save Image")
+                            .on_hover_text("Re-render the simulation at the current resolution and save it as a PNG")
+                            .clicked() {
+                            self.save_2d_png(ui.ctx());
+                        }
                         ui.label("Tip: Drag to pan (fractals), mousewheel to zoom (fractals)");
                     }
                     SimulationType::ThreeD => {
                         ui.horizontal(|ui| {
                             ui.label("View Zoom:");
                             if ui.add(egui::Slider::new(&mut self.viewer_3d.zoom, 0.5..=5.0)
+                                .nudge(ui, 0.02)
                                 .text("Zoom")).changed() {
                                 // Clamp value to ensure it stays within valid range
                                 self.viewer_3d.zoom = self.viewer_3d.zoom.clamp(0.5, 5.0);
                             }
                         });
+                        ui.horizontal(|ui| {
+                            ui.label("Point Cap:");
+                            ui.add_enabled(!self.auto_quality.enabled, egui::Slider::new(&mut self.viewer_3d.max_points, 500..=20_000)
+                                .nudge(ui, 100.0)
+                                .logarithmic(true)
+                                .text("Points"))
+                                .on_disabled_hover_text("Set by Auto Quality; turn it off to set the cap by hand");
+                        });
                         ui.label("Tip: Mousewheel to zoom");
                     }
                 }
 
+                let detail = match self.sim_type {
+                    SimulationType::TwoD => format!("Render scale: {:.2}", self.viewer_2d.scale),
+                    SimulationType::ThreeD => format!("Point cap: {}", readout::count(self.viewer_3d.max_points)),
+                };
+                self.auto_quality.ui(ui, &detail);
+
                 ui.separator();
 
                 egui::ScrollArea::vertical().show(ui, |ui| {
@@ -192,12 +1118,43 @@ impl eframe::App for ComplexSystemsApp {
                                         }
                                     }
                                 });
+                            self.settings_clipboard_ui(ui);
+
+                            if ui.button("🔄 Reset Simulation")
+                                .on_hover_text("Start over from a fresh initial state, keeping the settings")
+                                .clicked()
+                            {
+                                self.simulations_2d[self.sim_2d_index].reset();
+                                self.viewer_2d.needs_update = true;
+                            }
 
                             ui.separator();
 
                             if self.simulations_2d[self.sim_2d_index].ui_parameters(ui) {
                                 self.viewer_2d.needs_update = true;
                             }
+                            equations_ui(ui, self.simulations_2d[self.sim_2d_index].equations());
+
+                            self.viewer_2d.timeline_ui(ui, &mut self.simulations_2d[self.sim_2d_index]);
+
+                            ui.separator();
+
+                            self.viewer_2d.show_controls(ui, self.simulations_2d[self.sim_2d_index].name());
+
+                            let names: Vec<&str> = self.simulations_2d.iter().map(|s| s.name()).collect();
+                            if self.viewer_2d.compositing.ui(ui, &names, self.sim_2d_index) {
+                                self.viewer_2d.needs_update = true;
+                            }
+                            // Keep the layer's controls on screen while it is blended
+                            if let Some(index) = self.viewer_2d.compositing.active_layer(self.sim_2d_index) {
+                                ui.separator();
+                                ui.label(egui::RichText::new("Blend Layer").strong());
+                                ui.push_id("blend_layer", |ui| {
+                                    if self.simulations_2d[index].ui_parameters(ui) {
+                                        self.viewer_2d.needs_update = true;
+                                    }
+                                });
+                            }
                         }
                         SimulationType::ThreeD => {
                             egui::ComboBox::from_label("Select Simulation")
@@ -207,10 +1164,30 @@ impl eframe::App for ComplexSystemsApp {
                                         ui.selectable_value(&mut self.sim_3d_index, i, sim.name());
                                     }
                                 });
+                            self.settings_clipboard_ui(ui);
 
                             ui.separator();
 
                             self.simulations_3d[self.sim_3d_index].ui_parameters(ui);
+                            equations_ui(ui, self.simulations_3d[self.sim_3d_index].equations());
+
+                            ui.separator();
+
+                            self.viewer_3d.show_controls(ui);
+
+                            #[cfg(not(target_arch = "wasm32"))]
+                            if self.viewer_3d.png_export_ui(ui) {
+                                self.save_3d_png(ui.ctx());
+                            }
+                            #[cfg(not(target_arch = "wasm32"))]
+                            if self.viewer_3d.turntable_ui(ui) {
+                                self.save_turntable(ui.ctx());
+                            }
+
+                            let can_render = cfg!(not(target_arch = "wasm32"));
+                            if self.camera_path.ui(ui, &mut self.viewer_3d, can_render) {
+                                self.render_camera_path();
+                            }
                         }
                     }
                 });
@@ -226,17 +1203,60 @@ impl eframe::App for ComplexSystemsApp {
                 });
             });
 
+        self.record_automation();
+        #[cfg(not(target_arch = "wasm32"))]
+        self.save_palette_export(ctx);
+
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            self.viewer_2d.capture_size = self.video.capture_size();
+            self.viewer_3d.capture_size = self.video.capture_size();
+        }
+        self.drive_camera_path();
+
         egui::CentralPanel::default().show(ctx, |ui| {
             match self.sim_type {
                 SimulationType::TwoD => {
-                    self.viewer_2d.show(ui, &mut self.simulations_2d[self.sim_2d_index]);
+                    let layer = self.viewer_2d.compositing.active_layer(self.sim_2d_index);
+                    let (width, height) = self.viewer_2d.image_size();
+                    for index in std::iter::once(self.sim_2d_index).chain(layer) {
+                        if self.simulations_2d[index].update(self.clock.dt(), width, height) {
+                            self.viewer_2d.needs_update = true;
+                        }
+                    }
+                    let (simulation, layer) = main_and_layer(&mut self.simulations_2d, self.sim_2d_index, layer);
+                    self.viewer_2d.show(ui, simulation, layer);
                 }
                 SimulationType::ThreeD => {
-                    let dt = ui.input(|i| i.stable_dt);
-                    self.simulations_3d[self.sim_3d_index].step(dt);
-                    self.viewer_3d.show(ui, &self.simulations_3d[self.sim_3d_index]);
+                    // Skipped entirely while paused, since some sims advance a
+                    // fixed amount per step regardless of dt
+                    if self.clock.dt() > 0.0 {
+                        self.simulations_3d[self.sim_3d_index].step(self.clock.dt());
+                    }
+                    self.viewer_3d.show(ui, &mut self.simulations_3d[self.sim_3d_index]);
                 }
             }
         });
+
+        self.follow_plane_click(ctx);
+
+        if let Some(recording) = self.viewer_2d.gif.take_finished() {
+            self.save_gif(recording);
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        if self.video.is_recording() {
+            // Take both so a view switched away from can't leave a stale frame
+            let frames = (self.viewer_2d.take_capture(), self.viewer_3d.take_capture());
+            let frame = match self.sim_type {
+                SimulationType::TwoD => frames.0,
+                SimulationType::ThreeD => frames.1,
+            };
+            if let Some(frame) = frame {
+                self.video.push_frame(&frame);
+            }
+        }
+
+        self.toast_ui(ctx);
     }
 }
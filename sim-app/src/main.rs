@@ -23,6 +23,8 @@ fn main() -> eframe::Result {
             .with_inner_size([1280.0, 800.0])
             .with_position([200.0, 50.0])
             .with_title("Complex Systems Visualizer")
+            // Scene and preset files can be dropped onto the window
+            .with_drag_and_drop(true)
             .with_icon(std::sync::Arc::new(load_icon())),
         ..Default::default()
     };
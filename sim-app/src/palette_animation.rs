@@ -0,0 +1,68 @@
+//! Global palette cycling. The app advances one phase from the sim clock and
+//! hands it to 2D sims through `RenderCtx::palette_phase`, so every sim that
+//! colors through a `ColorScheme` cycles at the same speed and direction.
+
+use eframe::egui;
+use sim_core::nudge::Nudge;
+
+pub struct PaletteAnimation {
+    pub enabled: bool,
+    /// Palette lengths per second of sim time
+    pub speed: f32,
+    /// Cycle the palette the other way
+    pub reverse: bool,
+    /// Kept in 0..2, the period of `RenderCtx::cycle_palette`
+    phase: f32,
+}
+
+impl Default for PaletteAnimation {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            speed: 0.1,
+            reverse: false,
+            phase: 0.0,
+        }
+    }
+}
+
+impl PaletteAnimation {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Move the phase on by `dt` seconds of sim time, so pausing the clock
+    /// also freezes the colors
+    pub fn advance(&mut self, dt: f32) {
+        if !self.enabled {
+            return;
+        }
+        let direction = if self.reverse { -1.0 } else { 1.0 };
+        self.phase = (self.phase + direction * self.speed * dt).rem_euclid(2.0);
+    }
+
+    /// Offset to render with; 0 when disabled, which restores the sims'
+    /// own colors
+    pub fn phase(&self) -> f32 {
+        if self.enabled { self.phase } else { 0.0 }
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui) {
+        egui::CollapsingHeader::new("🌈 Palette Animation")
+            .show(ui, |ui| {
+                ui.checkbox(&mut self.enabled, "Cycle Palettes")
+                    .on_hover_text("Shift the colors of every 2D sim that uses a color scheme");
+
+                ui.add_enabled_ui(self.enabled, |ui| {
+                    ui.add(egui::Slider::new(&mut self.speed, 0.01..=2.0)
+                        .nudge(ui, 0.005)
+                        .logarithmic(true)
+                        .text("Speed (cycles/s)"));
+                    ui.checkbox(&mut self.reverse, "Reverse Direction");
+                    if ui.button("Reset Phase").clicked() {
+                        self.phase = 0.0;
+                    }
+                });
+            });
+    }
+}
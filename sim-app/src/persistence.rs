@@ -0,0 +1,146 @@
+//! Persistence canvas shared by the 2D and 3D viewers: each rendered frame
+//! is lightened onto a buffer that fades a little every frame, so anything
+//! that moves leaves a glowing trail whatever the sim. Freezing stops the
+//! fade and keeps new frames off the buffer, leaving the trails built so far
+//! as a fixed backdrop that the live frame is drawn over.
+
+use eframe::egui;
+use serde_json::{json, Value};
+use sim_core::nudge::Nudge;
+use sim_core::state::{read, read_clamped};
+use sim_core::Color;
+
+/// A pixel the canvas can read and write as 8-bit RGB
+pub trait Pixel {
+    fn rgb(&self) -> [u8; 3];
+    fn set_rgb(&mut self, rgb: [u8; 3]);
+}
+
+impl Pixel for Color {
+    fn rgb(&self) -> [u8; 3] {
+        [self.r, self.g, self.b]
+    }
+
+    fn set_rgb(&mut self, [r, g, b]: [u8; 3]) {
+        *self = Color::from_rgb(r, g, b);
+    }
+}
+
+impl Pixel for egui::Color32 {
+    fn rgb(&self) -> [u8; 3] {
+        [self.r(), self.g(), self.b()]
+    }
+
+    fn set_rgb(&mut self, [r, g, b]: [u8; 3]) {
+        *self = egui::Color32::from_rgb(r, g, b);
+    }
+}
+
+pub struct Persistence {
+    pub enabled: bool,
+    /// Fraction of the canvas kept each frame
+    pub retention: f32,
+    /// Stop fading and recording; the live frame is drawn over the canvas
+    pub frozen: bool,
+    /// Accumulated trails in 0..255, kept in floats so slow fades still
+    /// reach black instead of stalling a step above it
+    canvas: Vec<[f32; 3]>,
+    /// Size and sim the canvas was built for
+    size: (usize, usize),
+    source: String,
+}
+
+impl Default for Persistence {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            retention: 0.92,
+            frozen: false,
+            canvas: Vec::new(),
+            size: (0, 0),
+            source: String::new(),
+        }
+    }
+}
+
+impl Persistence {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// For scene files; the canvas itself and the freeze aren't saved
+    pub fn state(&self) -> Value {
+        json!({ "enabled": self.enabled, "retention": self.retention })
+    }
+
+    pub fn load_state(&mut self, state: &Value) {
+        read(state, "enabled", &mut self.enabled);
+        read_clamped(state, "retention", &mut self.retention, 0.5..=0.995);
+    }
+
+    /// Drop the accumulated trails
+    pub fn clear(&mut self) {
+        self.canvas.clear();
+    }
+
+    /// Composite the frame `pixels` of `source` with the canvas in place.
+    /// A new size or sim starts the canvas over from this frame.
+    pub fn apply<P: Pixel>(&mut self, pixels: &mut [P], width: usize, height: usize, source: &str) {
+        if !self.enabled {
+            self.canvas.clear();
+            return;
+        }
+        if self.canvas.len() != pixels.len() || self.size != (width, height) || self.source != source {
+            self.size = (width, height);
+            self.source = source.to_string();
+            self.canvas = pixels.iter()
+                .map(|p| p.rgb().map(|c| c as f32))
+                .collect();
+            return;
+        }
+
+        let retention = self.retention.clamp(0.0, 1.0);
+        for (trail, pixel) in self.canvas.iter_mut().zip(pixels.iter_mut()) {
+            let rgb = pixel.rgb();
+            let shown = std::array::from_fn(|i| {
+                let live = rgb[i] as f32;
+                if !self.frozen {
+                    trail[i] = (trail[i] * retention).max(live);
+                }
+                trail[i].max(live).round() as u8
+            });
+            pixel.set_rgb(shown);
+        }
+    }
+
+    /// Settings; returns true if the view needs a new frame
+    pub fn ui(&mut self, ui: &mut egui::Ui) -> bool {
+        let mut changed = false;
+
+        egui::CollapsingHeader::new("🌠 Persistence")
+            .show(ui, |ui| {
+                changed |= ui.checkbox(&mut self.enabled, "Persistent Trails")
+                    .on_hover_text("Keep a fading copy of past frames under the current one, so moving things leave trails")
+                    .changed();
+
+                ui.add_enabled_ui(self.enabled, |ui| {
+                    changed |= ui.add(egui::Slider::new(&mut self.retention, 0.5..=0.995)
+                        .nudge(ui, 0.005)
+                        .text("Persistence"))
+                        .on_hover_text("How much of the trails is kept each frame")
+                        .changed();
+
+                    changed |= ui.checkbox(&mut self.frozen, "❄ Freeze Trails")
+                        .on_hover_text("Hold the trails as they are and draw the moving sim over them; unfreeze to let them fade again")
+                        .changed();
+
+                    if ui.button("Clear Trails").clicked() {
+                        self.clear();
+                        changed = true;
+                    }
+                });
+            });
+
+        changed
+    }
+}
@@ -0,0 +1,341 @@
+//! Coordinate axes, grid and tick labels over 2D sims that report a
+//! `PlaneView`. The overlay is normally painted on screen on top of the
+//! render, so exports stay clean; with "Bake Into Image" it is drawn into the
+//! render itself and shows up in video and ASCII exports too.
+
+use eframe::egui;
+use sim_core::nudge::Nudge;
+use sim_core::{Color, PlaneView};
+
+/// Aim for about this many image pixels between grid lines
+const TARGET_SPACING: f64 = 110.0;
+
+/// Half-length of the tick marks across each axis, in image pixels
+const TICK_HALF: f32 = 4.0;
+
+/// Space kept between a label and its axis, and between neighbouring labels
+const LABEL_GAP: f32 = 6.0;
+
+/// Pixel scale of the built-in 3x5 font when baking labels into the image
+const BAKED_FONT_SCALE: usize = 2;
+
+const SCREEN_FONT_SIZE: f32 = 12.0;
+
+pub struct PlaneOverlay {
+    pub enabled: bool,
+    pub grid: bool,
+    pub axes: bool,
+    pub labels: bool,
+    pub opacity: f32,
+    /// Draw into the rendered image rather than over it on screen
+    pub bake: bool,
+}
+
+impl Default for PlaneOverlay {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            grid: true,
+            axes: true,
+            labels: true,
+            opacity: 0.7,
+            bake: false,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Layer {
+    Grid,
+    Axis,
+}
+
+struct Label {
+    /// Top-left corner, in image pixels
+    pos: [f32; 2],
+    text: String,
+}
+
+/// Everything to draw, in image pixel coordinates
+#[derive(Default)]
+struct Shapes {
+    lines: Vec<([f32; 2], [f32; 2], Layer)>,
+    labels: Vec<Label>,
+}
+
+impl PlaneOverlay {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Settings; returns true if any changed
+    pub fn ui(&mut self, ui: &mut egui::Ui, available: bool) -> bool {
+        let mut changed = false;
+
+        egui::CollapsingHeader::new("📐 Axes & Grid")
+            .show(ui, |ui| {
+                if !available {
+                    ui.label("This simulation has no coordinate plane");
+                }
+
+                changed |= ui.checkbox(&mut self.enabled, "Show Overlay").changed();
+                ui.add_enabled_ui(self.enabled, |ui| {
+                    ui.horizontal(|ui| {
+                        changed |= ui.checkbox(&mut self.grid, "Grid").changed();
+                        changed |= ui.checkbox(&mut self.axes, "Axes").changed();
+                        changed |= ui.checkbox(&mut self.labels, "Labels").changed();
+                    });
+                    changed |= ui.add(egui::Slider::new(&mut self.opacity, 0.1..=1.0)
+                        .nudge(ui, 0.01)
+                        .text("Opacity")).changed();
+                    changed |= ui.checkbox(&mut self.bake, "Bake Into Image")
+                        .on_hover_text("Draw the overlay into the render so video and ASCII exports include it")
+                        .changed();
+                });
+            });
+
+        changed
+    }
+
+    /// Whether the overlay goes into the image rather than on screen
+    pub fn baked(&self) -> bool {
+        self.enabled && self.bake
+    }
+
+    /// Paint over the image shown in `rect`, which displays a
+    /// `width`x`height` render
+    pub fn paint(&self, painter: &egui::Painter, rect: egui::Rect, view: &PlaneView, width: usize, height: usize) {
+        if !self.enabled || width == 0 || height == 0 {
+            return;
+        }
+
+        let to_screen = rect.width() / width as f32;
+        let font = egui::FontId::monospace(SCREEN_FONT_SIZE);
+        let char_width = painter.ctx().fonts(|f| f.glyph_width(&font, '0'));
+        let char_size = [char_width / to_screen, SCREEN_FONT_SIZE / to_screen];
+
+        let shapes = self.layout(view, width, height, char_size);
+        let screen = |p: [f32; 2]| rect.min + egui::vec2(p[0], p[1]) * to_screen;
+
+        for (a, b, layer) in &shapes.lines {
+            let color = egui::Color32::from_white_alpha(self.alpha(*layer));
+            painter.line_segment([screen(*a), screen(*b)], egui::Stroke::new(1.0, color));
+        }
+        let label_color = egui::Color32::from_white_alpha((self.opacity * 255.0) as u8);
+        for label in &shapes.labels {
+            painter.text(screen(label.pos), egui::Align2::LEFT_TOP, &label.text, font.clone(), label_color);
+        }
+    }
+
+    /// Draw into a `width`x`height` image
+    pub fn bake_into(&self, pixels: &mut [Color], view: &PlaneView, width: usize, height: usize) {
+        if !self.enabled || width == 0 || height == 0 || pixels.len() < width * height {
+            return;
+        }
+
+        let advance = (GLYPH_WIDTH + 1) * BAKED_FONT_SCALE;
+        let char_size = [advance as f32, (GLYPH_HEIGHT * BAKED_FONT_SCALE) as f32];
+        let shapes = self.layout(view, width, height, char_size);
+
+        for (a, b, layer) in &shapes.lines {
+            let alpha = self.alpha(*layer) as f32 / 255.0;
+            // Every line is horizontal or vertical
+            let (x0, x1) = (a[0].min(b[0]).round() as i64, a[0].max(b[0]).round() as i64);
+            let (y0, y1) = (a[1].min(b[1]).round() as i64, a[1].max(b[1]).round() as i64);
+            for y in y0.max(0)..=y1.min(height as i64 - 1) {
+                for x in x0.max(0)..=x1.min(width as i64 - 1) {
+                    blend(&mut pixels[y as usize * width + x as usize], alpha);
+                }
+            }
+        }
+
+        for label in &shapes.labels {
+            let mut x = label.pos[0].round() as i64;
+            let y = label.pos[1].round() as i64;
+            for c in label.text.chars() {
+                draw_glyph(pixels, width, height, x, y, c, self.opacity);
+                x += advance as i64;
+            }
+        }
+    }
+
+    fn alpha(&self, layer: Layer) -> u8 {
+        let strength = match layer {
+            Layer::Grid => 0.3,
+            Layer::Axis => 0.9,
+        };
+        (self.opacity * strength * 255.0) as u8
+    }
+
+    /// Grid lines, axes, ticks and labels for the view. Ticks sit on whole
+    /// multiples of a round step (1, 2 or 5 times a power of ten) chosen
+    /// from the zoom; a label that would overlap the previous one on its
+    /// axis is left out.
+    fn layout(&self, view: &PlaneView, width: usize, height: usize, char_size: [f32; 2]) -> Shapes {
+        let mut shapes = Shapes::default();
+        if !(view.units_per_pixel > 0.0 && view.units_per_pixel.is_finite()) {
+            return shapes;
+        }
+
+        let (w, h) = (width as f32, height as f32);
+        let step = nice_step(TARGET_SPACING * view.units_per_pixel);
+        let (left, top) = view.to_plane(0.0, 0.0, width, height);
+        let (right, bottom) = view.to_plane(width as f64, height as f64, width, height);
+        let (origin_x, origin_y) = view.to_pixel(0.0, 0.0, width, height);
+        let (origin_x, origin_y) = (origin_x as f32, origin_y as f32);
+
+        let ticks = |lo: f64, hi: f64| ((lo / step).ceil() as i64)..=((hi / step).floor() as i64);
+        let x_ticks = ticks(left, right);
+        let y_ticks = ticks(top, bottom);
+        // A zoomed-out view of a tiny step would mean millions of lines
+        if x_ticks.clone().count() > width || y_ticks.clone().count() > height {
+            return shapes;
+        }
+
+        let pixel_x = |k: i64| view.to_pixel(k as f64 * step, 0.0, width, height).0 as f32;
+        let pixel_y = |k: i64| view.to_pixel(0.0, k as f64 * step, width, height).1 as f32;
+
+        if self.grid {
+            for k in x_ticks.clone() {
+                let x = pixel_x(k);
+                shapes.lines.push(([x, 0.0], [x, h], Layer::Grid));
+            }
+            for k in y_ticks.clone() {
+                let y = pixel_y(k);
+                shapes.lines.push(([0.0, y], [w, y], Layer::Grid));
+            }
+        }
+
+        if self.axes {
+            if (0.0..h).contains(&origin_y) {
+                shapes.lines.push(([0.0, origin_y], [w, origin_y], Layer::Axis));
+                for k in x_ticks.clone() {
+                    let x = pixel_x(k);
+                    shapes.lines.push(([x, origin_y - TICK_HALF], [x, origin_y + TICK_HALF], Layer::Axis));
+                }
+            }
+            if (0.0..w).contains(&origin_x) {
+                shapes.lines.push(([origin_x, 0.0], [origin_x, h], Layer::Axis));
+                for k in y_ticks.clone() {
+                    let y = pixel_y(k);
+                    shapes.lines.push(([origin_x - TICK_HALF, y], [origin_x + TICK_HALF, y], Layer::Axis));
+                }
+            }
+        }
+
+        if self.labels {
+            let [char_width, char_height] = char_size;
+
+            // Horizontal axis values below the axis, or along the nearest
+            // edge when it's out of view
+            let label_y = (origin_y + TICK_HALF + 2.0).clamp(2.0, (h - char_height - 2.0).max(2.0));
+            let mut last_right = f32::MIN;
+            for k in x_ticks {
+                let text = format_tick(k, step);
+                let text_width = text.chars().count() as f32 * char_width;
+                let x = pixel_x(k) - text_width / 2.0;
+                if x < last_right + LABEL_GAP || x < 0.0 || x + text_width > w {
+                    continue;
+                }
+                last_right = x + text_width;
+                shapes.labels.push(Label { pos: [x, label_y], text });
+            }
+
+            // Vertical axis values to the right of the axis; 0 is already
+            // labeled on the horizontal axis
+            let suffix = if view.complex { "i" } else { "" };
+            let texts: Vec<(i64, String)> = y_ticks
+                .filter(|&k| k != 0)
+                .map(|k| (k, format!("{}{suffix}", format_tick(k, step))))
+                .collect();
+            let widest = texts.iter().map(|(_, t)| t.chars().count()).max().unwrap_or(0) as f32 * char_width;
+            let label_x = (origin_x + TICK_HALF + 2.0).clamp(2.0, (w - widest - 2.0).max(2.0));
+            let mut last_bottom = f32::MIN;
+            for (k, text) in texts {
+                let y = pixel_y(k) - char_height / 2.0;
+                if y < last_bottom + LABEL_GAP / 2.0 || y < 0.0 || y + char_height > h {
+                    continue;
+                }
+                // Keep clear of the horizontal axis labels
+                if (y - label_y).abs() < char_height + LABEL_GAP / 2.0 {
+                    continue;
+                }
+                last_bottom = y + char_height;
+                shapes.labels.push(Label { pos: [label_x, y], text });
+            }
+        }
+
+        shapes
+    }
+}
+
+/// Smallest of 1, 2 or 5 times a power of ten that is at least `raw`
+fn nice_step(raw: f64) -> f64 {
+    let magnitude = 10f64.powf(raw.log10().floor());
+    [1.0, 2.0, 5.0]
+        .into_iter()
+        .map(|m| m * magnitude)
+        .find(|&step| step >= raw)
+        .unwrap_or(10.0 * magnitude)
+}
+
+/// The value of tick `k` with just enough decimals to tell neighbouring ticks
+/// apart. Computed from `k` rather than accumulated, so it stays round.
+fn format_tick(k: i64, step: f64) -> String {
+    if k == 0 {
+        return "0".to_string();
+    }
+    let decimals = (-step.log10().floor()).max(0.0) as usize;
+    format!("{:.*}", decimals, k as f64 * step)
+}
+
+/// Lighten toward white
+fn blend(c: &mut Color, alpha: f32) {
+    *c = Color::lerp(*c, Color::WHITE, alpha);
+}
+
+const GLYPH_WIDTH: usize = 3;
+const GLYPH_HEIGHT: usize = 5;
+
+/// 3x5 bitmap for the characters tick labels use; one row per byte, high bit
+/// on the left
+fn glyph(c: char) -> Option<[u8; GLYPH_HEIGHT]> {
+    Some(match c {
+        '0' => [0b111, 0b101, 0b101, 0b101, 0b111],
+        '1' => [0b010, 0b110, 0b010, 0b010, 0b111],
+        '2' => [0b111, 0b001, 0b111, 0b100, 0b111],
+        '3' => [0b111, 0b001, 0b111, 0b001, 0b111],
+        '4' => [0b101, 0b101, 0b111, 0b001, 0b001],
+        '5' => [0b111, 0b100, 0b111, 0b001, 0b111],
+        '6' => [0b111, 0b100, 0b111, 0b101, 0b111],
+        '7' => [0b111, 0b001, 0b001, 0b001, 0b001],
+        '8' => [0b111, 0b101, 0b111, 0b101, 0b111],
+        '9' => [0b111, 0b101, 0b111, 0b001, 0b111],
+        '-' => [0b000, 0b000, 0b111, 0b000, 0b000],
+        '.' => [0b000, 0b000, 0b000, 0b000, 0b010],
+        'i' => [0b010, 0b000, 0b010, 0b010, 0b010],
+        _ => return None,
+    })
+}
+
+fn draw_glyph(pixels: &mut [Color], width: usize, height: usize, x: i64, y: i64, c: char, alpha: f32) {
+    let Some(rows) = glyph(c) else {
+        return;
+    };
+    for (row, bits) in rows.iter().enumerate() {
+        for col in 0..GLYPH_WIDTH {
+            if bits & (0b100 >> col) == 0 {
+                continue;
+            }
+            for dy in 0..BAKED_FONT_SCALE {
+                for dx in 0..BAKED_FONT_SCALE {
+                    let px = x + (col * BAKED_FONT_SCALE + dx) as i64;
+                    let py = y + (row * BAKED_FONT_SCALE + dy) as i64;
+                    if px >= 0 && py >= 0 && (px as usize) < width && (py as usize) < height {
+                        blend(&mut pixels[py as usize * width + px as usize], alpha);
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,259 @@
+//! Warning before slow 2D renders. Sims whose settings can make a frame take
+//! seconds report a `render_cost`; the budget turns it into an estimate with
+//! a seconds-per-iteration rate measured on recent renders, and asks before
+//! going ahead with anything over the threshold. Natively the render can run
+//! on a worker thread instead, so the window doesn't freeze while it works.
+
+use eframe::egui;
+use sim_core::nudge::Nudge;
+use sim_core::readout;
+
+#[cfg(not(target_arch = "wasm32"))]
+use serde_json::Value;
+#[cfg(not(target_arch = "wasm32"))]
+use sim_core::{registry, Color, RenderCtx, Simulation2D, Simulation2DBox};
+#[cfg(not(target_arch = "wasm32"))]
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+
+/// Seconds per unit of `render_cost` until a render has been timed; about
+/// what a release build of Mandelbrot does across a few cores
+const DEFAULT_SECONDS_PER_UNIT: f64 = 4e-9;
+
+/// Weight of each new timing in the rate average
+const SMOOTHING: f64 = 0.3;
+
+/// Renders quicker than this are mostly coloring and texture upload, which
+/// says little about the rate, so they aren't timed
+const MIN_TIMED_SECONDS: f64 = 0.02;
+
+/// Answer to the slow-render question
+#[derive(Clone, Copy, PartialEq)]
+pub enum Choice {
+    Render,
+    Background,
+    Cancel,
+}
+
+/// What to do with a render of a given cost
+#[derive(Clone, Copy, PartialEq)]
+pub enum Verdict {
+    /// Render now, on this thread
+    Render,
+    /// Render on a worker thread
+    Background,
+    /// Don't render; wait for an answer or for cheaper settings
+    Hold,
+}
+
+pub struct RenderBudget {
+    /// Ask before renders estimated to take longer than this, in seconds
+    pub threshold: f32,
+    pub warn: bool,
+    /// Measured seconds per unit of `render_cost`
+    seconds_per_unit: f64,
+    /// Cost the user agreed to and whether it runs in the background.
+    /// Renders up to it go ahead without asking again.
+    approved: f64,
+    background: bool,
+    /// Cost and estimate of the render waiting for an answer
+    pending: Option<(f64, f32)>,
+    /// Cost the user declined; held without asking again until it changes
+    declined: Option<f64>,
+    /// "Don't warn again" box in the question
+    stop_warning: bool,
+}
+
+impl Default for RenderBudget {
+    fn default() -> Self {
+        Self {
+            threshold: 3.0,
+            warn: true,
+            seconds_per_unit: DEFAULT_SECONDS_PER_UNIT,
+            approved: 0.0,
+            background: false,
+            pending: None,
+            declined: None,
+            stop_warning: false,
+        }
+    }
+}
+
+impl RenderBudget {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Estimated seconds for a render of `cost`
+    pub fn estimate(&self, cost: f64) -> f32 {
+        (cost * self.seconds_per_unit) as f32
+    }
+
+    /// Decide how to go ahead with a render of `cost`. Over the threshold,
+    /// and more than the user already agreed to, the render is held and the
+    /// question comes up in `dialog`.
+    pub fn check(&mut self, cost: f64) -> Verdict {
+        let estimate = self.estimate(cost);
+        if !self.warn || estimate <= self.threshold {
+            self.pending = None;
+            self.declined = None;
+            return Verdict::Render;
+        }
+        if cost <= self.approved {
+            return if self.background { Verdict::Background } else { Verdict::Render };
+        }
+        if self.declined != Some(cost) {
+            self.declined = None;
+            self.pending = Some((cost, estimate));
+        }
+        Verdict::Hold
+    }
+
+    /// Fold the time a render of `cost` took into the rate
+    pub fn record(&mut self, cost: f64, seconds: f64) {
+        if cost <= 0.0 || seconds < MIN_TIMED_SECONDS {
+            return;
+        }
+        let rate = seconds / cost;
+        self.seconds_per_unit += (rate - self.seconds_per_unit) * SMOOTHING;
+    }
+
+    /// Give up on background rendering, e.g. after the worker failed
+    pub fn render_in_foreground(&mut self) {
+        self.background = false;
+    }
+
+    /// The question for a held render, if one is waiting. Returns the answer
+    /// on the frame it is given.
+    pub fn dialog(&mut self, ctx: &egui::Context) -> Option<Choice> {
+        let (cost, estimate) = self.pending?;
+        let mut choice = None;
+
+        egui::Window::new("⏳ Slow Render")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .show(ctx, |ui| {
+                ui.label(format!("This render may take about {}.", readout::seconds(estimate, 0)));
+                ui.label(egui::RichText::new("Lower the iterations or render scale to make it faster.").weak());
+                ui.checkbox(&mut self.stop_warning, "Don't warn again");
+                ui.horizontal(|ui| {
+                    if ui.button("Render").clicked() {
+                        choice = Some(Choice::Render);
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    if ui.button("Render in Background")
+                        .on_hover_text("Keep the window responsive and show the result when it's done")
+                        .clicked() {
+                        choice = Some(Choice::Background);
+                    }
+                    if ui.button("Cancel")
+                        .on_hover_text("Go back to the last rendered settings")
+                        .clicked() {
+                        choice = Some(Choice::Cancel);
+                    }
+                });
+            });
+
+        match choice? {
+            Choice::Cancel => self.declined = Some(cost),
+            answer => {
+                self.approved = cost;
+                self.background = answer == Choice::Background;
+                self.warn = !self.stop_warning;
+            }
+        }
+        self.pending = None;
+        self.stop_warning = false;
+        choice
+    }
+
+    /// Toggle, threshold and the measured rate
+    pub fn ui(&mut self, ui: &mut egui::Ui) {
+        egui::CollapsingHeader::new("⏳ Slow Render Warning")
+            .show(ui, |ui| {
+                ui.checkbox(&mut self.warn, "Ask Before Slow Renders")
+                    .on_hover_text("Estimate the render time of expensive settings, such as high iteration counts, and ask before a long render");
+                ui.add_enabled_ui(self.warn, |ui| {
+                    ui.add(egui::Slider::new(&mut self.threshold, 0.5..=60.0)
+                        .nudge(ui, 0.5)
+                        .logarithmic(true)
+                        .suffix(" s")
+                        .text("Threshold"));
+                });
+                ui.label(format!("Measured speed: {} iterations/s", readout::count((1.0 / self.seconds_per_unit) as u64)));
+            });
+    }
+}
+
+/// Outcome of a background render
+#[cfg(not(target_arch = "wasm32"))]
+pub enum Progress {
+    Running,
+    /// The worker's sim instance, only for handing its iteration cache back,
+    /// the sim's pixels and the blend layer's, and how long it took
+    Done {
+        sim: Simulation2DBox,
+        colors: Vec<Color>,
+        layer: Option<Vec<Color>>,
+        seconds: f64,
+    },
+    Failed,
+}
+
+/// A render running on a worker thread. The worker renders its own copy of
+/// the sim, built from the registry and the sim's saved state, so the app
+/// keeps full use of the original meanwhile.
+#[cfg(not(target_arch = "wasm32"))]
+pub struct BackgroundRender {
+    pub ctx: RenderCtx,
+    /// Cost and estimate it started with
+    pub cost: f64,
+    pub estimate: f32,
+    started: std::time::Instant,
+    receiver: Receiver<Progress>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl BackgroundRender {
+    pub fn start(sim: &dyn Simulation2D, layer: Option<&dyn Simulation2D>, ctx: RenderCtx, cost: f64, estimate: f32) -> Self {
+        let main = (sim.name().to_string(), sim.save_state());
+        let layer = layer.map(|layer| (layer.name().to_string(), layer.save_state()));
+        let (sender, receiver) = mpsc::channel();
+
+        std::thread::spawn(move || {
+            let started = std::time::Instant::now();
+            let progress = match instance(&main) {
+                Some(sim) => {
+                    let colors = sim.compute_ctx(&ctx);
+                    let layer = layer.as_ref().and_then(instance).map(|layer| layer.compute_ctx(&ctx));
+                    Progress::Done { sim, colors, layer, seconds: started.elapsed().as_secs_f64() }
+                }
+                None => Progress::Failed,
+            };
+            let _ = sender.send(progress);
+        });
+
+        Self { ctx, cost, estimate, started: std::time::Instant::now(), receiver }
+    }
+
+    pub fn elapsed(&self) -> f32 {
+        self.started.elapsed().as_secs_f32()
+    }
+
+    pub fn poll(&self) -> Progress {
+        match self.receiver.try_recv() {
+            Ok(progress) => progress,
+            Err(TryRecvError::Empty) => Progress::Running,
+            // The worker panicked
+            Err(TryRecvError::Disconnected) => Progress::Failed,
+        }
+    }
+}
+
+/// A fresh instance of the named sim with `state` loaded
+#[cfg(not(target_arch = "wasm32"))]
+fn instance((name, state): &(String, Value)) -> Option<Simulation2DBox> {
+    let mut sim = registry::simulation_2d(name)?;
+    sim.load_state(state);
+    Some(sim)
+}
@@ -0,0 +1,70 @@
+//! Scene files: the active simulation, its parameters and the viewer state as
+//! JSON, so a complete view can be shared and reproduced.
+
+use crate::automation::AutomationTrack;
+use crate::SimulationType;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Bumped when the layout changes in a way older readers can't handle
+pub const SCENE_VERSION: u32 = 1;
+
+/// Prefix of the URL fragment holding an encoded scene (`#scene=...`)
+pub const FRAGMENT_PREFIX: &str = "scene=";
+
+/// Largest decompressed scene accepted from a URL, so a crafted link can't
+/// expand into something huge
+const MAX_FRAGMENT_JSON: usize = 1 << 20;
+
+#[derive(Serialize, Deserialize)]
+pub struct Scene {
+    pub version: u32,
+    pub sim_type: SimulationType,
+    /// Looked up by `name()` in the app's simulation list on load
+    pub simulation: String,
+    /// The sim's `save_state`
+    #[serde(default)]
+    pub params: Value,
+    /// The active viewer's `view_state`
+    #[serde(default)]
+    pub view: Value,
+    /// Recorded parameter automation, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub automation: Option<AutomationTrack>,
+}
+
+impl Scene {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_default()
+    }
+
+    pub fn from_json(text: &str) -> Result<Self, String> {
+        let scene: Scene = serde_json::from_str(text).map_err(|e| format!("Invalid scene file: {e}"))?;
+        if scene.version > SCENE_VERSION {
+            return Err(format!("Scene version {} is newer than supported ({SCENE_VERSION})", scene.version));
+        }
+        Ok(scene)
+    }
+
+    /// URL fragment (without the `#`) carrying this scene: compact JSON,
+    /// deflated, then URL-safe base64 without padding
+    pub fn to_fragment(&self) -> String {
+        let json = serde_json::to_vec(self).unwrap_or_default();
+        let compressed = miniz_oxide::deflate::compress_to_vec(&json, 9);
+        format!("{FRAGMENT_PREFIX}{}", URL_SAFE_NO_PAD.encode(compressed))
+    }
+
+    /// Parse a fragment written by `to_fragment`; a leading `#` is ignored
+    pub fn from_fragment(fragment: &str) -> Result<Self, String> {
+        let encoded = fragment.trim_start_matches('#')
+            .strip_prefix(FRAGMENT_PREFIX)
+            .ok_or("Link has no scene")?;
+        let compressed = URL_SAFE_NO_PAD.decode(encoded).map_err(|e| format!("Invalid scene link: {e}"))?;
+        let json = miniz_oxide::inflate::decompress_to_vec_with_limit(&compressed, MAX_FRAGMENT_JSON)
+            .map_err(|_| "Invalid scene link: bad compressed data".to_string())?;
+        let text = String::from_utf8(json).map_err(|_| "Invalid scene link: not text".to_string())?;
+        Self::from_json(&text)
+    }
+}
@@ -0,0 +1,127 @@
+//! Kiosk-style playlist that cycles through simulations on a timer.
+
+use crate::SimulationType;
+
+/// Seconds after starting during which input is ignored, so the click that
+/// started the screensaver (and the hand leaving the mouse) doesn't stop it
+const INPUT_GRACE: f32 = 1.0;
+
+/// One stop in the playlist
+#[derive(Clone)]
+pub struct PlaylistEntry {
+    pub sim_type: SimulationType,
+    /// Matched against `Simulation2D::name` / `Simulation3D::name`
+    pub sim_name: String,
+    /// Passed to `load_preset`; `None` keeps the sim's current parameters
+    pub preset: Option<String>,
+    /// Seconds to stay on this entry
+    pub duration: f32,
+}
+
+impl PlaylistEntry {
+    fn new(sim_type: SimulationType, sim_name: &str, preset: Option<&str>, duration: f32) -> Self {
+        Self {
+            sim_type,
+            sim_name: sim_name.to_string(),
+            preset: preset.map(str::to_string),
+            duration,
+        }
+    }
+}
+
+pub struct Screensaver {
+    pub playlist: Vec<PlaylistEntry>,
+    /// Multiplies every entry's duration
+    pub duration_scale: f32,
+    active: bool,
+    index: usize,
+    elapsed: f32,
+    since_start: f32,
+}
+
+impl Default for Screensaver {
+    fn default() -> Self {
+        use SimulationType::{ThreeD, TwoD};
+
+        Self {
+            playlist: vec![
+                PlaylistEntry::new(TwoD, "Mandelbrot Set", Some("Seahorse Valley"), 20.0),
+                PlaylistEntry::new(ThreeD, "Lorenz Attractor", Some("Classic Butterfly (ρ=28)"), 20.0),
+                PlaylistEntry::new(TwoD, "Reaction-Diffusion", Some("Coral Growth"), 30.0),
+                PlaylistEntry::new(TwoD, "Julia Set", Some("Douady's Rabbit"), 20.0),
+                PlaylistEntry::new(ThreeD, "Galaxy Spiral", None, 20.0),
+                PlaylistEntry::new(TwoD, "Reaction-Diffusion", Some("Maze"), 30.0),
+                PlaylistEntry::new(ThreeD, "Lorenz Attractor", Some("Torus Knot (ρ=99.96)"), 20.0),
+                PlaylistEntry::new(TwoD, "Julia Set", Some("San Marco Dragon"), 20.0),
+            ],
+            duration_scale: 1.0,
+            active: false,
+            index: 0,
+            elapsed: 0.0,
+            since_start: 0.0,
+        }
+    }
+}
+
+impl Screensaver {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.active
+    }
+
+    /// Start from the top of the playlist. Returns the first entry to show.
+    pub fn start(&mut self) -> Option<&PlaylistEntry> {
+        self.active = !self.playlist.is_empty();
+        self.index = 0;
+        self.elapsed = 0.0;
+        self.since_start = 0.0;
+        self.current()
+    }
+
+    pub fn stop(&mut self) {
+        self.active = false;
+    }
+
+    pub fn current(&self) -> Option<&PlaylistEntry> {
+        if self.active {
+            self.playlist.get(self.index)
+        } else {
+            None
+        }
+    }
+
+    /// Whether input should stop the screensaver yet
+    pub fn accepts_input(&self) -> bool {
+        self.active && self.since_start >= INPUT_GRACE
+    }
+
+    /// Advance the timer. Returns the next entry when it's time to switch,
+    /// wrapping to the start of the playlist forever.
+    pub fn tick(&mut self, dt: f32) -> Option<&PlaylistEntry> {
+        if !self.active || self.playlist.is_empty() {
+            return None;
+        }
+
+        self.since_start += dt;
+        self.elapsed += dt;
+
+        let duration = self.playlist[self.index].duration * self.duration_scale;
+        if self.elapsed < duration.max(1.0) {
+            return None;
+        }
+
+        self.elapsed = 0.0;
+        self.index = (self.index + 1) % self.playlist.len();
+        self.current()
+    }
+
+    /// Seconds left on the current entry
+    pub fn remaining(&self) -> f32 {
+        self.current()
+            .map(|entry| (entry.duration * self.duration_scale).max(1.0) - self.elapsed)
+            .unwrap_or(0.0)
+    }
+}
@@ -0,0 +1,302 @@
+//! Fixed-length video export: rendered frames are piped as raw RGBA into an
+//! `ffmpeg` subprocess, which encodes them straight to disk, so a long
+//! recording never has to fit in memory. The output format follows the file
+//! extension: MP4 (and other video containers) get H.264, while `.gif` or an
+//! image sequence pattern like `frame_%04d.png` are left to ffmpeg.
+
+use eframe::egui;
+use sim_core::nudge::Nudge;
+use sim_core::readout;
+use std::io::Write;
+use std::process::{Child, ChildStdin, Command, Stdio};
+
+/// Whether an `ffmpeg` executable can be run from `PATH`
+pub fn ffmpeg_available() -> bool {
+    Command::new("ffmpeg")
+        .arg("-version")
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .map(|status| status.success())
+        .unwrap_or(false)
+}
+
+pub struct VideoSettings {
+    pub frames: u32,
+    pub fps: u32,
+    pub path: String,
+}
+
+impl Default for VideoSettings {
+    fn default() -> Self {
+        Self {
+            frames: 300,
+            fps: 30,
+            path: "capture.mp4".to_string(),
+        }
+    }
+}
+
+/// A running ffmpeg encode. Dropping it before `finish` kills ffmpeg, so an
+/// error or early exit never leaves the process behind.
+pub struct VideoRecorder {
+    child: Child,
+    stdin: Option<ChildStdin>,
+    width: usize,
+    height: usize,
+    fps: u32,
+    frames: u32,
+    written: u32,
+    path: String,
+}
+
+impl VideoRecorder {
+    /// Start ffmpeg for `settings.frames` frames of `width`x`height`. The
+    /// size is rounded down to even numbers (H.264 with 4:2:0 chroma needs
+    /// them) and stays fixed for the whole recording.
+    pub fn start(settings: &VideoSettings, frames: u32, width: usize, height: usize) -> Result<Self, String> {
+        let width = width & !1;
+        let height = height & !1;
+        if width == 0 || height == 0 {
+            return Err("Nothing to record yet".to_string());
+        }
+
+        let extension = std::path::Path::new(&settings.path)
+            .extension()
+            .map(|e| e.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        let codec: &[&str] = match extension.as_str() {
+            "mp4" | "mkv" | "mov" => &["-c:v", "libx264", "-pix_fmt", "yuv420p", "-crf", "18"],
+            _ => &[],
+        };
+
+        let mut child = Command::new("ffmpeg")
+            .args(["-y", "-loglevel", "error"])
+            .args(["-f", "rawvideo", "-pix_fmt", "rgba"])
+            .args(["-s", &format!("{width}x{height}")])
+            .args(["-r", &settings.fps.to_string()])
+            .args(["-i", "-"])
+            .args(codec)
+            .arg(&settings.path)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|e| format!("Could not start ffmpeg: {e}"))?;
+        let stdin = child.stdin.take();
+
+        Ok(Self {
+            child,
+            stdin,
+            width,
+            height,
+            fps: settings.fps.max(1),
+            frames: frames.max(1),
+            written: 0,
+            path: settings.path.clone(),
+        })
+    }
+
+    /// The locked frame size
+    pub fn size(&self) -> (usize, usize) {
+        (self.width, self.height)
+    }
+
+    /// Sim time per video frame
+    pub fn frame_dt(&self) -> f32 {
+        1.0 / self.fps as f32
+    }
+
+    /// Fraction of the frames written
+    pub fn progress(&self) -> f32 {
+        self.written as f32 / self.frames as f32
+    }
+
+    pub fn frames_written(&self) -> u32 {
+        self.written
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.written >= self.frames
+    }
+
+    /// Send one frame. An image of a different size (e.g. after switching
+    /// views) is cropped or padded with black to the locked size.
+    pub fn write_frame(&mut self, image: &egui::ColorImage) -> Result<(), String> {
+        if self.is_complete() {
+            return Ok(());
+        }
+
+        let [src_width, src_height] = image.size;
+        let mut bytes = vec![0u8; self.width * self.height * 4];
+        for y in 0..self.height.min(src_height) {
+            for x in 0..self.width.min(src_width) {
+                let c = image.pixels[y * src_width + x];
+                let i = (y * self.width + x) * 4;
+                bytes[i..i + 4].copy_from_slice(&[c.r(), c.g(), c.b(), 255]);
+            }
+        }
+
+        let stdin = self.stdin.as_mut().ok_or("ffmpeg input is closed")?;
+        stdin.write_all(&bytes).map_err(|e| format!("ffmpeg stopped accepting frames: {e}"))?;
+        self.written += 1;
+        Ok(())
+    }
+
+    /// Close the input and wait for ffmpeg to finish the file. Also used to
+    /// stop early, which keeps the frames recorded so far.
+    pub fn finish(mut self) -> Result<String, String> {
+        drop(self.stdin.take());
+        let status = self.child.wait().map_err(|e| format!("ffmpeg failed: {e}"))?;
+        if status.success() {
+            Ok(format!("Saved {} ({} frames)", self.path, self.written))
+        } else {
+            Err(format!("ffmpeg exited with {status}"))
+        }
+    }
+}
+
+impl Drop for VideoRecorder {
+    fn drop(&mut self) {
+        // Already waited on by `finish`; otherwise this is an abort
+        if self.stdin.is_some() {
+            let _ = self.child.kill();
+            let _ = self.child.wait();
+        }
+    }
+}
+
+/// Video export panel state: settings, the recording in progress and the
+/// last result
+#[derive(Default)]
+pub struct VideoExport {
+    pub settings: VideoSettings,
+    recorder: Option<VideoRecorder>,
+    /// Checked the first time the panel is shown
+    ffmpeg_found: Option<bool>,
+    status: Option<String>,
+}
+
+impl VideoExport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.recorder.is_some()
+    }
+
+    /// Fixed sim step while recording, so the video plays at real speed
+    /// however long each frame takes to render
+    pub fn frame_dt(&self) -> Option<f32> {
+        self.recorder.as_ref().map(VideoRecorder::frame_dt)
+    }
+
+    /// Locked render size while recording
+    pub fn capture_size(&self) -> Option<(usize, usize)> {
+        self.recorder.as_ref().map(VideoRecorder::size)
+    }
+
+    /// Frames written so far while recording
+    pub fn frames_written(&self) -> Option<u32> {
+        self.recorder.as_ref().map(VideoRecorder::frames_written)
+    }
+
+    /// Record `frames` frames at `canvas_size` with the current settings
+    pub fn start(&mut self, frames: u32, canvas_size: (usize, usize)) -> Result<(), String> {
+        let result = VideoRecorder::start(&self.settings, frames, canvas_size.0, canvas_size.1);
+        match result {
+            Ok(recorder) => {
+                self.recorder = Some(recorder);
+                self.status = None;
+                Ok(())
+            }
+            Err(e) => {
+                self.status = Some(e.clone());
+                Err(e)
+            }
+        }
+    }
+
+    /// Write a rendered frame, finishing the file once enough are in. A
+    /// failed write aborts the recording.
+    pub fn push_frame(&mut self, image: &egui::ColorImage) {
+        let Some(recorder) = &mut self.recorder else {
+            return;
+        };
+
+        if let Err(e) = recorder.write_frame(image) {
+            self.status = Some(e);
+            self.recorder = None;
+        } else if recorder.is_complete() {
+            self.stop();
+        }
+    }
+
+    /// End the recording, keeping what was captured
+    pub fn stop(&mut self) {
+        if let Some(recorder) = self.recorder.take() {
+            self.status = Some(match recorder.finish() {
+                Ok(message) | Err(message) => message,
+            });
+        }
+    }
+
+    /// Collapsible settings and start/stop controls. `canvas_size` is the
+    /// size the recording is locked to when it starts.
+    pub fn ui(&mut self, ui: &mut egui::Ui, canvas_size: (usize, usize)) {
+        egui::CollapsingHeader::new("🎬 Video Export")
+            .show(ui, |ui| {
+                let found = *self.ffmpeg_found.get_or_insert_with(ffmpeg_available);
+                if !found {
+                    ui.label("ffmpeg was not found on PATH; install it to export video");
+                    return;
+                }
+
+                if let Some(recorder) = &self.recorder {
+                    let (width, height) = recorder.size();
+                    ui.label(format!("Recording {width}x{height}: frame {} of {}",
+                        readout::count(recorder.frames_written()), readout::count(recorder.frames)));
+                    ui.add(egui::ProgressBar::new(recorder.progress()).show_percentage());
+                    if ui.button("⏹ Stop").clicked() {
+                        self.stop();
+                    }
+                    return;
+                }
+
+                ui.add(egui::Slider::new(&mut self.settings.frames, 30..=3600)
+                    .nudge(ui, 1.0)
+                    .logarithmic(true)
+                    .text("Frames"));
+                ui.add(egui::Slider::new(&mut self.settings.fps, 10..=60)
+                    .nudge(ui, 1.0)
+                    .text("FPS"));
+                ui.label(format!("Length: {} at {}x{}",
+                    readout::seconds(self.settings.frames as f32 / self.settings.fps as f32, 1),
+                    canvas_size.0 & !1, canvas_size.1 & !1));
+
+                ui.horizontal(|ui| {
+                    ui.label("Output:");
+                    ui.text_edit_singleline(&mut self.settings.path);
+                    if ui.button("…").clicked() {
+                        if let Some(path) = rfd::FileDialog::new()
+                            .add_filter("MP4 video", &["mp4"])
+                            .add_filter("Animated GIF", &["gif"])
+                            .set_file_name("capture.mp4")
+                            .save_file() {
+                            self.settings.path = path.display().to_string();
+                        }
+                    }
+                });
+
+                if ui.button("⏺ Start Recording").clicked() {
+                    // Failures are shown through `status`
+                    let _ = self.start(self.settings.frames, canvas_size);
+                }
+
+                if let Some(status) = &self.status {
+                    ui.label(status);
+                }
+            });
+    }
+}
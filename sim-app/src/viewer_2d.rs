@@ -1,11 +1,90 @@
+use crate::ascii_art::AsciiExport;
+use crate::color_vision::ColorVision;
+use crate::compositing::Compositing;
+use crate::gif_export::GifRecorder;
+use crate::isometric::IsometricView;
+use crate::persistence::Persistence;
+use crate::plane_overlay::PlaneOverlay;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::render_budget::{BackgroundRender, Progress};
+use crate::render_budget::{Choice, RenderBudget, Verdict};
 use eframe::egui;
-use sim_core::Simulation2D;
+use serde_json::{json, Value};
+use sim_core::clock::SimClock;
+use sim_core::color_ui::color_scheme_combo_labeled;
+use sim_core::nudge::Nudge;
+use sim_core::state::{read, read_clamped};
+use sim_core::tone_map::{tone_ui, ToneMap};
+use sim_core::{Color, ColorScheme, RenderCtx, Simulation2D};
+#[cfg(not(target_arch = "wasm32"))]
+use std::path::Path;
 
 pub struct Viewer2D {
     pub needs_update: bool,
+    /// Whether the last `show` rendered a new frame rather than reusing the
+    /// texture
+    rendered: bool,
     pub scale: f32,
     pub pan_x: f32,
     pub pan_y: f32,
+    pub kaleidoscope: bool,
+    pub kaleidoscope_segments: u32,
+    pub kaleidoscope_rotation: f32,
+    /// Show the image repeated 3×3 on screen, to check that it tiles.
+    /// Exports and recordings get the plain frame.
+    pub tiling_preview: bool,
+    /// Thin lines where the preview's copies meet
+    pub tiling_outline: bool,
+    pub heatmap: bool,
+    pub heatmap_scheme: ColorScheme,
+    pub heatmap_auto_range: bool,
+    pub heatmap_min: f32,
+    pub heatmap_max: f32,
+    /// Canvas color behind every sim; black leaves the render untouched
+    pub background: [u8; 3],
+    /// Passed to the sim as `RenderCtx::palette_phase`
+    pub palette_phase: f32,
+    /// Passed to the sim as `RenderCtx::exposure` and `tone_map`, for glow
+    /// and density renders
+    pub exposure: f32,
+    pub tone_map: ToneMap,
+    /// Whether the last shown sim exposed a scalar field
+    field_available: bool,
+    /// Sim the timeline range belongs to, the furthest step it has reached,
+    /// and its step count last frame (a drop means it was restarted)
+    timeline_sim: String,
+    timeline_end: u64,
+    timeline_last: u64,
+    /// Fixed render size while recording video, instead of the canvas size
+    pub capture_size: Option<(usize, usize)>,
+    /// Last frame rendered while `capture_size` is set
+    captured: Option<egui::ColorImage>,
+    /// Last rendered pixels, as shown, for exports
+    frame: Vec<Color>,
+    /// Context the last frame was rendered with, so exports match it
+    frame_ctx: RenderCtx,
+    pub ascii: AsciiExport,
+    pub gif: GifRecorder,
+    pub overlay: PlaneOverlay,
+    /// Whether the last shown sim reported a coordinate plane
+    overlay_available: bool,
+    /// Plane point of the last click on the view, until taken
+    plane_click: Option<(f64, f64)>,
+    pub isometric: IsometricView,
+    /// Blending of a second sim over the main one
+    pub compositing: Compositing,
+    pub persistence: Persistence,
+    /// Colorblindness simulation of the final image
+    pub color_vision: ColorVision,
+    /// Asks before renders that would take seconds
+    pub render_budget: RenderBudget,
+    /// `render_cost` of the last frame rendered on this thread, for timing
+    last_cost: Option<f64>,
+    /// Sim name, state and scale of the last costed render, restored when
+    /// the user cancels a slower one
+    accepted: Option<(String, Value, f32)>,
+    #[cfg(not(target_arch = "wasm32"))]
+    background_render: Option<BackgroundRender>,
     texture: Option<egui::TextureHandle>,
     width: usize,
     height: usize,
@@ -15,37 +94,570 @@ impl Viewer2D {
     pub fn new() -> Self {
         Self {
             needs_update: true,
+            rendered: false,
             scale: 1.0,
             pan_x: 0.0,
             pan_y: 0.0,
+            kaleidoscope: false,
+            kaleidoscope_segments: 6,
+            kaleidoscope_rotation: 0.0,
+            tiling_preview: false,
+            tiling_outline: false,
+            heatmap: false,
+            heatmap_scheme: ColorScheme::Viridis,
+            heatmap_auto_range: true,
+            heatmap_min: 0.0,
+            heatmap_max: 1.0,
+            background: [0, 0, 0],
+            palette_phase: 0.0,
+            exposure: 1.0,
+            tone_map: ToneMap::Clamp,
+            field_available: false,
+            timeline_sim: String::new(),
+            timeline_end: 0,
+            timeline_last: 0,
+            capture_size: None,
+            captured: None,
+            frame: Vec::new(),
+            frame_ctx: RenderCtx::new(800, 600),
+            ascii: AsciiExport::new(),
+            gif: GifRecorder::new(),
+            overlay: PlaneOverlay::new(),
+            overlay_available: false,
+            plane_click: None,
+            isometric: IsometricView::new(),
+            compositing: Compositing::new(),
+            persistence: Persistence::new(),
+            color_vision: ColorVision::Normal,
+            render_budget: RenderBudget::new(),
+            last_cost: None,
+            accepted: None,
+            #[cfg(not(target_arch = "wasm32"))]
+            background_render: None,
             texture: None,
             width: 800,
             height: 600,
         }
     }
 
-    pub fn show(&mut self, ui: &mut egui::Ui, simulation: &mut Box<dyn Simulation2D>) {
+    /// Scale, pan and post-processing settings, for scene files
+    pub fn view_state(&self) -> Value {
+        json!({
+            "scale": self.scale,
+            "pan_x": self.pan_x,
+            "pan_y": self.pan_y,
+            "kaleidoscope": self.kaleidoscope,
+            "kaleidoscope_segments": self.kaleidoscope_segments,
+            "kaleidoscope_rotation": self.kaleidoscope_rotation,
+            "tiling_preview": self.tiling_preview,
+            "tiling_outline": self.tiling_outline,
+            "heatmap": self.heatmap,
+            "heatmap_scheme": self.heatmap_scheme,
+            "heatmap_auto_range": self.heatmap_auto_range,
+            "heatmap_min": self.heatmap_min,
+            "heatmap_max": self.heatmap_max,
+            "background": self.background,
+            "exposure": self.exposure,
+            "tone_map": self.tone_map,
+            "isometric": self.isometric.state(),
+            "persistence": self.persistence.state(),
+            "color_vision": self.color_vision,
+        })
+    }
+
+    /// Restore settings written by `view_state`; missing keys are left alone
+    pub fn load_view_state(&mut self, state: &Value) {
+        read_clamped(state, "scale", &mut self.scale, 0.25..=2.0);
+        read(state, "pan_x", &mut self.pan_x);
+        read(state, "pan_y", &mut self.pan_y);
+        read(state, "kaleidoscope", &mut self.kaleidoscope);
+        read_clamped(state, "kaleidoscope_segments", &mut self.kaleidoscope_segments, 2..=24);
+        read_clamped(state, "kaleidoscope_rotation", &mut self.kaleidoscope_rotation, 0.0..=std::f32::consts::TAU);
+        read(state, "tiling_preview", &mut self.tiling_preview);
+        read(state, "tiling_outline", &mut self.tiling_outline);
+        read(state, "heatmap", &mut self.heatmap);
+        read(state, "heatmap_scheme", &mut self.heatmap_scheme);
+        read(state, "heatmap_auto_range", &mut self.heatmap_auto_range);
+        read(state, "heatmap_min", &mut self.heatmap_min);
+        read(state, "heatmap_max", &mut self.heatmap_max);
+        read(state, "background", &mut self.background);
+        read_clamped(state, "exposure", &mut self.exposure, 0.1..=8.0);
+        read(state, "tone_map", &mut self.tone_map);
+        read(state, "color_vision", &mut self.color_vision);
+        if let Some(isometric) = state.get("isometric") {
+            self.isometric.load_state(isometric);
+        }
+        if let Some(persistence) = state.get("persistence") {
+            self.persistence.load_state(persistence);
+        }
+        self.needs_update = true;
+    }
+
+    /// Clear any pan and schedule a redraw
+    pub fn reset_view(&mut self) {
+        self.pan_x = 0.0;
+        self.pan_y = 0.0;
+        self.needs_update = true;
+    }
+
+    /// Plane point of the last click on the view, if not yet taken
+    pub fn take_plane_click(&mut self) -> Option<(f64, f64)> {
+        self.plane_click.take()
+    }
+
+    /// Size of the last computed image, in simulation pixels
+    pub fn image_size(&self) -> (usize, usize) {
+        (self.width, self.height)
+    }
+
+    /// The sim re-rendered at the current image size, with the time,
+    /// background and palette phase of the last frame, encoded as a PNG.
+    /// Post-processing is left out. A sim returning the wrong number of
+    /// pixels gets its buffer padded with the background or cut to size.
+    pub fn encode_png(&self, simulation: &dyn Simulation2D) -> image::ImageResult<Vec<u8>> {
+        let ctx = RenderCtx { width: self.width, height: self.height, ..self.frame_ctx };
+        let mut colors = simulation.compute_ctx(&ctx);
+        colors.resize(ctx.width * ctx.height, ctx.background);
+
+        let bytes = colors.iter().flat_map(|c| [c.r, c.g, c.b]).collect();
+        let image = image::RgbImage::from_raw(ctx.width as u32, ctx.height as u32, bytes)
+            .ok_or_else(|| image::ImageError::Parameter(image::error::ParameterError::from_kind(
+                image::error::ParameterErrorKind::DimensionMismatch,
+            )))?;
+        let mut png = Vec::new();
+        image.write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png)?;
+        Ok(png)
+    }
+
+    /// Write `encode_png` to `path`
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn export_png(&self, simulation: &dyn Simulation2D, path: &Path) -> image::ImageResult<()> {
+        std::fs::write(path, self.encode_png(simulation)?)?;
+        Ok(())
+    }
+
+    /// Whether the last frame re-rendered the sim; frames that only redrew
+    /// the cached texture say nothing about render cost
+    pub fn rendered(&self) -> bool {
+        self.rendered
+    }
+
+    /// Take the CPU time of last frame; when it rendered a costed sim, the
+    /// slow-render estimate learns from it
+    pub fn record_frame_time(&mut self, seconds: f32) {
+        if let Some(cost) = self.last_cost.filter(|_| self.rendered) {
+            self.render_budget.record(cost, seconds as f64);
+        }
+    }
+
+    fn background_running(&self) -> bool {
+        #[cfg(not(target_arch = "wasm32"))]
+        return self.background_render.is_some();
+        #[cfg(target_arch = "wasm32")]
+        return false;
+    }
+
+    /// Decide whether to render `simulation` at `ctx`'s size now, on a
+    /// worker, or not yet. Recordings take every frame as it comes.
+    fn plan_render(&mut self, simulation: &dyn Simulation2D, layer: Option<&dyn Simulation2D>, ctx: &RenderCtx) -> Verdict {
+        let cost = [Some(simulation), layer].into_iter().flatten()
+            .filter_map(|sim| sim.render_cost(ctx.width, ctx.height))
+            .reduce(|a, b| a + b);
+        self.last_cost = cost;
+        let Some(cost) = cost.filter(|_| !self.is_capturing()) else {
+            return Verdict::Render;
+        };
+
+        // The heatmap and isometric views read the sim's field as they go,
+        // so only the plain render moves off this thread
+        let uses_field = self.heatmap || self.isometric.is_enabled(simulation.name());
+        match self.render_budget.check(cost) {
+            Verdict::Background if uses_field => Verdict::Render,
+            #[cfg(not(target_arch = "wasm32"))]
+            Verdict::Background => {
+                let estimate = self.render_budget.estimate(cost);
+                self.background_render = Some(BackgroundRender::start(simulation, layer, *ctx, cost, estimate));
+                self.last_cost = None;
+                Verdict::Background
+            }
+            verdict => verdict,
+        }
+    }
+
+    /// Put back the settings of the last costed render
+    fn restore_accepted(&mut self, simulation: &mut Box<dyn Simulation2D>) {
+        if let Some((name, state, scale)) = &self.accepted {
+            if name == simulation.name() {
+                simulation.load_state(state);
+                self.scale = *scale;
+                self.needs_update = true;
+            }
+        }
+    }
+
+    /// Elapsed and estimated time over the image while a background render runs
+    fn paint_background_progress(&self, painter: &egui::Painter, rect: egui::Rect) {
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(job) = &self.background_render {
+            let text = format!("⏳ Rendering in background… {} of ~{}",
+                sim_core::readout::seconds(job.elapsed(), 0), sim_core::readout::seconds(job.estimate, 0));
+            let pos = rect.left_bottom() + egui::vec2(8.0, -8.0);
+            let galley = painter.layout_no_wrap(text, egui::FontId::proportional(14.0), egui::Color32::WHITE);
+            let background = egui::Rect::from_min_size(pos - egui::vec2(0.0, galley.size().y), galley.size()).expand(4.0);
+            painter.rect_filled(background, 4.0, egui::Color32::from_black_alpha(180));
+            painter.galley(background.min + egui::vec2(4.0, 4.0), galley, egui::Color32::WHITE);
+        }
+        #[cfg(target_arch = "wasm32")]
+        let _ = (painter, rect);
+    }
+
+    /// Whether every frame is being rendered for a video or GIF recording
+    pub fn is_capturing(&self) -> bool {
+        self.capture_size.is_some() || self.gif.is_recording()
+    }
+
+    /// The frame rendered for capture since the last call
+    pub fn take_capture(&mut self) -> Option<egui::ColorImage> {
+        self.captured.take()
+    }
+
+    /// Step scrubber for sims that support seeking. The range runs to the
+    /// furthest step reached, so scrubbing back keeps the later steps
+    /// reachable; it starts over when the sim restarts.
+    pub fn timeline_ui(&mut self, ui: &mut egui::Ui, simulation: &mut Box<dyn Simulation2D>) {
+        if !simulation.supports_seek() {
+            return;
+        }
+
+        let step = simulation.step_count();
+        if simulation.name() != self.timeline_sim || step < self.timeline_last {
+            self.timeline_sim = simulation.name().to_string();
+            self.timeline_end = step;
+        }
+        self.timeline_end = self.timeline_end.max(step);
+
+        egui::CollapsingHeader::new("⏱ Timeline")
+            .default_open(true)
+            .show(ui, |ui| {
+                let mut target = step;
+                ui.add(egui::Slider::new(&mut target, 0..=self.timeline_end.max(1))
+                    .nudge(ui, 1.0)
+                    .text("Step"));
+
+                ui.horizontal(|ui| {
+                    if ui.button("⏮").on_hover_text("Back to the start").clicked() {
+                        target = 0;
+                    }
+                    if ui.button("◀").on_hover_text("Step back").clicked() {
+                        target = step.saturating_sub(1);
+                    }
+                    if ui.button("▶").on_hover_text("Step forward").clicked() {
+                        target = step + 1;
+                    }
+                });
+
+                if target != step {
+                    simulation.seek(target);
+                    self.needs_update = true;
+                }
+            });
+
+        self.timeline_last = simulation.step_count();
+    }
+
+    /// Post-processing and export controls; `simulation` is the name of the
+    /// sim on screen, for its per-sim settings
+    pub fn show_controls(&mut self, ui: &mut egui::Ui, simulation: &str) {
+        self.render_budget.ui(ui);
+
+        egui::CollapsingHeader::new("🖼 Background")
+            .show(ui, |ui| {
+                let mut changed = false;
+                ui.horizontal(|ui| {
+                    ui.label("Color:");
+                    changed |= ui.color_edit_button_srgb(&mut self.background).changed();
+                    if ui.button("Black").clicked() {
+                        self.background = [0, 0, 0];
+                        changed = true;
+                    }
+                });
+
+                if changed {
+                    self.needs_update = true;
+                }
+            });
+
+        egui::CollapsingHeader::new("💡 Glow Exposure")
+            .show(ui, |ui| {
+                ui.label("For glow and density renders, like attractor point glow, Perlin particle glow and Symmetric Icons");
+                if tone_ui(ui, &mut self.exposure, &mut self.tone_map) {
+                    self.needs_update = true;
+                }
+            });
+
+        egui::CollapsingHeader::new("🔮 Kaleidoscope")
+            .show(ui, |ui| {
+                let mut changed = ui.checkbox(&mut self.kaleidoscope, "Enable").changed();
+
+                ui.add_enabled_ui(self.kaleidoscope, |ui| {
+                    changed |= ui.add(egui::Slider::new(&mut self.kaleidoscope_segments, 2..=24)
+                        .nudge(ui, 1.0)
+                        .text("Segments")).changed();
+                    changed |= ui.add(egui::Slider::new(&mut self.kaleidoscope_rotation, 0.0..=std::f32::consts::TAU)
+                        .nudge(ui, 0.05)
+                        .text("Rotation")).changed();
+                });
+
+                if changed {
+                    self.needs_update = true;
+                }
+            });
+
+        egui::CollapsingHeader::new("🧱 Tiling Preview")
+            .show(ui, |ui| {
+                if ui.checkbox(&mut self.tiling_preview, "Show Tiling")
+                    .on_hover_text("Repeat the image 3×3 at a third of the size, so mismatched edges show up as seams. Only the screen shows it; exports get the plain image.")
+                    .changed() {
+                    self.needs_update = true;
+                }
+                ui.add_enabled_ui(self.tiling_preview, |ui| {
+                    ui.checkbox(&mut self.tiling_outline, "Outline Tiles");
+                });
+            });
+
+        egui::CollapsingHeader::new("🌡 Heatmap")
+            .show(ui, |ui| {
+                let mut changed = ui.checkbox(&mut self.heatmap, "Show Raw Field")
+                    .on_hover_text("False-color the underlying scalar data instead of the artistic render")
+                    .changed();
+
+                if self.heatmap && !self.field_available {
+                    ui.label("This simulation has no scalar field");
+                }
+
+                ui.add_enabled_ui(self.heatmap, |ui| {
+                    changed |= color_scheme_combo_labeled(ui, "Heatmap Scheme", &mut self.heatmap_scheme);
+
+                    changed |= ui.checkbox(&mut self.heatmap_auto_range, "Auto Range").changed();
+                    ui.add_enabled_ui(!self.heatmap_auto_range, |ui| {
+                        ui.horizontal(|ui| {
+                            ui.label("Range:");
+                            changed |= ui.add(egui::DragValue::new(&mut self.heatmap_min).speed(0.01)).changed();
+                            changed |= ui.add(egui::DragValue::new(&mut self.heatmap_max).speed(0.01)).changed();
+                        });
+                    });
+
+                    self.colorbar(ui);
+                });
+
+                if changed {
+                    self.needs_update = true;
+                }
+            });
+
+        if self.isometric.ui(ui, simulation, self.field_available) {
+            self.needs_update = true;
+        }
+
+        if self.persistence.ui(ui) {
+            self.needs_update = true;
+        }
+
+        if self.color_vision.ui(ui) {
+            self.needs_update = true;
+        }
+
+        // Re-render so a baked overlay is added, updated or removed
+        if self.overlay.ui(ui, self.overlay_available) {
+            self.needs_update = true;
+        }
+
+        self.ascii.ui(ui, &self.frame, self.width, self.height);
+        self.gif.ui(ui);
+    }
+
+    /// Gradient strip of the heatmap scheme labelled with the current range
+    fn colorbar(&self, ui: &mut egui::Ui) {
+        let (rect, _) = ui.allocate_exact_size(egui::vec2(ui.available_width(), 14.0), egui::Sense::hover());
+        let painter = ui.painter_at(rect);
+        let steps = 64;
+        let step_width = rect.width() / steps as f32;
+        for i in 0..steps {
+            let c = self.heatmap_scheme.map(i as f32 / (steps - 1) as f32, true);
+            let x = rect.left() + i as f32 * step_width;
+            painter.rect_filled(
+                egui::Rect::from_min_max(egui::pos2(x, rect.top()), egui::pos2(x + step_width + 0.5, rect.bottom())),
+                0.0,
+                egui::Color32::from_rgb(c.r, c.g, c.b),
+            );
+        }
+
+        ui.horizontal(|ui| {
+            ui.label(format!("{:.3}", self.heatmap_min));
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                ui.label(format!("{:.3}", self.heatmap_max));
+            });
+        });
+    }
+
+    /// Map a scalar field onto the image through the heatmap scheme. In auto
+    /// mode the range tracks the field's min/max and is written back so the
+    /// colorbar shows it.
+    fn render_heatmap(&mut self, field: &[f32], field_width: usize, field_height: usize, width: usize, height: usize) -> Vec<Color> {
+        if self.heatmap_auto_range {
+            let (min, max) = field.iter()
+                .filter(|v| v.is_finite())
+                .fold((f32::MAX, f32::MIN), |(lo, hi), &v| (lo.min(v), hi.max(v)));
+            if min <= max {
+                self.heatmap_min = min;
+                self.heatmap_max = max;
+            }
+        }
+
+        let range = (self.heatmap_max - self.heatmap_min).max(f32::EPSILON);
+        let mut pixels = vec![Color::BLACK; width * height];
+        if field_width == 0 || field_height == 0 || field.len() < field_width * field_height {
+            return pixels;
+        }
+
+        for y in 0..height {
+            let fy = y * field_height / height;
+            for x in 0..width {
+                let fx = x * field_width / width;
+                let t = ((field[fy * field_width + fx] - self.heatmap_min) / range).clamp(0.0, 1.0);
+                pixels[y * width + x] = self.heatmap_scheme.map(t, true);
+            }
+        }
+        pixels
+    }
+
+    /// Render and display `simulation`, with `layer` blended over it when
+    /// compositing is on
+    pub fn show(&mut self, ui: &mut egui::Ui, simulation: &mut Box<dyn Simulation2D>, layer: Option<&dyn Simulation2D>) {
         let available_size = ui.available_size();
-        let width = (available_size.x * self.scale) as usize;
-        let height = (available_size.y * self.scale) as usize;
+        let (width, height) = self.capture_size.unwrap_or((
+            (available_size.x * self.scale) as usize,
+            (available_size.y * self.scale) as usize,
+        ));
 
-        // Check if we need to recompute
-        if self.needs_update || width != self.width || height != self.height {
+        if self.render_budget.dialog(ui.ctx()) == Some(Choice::Cancel) {
+            self.restore_accepted(simulation);
+        }
+
+        let [r, g, b] = self.background;
+        let background = Color::from_rgb(r, g, b);
+        let mut ctx = RenderCtx {
+            width,
+            height,
+            time: SimClock::current(ui.ctx())
+                .map(|clock| clock.time())
+                .unwrap_or_else(|| ui.input(|i| i.time)),
+            seed: 0,
+            background,
+            palette_phase: self.palette_phase,
+            exposure: self.exposure,
+            tone_map: self.tone_map,
+        };
+
+        // A finished background render stands in for this frame's compute:
+        // the sim's pixels and the layer's
+        let mut ready: Option<(Vec<Color>, Option<Vec<Color>>)> = None;
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(progress) = self.background_render.as_ref().map(BackgroundRender::poll) {
+            match progress {
+                Progress::Running => {}
+                Progress::Done { sim, colors, layer, seconds } => {
+                    let job = self.background_render.take().unwrap();
+                    self.render_budget.record(job.cost, seconds);
+                    // Unless the user moved on to another sim, show the
+                    // pixels and take over the worker's iteration cache, so
+                    // recoloring the result doesn't iterate again
+                    if sim.name() == simulation.name() {
+                        if let (Some(ours), Some(theirs)) = (simulation.iteration_cache(), sim.iteration_cache()) {
+                            ours.copy_from(theirs);
+                        }
+                        ready = Some((colors, layer));
+                        ctx = job.ctx;
+                    }
+                }
+                Progress::Failed => {
+                    self.background_render = None;
+                    self.render_budget.render_in_foreground();
+                    self.needs_update = true;
+                }
+            }
+        }
+
+        // Check if we need to recompute; every frame is rendered while capturing
+        self.rendered = false;
+        let due = self.needs_update || self.is_capturing() || width != self.width || height != self.height;
+        let render = ready.is_some()
+            || (due && !self.background_running() && match self.plan_render(simulation.as_ref(), layer, &ctx) {
+                Verdict::Render => true,
+                Verdict::Background => {
+                    self.needs_update = false;
+                    false
+                }
+                Verdict::Hold => false,
+            });
+        if render {
+            let (width, height) = (ctx.width, ctx.height);
             self.width = width;
             self.height = height;
+            let (mut ready_colors, ready_layer) = ready.map_or((None, None), |(colors, layer)| (Some(colors), layer));
 
-            // Compute simulation
-            let colors = simulation.compute(width, height);
+            // Compute simulation, or false-color its raw field
+            let isometric = self.isometric.is_enabled(simulation.name());
+            let field = if self.heatmap || isometric { simulation.scalar_field() } else { None };
+            self.field_available = field.is_some();
+            let mut colors = match &field {
+                Some((values, field_width, field_height)) if self.heatmap => {
+                    self.render_heatmap(values, *field_width, *field_height, width, height)
+                }
+                _ => ready_colors.take().unwrap_or_else(|| simulation.compute_ctx(&ctx)),
+            };
+
+            // The layer gets the same context, so it renders at the same size
+            if let Some(layer) = layer {
+                let layer_colors = ready_layer.unwrap_or_else(|| layer.compute_ctx(&ctx));
+                self.compositing.blend(&mut colors, &layer_colors);
+            }
+
+            // Raise the flat render into columns over the same field
+            if let Some((values, field_width, field_height)) = field.filter(|_| isometric) {
+                colors = self.isometric.render(&values, field_width, field_height, &colors, width, height, background);
+            }
+
+            if self.kaleidoscope {
+                colors = kaleidoscope(&colors, width, height, self.kaleidoscope_segments, self.kaleidoscope_rotation);
+            } else if self.overlay.baked() {
+                if let Some(view) = simulation.plane_view(width, height) {
+                    self.overlay.bake_into(&mut colors, &view, width, height);
+                }
+            }
+
+            // After everything that makes up the image, so the trails hold
+            // exactly what was rendered
+            self.persistence.apply(&mut colors, width, height, simulation.name());
+
+            // A viewing aid, so it sees the finished frame trails included
+            self.color_vision.apply(&mut colors);
 
             // Convert to egui color image
-            let pixels: Vec<egui::Color32> = colors
-                .iter()
-                .map(|c| egui::Color32::from_rgb(c.r, c.g, c.b))
-                .collect();
-
-            let color_image = egui::ColorImage {
-                size: [width, height],
-                pixels,
+            let color_image = to_color_image(&colors, width, height);
+            self.frame = colors;
+            self.frame_ctx = ctx;
+            if self.capture_size.is_some() {
+                self.captured = Some(color_image.clone());
+            }
+            self.gif.push_frame(&self.frame, width, height, background);
+
+            // Only what's on screen is tiled; the frame kept for exports and
+            // the capture above stay as rendered
+            let color_image = if self.tiling_preview {
+                to_color_image(&tile_preview(&self.frame, width, height), width, height)
+            } else {
+                color_image
             };
 
             // Update or create texture
@@ -59,7 +671,11 @@ impl Viewer2D {
                 ));
             }
 
+            if simulation.render_cost(width, height).is_some() {
+                self.accepted = Some((simulation.name().to_string(), simulation.save_state(), self.scale));
+            }
             self.needs_update = false;
+            self.rendered = true;
         }
 
         // Create an interactive area for the image
@@ -80,16 +696,60 @@ impl Viewer2D {
                 self.needs_update = true;
             }
 
+            // Non-zoomable sims get the pointer for painting instead, except
+            // in the isometric view, whose pixels no longer line up with cells
+            let isometric = self.field_available && self.isometric.is_enabled(simulation.name());
+            if !simulation.supports_zoom() && !isometric && response.is_pointer_button_down_on() {
+                if let Some(pos) = response.interact_pointer_pos() {
+                    // The texture is rendered at `scale` times the display size
+                    let mut x = (pos.x - response.rect.min.x) * self.scale;
+                    let mut y = (pos.y - response.rect.min.y) * self.scale;
+                    // Paint through whichever copy of the tiling is under the pointer
+                    if self.tiling_preview {
+                        x = (x * 3.0).rem_euclid(width as f32);
+                        y = (y * 3.0).rem_euclid(height as f32);
+                    }
+                    let erase = ui.input(|i| i.pointer.secondary_down());
+                    if simulation.paint_at(x, y, width, height, erase) {
+                        self.needs_update = true;
+                    }
+                }
+            }
+
+            // Clicks are reported in plane coordinates, where the image maps
+            // straight onto the plane
+            if response.clicked() && !self.kaleidoscope && !self.tiling_preview {
+                if let (Some(pos), Some(view)) = (response.interact_pointer_pos(), simulation.plane_view(width, height)) {
+                    let x = (pos.x - response.rect.min.x) * self.scale;
+                    let y = (pos.y - response.rect.min.y) * self.scale;
+                    self.plane_click = Some(view.to_plane(x as f64, y as f64, width, height));
+                }
+            }
+
             // Handle mousewheel for zoom control
             if simulation.supports_zoom() {
-                ui.input(|i| {
-                    let scroll_delta = i.smooth_scroll_delta.y;
-                    if scroll_delta.abs() > 0.1 {
-                        // Adjust the simulation's zoom parameter
-                        simulation.adjust_zoom(scroll_delta as f64);
-                        self.needs_update = true;
+                let scroll_delta = ui.input(|i| i.smooth_scroll_delta.y);
+                if scroll_delta.abs() > 0.1 {
+                    // The plane point under the pointer stays put, where the
+                    // image maps straight onto a plane
+                    let anchor = response.hover_pos()
+                        .filter(|_| !self.kaleidoscope && !self.tiling_preview)
+                        .zip(simulation.plane_view(width, height))
+                        .map(|(pos, view)| {
+                            let x = ((pos.x - response.rect.min.x) * self.scale) as f64;
+                            let y = ((pos.y - response.rect.min.y) * self.scale) as f64;
+                            (x, y, view.to_plane(x, y, width, height))
+                        });
+
+                    simulation.adjust_zoom(scroll_delta as f64);
+                    if let Some((x, y, (u, v))) = anchor {
+                        if let Some(view) = simulation.plane_view(width, height) {
+                            let (px, py) = view.to_pixel(u, v, width, height);
+                            simulation.adjust_center(x - px, y - py, width, height);
+                        }
                     }
-                });
+                    self.needs_update = true;
+                }
             }
 
             // Draw the image
@@ -100,6 +760,97 @@ impl Viewer2D {
                 egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0)),
                 egui::Color32::WHITE,
             );
+
+            // The kaleidoscope folds the plane and the tiling repeats it, so
+            // axes wouldn't line up
+            let view = simulation.plane_view(width, height);
+            self.overlay_available = view.is_some();
+            if let Some(view) = view.filter(|_| !self.kaleidoscope && !self.tiling_preview && !self.overlay.baked()) {
+                self.overlay.paint(&ui.painter_at(rect), rect, &view, width, height);
+            }
+            if self.tiling_preview && self.tiling_outline {
+                paint_tile_outlines(&ui.painter_at(rect), rect);
+            }
+            self.paint_background_progress(&ui.painter_at(rect), rect);
+        }
+    }
+}
+
+fn to_color_image(colors: &[Color], width: usize, height: usize) -> egui::ColorImage {
+    egui::ColorImage {
+        size: [width, height],
+        pixels: colors.iter().map(|c| egui::Color32::from_rgb(c.r, c.g, c.b)).collect(),
+    }
+}
+
+/// The image repeated 3×3 at a third of the size. Each output pixel averages
+/// the 3×3 source pixels it covers, read with wrap-around, so where two
+/// copies meet it is the image's own opposite edges side by side.
+fn tile_preview(src: &[Color], width: usize, height: usize) -> Vec<Color> {
+    if width == 0 || height == 0 || src.len() < width * height {
+        return src.to_vec();
+    }
+
+    let mut out = Vec::with_capacity(width * height);
+    for y in 0..height {
+        for x in 0..width {
+            let mut sum = [0u32; 3];
+            for sy in (3 * y..3 * y + 3).map(|sy| sy % height) {
+                for sx in (3 * x..3 * x + 3).map(|sx| sx % width) {
+                    let c = src[sy * width + sx];
+                    sum[0] += c.r as u32;
+                    sum[1] += c.g as u32;
+                    sum[2] += c.b as u32;
+                }
+            }
+            out.push(Color::from_rgb((sum[0] / 9) as u8, (sum[1] / 9) as u8, (sum[2] / 9) as u8));
         }
     }
+    out
+}
+
+/// Hairlines at the thirds of `rect`, where the tiling preview's copies meet
+fn paint_tile_outlines(painter: &egui::Painter, rect: egui::Rect) {
+    let stroke = egui::Stroke::new(1.0, egui::Color32::from_white_alpha(90));
+    for i in 1..3 {
+        let t = i as f32 / 3.0;
+        let x = rect.left() + rect.width() * t;
+        let y = rect.top() + rect.height() * t;
+        painter.vline(x, rect.y_range(), stroke);
+        painter.hline(rect.x_range(), y, stroke);
+    }
+}
+
+/// Mirror one wedge of the image around the center into `segments` wedges.
+/// Every output pixel samples the source through the fold, and alternate
+/// wedges are reflected so neighbouring edges always match up.
+fn kaleidoscope(src: &[Color], width: usize, height: usize, segments: u32, rotation: f32) -> Vec<Color> {
+    if width == 0 || height == 0 || src.len() < width * height {
+        return src.to_vec();
+    }
+
+    let wedge = std::f32::consts::TAU / segments.max(1) as f32;
+    let cx = width as f32 / 2.0;
+    let cy = height as f32 / 2.0;
+    let mut out = vec![Color::BLACK; width * height];
+
+    for y in 0..height {
+        for x in 0..width {
+            let dx = x as f32 + 0.5 - cx;
+            let dy = y as f32 + 0.5 - cy;
+            let r = (dx * dx + dy * dy).sqrt();
+
+            let mut a = (dy.atan2(dx) - rotation).rem_euclid(wedge);
+            if a > wedge / 2.0 {
+                a = wedge - a;
+            }
+            let a = a + rotation;
+
+            let sx = ((cx + r * a.cos()) as isize).clamp(0, width as isize - 1) as usize;
+            let sy = ((cy + r * a.sin()) as isize).clamp(0, height as isize - 1) as usize;
+            out[y * width + x] = src[sy * width + sx];
+        }
+    }
+
+    out
 }
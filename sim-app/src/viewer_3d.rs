@@ -1,4 +1,11 @@
+use crate::persistence::Persistence;
 use eframe::egui;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sim_core::nudge::Nudge;
+use sim_core::readout;
+use sim_core::state::{read, read_clamped};
+use sim_core::tone_map::{tone_ui, ToneMap};
 use sim_core::Simulation3D;
 
 pub struct Viewer3D {
@@ -7,21 +14,111 @@ pub struct Viewer3D {
     pub zoom: f32,
     pub auto_rotate: bool,
     pub point_size: f32,
+    /// Blend points additively so dense regions bloom; off draws opaque,
+    /// depth-tested points
+    pub additive_glow: bool,
+    /// Multiplier applied to the glow accumulation before tone mapping
+    pub exposure: f32,
+    /// Light direction for opaque points, relative to the view: azimuth
+    /// turns it left/right of the camera, elevation above/below (radians)
+    pub light_azimuth: f32,
+    pub light_elevation: f32,
+    /// Overlay with point count, bounds, rotation and FPS
+    pub show_hud: bool,
+    /// Draw the sim's labels
+    pub show_labels: bool,
+    /// Label the positive X/Y/Z axes at the edge of the point cloud
+    pub axis_labels: bool,
+    /// Most points drawn per frame
+    pub max_points: usize,
+    /// Trails of past frames in screen space
+    pub persistence: Persistence,
+    /// Size of PNG exports, independent of the panel
+    pub png_size: (usize, usize),
+    /// Turntable sprite sheet: frames around the Y axis and the square tile
+    /// each is rendered into
+    pub turntable_frames: usize,
+    pub turntable_tile: usize,
+    /// Smoothed frames per second for the HUD
+    fps: f32,
+    tone_map: ToneMap,
     color_mode: ColorMode,
     background_style: BackgroundStyle,
+    /// Fixed render size while recording video, instead of the canvas size
+    pub capture_size: Option<(usize, usize)>,
+    /// Last frame rendered while `capture_size` is set
+    captured: Option<egui::ColorImage>,
+    /// Size of the last rendered frame
+    size: (usize, usize),
+    /// Index of the sim source being dragged, if any
+    dragging_source: Option<usize>,
     texture: Option<egui::TextureHandle>,
 }
 
-#[derive(Clone, Copy, PartialEq)]
+/// Radians of rotation per pixel of pointer drag over the view
+const DRAG_ROTATE_SPEED: f32 = 0.01;
+
+/// Furthest the view tilts up or down. Past straight up or down the
+/// picture would flip over.
+const MAX_PITCH: f32 = std::f32::consts::FRAC_PI_2;
+
+/// Light reaching the side of an opaque point facing away from the light
+const AMBIENT_LIGHT: f32 = 0.25;
+
+/// Largest PNG export side, in pixels
+const MAX_PNG_SIDE: usize = 7680;
+
+/// Largest turntable tile side, in pixels
+const MAX_TILE_SIDE: usize = 1024;
+
+/// How close (in pixels) a press must be to a source to pick it up
+const SOURCE_GRAB_RADIUS: f32 = 14.0;
+
+/// What a sim hands the viewer to draw for one frame
+struct Geometry {
+    points: Vec<[f32; 3]>,
+    /// The sim's own point colors, when it has one per point
+    colors: Option<Vec<sim_core::Color>>,
+    segments: Vec<([f32; 3], [f32; 3])>,
+    /// World-space segment widths, when the sim has one per segment
+    widths: Option<Vec<f32>>,
+}
+
+impl Geometry {
+    fn of(simulation: &dyn Simulation3D) -> Self {
+        let points = simulation.get_points();
+        let colors = simulation.get_colors().filter(|c| c.len() == points.len());
+        let segments = simulation.get_segments();
+        let widths = simulation.get_segment_widths().filter(|w| w.len() == segments.len());
+        Self { points, colors, segments, widths }
+    }
+}
+
+/// Output of `Viewer3D::render_frame`
+struct RenderedFrame {
+    pixels: Vec<egui::Color32>,
+    /// Nearest depth per pixel; empty with additive glow
+    depth: Vec<f32>,
+    /// World-to-pixel scale the points were projected with
+    scale: f32,
+    /// Extent of the sampled points across the screen, in world units
+    range: f32,
+    /// Points actually drawn, after the `max_points` cap
+    drawn: usize,
+}
+
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
 #[allow(dead_code)]
 enum ColorMode {
+    /// The sim's own per-point colors, or Rainbow for sims without any
+    Simulation,
     Rainbow,
     Depth,
     Velocity,
     Solid,
 }
 
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
 #[allow(dead_code)]
 enum BackgroundStyle {
     Gradient,
@@ -30,6 +127,91 @@ enum BackgroundStyle {
 }
 
 impl Viewer3D {
+    /// Camera back to its starting rotation and zoom
+    pub fn reset_view(&mut self) {
+        let defaults = Self::new();
+        self.rotation_x = defaults.rotation_x;
+        self.rotation_y = defaults.rotation_y;
+        self.zoom = defaults.zoom;
+    }
+
+    /// Camera and rendering settings, for scene files
+    pub fn view_state(&self) -> Value {
+        json!({
+            "rotation_x": self.rotation_x,
+            "rotation_y": self.rotation_y,
+            "zoom": self.zoom,
+            "auto_rotate": self.auto_rotate,
+            "point_size": self.point_size,
+            "additive_glow": self.additive_glow,
+            "exposure": self.exposure,
+            "light_azimuth": self.light_azimuth,
+            "light_elevation": self.light_elevation,
+            "show_hud": self.show_hud,
+            "show_labels": self.show_labels,
+            "axis_labels": self.axis_labels,
+            "tone_map": self.tone_map,
+            "color_mode": self.color_mode,
+            "background_style": self.background_style,
+            "persistence": self.persistence.state(),
+            "png_size": self.png_size,
+            "turntable_frames": self.turntable_frames,
+            "turntable_tile": self.turntable_tile,
+        })
+    }
+
+    /// Restore settings written by `view_state`; missing keys are left alone
+    pub fn load_view_state(&mut self, state: &Value) {
+        read(state, "rotation_x", &mut self.rotation_x);
+        read(state, "rotation_y", &mut self.rotation_y);
+        read_clamped(state, "zoom", &mut self.zoom, 0.5..=5.0);
+        read(state, "auto_rotate", &mut self.auto_rotate);
+        read_clamped(state, "point_size", &mut self.point_size, 1.0..=15.0);
+        read(state, "additive_glow", &mut self.additive_glow);
+        read_clamped(state, "exposure", &mut self.exposure, 0.1..=8.0);
+        read_clamped(state, "light_azimuth", &mut self.light_azimuth, -std::f32::consts::PI..=std::f32::consts::PI);
+        read_clamped(state, "light_elevation", &mut self.light_elevation, -std::f32::consts::FRAC_PI_2..=std::f32::consts::FRAC_PI_2);
+        read(state, "show_hud", &mut self.show_hud);
+        read(state, "show_labels", &mut self.show_labels);
+        read(state, "axis_labels", &mut self.axis_labels);
+        read(state, "tone_map", &mut self.tone_map);
+        read(state, "color_mode", &mut self.color_mode);
+        read(state, "background_style", &mut self.background_style);
+        read(state, "png_size", &mut self.png_size);
+        self.png_size = (self.png_size.0.clamp(16, MAX_PNG_SIDE), self.png_size.1.clamp(16, MAX_PNG_SIDE));
+        read_clamped(state, "turntable_frames", &mut self.turntable_frames, 2..=72);
+        read_clamped(state, "turntable_tile", &mut self.turntable_tile, 16..=MAX_TILE_SIDE);
+        if let Some(persistence) = state.get("persistence") {
+            self.persistence.load_state(persistence);
+        }
+        self.set_rotation(self.rotation_x, self.rotation_y);
+    }
+
+    /// Set the camera angles, with the tilt clamped short of flipping over
+    /// and the turn wrapped into one revolution
+    pub fn set_rotation(&mut self, x: f32, y: f32) {
+        self.rotation_x = x.clamp(-MAX_PITCH, MAX_PITCH);
+        self.rotation_y = y.rem_euclid(std::f32::consts::TAU);
+    }
+
+    /// Turn the camera with a drag over the view that didn't pick up a
+    /// source. The first drag takes over from auto-rotation.
+    fn drag_rotate(&mut self, ui: &egui::Ui, response: &egui::Response) {
+        if !response.dragged() || self.dragging_source.is_some() {
+            return;
+        }
+        self.auto_rotate = false;
+
+        let delta = response.drag_delta();
+        if delta != egui::Vec2::ZERO {
+            self.set_rotation(
+                self.rotation_x + delta.y * DRAG_ROTATE_SPEED,
+                self.rotation_y + delta.x * DRAG_ROTATE_SPEED,
+            );
+        }
+        ui.ctx().set_cursor_icon(egui::CursorIcon::Grabbing);
+    }
+
     pub fn new() -> Self {
         Self {
             rotation_x: 0.3,
@@ -37,12 +219,40 @@ impl Viewer3D {
             zoom: 1.5,
             auto_rotate: true,
             point_size: 4.0,
-            color_mode: ColorMode::Rainbow,
+            additive_glow: true,
+            exposure: 1.0,
+            light_azimuth: -0.6,
+            light_elevation: 0.6,
+            show_hud: false,
+            show_labels: true,
+            axis_labels: false,
+            max_points: 5000,
+            persistence: Persistence::new(),
+            png_size: (3840, 2160),
+            turntable_frames: 16,
+            turntable_tile: 256,
+            fps: 0.0,
+            tone_map: ToneMap::Clamp,
+            color_mode: ColorMode::Simulation,
             background_style: BackgroundStyle::Black,
+            capture_size: None,
+            captured: None,
+            size: (0, 0),
+            dragging_source: None,
             texture: None,
         }
     }
 
+    /// Size of the last rendered frame, in pixels
+    pub fn image_size(&self) -> (usize, usize) {
+        self.size
+    }
+
+    /// The frame rendered for capture since the last call
+    pub fn take_capture(&mut self) -> Option<egui::ColorImage> {
+        self.captured.take()
+    }
+
     fn rotate_point(&self, point: [f32; 3]) -> [f32; 3] {
         let [x, y, z] = point;
 
@@ -61,6 +271,39 @@ impl Viewer3D {
         [x1, y1, z2]
     }
 
+    /// Inverse of `rotate_point`: view space back to world space
+    fn unrotate_point(&self, point: [f32; 3]) -> [f32; 3] {
+        let [x1, y1, z2] = point;
+
+        let cos_x = self.rotation_x.cos();
+        let sin_x = self.rotation_x.sin();
+        let y = y1 * cos_x + z2 * sin_x;
+        let z1 = -y1 * sin_x + z2 * cos_x;
+
+        let cos_y = self.rotation_y.cos();
+        let sin_y = self.rotation_y.sin();
+        let x = x1 * cos_y + z1 * sin_y;
+        let z = -x1 * sin_y + z1 * cos_y;
+
+        [x, y, z]
+    }
+
+    /// World-space move for a screen drag of `delta` pixels at `point`,
+    /// parallel to the screen at that point's depth
+    fn screen_to_world_delta(&self, point: [f32; 3], delta: egui::Vec2, scale: f32) -> [f32; 3] {
+        let z = self.rotate_point(point)[2];
+        let pixels_per_unit = scale * self.zoom * 300.0 / (300.0 + z);
+        self.unrotate_point([delta.x / pixels_per_unit, delta.y / pixels_per_unit, 0.0])
+    }
+
+    /// Unit vector toward the light in screen space (x right, y down, z away
+    /// from the camera). Azimuth and elevation 0 light points head-on.
+    fn light_direction(&self) -> [f32; 3] {
+        let (sin_az, cos_az) = self.light_azimuth.sin_cos();
+        let (sin_el, cos_el) = self.light_elevation.sin_cos();
+        [cos_el * sin_az, -sin_el, -cos_el * cos_az]
+    }
+
     fn project_to_screen(&self, point: [f32; 3], width: f32, height: f32, scale: f32) -> (f32, f32, f32) {
         let rotated = self.rotate_point(point);
         let [x, y, z] = rotated;
@@ -74,8 +317,9 @@ impl Viewer3D {
         (screen_x, screen_y, z)
     }
 
-    fn draw_sphere(&self, pixels: &mut [egui::Color32], width: usize, height: usize,
-                   cx: f32, cy: f32, radius: f32, color: egui::Color32, glow: bool) {
+    #[allow(clippy::too_many_arguments)]
+    fn draw_sphere(&self, frame: &mut [[f32; 3]], depth: &mut [f32], width: usize, height: usize,
+                   cx: f32, cy: f32, z: f32, radius: f32, color: egui::Color32, glow: bool) {
         if width == 0 || height == 0 || radius < 0.1 {
             return;
         }
@@ -85,6 +329,9 @@ impl Viewer3D {
         let min_y = ((cy - radius).max(0.0) as usize).min(height.saturating_sub(1));
         let max_y = ((cy + radius).min(height as f32 - 1.0) as usize).min(height.saturating_sub(1));
 
+        // Same light for every sphere, so the cloud reads as one lit scene
+        let light = self.light_direction();
+
         for y in min_y..=max_y {
             for x in min_x..=max_x {
                 let dx = x as f32 - cx;
@@ -93,61 +340,212 @@ impl Viewer3D {
 
                 if dist <= radius {
                     let idx = y * width + x;
-                    if idx >= pixels.len() {
+                    if idx >= frame.len() {
                         continue;
                     }
 
                     if glow {
                         // Soft glow effect
                         let intensity = (1.0 - dist / radius).powf(2.0);
-                        let current = pixels[idx];
-                        pixels[idx] = egui::Color32::from_rgba_premultiplied(
-                            ((current.r() as f32 + color.r() as f32 * intensity).min(255.0)) as u8,
-                            ((current.g() as f32 + color.g() as f32 * intensity).min(255.0)) as u8,
-                            ((current.b() as f32 + color.b() as f32 * intensity).min(255.0)) as u8,
-                            255,
-                        );
+                        // Accumulate unclamped; tone_map_frame brings it back into range
+                        let current = &mut frame[idx];
+                        current[0] += color.r() as f32 * intensity;
+                        current[1] += color.g() as f32 * intensity;
+                        current[2] += color.b() as f32 * intensity;
                     } else {
-                        // Spherical shading
-                        let intensity = (1.0 - (dist / radius) * 0.5).max(0.3);
-                        pixels[idx] = egui::Color32::from_rgb(
-                            (color.r() as f32 * intensity) as u8,
-                            (color.g() as f32 * intensity) as u8,
-                            (color.b() as f32 * intensity) as u8,
-                        );
+                        // Opaque: nearest point wins (smaller z is closer to the camera)
+                        if z >= depth[idx] {
+                            continue;
+                        }
+                        depth[idx] = z;
+
+                        // Lambert shading: the visible hemisphere's normal
+                        // faces the camera (-z), with some ambient so the
+                        // unlit side isn't black
+                        let nx = dx / radius;
+                        let ny = dy / radius;
+                        let nz = -(1.0 - nx * nx - ny * ny).max(0.0).sqrt();
+                        let diffuse = (nx * light[0] + ny * light[1] + nz * light[2]).max(0.0);
+                        let intensity = AMBIENT_LIGHT + (1.0 - AMBIENT_LIGHT) * diffuse;
+                        frame[idx] = [
+                            color.r() as f32 * intensity,
+                            color.g() as f32 * intensity,
+                            color.b() as f32 * intensity,
+                        ];
                     }
                 }
             }
         }
     }
 
-    #[allow(dead_code)]
+    /// Draw a line between two projected points (screen x, y and depth),
+    /// Bresenham-style: one pixel at a time along its longer screen axis,
+    /// each with a span across the other axis for the width. The half-width
+    /// tapers linearly from `half_widths.0` to `half_widths.1`; opaque spans
+    /// are shaded as the cross-section of a tube.
+    #[allow(clippy::too_many_arguments)]
+    fn draw_segment(&self, frame: &mut [[f32; 3]], depth: &mut [f32], width: usize, height: usize,
+                    a: (f32, f32, f32), b: (f32, f32, f32), half_widths: (f32, f32),
+                    color: egui::Color32, glow: bool) {
+        if width == 0 || height == 0 {
+            return;
+        }
+
+        // Only walk the part that can touch the screen
+        let margin = half_widths.0.max(half_widths.1) + 1.0;
+        let Some((t0, t1)) = clip_to_rect([a.0, a.1], [b.0, b.1], [-margin, -margin],
+                                          [width as f32 + margin, height as f32 + margin]) else {
+            return;
+        };
+
+        let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+        let x_major = dx.abs() >= dy.abs();
+        let length = (dx * dx + dy * dy).sqrt();
+        // Unit normal of the line on screen, for shading across the span
+        let normal = if length > 0.0 { [-dy / length, dx / length] } else { [0.0, 1.0] };
+        let across = if x_major { normal[1].signum() } else { normal[0].signum() };
+        let light = self.light_direction();
+
+        let steps = ((dx.abs().max(dy.abs()) * (t1 - t0)).ceil() as usize).max(1);
+        let mut last_major = None;
+        for step in 0..=steps {
+            let t = t0 + (t1 - t0) * step as f32 / steps as f32;
+            let (x, y, z) = (a.0 + dx * t, a.1 + dy * t, a.2 + (b.2 - a.2) * t);
+
+            // Sub-pixel steps can land on the same column (or row) twice,
+            // which would double up additive glow
+            let major = if x_major { x.round() } else { y.round() } as i64;
+            if last_major == Some(major) {
+                continue;
+            }
+            last_major = Some(major);
+
+            let half = half_widths.0 + (half_widths.1 - half_widths.0) * t;
+            let reach = (half - 0.5).round().max(0.0) as i64;
+            for offset in -reach..=reach {
+                let (px, py) = if x_major {
+                    (major, (y + offset as f32).round() as i64)
+                } else {
+                    ((x + offset as f32).round() as i64, major)
+                };
+                if px < 0 || py < 0 || px >= width as i64 || py >= height as i64 {
+                    continue;
+                }
+                let idx = py as usize * width + px as usize;
+
+                if glow {
+                    let intensity = (1.0 - offset.abs() as f32 / (reach + 1) as f32).powf(2.0);
+                    let current = &mut frame[idx];
+                    current[0] += color.r() as f32 * intensity;
+                    current[1] += color.g() as f32 * intensity;
+                    current[2] += color.b() as f32 * intensity;
+                } else {
+                    if z >= depth[idx] {
+                        continue;
+                    }
+                    depth[idx] = z;
+
+                    // Position across the tube, -1 to 1, and the surface
+                    // normal there, facing the camera at the middle
+                    let u = across * offset as f32 / (reach as f32 + 0.5);
+                    let nx = normal[0] * u;
+                    let ny = normal[1] * u;
+                    let nz = -(1.0 - u * u).max(0.0).sqrt();
+                    let diffuse = (nx * light[0] + ny * light[1] + nz * light[2]).max(0.0);
+                    let intensity = AMBIENT_LIGHT + (1.0 - AMBIENT_LIGHT) * diffuse;
+                    frame[idx] = [
+                        color.r() as f32 * intensity,
+                        color.g() as f32 * intensity,
+                        color.b() as f32 * intensity,
+                    ];
+                }
+            }
+        }
+    }
+
+    /// Color from the viewer's color mode, for something `t` (0 to 1) of
+    /// the way through the sim's output at depth `z`. `velocity` is the
+    /// distance from the previous point, where there is one.
+    fn mode_color(&self, t: f32, z: f32, velocity: Option<f32>) -> egui::Color32 {
+        match self.color_mode {
+            ColorMode::Simulation | ColorMode::Rainbow => {
+                let hue = t * 360.0;
+                let (r, g, b) = hsv_to_rgb(hue, 0.9, 1.0);
+                egui::Color32::from_rgb(r, g, b)
+            }
+            ColorMode::Depth => {
+                let z_norm = (z + 100.0) / 200.0;
+                let intensity = z_norm.clamp(0.0, 1.0);
+                egui::Color32::from_rgb(
+                    (255.0 * (1.0 - intensity)) as u8,
+                    (150.0 * intensity) as u8,
+                    (255.0 * intensity) as u8,
+                )
+            }
+            ColorMode::Velocity => match velocity {
+                Some(velocity) => {
+                    let vel_t = (velocity * 0.5).min(1.0);
+                    egui::Color32::from_rgb(
+                        (255.0 * vel_t) as u8,
+                        (200.0 * (1.0 - vel_t)) as u8,
+                        (100.0) as u8,
+                    )
+                }
+                None => egui::Color32::from_rgb(100, 200, 100),
+            },
+            ColorMode::Solid => egui::Color32::from_rgb(100, 200, 255),
+        }
+    }
+
+    /// Resolve the float frame to display pixels. Exposure and tone mapping
+    /// only apply to additive glow; opaque points are already in range.
+    fn tone_map_frame(&self, frame: &[[f32; 3]]) -> Vec<egui::Color32> {
+        let (exposure, tone_map) = if self.additive_glow {
+            (self.exposure, self.tone_map)
+        } else {
+            (1.0, ToneMap::Clamp)
+        };
+
+        frame
+            .iter()
+            .map(|c| {
+                let channel = |v: f32| tone_map.byte(v, exposure);
+                egui::Color32::from_rgb(channel(c[0]), channel(c[1]), channel(c[2]))
+            })
+            .collect()
+    }
+
     pub fn show_controls(&mut self, ui: &mut egui::Ui) {
         egui::CollapsingHeader::new("🎮 View Controls")
             .default_open(true)
             .show(ui, |ui| {
                 ui.horizontal(|ui| {
                     ui.label("Rotation X:");
-                    if ui.add(egui::Slider::new(&mut self.rotation_x, 0.0..=std::f32::consts::TAU)).changed() {
-                        self.rotation_x = self.rotation_x.clamp(0.0, std::f32::consts::TAU);
+                    if ui.add(egui::Slider::new(&mut self.rotation_x, -MAX_PITCH..=MAX_PITCH).nudge(ui, 0.05)).changed() {
+                        self.rotation_x = self.rotation_x.clamp(-MAX_PITCH, MAX_PITCH);
                     }
                 });
 
                 ui.horizontal(|ui| {
                     ui.label("Rotation Y:");
-                    if ui.add(egui::Slider::new(&mut self.rotation_y, 0.0..=std::f32::consts::TAU)).changed() {
+                    if ui.add(egui::Slider::new(&mut self.rotation_y, 0.0..=std::f32::consts::TAU).nudge(ui, 0.05)).changed() {
                         self.rotation_y = self.rotation_y.clamp(0.0, std::f32::consts::TAU);
                     }
                 });
 
                 ui.horizontal(|ui| {
                     ui.label("Point Size:");
-                    if ui.add(egui::Slider::new(&mut self.point_size, 1.0..=15.0)).changed() {
+                    if ui.add(egui::Slider::new(&mut self.point_size, 1.0..=15.0).nudge(ui, 0.1)).changed() {
                         self.point_size = self.point_size.clamp(1.0, 15.0);
                     }
                 });
 
-                ui.checkbox(&mut self.auto_rotate, "Auto-Rotate");
+                ui.checkbox(&mut self.auto_rotate, "Auto-Rotate")
+                    .on_hover_text("Dragging the view turns it by hand and switches this off");
+                ui.checkbox(&mut self.show_hud, "Show HUD")
+                    .on_hover_text("Point count, bounding box, rotation and FPS over the view");
+                ui.checkbox(&mut self.show_labels, "Show Labels");
+                ui.checkbox(&mut self.axis_labels, "Axis Labels");
 
                 if ui.button("Reset View").clicked() {
                     self.rotation_x = 0.3;
@@ -162,12 +560,15 @@ impl Viewer3D {
                     ui.label("Color Mode:");
                     egui::ComboBox::from_label("")
                         .selected_text(match self.color_mode {
+                            ColorMode::Simulation => "Simulation",
                             ColorMode::Rainbow => "Rainbow",
                             ColorMode::Depth => "Depth",
                             ColorMode::Velocity => "Velocity",
                             ColorMode::Solid => "Solid",
                         })
                         .show_ui(ui, |ui| {
+                            ui.selectable_value(&mut self.color_mode, ColorMode::Simulation, "Simulation")
+                                .on_hover_text("Colors chosen by the simulation, where it has any; Rainbow otherwise");
                             ui.selectable_value(&mut self.color_mode, ColorMode::Rainbow, "Rainbow");
                             ui.selectable_value(&mut self.color_mode, ColorMode::Depth, "Depth");
                             ui.selectable_value(&mut self.color_mode, ColorMode::Velocity, "Velocity");
@@ -175,6 +576,23 @@ impl Viewer3D {
                         });
                 });
 
+                ui.checkbox(&mut self.additive_glow, "Additive Glow")
+                    .on_hover_text("Overlapping points add up and bloom; off draws crisp opaque points");
+
+                ui.add_enabled_ui(self.additive_glow, |ui| {
+                    tone_ui(ui, &mut self.exposure, &mut self.tone_map);
+                });
+
+                ui.add_enabled_ui(!self.additive_glow, |ui| {
+                    ui.add(egui::Slider::new(&mut self.light_azimuth, -std::f32::consts::PI..=std::f32::consts::PI)
+                        .nudge(ui, 0.05)
+                        .text("Light Azimuth"))
+                        .on_hover_text("Light direction for opaque points, left/right of the camera");
+                    ui.add(egui::Slider::new(&mut self.light_elevation, -std::f32::consts::FRAC_PI_2..=std::f32::consts::FRAC_PI_2)
+                        .nudge(ui, 0.05)
+                        .text("Light Elevation"));
+                });
+
                 ui.horizontal(|ui| {
                     ui.label("Background:");
                     egui::ComboBox::from_label(" ")
@@ -190,12 +608,77 @@ impl Viewer3D {
                         });
                 });
             });
+
+        // Every 3D frame is rendered anyway, so there's nothing to redraw
+        self.persistence.ui(ui);
+    }
+
+    /// Resolution for PNG exports; returns true when a save is requested
+    pub fn png_export_ui(&mut self, ui: &mut egui::Ui) -> bool {
+        let mut save = false;
+
+        egui::CollapsingHeader::new("📷 PNG Export")
+            .show(ui, |ui| {
+                let (width, height) = &mut self.png_size;
+                ui.horizontal(|ui| {
+                    ui.add(egui::DragValue::new(width).range(16..=MAX_PNG_SIDE).suffix(" px"));
+                    ui.label("×");
+                    ui.add(egui::DragValue::new(height).range(16..=MAX_PNG_SIDE).suffix(" px"));
+                });
+
+                ui.horizontal(|ui| {
+                    let (view_width, view_height) = self.size;
+                    for factor in [2, 4] {
+                        let size = ((view_width * factor).min(MAX_PNG_SIDE), (view_height * factor).min(MAX_PNG_SIDE));
+                        if ui.add_enabled(view_width > 0, egui::Button::new(format!("{factor}× View"))).clicked() {
+                            self.png_size = size;
+                        }
+                    }
+                    if ui.button("4K").clicked() {
+                        self.png_size = (3840, 2160);
+                    }
+                });
+
+                ui.label(egui::RichText::new("Framed like the view, with points scaled to match").weak());
+                save = ui.button("💾 Save PNG…").clicked();
+            });
+
+        save
     }
 
-    pub fn show(&mut self, ui: &mut egui::Ui, simulation: &Box<dyn Simulation3D>) {
+    /// Frame count and tile size for turntable sheets; returns true when a
+    /// save is requested
+    pub fn turntable_ui(&mut self, ui: &mut egui::Ui) -> bool {
+        let mut save = false;
 
-        // Auto-rotation
-        if self.auto_rotate {
+        egui::CollapsingHeader::new("🔄 Turntable Sprite Sheet")
+            .show(ui, |ui| {
+                ui.add(egui::Slider::new(&mut self.turntable_frames, 2..=72)
+                    .nudge(ui, 1.0)
+                    .text("Rotations"))
+                    .on_hover_text("Frames at evenly spaced turns about the Y axis, starting from the current view");
+                ui.horizontal(|ui| {
+                    ui.label("Tile:");
+                    ui.add(egui::DragValue::new(&mut self.turntable_tile).range(16..=MAX_TILE_SIDE).suffix(" px"));
+                });
+
+                let (rows, columns) = sheet_layout(self.turntable_frames);
+                let tile = self.turntable_tile;
+                ui.label(egui::RichText::new(format!(
+                    "{rows} rows × {columns} columns, {}×{} px sheet",
+                    columns * tile, rows * tile,
+                )).weak());
+                save = ui.button("💾 Save Sprite Sheet…").clicked();
+            });
+
+        save
+    }
+
+    pub fn show(&mut self, ui: &mut egui::Ui, simulation: &mut Box<dyn Simulation3D>) {
+
+        // Auto-rotation, held while a source is dragged so it stays under
+        // the pointer
+        if self.auto_rotate && self.dragging_source.is_none() {
             let dt = ui.input(|i| i.stable_dt);
             self.rotation_y += dt * 0.3;
             if self.rotation_y > std::f32::consts::TAU {
@@ -203,8 +686,15 @@ impl Viewer3D {
             }
         }
 
-        // Get 3D points
-        let points_3d = simulation.get_points();
+        let dt = ui.input(|i| i.unstable_dt);
+        if dt > 0.0 {
+            let instant = 1.0 / dt;
+            self.fps = if self.fps > 0.0 { self.fps * 0.9 + instant * 0.1 } else { instant };
+        }
+
+        // Get 3D points, and the lines of sims drawn with strokes
+        let geometry = Geometry::of(simulation.as_ref());
+        let points_3d = &geometry.points;
 
         if points_3d.is_empty() {
             ui.label("Generating visualization...");
@@ -213,13 +703,103 @@ impl Viewer3D {
 
         // Get available space and render
         let available_size = ui.available_size();
-        let width = available_size.x as usize;
-        let height = available_size.y as usize;
+        let (width, height) = self.capture_size
+            .unwrap_or((available_size.x as usize, available_size.y as usize));
 
         if width == 0 || height == 0 {
             return;
         }
+        self.size = (width, height);
+
+        let frame = self.render_frame(&geometry, width, height, 1.0, None);
+        let max_points = frame.drawn;
+
+        // Project labels now, while the depth buffer and this frame's camera
+        // are at hand
+        let labels = self.project_labels(simulation.as_ref(), points_3d, &frame.depth, width, height, frame.scale, frame.range);
+
+        let mut pixels = frame.pixels;
+        self.persistence.apply(&mut pixels, width, height, simulation.name());
+
+        // Create texture from pixels
+        let color_image = egui::ColorImage {
+            size: [width, height],
+            pixels,
+        };
+        if self.capture_size.is_some() {
+            self.captured = Some(color_image.clone());
+        }
+
+        if let Some(texture) = &mut self.texture {
+            texture.set(color_image, Default::default());
+        } else {
+            self.texture = Some(ui.ctx().load_texture(
+                "3d_view",
+                color_image,
+                Default::default(),
+            ));
+        }
+
+        // Display texture with interactive controls
+        if let Some(texture_id) = self.texture.as_ref().map(|t| t.id()) {
+            let size = egui::vec2(width as f32, height as f32);
+            let response = ui.allocate_rect(
+                egui::Rect::from_min_size(ui.cursor().min, size),
+                egui::Sense::drag(),
+            );
+
+            self.drag_sources(ui, &response, simulation.as_mut(), width, height, frame.scale);
+            self.drag_rotate(ui, &response);
+
+            // Handle mousewheel for zoom control
+            if response.hovered() {
+                ui.input(|i| {
+                    let scroll_delta = i.smooth_scroll_delta.y;
+                    if scroll_delta.abs() > 0.1 {
+                        // Adjust zoom based on scroll direction
+                        let zoom_factor = 1.0 + scroll_delta * 0.001;
+                        self.zoom = (self.zoom * zoom_factor).clamp(0.5, 5.0);
+                    }
+                });
+            }
+
+            ui.painter().image(
+                texture_id,
+                response.rect,
+                egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0)),
+                egui::Color32::WHITE,
+            );
+
+            // Poincaré section inset in the top-right corner
+            if let Some(section) = simulation.poincare_section().filter(|s| s.enabled) {
+                let side = (response.rect.width().min(response.rect.height()) * 0.3).clamp(120.0, 260.0);
+                let inset = egui::Rect::from_min_size(
+                    egui::pos2(response.rect.right() - side - 8.0, response.rect.top() + 8.0),
+                    egui::vec2(side, side),
+                );
+                section.paint(&ui.painter_at(inset), inset);
+            }
+
+            let painter = ui.painter_at(response.rect);
+            for (pos, text) in &labels {
+                let pos = response.rect.min + *pos;
+                let font = egui::FontId::proportional(13.0);
+                painter.text(pos + egui::vec2(1.0, 1.0), egui::Align2::LEFT_BOTTOM, text, font.clone(), egui::Color32::BLACK);
+                painter.text(pos, egui::Align2::LEFT_BOTTOM, text, font, egui::Color32::WHITE);
+            }
+
+            if self.show_hud {
+                self.hud(ui.ctx(), response.rect, points_3d, max_points);
+            }
+        }
+    }
 
+    /// Draw `points_3d` at `width`x`height`. `magnification` scales
+    /// positions and point sizes together: 1 for the view, more for a larger
+    /// render of the same framing.
+    /// Screen extent of the points under the current rotation, in world
+    /// units, which the auto-scaling fits to the view
+    fn view_range(&self, points_3d: &[[f32; 3]]) -> f32 {
         // Calculate bounding box for auto-scaling
         let mut min_val = f32::MAX;
         let mut max_val = f32::MIN;
@@ -233,15 +813,30 @@ impl Viewer3D {
         }
 
         // Ensure we have a valid range, even for single points or small point sets
-        let range = if max_val > min_val {
+        if max_val > min_val {
             (max_val - min_val).max(0.1)
         } else {
             // Single point or all points at same location - use a default range
             10.0
-        };
+        }
+    }
 
-        let target_size = width.min(height) as f32 * 0.7;
+    /// Render the points and segments at `width`x`height`. `range`
+    /// overrides the auto-scaling extent, to keep the scale fixed across
+    /// several frames.
+    fn render_frame(&self, geometry: &Geometry, width: usize, height: usize,
+                    magnification: f32, range: Option<f32>) -> RenderedFrame {
+        let points_3d = &geometry.points;
+        let range = range.unwrap_or_else(|| self.view_range(points_3d));
+        // Sims that color their own points (e.g. attractors colored by
+        // curvature) only do so in the Simulation color mode
+        let sim_colors = geometry.colors.as_deref().filter(|_| self.color_mode == ColorMode::Simulation);
+
+        // Fit to the view size, then enlarge everything together, so a
+        // magnified render is framed exactly like the view
+        let target_size = width.min(height) as f32 / magnification * 0.7;
         let auto_scale = ((target_size / range).min(50.0)).max(0.5); // Cap scaling between 0.5x and 50x
+        let scale = auto_scale * magnification;
 
         // Create pixel buffer
         let mut pixels = vec![egui::Color32::BLACK; width * height];
@@ -287,112 +882,284 @@ impl Viewer3D {
             BackgroundStyle::Black => {}
         }
 
+        // Points are drawn into a float buffer so additive glow can exceed white
+        // until tone mapping
+        let mut frame: Vec<[f32; 3]> = pixels
+            .iter()
+            .map(|c| [c.r() as f32, c.g() as f32, c.b() as f32])
+            .collect();
+
         // Project and sort points by depth (back to front)
         let mut projected: Vec<(f32, f32, f32, usize)> = points_3d
             .iter()
             .enumerate()
             .map(|(i, &p)| {
-                let (x, y, z) = self.project_to_screen(p, width as f32, height as f32, auto_scale);
+                let (x, y, z) = self.project_to_screen(p, width as f32, height as f32, scale);
                 (x, y, z, i)
             })
             .collect();
 
         projected.sort_by(|a, b| a.2.partial_cmp(&b.2).unwrap_or(std::cmp::Ordering::Equal));
 
-        // Draw all points as spheres with glow (limit to avoid hanging)
-        let max_points = projected.len().min(5000);
+        // Only used by the opaque path; additive blending is order-independent
+        let mut depth = if self.additive_glow {
+            Vec::new()
+        } else {
+            vec![f32::INFINITY; width * height]
+        };
+
+        // Segments are depth-tested against the points through the same
+        // buffer, so they need no sorting of their own
+        for (i, &(a, b)) in geometry.segments.iter().enumerate() {
+            let start = self.project_to_screen(a, width as f32, height as f32, scale);
+            let end = self.project_to_screen(b, width as f32, height as f32, scale);
+            let half_width = |p: (f32, f32, f32), world_width: Option<f32>| {
+                let perspective_scale = 300.0 / (300.0 + p.2);
+                let half = match world_width {
+                    Some(w) => 0.5 * w * scale * self.zoom * perspective_scale,
+                    None => 0.5 * self.point_size * perspective_scale * (auto_scale / 10.0).max(0.5) * magnification,
+                };
+                half.clamp(0.5, 100.0 * magnification)
+            };
+            let world_width = geometry.widths.as_ref().map(|w| w[i]);
+            let half_widths = (half_width(start, world_width), half_width(end, world_width));
+
+            let t = i as f32 / geometry.segments.len() as f32;
+            let length = (0..3).map(|k| (b[k] - a[k]).powi(2)).sum::<f32>().sqrt();
+            let color = self.mode_color(t, (start.2 + end.2) / 2.0, Some(length));
+
+            self.draw_segment(&mut frame, &mut depth, width, height, start, end, half_widths, color, self.additive_glow);
+        }
+
+        // Draw all points as spheres (limit to avoid hanging)
+        let max_points = projected.len().min(self.max_points);
         for (screen_x, screen_y, z, i) in projected.iter().take(max_points) {
-            if *screen_x < -100.0 || *screen_x >= width as f32 + 100.0 ||
-               *screen_y < -100.0 || *screen_y >= height as f32 + 100.0 {
+            if *screen_x < -100.0 * magnification || *screen_x >= width as f32 + 100.0 * magnification ||
+               *screen_y < -100.0 * magnification || *screen_y >= height as f32 + 100.0 * magnification {
                 continue;
             }
 
             let t = *i as f32 / points_3d.len() as f32;
 
-            let color = match self.color_mode {
-                ColorMode::Rainbow => {
-                    let hue = t * 360.0;
-                    let (r, g, b) = hsv_to_rgb(hue, 0.9, 1.0);
-                    egui::Color32::from_rgb(r, g, b)
-                }
-                ColorMode::Depth => {
-                    let z_norm = (*z + 100.0) / 200.0;
-                    let intensity = z_norm.clamp(0.0, 1.0);
-                    egui::Color32::from_rgb(
-                        (255.0 * (1.0 - intensity)) as u8,
-                        (150.0 * intensity) as u8,
-                        (255.0 * intensity) as u8,
-                    )
-                }
-                ColorMode::Velocity => {
-                    if *i > 0 && *i < points_3d.len() {
-                        let dx = points_3d[*i][0] - points_3d[i.saturating_sub(1)][0];
-                        let dy = points_3d[*i][1] - points_3d[i.saturating_sub(1)][1];
-                        let dz = points_3d[*i][2] - points_3d[i.saturating_sub(1)][2];
-                        let velocity = (dx * dx + dy * dy + dz * dz).sqrt();
-                        let vel_t = (velocity * 0.5).min(1.0);
-                        egui::Color32::from_rgb(
-                            (255.0 * vel_t) as u8,
-                            (200.0 * (1.0 - vel_t)) as u8,
-                            (100.0) as u8,
-                        )
-                    } else {
-                        egui::Color32::from_rgb(100, 200, 100)
-                    }
-                }
-                ColorMode::Solid => egui::Color32::from_rgb(100, 200, 255),
+            let color = if let Some(colors) = sim_colors {
+                let c = colors[*i];
+                egui::Color32::from_rgb(c.r, c.g, c.b)
+            } else {
+                let velocity = (*i > 0).then(|| {
+                    let dx = points_3d[*i][0] - points_3d[i - 1][0];
+                    let dy = points_3d[*i][1] - points_3d[i - 1][1];
+                    let dz = points_3d[*i][2] - points_3d[i - 1][2];
+                    (dx * dx + dy * dy + dz * dz).sqrt()
+                });
+                self.mode_color(t, *z, velocity)
             };
 
             // Calculate size with perspective
             let perspective_scale = 300.0 / (300.0 + z);
-            let radius = (self.point_size * perspective_scale * (auto_scale / 10.0).max(0.5)).max(1.5);
+            let radius = (self.point_size * perspective_scale * (auto_scale / 10.0).max(0.5)).max(1.5) * magnification;
 
-            self.draw_sphere(&mut pixels, width, height, *screen_x, *screen_y, radius, color, true);
+            self.draw_sphere(&mut frame, &mut depth, width, height, *screen_x, *screen_y, *z, radius, color, self.additive_glow);
         }
 
-        // Create texture from pixels
-        let color_image = egui::ColorImage {
-            size: [width, height],
-            pixels,
-        };
+        RenderedFrame {
+            pixels: self.tone_map_frame(&frame),
+            depth,
+            scale,
+            range,
+            drawn: max_points,
+        }
+    }
 
-        if let Some(texture) = &mut self.texture {
-            texture.set(color_image, Default::default());
+    /// The current view rendered offscreen at `width`x`height`, with the
+    /// same rotation, zoom and framing as on screen and points scaled up to
+    /// match. Persistence trails and overlays are left out.
+    pub fn render_to_buffer(&self, simulation: &dyn Simulation3D, width: usize, height: usize) -> Vec<egui::Color32> {
+        let geometry = Geometry::of(simulation);
+        let magnification = self.magnification(width, height);
+        self.render_frame(&geometry, width, height, magnification, None).pixels
+    }
+
+    /// Point scale-up for an offscreen render of `width`x`height` to match
+    /// the view
+    fn magnification(&self, width: usize, height: usize) -> f32 {
+        let (view_width, view_height) = self.size;
+        if view_width > 0 && view_height > 0 {
+            width.min(height) as f32 / view_width.min(view_height) as f32
         } else {
-            self.texture = Some(ui.ctx().load_texture(
-                "3d_view",
-                color_image,
-                Default::default(),
-            ));
+            1.0
         }
+    }
 
-        // Display texture with interactive controls
-        if let Some(texture) = &self.texture {
-            let size = egui::vec2(width as f32, height as f32);
-            let response = ui.allocate_rect(
-                egui::Rect::from_min_size(ui.cursor().min, size),
-                egui::Sense::hover(),
-            );
+    /// A turntable sprite sheet: `turntable_frames` renders of the current
+    /// view, each turned a further 1/frames of a revolution about the Y
+    /// axis, packed row by row into a grid of square tiles. Every frame uses
+    /// the scale that fits the widest of the turns, so the object stays the
+    /// same size as it spins instead of breathing with each frame's extent.
+    /// Returns the sheet's pixels, width and height.
+    pub fn render_turntable(&mut self, simulation: &dyn Simulation3D) -> (Vec<egui::Color32>, usize, usize) {
+        let geometry = Geometry::of(simulation);
+        let frames = self.turntable_frames.max(1);
+        let tile = self.turntable_tile.clamp(16, MAX_TILE_SIDE);
+        let (rows, columns) = sheet_layout(frames);
+        let (sheet_width, sheet_height) = (columns * tile, rows * tile);
 
-            // Handle mousewheel for zoom control
-            if response.hovered() {
-                ui.input(|i| {
-                    let scroll_delta = i.smooth_scroll_delta.y;
-                    if scroll_delta.abs() > 0.1 {
-                        // Adjust zoom based on scroll direction
-                        let zoom_factor = 1.0 + scroll_delta * 0.001;
-                        self.zoom = (self.zoom * zoom_factor).clamp(0.5, 5.0);
-                    }
-                });
+        let start = self.rotation_y;
+        let turn = |k: usize| start + k as f32 * std::f32::consts::TAU / frames as f32;
+        let mut range = 0.0f32;
+        for k in 0..frames {
+            self.rotation_y = turn(k);
+            range = range.max(self.view_range(&geometry.points));
+        }
+
+        let magnification = self.magnification(tile, tile);
+        let mut sheet = vec![egui::Color32::BLACK; sheet_width * sheet_height];
+        for k in 0..frames {
+            self.rotation_y = turn(k);
+            let frame = self.render_frame(&geometry, tile, tile, magnification, Some(range));
+            let (left, top) = ((k % columns) * tile, (k / columns) * tile);
+            for (y, row) in frame.pixels.chunks(tile).enumerate() {
+                let offset = (top + y) * sheet_width + left;
+                sheet[offset..offset + tile].copy_from_slice(row);
             }
+        }
+        self.rotation_y = start;
 
-            ui.painter().image(
-                texture.id(),
-                response.rect,
-                egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0)),
-                egui::Color32::WHITE,
-            );
+        (sheet, sheet_width, sheet_height)
+    }
+
+    /// Pick up the source nearest a press on the view and move it with the
+    /// pointer. The sim sees the new position on its next step.
+    fn drag_sources(&mut self, ui: &egui::Ui, response: &egui::Response, simulation: &mut dyn Simulation3D,
+                    width: usize, height: usize, scale: f32) {
+        let sources = simulation.sources();
+        let on_screen: Vec<egui::Pos2> = sources
+            .iter()
+            .map(|&p| {
+                let (x, y, _) = self.project_to_screen(p, width as f32, height as f32, scale);
+                response.rect.min + egui::vec2(x, y)
+            })
+            .collect();
+        let nearest = |pointer: egui::Pos2| {
+            on_screen
+                .iter()
+                .enumerate()
+                .map(|(i, p)| (i, p.distance(pointer)))
+                .filter(|&(_, distance)| distance <= SOURCE_GRAB_RADIUS)
+                .min_by(|a, b| a.1.total_cmp(&b.1))
+                .map(|(i, _)| i)
+        };
+
+        if response.drag_started() {
+            self.dragging_source = ui.input(|i| i.pointer.press_origin()).and_then(nearest);
+        } else if !response.dragged() {
+            self.dragging_source = None;
+        }
+
+        match self.dragging_source.filter(|&i| i < sources.len()) {
+            Some(index) => {
+                let delta = response.drag_delta();
+                if delta != egui::Vec2::ZERO {
+                    simulation.move_source(index, self.screen_to_world_delta(sources[index], delta, scale));
+                }
+                ui.ctx().set_cursor_icon(egui::CursorIcon::Grabbing);
+            }
+            None => {
+                if response.hover_pos().and_then(nearest).is_some() {
+                    ui.ctx().set_cursor_icon(egui::CursorIcon::Grab);
+                }
+            }
+        }
+    }
+
+    /// Screen offsets (from the image's top-left) and text of the labels to
+    /// draw. With opaque points, a label is hidden when the depth buffer has
+    /// something clearly nearer at its pixel.
+    #[allow(clippy::too_many_arguments)]
+    fn project_labels(&self, simulation: &dyn Simulation3D, points: &[[f32; 3]], depth: &[f32],
+                      width: usize, height: usize, scale: f32, range: f32) -> Vec<(egui::Vec2, String)> {
+        let mut labels = Vec::new();
+        if self.show_labels {
+            labels = simulation.labels();
+        }
+
+        if self.axis_labels {
+            let extent = points
+                .iter()
+                .flat_map(|p| p.iter().copied())
+                .filter(|v| v.is_finite())
+                .fold(0.0f32, |m, v| m.max(v.abs()))
+                .max(1.0) * 1.1;
+            labels.push(([extent, 0.0, 0.0], "X".to_string()));
+            labels.push(([0.0, extent, 0.0], "Y".to_string()));
+            labels.push(([0.0, 0.0, extent], "Z".to_string()));
+        }
+
+        let tolerance = range * 0.01;
+        labels
+            .into_iter()
+            .filter_map(|(world, text)| {
+                let (x, y, z) = self.project_to_screen(world, width as f32, height as f32, scale);
+                if !(x >= 0.0 && x < width as f32 && y >= 0.0 && y < height as f32) {
+                    return None;
+                }
+
+                if !depth.is_empty() && depth[y as usize * width + x as usize] < z - tolerance {
+                    return None;
+                }
+
+                Some((egui::vec2(x + 4.0, y - 4.0), text))
+            })
+            .collect()
+    }
+
+    /// Debug overlay in the top-left corner of `rect`. The bounding box covers
+    /// every point, not just the sample used for auto-scaling.
+    fn hud(&self, ctx: &egui::Context, rect: egui::Rect, points: &[[f32; 3]], drawn: usize) {
+        let mut min = [f32::MAX; 3];
+        let mut max = [f32::MIN; 3];
+        for p in points.iter().filter(|p| p.iter().all(|v| v.is_finite())) {
+            for axis in 0..3 {
+                min[axis] = min[axis].min(p[axis]);
+                max[axis] = max[axis].max(p[axis]);
+            }
         }
+
+        egui::Area::new(egui::Id::new("viewer_3d_hud"))
+            .fixed_pos(rect.left_top() + egui::vec2(8.0, 8.0))
+            .order(egui::Order::Foreground)
+            .interactable(false)
+            .show(ctx, |ui| {
+                egui::Frame::none()
+                    .fill(egui::Color32::from_black_alpha(180))
+                    .rounding(4.0)
+                    .inner_margin(6.0)
+                    .show(ui, |ui| {
+                        let line = |ui: &mut egui::Ui, text: String| {
+                            ui.label(egui::RichText::new(text).monospace().size(11.0).color(egui::Color32::LIGHT_GRAY));
+                        };
+
+                        line(ui, format!("FPS      {}", readout::decimal(self.fps, 0)));
+                        if drawn < points.len() {
+                            line(ui, format!("Points   {} ({} drawn)", readout::count(points.len()), readout::count(drawn)));
+                        } else {
+                            line(ui, format!("Points   {}", readout::count(points.len())));
+                        }
+                        if min[0] <= max[0] {
+                            line(ui, format!(
+                                "Size     {:.2} x {:.2} x {:.2}",
+                                max[0] - min[0], max[1] - min[1], max[2] - min[2],
+                            ));
+                            line(ui, format!("Min      ({:.2}, {:.2}, {:.2})", min[0], min[1], min[2]));
+                            line(ui, format!("Max      ({:.2}, {:.2}, {:.2})", max[0], max[1], max[2]));
+                        }
+                        line(ui, format!(
+                            "Rotation x {:.1}°  y {:.1}°",
+                            self.rotation_x.to_degrees(),
+                            self.rotation_y.to_degrees(),
+                        ));
+                        line(ui, format!("Zoom     {:.2}", self.zoom));
+                    });
+            });
     }
 }
 
@@ -421,3 +1188,39 @@ fn hsv_to_rgb(h: f32, s: f32, v: f32) -> (u8, u8, u8) {
         ((b + m) * 255.0) as u8,
     )
 }
+
+/// Part of the line from `a` to `b` inside the rectangle `lo`..`hi`, as a
+/// range of the line's parameter (0 at `a`, 1 at `b`); Liang-Barsky
+fn clip_to_rect(a: [f32; 2], b: [f32; 2], lo: [f32; 2], hi: [f32; 2]) -> Option<(f32, f32)> {
+    if !(a.iter().chain(&b).all(|v| v.is_finite())) {
+        return None;
+    }
+
+    let (mut t0, mut t1) = (0.0f32, 1.0f32);
+    for axis in 0..2 {
+        let d = b[axis] - a[axis];
+        for (p, q) in [(-d, a[axis] - lo[axis]), (d, hi[axis] - a[axis])] {
+            if p == 0.0 {
+                if q < 0.0 {
+                    return None;
+                }
+            } else {
+                let t = q / p;
+                if p < 0.0 {
+                    t0 = t0.max(t);
+                } else {
+                    t1 = t1.min(t);
+                }
+            }
+        }
+    }
+    (t0 <= t1).then_some((t0, t1))
+}
+
+/// Rows and columns of a sprite sheet holding `frames` tiles: as close to
+/// square as possible, columns first
+fn sheet_layout(frames: usize) -> (usize, usize) {
+    let frames = frames.max(1);
+    let columns = (frames as f64).sqrt().ceil() as usize;
+    (frames.div_ceil(columns), columns)
+}
@@ -0,0 +1,43 @@
+//! Full 1600x1200 renders (an 800x600 view at 2x scale) of the escape-time
+//! fractals, iterating and coloring. Each sample gets a fresh sim, so the
+//! iteration cache is always cold; the recolor-only path is timed on its
+//! own.
+//!
+//! cargo bench -p sim-core --bench fractal_render
+
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+use sim_core::burning_ship::BurningShip;
+use sim_core::julia::Julia;
+use sim_core::mandelbrot::Mandelbrot;
+use sim_core::Simulation2D;
+
+const WIDTH: usize = 1600;
+const HEIGHT: usize = 1200;
+
+type MakeSim = fn() -> Box<dyn Simulation2D>;
+
+fn fractals(c: &mut Criterion) {
+    let mut group = c.benchmark_group("fractal_render");
+    group.sample_size(10);
+
+    let sims: [(&str, MakeSim); 3] = [
+        ("mandelbrot", || Box::new(Mandelbrot::new())),
+        ("julia", || Box::new(Julia::new())),
+        ("burning_ship", || Box::new(BurningShip::new())),
+    ];
+    for (name, make) in sims {
+        group.bench_function(name, |b| {
+            b.iter_batched(make, |sim| sim.compute(WIDTH, HEIGHT), BatchSize::PerIteration)
+        });
+    }
+
+    // Palette changes reuse the cached iterations and only recolor
+    let mandelbrot = Mandelbrot::new();
+    mandelbrot.compute(WIDTH, HEIGHT);
+    group.bench_function("mandelbrot_recolor", |b| b.iter(|| mandelbrot.compute(WIDTH, HEIGHT)));
+
+    group.finish();
+}
+
+criterion_group!(benches, fractals);
+criterion_main!(benches);
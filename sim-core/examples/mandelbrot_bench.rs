@@ -0,0 +1,63 @@
+//! Time the scalar and SIMD Mandelbrot paths on a few views and check that
+//! they produce the same image.
+//!
+//! cargo run --release -p sim-core --features simd --example mandelbrot_bench
+
+use sim_core::mandelbrot::Mandelbrot;
+use sim_core::Simulation2D;
+use std::time::{Duration, Instant};
+
+const WIDTH: usize = 1920;
+const HEIGHT: usize = 1080;
+const RUNS: u32 = 5;
+
+fn view(center_x: f64, center_y: f64, zoom: f64, max_iterations: u32, simd: bool) -> Mandelbrot {
+    let mut sim = Mandelbrot::new();
+    sim.center_x = center_x;
+    sim.center_y = center_y;
+    sim.zoom = zoom;
+    sim.max_iterations = max_iterations;
+    sim.simd = simd;
+    sim
+}
+
+/// Best of `RUNS`, with a fresh sim each time so the iteration cache is cold
+fn time(make: impl Fn() -> Mandelbrot) -> Duration {
+    (0..RUNS)
+        .map(|_| {
+            let sim = make();
+            let start = Instant::now();
+            std::hint::black_box(sim.compute(WIDTH, HEIGHT));
+            start.elapsed()
+        })
+        .min()
+        .unwrap()
+}
+
+fn main() {
+    let views = [
+        ("Full set", -0.5, 0.0, 1.0, 200),
+        ("Seahorse Valley", -0.75, 0.1, 100.0, 500),
+        ("Mini Mandelbrot", -0.7453, 0.1127, 5000.0, 1000),
+    ];
+
+    println!("{WIDTH}x{HEIGHT}, best of {RUNS}");
+    for (name, x, y, zoom, iterations) in views {
+        let scalar = view(x, y, zoom, iterations, false);
+        let simd = view(x, y, zoom, iterations, true);
+        let identical = scalar.compute(WIDTH, HEIGHT)
+            .iter()
+            .zip(simd.compute(WIDTH, HEIGHT))
+            .all(|(a, b)| (a.r, a.g, a.b) == (b.r, b.g, b.b));
+
+        let t_scalar = time(|| view(x, y, zoom, iterations, false));
+        let t_simd = time(|| view(x, y, zoom, iterations, true));
+        println!(
+            "{name:<16} scalar {:>8.1} ms   simd {:>8.1} ms   {:.2}x   {}",
+            t_scalar.as_secs_f64() * 1000.0,
+            t_simd.as_secs_f64() * 1000.0,
+            t_scalar.as_secs_f64() / t_simd.as_secs_f64(),
+            if identical { "identical" } else { "MISMATCH" },
+        );
+    }
+}
@@ -1,4 +1,30 @@
-use crate::{ColorScheme, Simulation3D};
+use crate::color_ui::color_scheme_combo;
+use crate::nudge::Nudge;
+use crate::poincare::{PoincareSection, SectionAxis};
+use crate::seed::{SeedEdit, SeedPoint, SeedPreset};
+use crate::trajectory::{TrailEmitter, TrajectoryColoring};
+use crate::{Color, ColorScheme, Simulation3D};
+
+/// Seed points, with what the orbit does from each under the default
+/// parameters. The z axis is invariant and its orbits end on stable points,
+/// so none of them sit on it.
+const SEEDS: [SeedPreset; 3] = [
+    SeedPreset {
+        name: "Off Axis",
+        point: [0.1, 0.0, 0.0],
+        behavior: "Joins the apple-shaped tube within a few dozen steps",
+    },
+    SeedPreset {
+        name: "Top Pole",
+        point: [0.01, 0.0, 1.9],
+        behavior: "Starts on the central axis just below the top pole and spirals straight out into the tube",
+    },
+    SeedPreset {
+        name: "Wide Start",
+        point: [1.5, 0.0, 0.0],
+        behavior: "Starts well outside the tube, falls inward and is captured after a couple of turns",
+    },
+];
 
 pub struct AizawaAttractor {
     pub a: f32,
@@ -11,6 +37,10 @@ pub struct AizawaAttractor {
     pub point_count: usize,
     pub trail_length: usize,
     pub color_scheme: ColorScheme,
+    pub trajectory_coloring: TrajectoryColoring,
+    pub emitter: TrailEmitter,
+    pub section: PoincareSection,
+    pub seed: SeedPoint,
     pub color_by_velocity: bool,
     pub color_by_height: bool,
     pub auto_rotate: bool,
@@ -36,6 +66,10 @@ impl Default for AizawaAttractor {
             point_count: 5000,
             trail_length: 5000,
             color_scheme: ColorScheme::Rainbow,
+            trajectory_coloring: TrajectoryColoring::Index,
+            emitter: TrailEmitter::new(),
+            section: PoincareSection::new(SectionAxis::X, 0.0),
+            seed: SeedPoint::new(2.0, &SEEDS),
             color_by_velocity: false,
             color_by_height: true,
             auto_rotate: true,
@@ -54,6 +88,17 @@ impl AizawaAttractor {
     pub fn new() -> Self {
         Self::default()
     }
+
+    /// Aizawa equations
+    fn derivatives(&self, [x, y, z]: [f32; 3]) -> [f32; 3] {
+        [
+            (z - self.b) * x - self.d * y,
+            self.d * x + (z - self.b) * y,
+            self.c + self.a * z - (z * z * z) / 3.0
+                - (x * x + y * y) * (1.0 + self.e * z)
+                + self.f * z * x * x * x,
+        ]
+    }
 }
 
 impl Simulation3D for AizawaAttractor {
@@ -61,30 +106,29 @@ impl Simulation3D for AizawaAttractor {
         "Aizawa Attractor"
     }
 
+    fn equations(&self) -> Option<&str> {
+        Some(
+            "dx/dt = (z − b) x − d y\n\
+             dy/dt = d x + (z − b) y\n\
+             dz/dt = c + a z − z³/3 − (x² + y²)(1 + e z) + f z x³",
+        )
+    }
+
     fn step(&mut self, dt: f32) {
         self.time += dt;
 
         for _ in 0..10 {
-            // Aizawa equations
-            let dx = (self.z - self.b) * self.x - self.d * self.y;
-            let dy = self.d * self.x + (self.z - self.b) * self.y;
-            let dz = self.c + self.a * self.z - (self.z * self.z * self.z) / 3.0
-                   - (self.x * self.x + self.y * self.y) * (1.0 + self.e * self.z)
-                   + self.f * self.z * self.x * self.x * self.x;
+            let [dx, dy, dz] = self.derivatives([self.x, self.y, self.z]);
 
+            let prev = [self.x, self.y, self.z];
             self.x += dx * self.dt;
             self.y += dy * self.dt;
             self.z += dz * self.dt;
+            self.section.record(prev, [self.x, self.y, self.z]);
 
-            self.points.push([
-                self.x * self.scale,
-                self.y * self.scale,
-                self.z * self.scale,
-            ]);
-
-            if self.points.len() > self.trail_length {
-                self.points.remove(0);
-            }
+            let scale = self.scale;
+            let next = [self.x * scale, self.y * scale, self.z * scale];
+            self.emitter.push(&mut self.points, prev.map(|v| v * scale), next, self.trail_length);
         }
     }
 
@@ -92,6 +136,14 @@ impl Simulation3D for AizawaAttractor {
         self.points.clone()
     }
 
+    fn get_colors(&self) -> Option<Vec<Color>> {
+        self.emitter.colors(&self.points, self.trajectory_coloring, &self.color_scheme)
+    }
+
+    fn poincare_section(&self) -> Option<&PoincareSection> {
+        Some(&self.section)
+    }
+
     fn ui_parameters(&mut self, ui: &mut egui::Ui) -> bool {
         let mut changed = false;
 
@@ -101,16 +153,22 @@ impl Simulation3D for AizawaAttractor {
             .default_open(true)
             .show(ui, |ui| {
                 changed |= ui.add(egui::Slider::new(&mut self.a, 0.0..=2.0)
+                    .nudge(ui, 0.02)
                     .text("a")).changed();
                 changed |= ui.add(egui::Slider::new(&mut self.b, 0.0..=2.0)
+                    .nudge(ui, 0.02)
                     .text("b")).changed();
                 changed |= ui.add(egui::Slider::new(&mut self.c, 0.0..=2.0)
+                    .nudge(ui, 0.02)
                     .text("c")).changed();
                 changed |= ui.add(egui::Slider::new(&mut self.d, 0.0..=5.0)
+                    .nudge(ui, 0.05)
                     .text("d")).changed();
                 changed |= ui.add(egui::Slider::new(&mut self.e, 0.0..=1.0)
+                    .nudge(ui, 0.01)
                     .text("e")).changed();
                 changed |= ui.add(egui::Slider::new(&mut self.f, 0.0..=1.0)
+                    .nudge(ui, 0.01)
                     .text("f")).changed();
             });
 
@@ -118,22 +176,29 @@ impl Simulation3D for AizawaAttractor {
             .default_open(true)
             .show(ui, |ui| {
                 changed |= ui.add(egui::Slider::new(&mut self.point_count, 100..=10000)
+                    .nudge(ui, 1.0)
                     .text("Point Count")).changed();
                 changed |= ui.add(egui::Slider::new(&mut self.trail_length, 100..=10000)
+                    .nudge(ui, 1.0)
                     .text("Trail Length")).changed();
+                changed |= self.emitter.ui(ui, &mut self.points, self.trail_length);
                 changed |= ui.add(egui::Slider::new(&mut self.scale, 10.0..=100.0)
+                    .nudge(ui, 0.5)
                     .text("Scale")).changed();
                 changed |= ui.add(egui::Slider::new(&mut self.dt, 0.001..=0.05)
+                    .nudge(ui, 0.0002)
                     .text("Time Step")).changed();
             });
 
         egui::CollapsingHeader::new("🎨 Color Settings")
             .show(ui, |ui| {
-                egui::ComboBox::from_label("Color Scheme")
-                    .selected_text(self.color_scheme.name())
+                changed |= color_scheme_combo(ui, &mut self.color_scheme);
+
+                egui::ComboBox::from_label("Color By")
+                    .selected_text(self.trajectory_coloring.name())
                     .show_ui(ui, |ui| {
-                        for scheme in ColorScheme::all() {
-                            if ui.selectable_value(&mut self.color_scheme, scheme, scheme.name()).clicked() {
+                        for coloring in TrajectoryColoring::all() {
+                            if ui.selectable_value(&mut self.trajectory_coloring, coloring, coloring.name()).clicked() {
                                 changed = true;
                             }
                         }
@@ -143,6 +208,8 @@ impl Simulation3D for AizawaAttractor {
                 changed |= ui.checkbox(&mut self.color_by_velocity, "Color by Velocity").changed();
             });
 
+        self.section.ui(ui, -2.0..=2.0, &self.points);
+
         egui::CollapsingHeader::new("🎯 Presets")
             .show(ui, |ui| {
                 if ui.button("Classic").clicked() {
@@ -165,11 +232,19 @@ impl Simulation3D for AizawaAttractor {
                 }
             });
 
+        let edit = self.seed.ui(ui);
+        if edit == SeedEdit::Randomize {
+            if let Some(point) = self.seed.random(|p| self.derivatives(p), self.dt) {
+                self.seed.point = point;
+            }
+        }
+        if edit != SeedEdit::None {
+            self.reset();
+            changed = true;
+        }
+
         if ui.button("🔄 Reset").clicked() {
-            self.points.clear();
-            self.x = 0.1;
-            self.y = 0.0;
-            self.z = 0.0;
+            self.reset();
             changed = true;
         }
 
@@ -178,8 +253,8 @@ impl Simulation3D for AizawaAttractor {
 
     fn reset(&mut self) {
         self.points.clear();
-        self.x = 0.1;
-        self.y = 0.0;
-        self.z = 0.0;
+        self.emitter.clear();
+        self.section.clear();
+        [self.x, self.y, self.z] = self.seed.start(|p| self.derivatives(p));
     }
 }
@@ -1,4 +1,6 @@
 use crate::Simulation3D;
+use crate::nudge::Nudge;
+use crate::readout;
 
 struct Boid3D {
     position: [f32; 3],
@@ -262,24 +264,28 @@ impl Simulation3D for Boids3D {
 
         ui.heading("3D Boids Flocking");
 
-        ui.label(format!("Boid count: {}", self.boids.len()));
+        ui.label(format!("Boid count: {}", readout::count(self.boids.len())));
 
         egui::CollapsingHeader::new("🐦 Boid Settings")
             .default_open(true)
             .show(ui, |ui| {
                 if ui.add(egui::Slider::new(&mut self.boid_count, 10..=150)
+                    .nudge(ui, 1.0)
                     .text("Boid Count")).changed() {
                     self.init_boids();
                     changed = true;
                 }
 
                 changed |= ui.add(egui::Slider::new(&mut self.max_speed, 0.5..=5.0)
+                    .nudge(ui, 0.02)
                     .text("Max Speed")).changed();
 
                 changed |= ui.add(egui::Slider::new(&mut self.max_force, 0.01..=0.5)
+                    .nudge(ui, 0.002)
                     .text("Max Force")).changed();
 
                 changed |= ui.add(egui::Slider::new(&mut self.bound_radius, 10.0..=50.0)
+                    .nudge(ui, 0.2)
                     .text("Bound Radius")).changed();
             });
 
@@ -287,27 +293,34 @@ impl Simulation3D for Boids3D {
             .default_open(true)
             .show(ui, |ui| {
                 changed |= ui.add(egui::Slider::new(&mut self.separation_radius, 1.0..=15.0)
+                    .nudge(ui, 0.1)
                     .text("Separation Radius")).changed();
 
                 changed |= ui.add(egui::Slider::new(&mut self.alignment_radius, 5.0..=25.0)
+                    .nudge(ui, 0.2)
                     .text("Alignment Radius")).changed();
 
                 changed |= ui.add(egui::Slider::new(&mut self.cohesion_radius, 5.0..=25.0)
+                    .nudge(ui, 0.2)
                     .text("Cohesion Radius")).changed();
 
                 ui.separator();
 
                 changed |= ui.add(egui::Slider::new(&mut self.separation_strength, 0.0..=5.0)
+                    .nudge(ui, 0.05)
                     .text("Separation")).changed();
 
                 changed |= ui.add(egui::Slider::new(&mut self.alignment_strength, 0.0..=5.0)
+                    .nudge(ui, 0.05)
                     .text("Alignment")).changed();
 
                 changed |= ui.add(egui::Slider::new(&mut self.cohesion_strength, 0.0..=5.0)
+                    .nudge(ui, 0.05)
                     .text("Cohesion")).changed();
             });
 
         ui.add(egui::Slider::new(&mut self.speed, 0.1..=3.0)
+            .nudge(ui, 0.02)
             .text("Speed"));
 
         if ui.button("🔄 Reset").clicked() {
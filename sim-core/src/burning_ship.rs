@@ -1,9 +1,9 @@
-use crate::{Color, ColorScheme, Simulation2D};
+use crate::color_ui::{color_scheme_combo, PaletteTransform};
+use crate::escape_time::{colorize, location_ui, scroll_zoom, EscapeKey, EscapeTimeCache, MAX_ZOOM};
+use crate::nudge::Nudge;
+use crate::{Color, ColorScheme, PlaneView, RenderCtx, Simulation2D};
 use num_complex::Complex64;
 
-#[cfg(feature = "parallel")]
-use rayon::prelude::*;
-
 pub struct BurningShip {
     pub max_iterations: u32,
     pub center_x: f64,
@@ -13,7 +13,8 @@ pub struct BurningShip {
     pub color_scheme: ColorScheme,
     pub smooth_coloring: bool,
     pub invert_colors: bool,
-    pub color_offset: f32,
+    pub palette_transform: PaletteTransform,
+    iteration_cache: EscapeTimeCache,
 }
 
 impl Default for BurningShip {
@@ -27,7 +28,8 @@ impl Default for BurningShip {
             color_scheme: ColorScheme::Fire,
             smooth_coloring: true,
             invert_colors: false,
-            color_offset: 0.0,
+            palette_transform: PaletteTransform::default(),
+            iteration_cache: EscapeTimeCache::default(),
         }
     }
 }
@@ -59,6 +61,18 @@ impl BurningShip {
         (self.max_iterations, self.max_iterations as f64)
     }
 
+    /// Everything that changes the iteration counts of a `width`x`height` view
+    fn escape_key(&self, width: usize, height: usize) -> EscapeKey {
+        EscapeKey {
+            width,
+            height,
+            params: vec![
+                self.center_x, self.center_y, self.zoom, self.escape_radius,
+                self.max_iterations as f64, self.smooth_coloring as u8 as f64,
+            ],
+        }
+    }
+
     fn pixel_to_complex(&self, x: usize, y: usize, width: usize, height: usize) -> Complex64 {
         let aspect = width as f64 / height as f64;
         let range = 4.0 / self.zoom;
@@ -69,13 +83,13 @@ impl BurningShip {
         Complex64::new(real, imag)
     }
 
-    fn iterations_to_color(&self, iterations: u32, smooth_iter: f64) -> Color {
+    fn iterations_to_color(&self, iterations: u32, smooth_iter: f64, ctx: &RenderCtx) -> Color {
         if iterations == self.max_iterations {
             return Color::BLACK;
         }
 
-        let t = ((smooth_iter / self.max_iterations as f64) as f32 + self.color_offset) % 1.0;
-        let color = self.color_scheme.map(t, self.smooth_coloring);
+        let t = (smooth_iter / self.max_iterations as f64) as f32;
+        let color = self.palette_transform.map(&self.color_scheme, ctx.cycle_palette(t), self.smooth_coloring);
 
         if self.invert_colors {
             Color::from_rgb(255 - color.r, 255 - color.g, 255 - color.b)
@@ -90,22 +104,30 @@ impl Simulation2D for BurningShip {
         "Burning Ship Fractal"
     }
 
+    fn equations(&self) -> Option<&str> {
+        Some(
+            "z ← (|Re z| + i |Im z|)² + c,  z₀ = 0, c = pixel\n\
+             Color = iterations before |z| escapes",
+        )
+    }
+
     fn compute(&self, width: usize, height: usize) -> Vec<Color> {
-        #[cfg(feature = "parallel")]
-        let iter = (0..height).into_par_iter();
-        #[cfg(not(feature = "parallel"))]
-        let iter = (0..height).into_iter();
-
-        iter.flat_map(|y| {
-            (0..width)
-                .map(|x| {
-                    let c = self.pixel_to_complex(x, y, width, height);
-                    let (iterations, smooth_iter) = self.burning_ship_iterations(c);
-                    self.iterations_to_color(iterations, smooth_iter)
-                })
-                .collect::<Vec<_>>()
-        })
-        .collect()
+        self.compute_ctx(&RenderCtx::new(width, height))
+    }
+
+    fn compute_ctx(&self, ctx: &RenderCtx) -> Vec<Color> {
+        let (width, height) = (ctx.width, ctx.height);
+
+        // Colors are cheap; only re-iterate when the geometry changes
+        let key = self.escape_key(width, height);
+        let iterations = self.iteration_cache.get_or_compute(key, self.max_iterations, |x, y| {
+            self.burning_ship_iterations(self.pixel_to_complex(x, y, width, height))
+        });
+
+        let pixels = colorize(&iterations, width, height, [0.0; 3], |i, smooth_iter| {
+            self.iterations_to_color(i, smooth_iter, ctx)
+        });
+        ctx.over_background(pixels)
     }
 
     fn ui_parameters(&mut self, ui: &mut egui::Ui) -> bool {
@@ -117,35 +139,29 @@ impl Simulation2D for BurningShip {
             .default_open(true)
             .show(ui, |ui| {
                 changed |= ui.add(egui::Slider::new(&mut self.max_iterations, 10..=1000)
+                    .nudge(ui, 1.0)
                     .text("Max Iterations")).changed();
 
                 changed |= ui.add(egui::Slider::new(&mut self.escape_radius, 2.0..=10.0)
+                    .nudge(ui, 0.05)
                     .text("Escape Radius")).changed();
             });
 
         egui::CollapsingHeader::new("🎨 Color Settings")
             .default_open(true)
             .show(ui, |ui| {
-                egui::ComboBox::from_label("Color Scheme")
-                    .selected_text(self.color_scheme.name())
-                    .show_ui(ui, |ui| {
-                        for scheme in ColorScheme::all() {
-                            if ui.selectable_value(&mut self.color_scheme, scheme, scheme.name()).clicked() {
-                                changed = true;
-                            }
-                        }
-                    });
+                changed |= color_scheme_combo(ui, &mut self.color_scheme);
 
                 changed |= ui.checkbox(&mut self.smooth_coloring, "Smooth Coloring").changed();
                 changed |= ui.checkbox(&mut self.invert_colors, "Invert Colors").changed();
-                changed |= ui.add(egui::Slider::new(&mut self.color_offset, 0.0..=1.0)
-                    .text("Color Offset")).changed();
+                changed |= self.palette_transform.ui(ui);
             });
 
         egui::CollapsingHeader::new("🔍 Navigation")
             .default_open(true)
             .show(ui, |ui| {
-                changed |= ui.add(egui::Slider::new(&mut self.zoom, 0.1..=10000.0)
+                changed |= ui.add(egui::Slider::new(&mut self.zoom, 0.1..=MAX_ZOOM)
+                    .nudge(ui, 0.05)
                     .logarithmic(true)
                     .text("Zoom")).changed();
 
@@ -161,6 +177,8 @@ impl Simulation2D for BurningShip {
                         .speed(0.01 / self.zoom)).changed();
                 });
 
+                location_ui(ui, self.center_x, self.center_y, self.zoom);
+
                 if ui.button("🏠 Reset View").clicked() {
                     *self = Self::default();
                     changed = true;
@@ -192,23 +210,37 @@ impl Simulation2D for BurningShip {
         changed
     }
 
+    fn iteration_cache(&self) -> Option<&EscapeTimeCache> {
+        Some(&self.iteration_cache)
+    }
+
+    fn render_cost(&self, width: usize, height: usize) -> Option<f64> {
+        Some(self.iteration_cache.render_cost(&self.escape_key(width, height), self.max_iterations))
+    }
+
     fn supports_zoom(&self) -> bool {
         true
     }
 
-    fn adjust_center(&mut self, dx: f64, dy: f64, width: usize, height: usize) {
-        // Convert pixel delta to world space delta
-        let aspect = width as f64 / height as f64;
-        let view_width = 4.0 / self.zoom;
-        let view_height = view_width / aspect;
+    fn plane_view(&self, _width: usize, height: usize) -> Option<PlaneView> {
+        // Matches pixel_to_complex: the view is 4 / zoom tall
+        Some(PlaneView {
+            center_x: self.center_x,
+            center_y: self.center_y,
+            units_per_pixel: 4.0 / self.zoom / height.max(1) as f64,
+            complex: true,
+        })
+    }
 
-        self.center_x -= dx * view_width / width as f64;
-        self.center_y -= dy * view_height / height as f64;
+    fn adjust_center(&mut self, dx: f64, dy: f64, _width: usize, height: usize) {
+        // Pixels are square, so both axes move by plane_view's units per pixel
+        let units_per_pixel = 4.0 / self.zoom / height.max(1) as f64;
+        self.center_x -= dx * units_per_pixel;
+        self.center_y -= dy * units_per_pixel;
     }
 
     fn adjust_zoom(&mut self, delta: f64) {
-        let zoom_factor = 1.0 + delta * 0.001;
-        self.zoom = (self.zoom * zoom_factor).clamp(0.1, 10000.0);
+        self.zoom = scroll_zoom(self.zoom, delta);
     }
 
     fn get_zoom(&self) -> f64 {
@@ -1,3 +1,4 @@
+use crate::nudge::Nudge;
 use crate::{Color, Simulation2D};
 
 pub struct CellularAutomaton {
@@ -62,14 +63,6 @@ impl CellularAutomaton {
         self.current_row += 1;
     }
 
-    pub fn reset(&mut self) {
-        for row in &mut self.cells {
-            row.fill(false);
-        }
-        self.cells[0][self.grid_width / 2] = true;
-        self.current_row = 0;
-    }
-
     pub fn randomize_start(&mut self) {
         use std::collections::hash_map::RandomState;
         use std::hash::{BuildHasher, Hash, Hasher};
@@ -127,6 +120,38 @@ impl Simulation2D for CellularAutomaton {
         colors
     }
 
+    fn supports_seek(&self) -> bool {
+        true
+    }
+
+    fn step_count(&self) -> u64 {
+        self.current_row as u64
+    }
+
+    /// Every generation is kept as a row, so seeking back just clears the
+    /// rows after `step`. Capped at the last row.
+    fn seek(&mut self, step: u64) {
+        let target = (step as usize).min(self.rows - 1);
+        if target < self.current_row {
+            for row in &mut self.cells[target + 1..=self.current_row] {
+                row.fill(false);
+            }
+            self.current_row = target;
+        }
+        while self.current_row < target {
+            self.step();
+        }
+        self.time_accumulator = 0.0;
+    }
+
+    fn reset(&mut self) {
+        for row in &mut self.cells {
+            row.fill(false);
+        }
+        self.cells[0][self.grid_width / 2] = true;
+        self.current_row = 0;
+    }
+
     fn ui_parameters(&mut self, ui: &mut egui::Ui) -> bool {
         let mut changed = false;
 
@@ -159,6 +184,7 @@ impl Simulation2D for CellularAutomaton {
         });
 
         ui.add(egui::Slider::new(&mut self.speed, 1.0..=60.0)
+            .nudge(ui, 0.5)
             .text("Steps per second"));
 
         ui.separator();
@@ -184,8 +210,11 @@ impl Simulation2D for CellularAutomaton {
             changed = true;
         }
 
-        // Auto-stepping
-        let dt = ui.input(|i| i.stable_dt);
+        changed
+    }
+
+    fn update(&mut self, dt: f32, _width: usize, _height: usize) -> bool {
+        let mut changed = false;
         self.time_accumulator += dt * self.speed;
 
         while self.time_accumulator >= 1.0 && self.current_row + 1 < self.rows {
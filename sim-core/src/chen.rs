@@ -1,4 +1,30 @@
-use crate::{ColorScheme, Simulation3D};
+use crate::color_ui::color_scheme_combo;
+use crate::nudge::Nudge;
+use crate::poincare::{PoincareSection, SectionAxis};
+use crate::seed::{SeedEdit, SeedPoint, SeedPreset};
+use crate::trajectory::{TrailEmitter, TrajectoryColoring};
+use crate::{Color, ColorScheme, Simulation3D};
+
+/// Seed points, with what the orbit does from each under the default
+/// parameters. Orbits on the x axis grow without bound, so none of them sit
+/// on it.
+const SEEDS: [SeedPreset; 3] = [
+    SeedPreset {
+        name: "Unit Diagonal",
+        point: [1.0, 1.0, 1.0],
+        behavior: "Swings wide on its first loops before settling onto the attractor",
+    },
+    SeedPreset {
+        name: "Upper Octant",
+        point: [5.0, 5.0, 5.0],
+        behavior: "Joins the attractor straight away",
+    },
+    SeedPreset {
+        name: "Mirrored Octant",
+        point: [-5.0, -5.0, 5.0],
+        behavior: "The mirror image of Upper Octant: the system is symmetric under (x, y) -> (-x, -y), so the trail is the same shape turned half a revolution about z",
+    },
+];
 
 pub struct ChenAttractor {
     pub a: f32,
@@ -7,6 +33,10 @@ pub struct ChenAttractor {
     pub dt: f32,
     pub trail_length: usize,
     pub color_scheme: ColorScheme,
+    pub trajectory_coloring: TrajectoryColoring,
+    pub emitter: TrailEmitter,
+    pub section: PoincareSection,
+    pub seed: SeedPoint,
     pub scale: f32,
     points: Vec<[f32; 3]>,
     x: f32,
@@ -23,11 +53,15 @@ impl Default for ChenAttractor {
             dt: 0.003,
             trail_length: 5000,
             color_scheme: ColorScheme::Magma,
+            trajectory_coloring: TrajectoryColoring::Index,
+            emitter: TrailEmitter::new(),
+            section: PoincareSection::new(SectionAxis::Z, 20.0),
+            seed: SeedPoint::new(10.0, &SEEDS),
             scale: 8.0,
             points: Vec::new(),
-            x: 0.1,
-            y: 0.0,
-            z: 0.0,
+            x: 1.0,
+            y: 1.0,
+            z: 1.0,
         }
     }
 }
@@ -36,6 +70,15 @@ impl ChenAttractor {
     pub fn new() -> Self {
         Self::default()
     }
+
+    /// Chen attractor equations
+    fn derivatives(&self, [x, y, z]: [f32; 3]) -> [f32; 3] {
+        [
+            self.a * x - y * z,
+            self.b * y + x * z,
+            self.c * z + x * y / 3.0,
+        ]
+    }
 }
 
 impl Simulation3D for ChenAttractor {
@@ -43,26 +86,27 @@ impl Simulation3D for ChenAttractor {
         "Chen Attractor"
     }
 
+    fn equations(&self) -> Option<&str> {
+        Some(
+            "dx/dt = a x − y z\n\
+             dy/dt = b y + x z\n\
+             dz/dt = c z + x y / 3",
+        )
+    }
+
     fn step(&mut self, _dt: f32) {
         for _ in 0..10 {
-            // Chen attractor equations
-            let dx = self.a * self.x - self.y * self.z;
-            let dy = self.b * self.y + self.x * self.z;
-            let dz = self.c * self.z + self.x * self.y / 3.0;
+            let [dx, dy, dz] = self.derivatives([self.x, self.y, self.z]);
 
+            let prev = [self.x, self.y, self.z];
             self.x += dx * self.dt;
             self.y += dy * self.dt;
             self.z += dz * self.dt;
+            self.section.record(prev, [self.x, self.y, self.z]);
 
-            self.points.push([
-                self.x * self.scale,
-                self.y * self.scale,
-                self.z * self.scale,
-            ]);
-
-            if self.points.len() > self.trail_length {
-                self.points.remove(0);
-            }
+            let scale = self.scale;
+            let next = [self.x * scale, self.y * scale, self.z * scale];
+            self.emitter.push(&mut self.points, prev.map(|v| v * scale), next, self.trail_length);
         }
     }
 
@@ -70,6 +114,14 @@ impl Simulation3D for ChenAttractor {
         self.points.clone()
     }
 
+    fn get_colors(&self) -> Option<Vec<Color>> {
+        self.emitter.colors(&self.points, self.trajectory_coloring, &self.color_scheme)
+    }
+
+    fn poincare_section(&self) -> Option<&PoincareSection> {
+        Some(&self.section)
+    }
+
     fn ui_parameters(&mut self, ui: &mut egui::Ui) -> bool {
         let mut changed = false;
 
@@ -79,12 +131,16 @@ impl Simulation3D for ChenAttractor {
             .default_open(true)
             .show(ui, |ui| {
                 changed |= ui.add(egui::Slider::new(&mut self.a, 1.0..=10.0)
+                    .nudge(ui, 0.05)
                     .text("a")).changed();
                 changed |= ui.add(egui::Slider::new(&mut self.b, -20.0..=-5.0)
+                    .nudge(ui, 0.1)
                     .text("b")).changed();
                 changed |= ui.add(egui::Slider::new(&mut self.c, -1.0..=0.0)
+                    .nudge(ui, 0.01)
                     .text("c")).changed();
                 changed |= ui.add(egui::Slider::new(&mut self.dt, 0.001..=0.01)
+                    .nudge(ui, 0.00005)
                     .text("Time Step")).changed();
             });
 
@@ -92,24 +148,31 @@ impl Simulation3D for ChenAttractor {
             .default_open(true)
             .show(ui, |ui| {
                 changed |= ui.add(egui::Slider::new(&mut self.trail_length, 100..=10000)
+                    .nudge(ui, 1.0)
                     .text("Trail Length")).changed();
+                changed |= self.emitter.ui(ui, &mut self.points, self.trail_length);
                 changed |= ui.add(egui::Slider::new(&mut self.scale, 3.0..=20.0)
+                    .nudge(ui, 0.1)
                     .text("Scale")).changed();
             });
 
         egui::CollapsingHeader::new("🎨 Color Settings")
             .show(ui, |ui| {
-                egui::ComboBox::from_label("Color Scheme")
-                    .selected_text(self.color_scheme.name())
+                changed |= color_scheme_combo(ui, &mut self.color_scheme);
+
+                egui::ComboBox::from_label("Color By")
+                    .selected_text(self.trajectory_coloring.name())
                     .show_ui(ui, |ui| {
-                        for scheme in ColorScheme::all() {
-                            if ui.selectable_value(&mut self.color_scheme, scheme, scheme.name()).clicked() {
+                        for coloring in TrajectoryColoring::all() {
+                            if ui.selectable_value(&mut self.trajectory_coloring, coloring, coloring.name()).clicked() {
                                 changed = true;
                             }
                         }
                     });
             });
 
+        self.section.ui(ui, -40.0..=60.0, &self.points);
+
         egui::CollapsingHeader::new("🎯 Presets")
             .show(ui, |ui| {
                 if ui.button("Classic").clicked() {
@@ -130,6 +193,17 @@ impl Simulation3D for ChenAttractor {
                 }
             });
 
+        let edit = self.seed.ui(ui);
+        if edit == SeedEdit::Randomize {
+            if let Some(point) = self.seed.random(|p| self.derivatives(p), self.dt) {
+                self.seed.point = point;
+            }
+        }
+        if edit != SeedEdit::None {
+            self.reset();
+            changed = true;
+        }
+
         if ui.button("🔄 Reset").clicked() {
             self.reset();
             changed = true;
@@ -140,8 +214,8 @@ impl Simulation3D for ChenAttractor {
 
     fn reset(&mut self) {
         self.points.clear();
-        self.x = 0.1;
-        self.y = 0.0;
-        self.z = 0.0;
+        self.emitter.clear();
+        self.section.clear();
+        [self.x, self.y, self.z] = self.seed.start(|p| self.derivatives(p));
     }
 }
@@ -0,0 +1,68 @@
+//! Shared simulation time. The app owns one `SimClock` and advances it once
+//! per frame. Sims get its dt (`Simulation2D::update`, `Simulation3D::step`)
+//! instead of the raw frame dt, so pausing or slowing the clock affects every
+//! sim the same way; it is also published to the egui context for renderers
+//! that need the current time.
+
+/// Where the app's clock is stored in the egui context
+fn clock_id() -> egui::Id {
+    egui::Id::new("sim_clock")
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct SimClock {
+    pub paused: bool,
+    /// Multiplies real time; below 1 is slow motion
+    pub time_scale: f32,
+    /// Scaled seconds accumulated while running
+    time: f64,
+    /// Scaled step for the current frame, 0 while paused
+    dt: f32,
+}
+
+impl Default for SimClock {
+    fn default() -> Self {
+        Self {
+            paused: false,
+            time_scale: 1.0,
+            time: 0.0,
+            dt: 0.0,
+        }
+    }
+}
+
+impl SimClock {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start a new frame that took `real_dt` seconds of wall time
+    pub fn advance(&mut self, real_dt: f32) {
+        self.dt = if self.paused {
+            0.0
+        } else {
+            real_dt.max(0.0) * self.time_scale.max(0.0)
+        };
+        self.time += self.dt as f64;
+    }
+
+    /// Seconds of sim time to step this frame
+    pub fn dt(&self) -> f32 {
+        self.dt
+    }
+
+    /// Sim time elapsed since the clock was created
+    pub fn time(&self) -> f64 {
+        self.time
+    }
+
+    /// Make this frame's clock visible to `current`
+    pub fn publish(&self, ctx: &egui::Context) {
+        ctx.data_mut(|d| d.insert_temp(clock_id(), *self));
+    }
+
+    /// The clock published for this frame, if the host app has one
+    pub fn current(ctx: &egui::Context) -> Option<SimClock> {
+        ctx.data(|d| d.get_temp(clock_id()))
+    }
+}
@@ -0,0 +1,386 @@
+//! Color scheme picker with gradient previews, shared by every sim's color
+//! settings, a stop editor for custom gradients, palette reversal and
+//! offset, and an editable per-state palette for discrete automata.
+
+use crate::nudge::Nudge;
+use crate::palette_export::export_menu;
+use crate::readout;
+use crate::{Color, ColorScheme, GradientSpace};
+
+/// Displayed size of a gradient swatch
+const SWATCH_SIZE: egui::Vec2 = egui::vec2(128.0, 12.0);
+/// Gradient samples per swatch; stretched to `SWATCH_SIZE` when drawn
+const SWATCH_SAMPLES: usize = 128;
+
+/// Stops a custom gradient starts with, sampled from the scheme it replaces
+const CUSTOM_STOPS: usize = 5;
+/// Height of the stop editor's gradient bar
+const EDITOR_BAR_HEIGHT: f32 = 18.0;
+/// Size of the stop handles under the bar
+const HANDLE_SIZE: f32 = 10.0;
+
+/// One gradient texture per scheme, in `ColorScheme::all()` order. Built on
+/// first use and kept in the egui context, so every picker shares them.
+fn swatches(ctx: &egui::Context) -> Vec<egui::TextureHandle> {
+    let id = egui::Id::new("color_scheme_swatches");
+    if let Some(swatches) = ctx.data(|d| d.get_temp::<Vec<egui::TextureHandle>>(id)) {
+        return swatches;
+    }
+
+    let swatches: Vec<_> = ColorScheme::all()
+        .iter()
+        .map(|scheme| {
+            let pixels = (0..SWATCH_SAMPLES)
+                .map(|i| {
+                    let c = scheme.map(i as f32 / (SWATCH_SAMPLES - 1) as f32, true);
+                    egui::Color32::from_rgb(c.r, c.g, c.b)
+                })
+                .collect();
+            let image = egui::ColorImage {
+                size: [SWATCH_SAMPLES, 1],
+                pixels,
+            };
+            ctx.load_texture(format!("swatch_{}", scheme.name()), image, egui::TextureOptions::LINEAR)
+        })
+        .collect();
+
+    ctx.data_mut(|d| d.insert_temp(id, swatches.clone()));
+    swatches
+}
+
+fn swatch_for<'a>(swatches: &'a [egui::TextureHandle], scheme: &ColorScheme) -> Option<&'a egui::TextureHandle> {
+    ColorScheme::all()
+        .iter()
+        .position(|s| s == scheme)
+        .and_then(|i| swatches.get(i))
+}
+
+/// Paint `scheme` across `rect`, left to right. Used for custom gradients,
+/// which change too often to keep as textures.
+fn paint_gradient(painter: &egui::Painter, rect: egui::Rect, scheme: &ColorScheme) {
+    let mut mesh = egui::Mesh::default();
+    for i in 0..SWATCH_SAMPLES {
+        let t = i as f32 / (SWATCH_SAMPLES - 1) as f32;
+        let c = scheme.map(t, true);
+        let color = egui::Color32::from_rgb(c.r, c.g, c.b);
+        let x = rect.left() + rect.width() * t;
+        mesh.colored_vertex(egui::pos2(x, rect.top()), color);
+        mesh.colored_vertex(egui::pos2(x, rect.bottom()), color);
+        if i > 0 {
+            let k = 2 * i as u32;
+            mesh.add_triangle(k - 2, k - 1, k);
+            mesh.add_triangle(k - 1, k, k + 1);
+        }
+    }
+    painter.add(mesh);
+}
+
+/// A custom gradient that starts out looking like `scheme`
+fn custom_from(scheme: &ColorScheme) -> ColorScheme {
+    let stops = (0..CUSTOM_STOPS)
+        .map(|i| {
+            let t = i as f32 / (CUSTOM_STOPS - 1) as f32;
+            (t, scheme.map(t, true))
+        })
+        .collect();
+    ColorScheme::Custom(stops, GradientSpace::Rgb)
+}
+
+/// "Color Scheme" dropdown with a gradient preview beside it and in every
+/// entry, plus the stop editor while the scheme is custom. Returns true
+/// when the colors changed.
+pub fn color_scheme_combo(ui: &mut egui::Ui, scheme: &mut ColorScheme) -> bool {
+    color_scheme_combo_labeled(ui, "Color Scheme", scheme)
+}
+
+/// `color_scheme_combo` with a custom label
+pub fn color_scheme_combo_labeled(ui: &mut egui::Ui, label: &str, scheme: &mut ColorScheme) -> bool {
+    let swatches = swatches(ui.ctx());
+    let mut changed = false;
+
+    ui.horizontal(|ui| {
+        egui::ComboBox::from_label(label)
+            .selected_text(scheme.name())
+            .show_ui(ui, |ui| {
+                for option in ColorScheme::all() {
+                    ui.horizontal(|ui| {
+                        if let Some(swatch) = swatch_for(&swatches, &option) {
+                            ui.image((swatch.id(), SWATCH_SIZE));
+                        }
+                        let name = option.name().to_string();
+                        changed |= ui.selectable_value(scheme, option, name).clicked();
+                    });
+                }
+                ui.horizontal(|ui| {
+                    ui.add_space(SWATCH_SIZE.x + ui.spacing().item_spacing.x);
+                    let is_custom = matches!(scheme, ColorScheme::Custom(..));
+                    if ui.selectable_label(is_custom, "Custom")
+                        .on_hover_text("Build your own gradient, starting from the current scheme")
+                        .clicked() && !is_custom {
+                        *scheme = custom_from(scheme);
+                        changed = true;
+                    }
+                });
+            });
+
+        match swatch_for(&swatches, scheme) {
+            Some(swatch) => {
+                ui.image((swatch.id(), SWATCH_SIZE));
+            }
+            None => {
+                let (rect, _) = ui.allocate_exact_size(SWATCH_SIZE, egui::Sense::hover());
+                paint_gradient(ui.painter(), rect, scheme);
+            }
+        }
+        ui.menu_button("Export…", |ui| export_menu(ui, scheme))
+            .response
+            .on_hover_text("Export this palette for GIMP, CSS or as hex codes");
+    });
+
+    if let ColorScheme::Custom(stops, space) = scheme {
+        // The label tells apart editors shown together, like a sim's scheme
+        // and the heatmap's
+        changed |= gradient_editor(ui, label, stops, space);
+    }
+
+    changed
+}
+
+/// Stop editor for a custom gradient: the gradient as a bar with a handle
+/// under it per stop. Drag a handle to move its stop (it can't pass its
+/// neighbours), click one to pick its color, right-click it to remove it,
+/// and double-click the bar to add a stop there. `id_salt` keeps the
+/// selected stop apart from other editors in the same `ui`. Returns true on
+/// any change.
+fn gradient_editor(ui: &mut egui::Ui, id_salt: impl std::hash::Hash, stops: &mut Vec<(f32, Color)>, space: &mut GradientSpace) -> bool {
+    let mut changed = false;
+    // Stops loaded from a file may be out of order
+    stops.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    let id = ui.make_persistent_id(("gradient_editor", id_salt));
+    let mut selected = ui.data(|d| d.get_temp::<usize>(id)).unwrap_or(0);
+
+    let width = ui.available_width().min(2.0 * SWATCH_SIZE.x);
+    let (rect, bar) = ui.allocate_exact_size(egui::vec2(width, EDITOR_BAR_HEIGHT + HANDLE_SIZE), egui::Sense::click());
+    let bar_rect = egui::Rect::from_min_size(rect.min, egui::vec2(width, EDITOR_BAR_HEIGHT));
+    let to_t = |x: f32| ((x - rect.left()) / width).clamp(0.0, 1.0);
+
+    let gradient = ColorScheme::Custom(stops.clone(), *space);
+    paint_gradient(ui.painter(), bar_rect, &gradient);
+    ui.painter().rect_stroke(bar_rect, 0.0, ui.visuals().widgets.noninteractive.bg_stroke);
+
+    if bar.double_clicked() {
+        if let Some(pos) = bar.interact_pointer_pos() {
+            let t = to_t(pos.x);
+            selected = stops.partition_point(|s| s.0 < t);
+            stops.insert(selected, (t, gradient.map(t, true)));
+            changed = true;
+        }
+    }
+
+    let mut remove = None;
+    for i in 0..stops.len() {
+        let x = rect.left() + stops[i].0 * width;
+        let handle = egui::Rect::from_center_size(
+            egui::pos2(x, bar_rect.bottom() + HANDLE_SIZE / 2.0),
+            egui::vec2(HANDLE_SIZE, HANDLE_SIZE),
+        );
+        let response = ui.interact(handle, id.with(i), egui::Sense::click_and_drag())
+            .on_hover_text("Drag to move, right-click to remove");
+
+        if response.clicked() || response.drag_started() {
+            selected = i;
+        }
+        if response.dragged() {
+            if let Some(pos) = response.interact_pointer_pos() {
+                let low = if i > 0 { stops[i - 1].0 } else { 0.0 };
+                let high = stops.get(i + 1).map_or(1.0, |s| s.0);
+                stops[i].0 = to_t(pos.x).clamp(low, high);
+                changed = true;
+            }
+        }
+        if response.secondary_clicked() && stops.len() > 2 {
+            remove = Some(i);
+        }
+
+        let c = stops[i].1;
+        let stroke = if i == selected {
+            ui.visuals().selection.stroke
+        } else {
+            ui.visuals().widgets.noninteractive.fg_stroke
+        };
+        ui.painter().add(egui::Shape::convex_polygon(
+            vec![
+                egui::pos2(x, handle.top()),
+                handle.right_bottom(),
+                handle.left_bottom(),
+            ],
+            egui::Color32::from_rgb(c.r, c.g, c.b),
+            stroke,
+        ));
+    }
+    if let Some(i) = remove {
+        stops.remove(i);
+        changed = true;
+    }
+
+    ui.horizontal(|ui| {
+        if let Some((t, color)) = stops.get_mut(selected) {
+            let mut rgb = [color.r, color.g, color.b];
+            if ui.color_edit_button_srgb(&mut rgb).changed() {
+                *color = Color::from_rgb(rgb[0], rgb[1], rgb[2]);
+                changed = true;
+            }
+            ui.label(format!("Stop {} at {}", selected + 1, readout::percent(*t, 0)));
+        }
+
+        if ui.button("➕").on_hover_text("Add a stop in the middle of the widest gap").clicked() {
+            let t = stops
+                .windows(2)
+                .max_by(|a, b| (a[1].0 - a[0].0).total_cmp(&(b[1].0 - b[0].0)))
+                .map_or(if stops.first().is_some_and(|s| s.0 < 0.5) { 1.0 } else { 0.0 }, |w| (w[0].0 + w[1].0) / 2.0);
+            selected = stops.partition_point(|s| s.0 < t);
+            stops.insert(selected, (t, gradient.map(t, true)));
+            changed = true;
+        }
+        if ui.add_enabled(stops.len() > 2, egui::Button::new("🗑"))
+            .on_hover_text("Remove the selected stop")
+            .clicked() && selected < stops.len() {
+            stops.remove(selected);
+            changed = true;
+        }
+    });
+
+    let mut hsv = *space == GradientSpace::Hsv;
+    if ui.checkbox(&mut hsv, "Blend in HSV")
+        .on_hover_text("Blend around the hue wheel between stops instead of straight through RGB")
+        .changed() {
+        *space = if hsv { GradientSpace::Hsv } else { GradientSpace::Rgb };
+        changed = true;
+    }
+
+    ui.data_mut(|d| d.insert_temp(id, selected.min(stops.len().saturating_sub(1))));
+    changed
+}
+
+/// Reversal and cyclic offset applied on top of a sim's color scheme
+#[derive(Clone, Copy, PartialEq, Default)]
+pub struct PaletteTransform {
+    pub reversed: bool,
+    /// Shift along the palette in palette lengths, wrapping at the end
+    pub offset: f32,
+}
+
+impl PaletteTransform {
+    /// `scheme` at `t`, reversed and then shifted by the offset
+    pub fn map(&self, scheme: &ColorScheme, t: f32, smooth: bool) -> Color {
+        let t = if self.reversed { 1.0 - t } else { t };
+        scheme.map_rotated(t, self.offset, smooth)
+    }
+
+    /// "Reverse Palette" and "Palette Offset" controls. Returns true when
+    /// either changed.
+    pub fn ui(&mut self, ui: &mut egui::Ui) -> bool {
+        let mut changed = ui.checkbox(&mut self.reversed, "Reverse Palette")
+            .on_hover_text("Run the color scheme from its last color to its first")
+            .changed();
+        changed |= ui.add(egui::Slider::new(&mut self.offset, 0.0..=1.0)
+            .nudge(ui, 0.01)
+            .text("Palette Offset"))
+            .on_hover_text("Turn the palette, wrapping colors past the end back to the start")
+            .changed();
+        changed
+    }
+}
+
+/// Brightness of every other state in a generated palette. Neighbouring
+/// states then differ in lightness as well as hue, so each wave front reads
+/// as a hard edge even where the scheme changes slowly.
+const ALTERNATE_SHADE: f32 = 0.6;
+
+/// One explicit color per state of a discrete automaton, used in place of
+/// the continuous scheme. Entries are generated from the scheme until the
+/// user picks one by hand; picked entries stay put when the scheme or the
+/// state count changes, while the rest are regenerated to span the scheme.
+#[derive(Default)]
+pub struct StatePalette {
+    pub enabled: bool,
+    colors: Vec<Color>,
+    /// Entries picked by hand
+    edited: Vec<bool>,
+}
+
+impl StatePalette {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Generated color of `state` out of `count`
+    fn generated(scheme: &ColorScheme, state: usize, count: usize) -> Color {
+        let color = scheme.map(state as f32 / count.max(1) as f32, true);
+        if state % 2 == 1 {
+            Color::lerp(Color::BLACK, color, ALTERNATE_SHADE)
+        } else {
+            color
+        }
+    }
+
+    /// Resize to `count` states and regenerate the entries not picked by
+    /// hand. Cheap when nothing changed, so it can run every frame.
+    pub fn sync(&mut self, count: usize, scheme: &ColorScheme) {
+        self.colors.resize(count, Color::BLACK);
+        self.edited.resize(count, false);
+        for (state, color) in self.colors.iter_mut().enumerate() {
+            if !self.edited[state] {
+                *color = Self::generated(scheme, state, count);
+            }
+        }
+    }
+
+    /// Color of `state` out of `count`. Until the palette has been synced to
+    /// `count` (say, right after loading), the generated color stands in.
+    pub fn color(&self, state: usize, count: usize, scheme: &ColorScheme) -> Color {
+        match self.colors.get(state) {
+            Some(&color) if self.colors.len() == count => color,
+            _ => Self::generated(scheme, state, count),
+        }
+    }
+
+    /// "Discrete Colors" toggle and, when on, a picker per state. Call
+    /// after any change to the state count or scheme. Returns true when the
+    /// colors on screen change.
+    pub fn ui(&mut self, ui: &mut egui::Ui, count: usize, scheme: &ColorScheme) -> bool {
+        let mut changed = ui.checkbox(&mut self.enabled, "Discrete Colors")
+            .on_hover_text("Give each state its own color instead of a point on the scheme, so every wave front is a hard edge")
+            .changed();
+        self.sync(count, scheme);
+        if !self.enabled {
+            return changed;
+        }
+
+        ui.horizontal_wrapped(|ui| {
+            for state in 0..count {
+                let c = self.colors[state];
+                let mut rgb = [c.r, c.g, c.b];
+                if ui.color_edit_button_srgb(&mut rgb)
+                    .on_hover_text(format!("State {state}"))
+                    .changed() {
+                    self.colors[state] = Color::from_rgb(rgb[0], rgb[1], rgb[2]);
+                    self.edited[state] = true;
+                    changed = true;
+                }
+            }
+        });
+
+        let any_edited = self.edited.iter().any(|&e| e);
+        if ui.add_enabled(any_edited, egui::Button::new("↺ Reset to Scheme"))
+            .on_hover_text("Regenerate every state's color from the color scheme")
+            .clicked() {
+            self.edited.fill(false);
+            self.sync(count, scheme);
+            changed = true;
+        }
+
+        changed
+    }
+}
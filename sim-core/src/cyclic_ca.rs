@@ -1,6 +1,15 @@
-use crate::{Color, ColorScheme, Simulation2D};
+use crate::color_ui::{color_scheme_combo, PaletteTransform, StatePalette};
+use crate::grid_render::{grid_filter_ui, render_grid, GridFilter};
+use crate::nudge::Nudge;
+use crate::readout;
+use crate::step_runner::StepRunner;
+use crate::timeline::Timeline;
+use crate::{Color, ColorScheme, RenderCtx, Simulation2D};
 use rand::Rng;
 
+/// Steps a "Run N" takes per frame
+const RUN_BATCH: u64 = 20;
+
 pub struct CyclicCA {
     pub grid_width: usize,
     pub grid_height: usize,
@@ -8,11 +17,19 @@ pub struct CyclicCA {
     pub threshold: usize,
     pub speed: f32,
     pub color_scheme: ColorScheme,
+    pub palette_transform: PaletteTransform,
+    /// Explicit per-state colors, used instead of the scheme when enabled
+    pub palette: StatePalette,
     pub neighborhood: Neighborhood,
+    pub grid_filter: GridFilter,
+    pub paused: bool,
 
     grid: Vec<u8>,
     next_grid: Vec<u8>,
     time_accumulator: f32,
+    timeline: Timeline<Vec<u8>>,
+    /// Exact step counts queued from the UI
+    runner: StepRunner,
 }
 
 #[derive(Clone, Copy, PartialEq)]
@@ -70,6 +87,8 @@ impl Default for CyclicCA {
             .map(|_| rng.gen_range(0..14))
             .collect();
 
+        let timeline = Timeline::new(grid.clone());
+
         Self {
             grid_width: width,
             grid_height: height,
@@ -77,10 +96,16 @@ impl Default for CyclicCA {
             threshold: 3,
             speed: 10.0,
             color_scheme: ColorScheme::Rainbow,
+            palette_transform: PaletteTransform::default(),
+            palette: StatePalette::new(),
             neighborhood: Neighborhood::Moore,
+            grid_filter: GridFilter::Sharp,
+            paused: false,
             grid,
             next_grid: vec![0; width * height],
             time_accumulator: 0.0,
+            timeline,
+            runner: StepRunner::new(RUN_BATCH),
         }
     }
 }
@@ -119,21 +144,7 @@ impl CyclicCA {
         }
 
         std::mem::swap(&mut self.grid, &mut self.next_grid);
-    }
-
-    pub fn reset(&mut self) {
-        // Resize grids if dimensions changed
-        let required_size = self.grid_width * self.grid_height;
-        if self.grid.len() != required_size {
-            self.grid = vec![0; required_size];
-            self.next_grid = vec![0; required_size];
-        }
-
-        let mut rng = rand::thread_rng();
-        for cell in &mut self.grid {
-            *cell = rng.gen_range(0..self.num_states as u8);
-        }
-        self.time_accumulator = 0.0;
+        self.timeline.record(|| self.grid.clone());
     }
 
     pub fn reset_with_seed(&mut self, seed: &str) {
@@ -186,6 +197,8 @@ impl CyclicCA {
             }
             _ => self.reset(),
         }
+        self.runner.cancel();
+        self.timeline.restart(self.grid.clone());
     }
 }
 
@@ -195,27 +208,54 @@ impl Simulation2D for CyclicCA {
     }
 
     fn compute(&self, width: usize, height: usize) -> Vec<Color> {
-        let mut colors = Vec::with_capacity(width * height);
+        self.compute_ctx(&RenderCtx::new(width, height))
+    }
 
-        let cell_width = width / self.grid_width;
-        let cell_height = height / self.grid_height;
+    fn compute_ctx(&self, ctx: &RenderCtx) -> Vec<Color> {
+        let pixels = render_grid(self.grid_width, self.grid_height, ctx.width, ctx.height, self.grid_filter, |idx| {
+            if self.palette.enabled {
+                return self.palette.color(self.grid[idx] as usize, self.num_states, &self.color_scheme);
+            }
+            let t = self.grid[idx] as f32 / self.num_states as f32;
+            self.palette_transform.map(&self.color_scheme, ctx.cycle_palette(t), true)
+        });
+        ctx.over_background(pixels)
+    }
 
-        for py in 0..height {
-            for px in 0..width {
-                let gx = px / cell_width.max(1);
-                let gy = py / cell_height.max(1);
+    fn supports_seek(&self) -> bool {
+        true
+    }
 
-                if gx < self.grid_width && gy < self.grid_height {
-                    let state = self.grid[gy * self.grid_width + gx];
-                    let t = state as f32 / self.num_states as f32;
-                    colors.push(self.color_scheme.map(t, true));
-                } else {
-                    colors.push(Color::BLACK);
-                }
-            }
+    fn step_count(&self) -> u64 {
+        self.timeline.step()
+    }
+
+    fn seek(&mut self, step: u64) {
+        self.runner.cancel();
+        if let Some(grid) = self.timeline.rewind(step) {
+            self.grid = grid;
+        }
+        while self.timeline.step() < step {
+            self.step();
+        }
+        self.time_accumulator = 0.0;
+    }
+
+    fn reset(&mut self) {
+        // Resize grids if dimensions changed
+        let required_size = self.grid_width * self.grid_height;
+        if self.grid.len() != required_size {
+            self.grid = vec![0; required_size];
+            self.next_grid = vec![0; required_size];
         }
 
-        colors
+        let mut rng = rand::thread_rng();
+        for cell in &mut self.grid {
+            *cell = rng.gen_range(0..self.num_states as u8);
+        }
+        self.time_accumulator = 0.0;
+        self.runner.cancel();
+        self.timeline.restart(self.grid.clone());
     }
 
     fn ui_parameters(&mut self, ui: &mut egui::Ui) -> bool {
@@ -227,12 +267,14 @@ impl Simulation2D for CyclicCA {
             .default_open(true)
             .show(ui, |ui| {
                 if ui.add(egui::Slider::new(&mut self.num_states, 3..=24)
+                    .nudge(ui, 1.0)
                     .text("Number of States")).changed() {
                     self.reset();
                     changed = true;
                 }
 
                 changed |= ui.add(egui::Slider::new(&mut self.threshold, 1..=8)
+                    .nudge(ui, 1.0)
                     .text("Threshold")).changed();
 
                 egui::ComboBox::from_label("Neighborhood")
@@ -253,30 +295,38 @@ impl Simulation2D for CyclicCA {
             .default_open(true)
             .show(ui, |ui| {
                 if ui.add(egui::Slider::new(&mut self.grid_width, 50..=400)
+                    .nudge(ui, 1.0)
                     .text("Grid Width")).changed() {
                     self.reset();
                     changed = true;
                 }
 
                 if ui.add(egui::Slider::new(&mut self.grid_height, 50..=300)
+                    .nudge(ui, 1.0)
                     .text("Grid Height")).changed() {
                     self.reset();
                     changed = true;
                 }
 
                 ui.add(egui::Slider::new(&mut self.speed, 0.1..=60.0)
+                    .nudge(ui, 0.05)
                     .logarithmic(true)
                     .text("Steps per second"));
 
-                egui::ComboBox::from_label("Color Scheme")
-                    .selected_text(self.color_scheme.name())
-                    .show_ui(ui, |ui| {
-                        for scheme in ColorScheme::all() {
-                            if ui.selectable_value(&mut self.color_scheme, scheme, scheme.name()).clicked() {
-                                changed = true;
-                            }
-                        }
-                    });
+                ui.horizontal(|ui| {
+                    if ui.button(if self.paused { "▶ Play" } else { "⏸ Pause" }).clicked() {
+                        self.paused = !self.paused;
+                    }
+                    ui.label(format!("Step: {}", readout::count(self.timeline.step())));
+                });
+                if self.runner.ui(ui) {
+                    self.paused = true;
+                }
+
+                changed |= color_scheme_combo(ui, &mut self.color_scheme);
+                changed |= self.palette_transform.ui(ui);
+                changed |= self.palette.ui(ui, self.num_states, &self.color_scheme);
+                changed |= grid_filter_ui(ui, &mut self.grid_filter);
             });
 
         egui::CollapsingHeader::new("🎯 Initial Conditions")
@@ -310,8 +360,24 @@ impl Simulation2D for CyclicCA {
                 ui.label("'consumed' by the next state.");
             });
 
-        // Auto-stepping
-        let dt = ui.input(|i| i.stable_dt);
+        changed
+    }
+
+    fn update(&mut self, dt: f32, _width: usize, _height: usize) -> bool {
+        let batch = self.runner.take();
+        if batch > 0 {
+            for _ in 0..batch {
+                self.step();
+            }
+            self.time_accumulator = 0.0;
+            return true;
+        }
+
+        if self.paused {
+            return false;
+        }
+
+        let mut changed = false;
         self.time_accumulator += dt * self.speed;
 
         while self.time_accumulator >= 1.0 {
@@ -0,0 +1,294 @@
+use crate::color_ui::color_scheme_combo;
+use crate::nudge::Nudge;
+use crate::poincare::{PoincareSection, SectionAxis};
+use crate::seed::{SeedEdit, SeedPoint, SeedPreset};
+use crate::trajectory::{TrailEmitter, TrajectoryColoring};
+use crate::{Color, ColorScheme, Simulation3D};
+
+/// Seed points, with what the orbit does from each under the default
+/// parameters. All are slightly asymmetric: the diagonal x = y = z is
+/// invariant.
+const SEEDS: [SeedPreset; 3] = [
+    SeedPreset {
+        name: "Near Origin",
+        point: [0.1, 0.0, -0.1],
+        behavior: "With the sine coupling: leaves the origin and fills the tangle within a few hundred steps",
+    },
+    SeedPreset {
+        name: "Inside the Tangle",
+        point: [-4.0, -1.0, 2.0],
+        behavior: "With the sine coupling: lands in the tangle almost immediately",
+    },
+    SeedPreset {
+        name: "Positive Corner",
+        point: [5.0, 1.0, -3.0],
+        behavior: "With the sine coupling: stays on the positive-x side for over a thousand steps before first crossing x = 0",
+    },
+];
+
+/// Coupling term g(u, v) of the cyclically symmetric system
+///   dx/dt = -b·x + g(y, z)
+///   dy/dt = -b·y + g(z, x)
+///   dz/dt = -b·z + g(x, y)
+#[derive(Clone, Copy, PartialEq)]
+pub enum CyclicNonlinearity {
+    Sine,      // g = sin(u)            (Thomas)
+    Quadratic, // g = -4u - 4v - u²     (Halvorsen)
+    Cubic,     // g = u - u³
+}
+
+impl CyclicNonlinearity {
+    pub fn all() -> Vec<CyclicNonlinearity> {
+        vec![
+            CyclicNonlinearity::Sine,
+            CyclicNonlinearity::Quadratic,
+            CyclicNonlinearity::Cubic,
+        ]
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            CyclicNonlinearity::Sine => "Sine (Thomas)",
+            CyclicNonlinearity::Quadratic => "Quadratic (Halvorsen)",
+            CyclicNonlinearity::Cubic => "Cubic",
+        }
+    }
+
+    fn apply(&self, u: f32, v: f32) -> f32 {
+        match self {
+            CyclicNonlinearity::Sine => u.sin(),
+            CyclicNonlinearity::Quadratic => -4.0 * u - 4.0 * v - u * u,
+            CyclicNonlinearity::Cubic => u - u * u * u,
+        }
+    }
+}
+
+pub struct CyclicSymmetricAttractor {
+    pub nonlinearity: CyclicNonlinearity,
+    pub b: f32,
+    pub dt: f32,
+    pub trail_length: usize,
+    pub color_scheme: ColorScheme,
+    pub trajectory_coloring: TrajectoryColoring,
+    pub emitter: TrailEmitter,
+    pub section: PoincareSection,
+    pub seed: SeedPoint,
+    pub scale: f32,
+    points: Vec<[f32; 3]>,
+    x: f32,
+    y: f32,
+    z: f32,
+}
+
+impl Default for CyclicSymmetricAttractor {
+    fn default() -> Self {
+        Self {
+            nonlinearity: CyclicNonlinearity::Sine,
+            b: 0.208186,
+            dt: 0.1,
+            trail_length: 5000,
+            color_scheme: ColorScheme::Viridis,
+            trajectory_coloring: TrajectoryColoring::Index,
+            emitter: TrailEmitter::new(),
+            section: PoincareSection::new(SectionAxis::X, 0.0),
+            seed: SeedPoint::new(5.0, &SEEDS),
+            scale: 80.0,
+            points: Vec::new(),
+            x: 0.1,
+            y: 0.0,
+            z: -0.1,
+        }
+    }
+}
+
+impl CyclicSymmetricAttractor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The same g is applied to each rotated (x, y, z) triple, so the
+    /// system is invariant under x -> y -> z -> x
+    fn derivatives(&self, [x, y, z]: [f32; 3]) -> [f32; 3] {
+        let g = self.nonlinearity;
+        [
+            -self.b * x + g.apply(y, z),
+            -self.b * y + g.apply(z, x),
+            -self.b * z + g.apply(x, y),
+        ]
+    }
+
+    fn apply_preset(&mut self, nonlinearity: CyclicNonlinearity, b: f32, dt: f32, scale: f32) {
+        self.nonlinearity = nonlinearity;
+        self.b = b;
+        self.dt = dt;
+        self.scale = scale;
+        self.reset();
+    }
+}
+
+impl Simulation3D for CyclicSymmetricAttractor {
+    fn name(&self) -> &str {
+        "Cyclic Symmetric Attractor"
+    }
+
+    fn equations(&self) -> Option<&str> {
+        Some(match self.nonlinearity {
+            CyclicNonlinearity::Sine => "dx/dt = −b x + g(y, z)\n\
+                                         dy/dt = −b y + g(z, x)\n\
+                                         dz/dt = −b z + g(x, y)\n\
+                                         g(u, v) = sin u",
+            CyclicNonlinearity::Quadratic => "dx/dt = −b x + g(y, z)\n\
+                                              dy/dt = −b y + g(z, x)\n\
+                                              dz/dt = −b z + g(x, y)\n\
+                                              g(u, v) = −4u − 4v − u²",
+            CyclicNonlinearity::Cubic => "dx/dt = −b x + g(y, z)\n\
+                                          dy/dt = −b y + g(z, x)\n\
+                                          dz/dt = −b z + g(x, y)\n\
+                                          g(u, v) = u − u³",
+        })
+    }
+
+    fn step(&mut self, _dt: f32) {
+        for _ in 0..10 {
+            let [dx, dy, dz] = self.derivatives([self.x, self.y, self.z]);
+
+            let prev = [self.x, self.y, self.z];
+            self.x += dx * self.dt;
+            self.y += dy * self.dt;
+            self.z += dz * self.dt;
+
+            // Unbounded nonlinearities can escape for small b; restart instead of
+            // filling the trail with NaNs
+            if !(self.x.is_finite() && self.y.is_finite() && self.z.is_finite())
+                || self.x.abs().max(self.y.abs()).max(self.z.abs()) > 1.0e4
+            {
+                self.reset();
+                return;
+            }
+            self.section.record(prev, [self.x, self.y, self.z]);
+
+            let scale = self.scale;
+            let next = [self.x * scale, self.y * scale, self.z * scale];
+            self.emitter.push(&mut self.points, prev.map(|v| v * scale), next, self.trail_length);
+        }
+    }
+
+    fn get_points(&self) -> Vec<[f32; 3]> {
+        self.points.clone()
+    }
+
+    fn get_colors(&self) -> Option<Vec<Color>> {
+        self.emitter.colors(&self.points, self.trajectory_coloring, &self.color_scheme)
+    }
+
+    fn poincare_section(&self) -> Option<&PoincareSection> {
+        Some(&self.section)
+    }
+
+    fn ui_parameters(&mut self, ui: &mut egui::Ui) -> bool {
+        let mut changed = false;
+
+        ui.heading("Cyclic Symmetric Attractor");
+
+        egui::CollapsingHeader::new("⚙ System Parameters")
+            .default_open(true)
+            .show(ui, |ui| {
+                egui::ComboBox::from_label("Nonlinearity")
+                    .selected_text(self.nonlinearity.name())
+                    .show_ui(ui, |ui| {
+                        for g in CyclicNonlinearity::all() {
+                            if ui.selectable_value(&mut self.nonlinearity, g, g.name()).clicked() {
+                                self.reset();
+                                changed = true;
+                            }
+                        }
+                    });
+
+                changed |= ui.add(egui::Slider::new(&mut self.b, 0.0..=3.0)
+                    .nudge(ui, 0.02)
+                    .text("b (Dissipation)")).changed();
+                changed |= ui.add(egui::Slider::new(&mut self.dt, 0.001..=0.2)
+                    .nudge(ui, 0.0005)
+                    .logarithmic(true)
+                    .text("Time Step")).changed();
+
+                ui.label("dx/dt = -b·x + g(y, z)");
+                ui.label("dy/dt = -b·y + g(z, x)");
+                ui.label("dz/dt = -b·z + g(x, y)");
+            });
+
+        egui::CollapsingHeader::new("🔍 Display Settings")
+            .default_open(true)
+            .show(ui, |ui| {
+                changed |= ui.add(egui::Slider::new(&mut self.trail_length, 100..=10000)
+                    .nudge(ui, 1.0)
+                    .text("Trail Length")).changed();
+                changed |= self.emitter.ui(ui, &mut self.points, self.trail_length);
+                changed |= ui.add(egui::Slider::new(&mut self.scale, 5.0..=150.0)
+                    .nudge(ui, 1.0)
+                    .text("Scale")).changed();
+            });
+
+        egui::CollapsingHeader::new("🎨 Color Settings")
+            .show(ui, |ui| {
+                changed |= color_scheme_combo(ui, &mut self.color_scheme);
+
+                egui::ComboBox::from_label("Color By")
+                    .selected_text(self.trajectory_coloring.name())
+                    .show_ui(ui, |ui| {
+                        for coloring in TrajectoryColoring::all() {
+                            if ui.selectable_value(&mut self.trajectory_coloring, coloring, coloring.name()).clicked() {
+                                changed = true;
+                            }
+                        }
+                    });
+            });
+
+        self.section.ui(ui, -10.0..=10.0, &self.points);
+
+        egui::CollapsingHeader::new("🎯 Presets")
+            .show(ui, |ui| {
+                if ui.button("Thomas").clicked() {
+                    self.apply_preset(CyclicNonlinearity::Sine, 0.208186, 0.1, 80.0);
+                    changed = true;
+                }
+                if ui.button("Labyrinth (Low Dissipation)").clicked() {
+                    self.apply_preset(CyclicNonlinearity::Sine, 0.05, 0.1, 15.0);
+                    changed = true;
+                }
+                if ui.button("Halvorsen").clicked() {
+                    self.apply_preset(CyclicNonlinearity::Quadratic, 1.89, 0.005, 20.0);
+                    changed = true;
+                }
+                if ui.button("Cubic Limit Cycle").clicked() {
+                    self.apply_preset(CyclicNonlinearity::Cubic, 0.3, 0.05, 80.0);
+                    changed = true;
+                }
+            });
+
+        let edit = self.seed.ui(ui);
+        if edit == SeedEdit::Randomize {
+            if let Some(point) = self.seed.random(|p| self.derivatives(p), self.dt) {
+                self.seed.point = point;
+            }
+        }
+        if edit != SeedEdit::None {
+            self.reset();
+            changed = true;
+        }
+
+        if ui.button("🔄 Reset").clicked() {
+            self.reset();
+            changed = true;
+        }
+
+        changed
+    }
+
+    fn reset(&mut self) {
+        self.points.clear();
+        self.emitter.clear();
+        self.section.clear();
+        [self.x, self.y, self.z] = self.seed.start(|p| self.derivatives(p));
+    }
+}
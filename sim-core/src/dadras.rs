@@ -1,4 +1,30 @@
-use crate::{ColorScheme, Simulation3D};
+use crate::color_ui::color_scheme_combo;
+use crate::nudge::Nudge;
+use crate::poincare::{PoincareSection, SectionAxis};
+use crate::seed::{SeedEdit, SeedPoint, SeedPreset};
+use crate::trajectory::{TrailEmitter, TrajectoryColoring};
+use crate::{Color, ColorScheme, Simulation3D};
+
+/// Seed points, with what the orbit does from each under the default
+/// parameters. The x axis is invariant and decays into the origin, so none
+/// of them sit on it.
+const SEEDS: [SeedPreset; 3] = [
+    SeedPreset {
+        name: "Near Origin",
+        point: [0.1, 0.1, 0.1],
+        behavior: "Lingers on the positive-x scroll for a long time before its first crossing to the other",
+    },
+    SeedPreset {
+        name: "Opposite Corner",
+        point: [-1.0, 1.0, -1.0],
+        behavior: "Switches between the scrolls almost immediately",
+    },
+    SeedPreset {
+        name: "Far Corner",
+        point: [3.0, 3.0, 0.0],
+        behavior: "Swings in from outside and starts switching scrolls after a few loops",
+    },
+];
 
 pub struct DadrasAttractor {
     pub a: f32,
@@ -9,6 +35,10 @@ pub struct DadrasAttractor {
     pub dt: f32,
     pub trail_length: usize,
     pub color_scheme: ColorScheme,
+    pub trajectory_coloring: TrajectoryColoring,
+    pub emitter: TrailEmitter,
+    pub section: PoincareSection,
+    pub seed: SeedPoint,
     pub scale: f32,
     points: Vec<[f32; 3]>,
     x: f32,
@@ -27,6 +57,10 @@ impl Default for DadrasAttractor {
             dt: 0.005,
             trail_length: 5000,
             color_scheme: ColorScheme::Inferno,
+            trajectory_coloring: TrajectoryColoring::Index,
+            emitter: TrailEmitter::new(),
+            section: PoincareSection::new(SectionAxis::X, 0.0),
+            seed: SeedPoint::new(5.0, &SEEDS),
             scale: 15.0,
             points: Vec::new(),
             x: 0.1,
@@ -40,6 +74,15 @@ impl DadrasAttractor {
     pub fn new() -> Self {
         Self::default()
     }
+
+    /// Dadras equations
+    fn derivatives(&self, [x, y, z]: [f32; 3]) -> [f32; 3] {
+        [
+            y - self.a * x + self.b * y * z,
+            self.c * y - x * z + z,
+            self.d * x * y - self.e * z,
+        ]
+    }
 }
 
 impl Simulation3D for DadrasAttractor {
@@ -47,26 +90,27 @@ impl Simulation3D for DadrasAttractor {
         "Dadras Attractor"
     }
 
+    fn equations(&self) -> Option<&str> {
+        Some(
+            "dx/dt = y − a x + b y z\n\
+             dy/dt = c y − x z + z\n\
+             dz/dt = d x y − e z",
+        )
+    }
+
     fn step(&mut self, _dt: f32) {
         for _ in 0..10 {
-            // Dadras equations
-            let dx = self.y - self.a * self.x + self.b * self.y * self.z;
-            let dy = self.c * self.y - self.x * self.z + self.z;
-            let dz = self.d * self.x * self.y - self.e * self.z;
+            let [dx, dy, dz] = self.derivatives([self.x, self.y, self.z]);
 
+            let prev = [self.x, self.y, self.z];
             self.x += dx * self.dt;
             self.y += dy * self.dt;
             self.z += dz * self.dt;
+            self.section.record(prev, [self.x, self.y, self.z]);
 
-            self.points.push([
-                self.x * self.scale,
-                self.y * self.scale,
-                self.z * self.scale,
-            ]);
-
-            if self.points.len() > self.trail_length {
-                self.points.remove(0);
-            }
+            let scale = self.scale;
+            let next = [self.x * scale, self.y * scale, self.z * scale];
+            self.emitter.push(&mut self.points, prev.map(|v| v * scale), next, self.trail_length);
         }
     }
 
@@ -74,6 +118,14 @@ impl Simulation3D for DadrasAttractor {
         self.points.clone()
     }
 
+    fn get_colors(&self) -> Option<Vec<Color>> {
+        self.emitter.colors(&self.points, self.trajectory_coloring, &self.color_scheme)
+    }
+
+    fn poincare_section(&self) -> Option<&PoincareSection> {
+        Some(&self.section)
+    }
+
     fn ui_parameters(&mut self, ui: &mut egui::Ui) -> bool {
         let mut changed = false;
 
@@ -83,16 +135,22 @@ impl Simulation3D for DadrasAttractor {
             .default_open(true)
             .show(ui, |ui| {
                 changed |= ui.add(egui::Slider::new(&mut self.a, 1.0..=5.0)
+                    .nudge(ui, 0.02)
                     .text("a")).changed();
                 changed |= ui.add(egui::Slider::new(&mut self.b, 1.0..=5.0)
+                    .nudge(ui, 0.02)
                     .text("b")).changed();
                 changed |= ui.add(egui::Slider::new(&mut self.c, 0.5..=3.0)
+                    .nudge(ui, 0.02)
                     .text("c")).changed();
                 changed |= ui.add(egui::Slider::new(&mut self.d, 0.5..=4.0)
+                    .nudge(ui, 0.02)
                     .text("d")).changed();
                 changed |= ui.add(egui::Slider::new(&mut self.e, 5.0..=12.0)
+                    .nudge(ui, 0.05)
                     .text("e")).changed();
                 changed |= ui.add(egui::Slider::new(&mut self.dt, 0.001..=0.02)
+                    .nudge(ui, 0.0001)
                     .text("Time Step")).changed();
             });
 
@@ -100,24 +158,31 @@ impl Simulation3D for DadrasAttractor {
             .default_open(true)
             .show(ui, |ui| {
                 changed |= ui.add(egui::Slider::new(&mut self.trail_length, 100..=10000)
+                    .nudge(ui, 1.0)
                     .text("Trail Length")).changed();
+                changed |= self.emitter.ui(ui, &mut self.points, self.trail_length);
                 changed |= ui.add(egui::Slider::new(&mut self.scale, 5.0..=30.0)
+                    .nudge(ui, 0.2)
                     .text("Scale")).changed();
             });
 
         egui::CollapsingHeader::new("🎨 Color Settings")
             .show(ui, |ui| {
-                egui::ComboBox::from_label("Color Scheme")
-                    .selected_text(self.color_scheme.name())
+                changed |= color_scheme_combo(ui, &mut self.color_scheme);
+
+                egui::ComboBox::from_label("Color By")
+                    .selected_text(self.trajectory_coloring.name())
                     .show_ui(ui, |ui| {
-                        for scheme in ColorScheme::all() {
-                            if ui.selectable_value(&mut self.color_scheme, scheme, scheme.name()).clicked() {
+                        for coloring in TrajectoryColoring::all() {
+                            if ui.selectable_value(&mut self.trajectory_coloring, coloring, coloring.name()).clicked() {
                                 changed = true;
                             }
                         }
                     });
             });
 
+        self.section.ui(ui, -15.0..=15.0, &self.points);
+
         egui::CollapsingHeader::new("🎯 Presets")
             .show(ui, |ui| {
                 if ui.button("Classic").clicked() {
@@ -138,6 +203,17 @@ impl Simulation3D for DadrasAttractor {
                 }
             });
 
+        let edit = self.seed.ui(ui);
+        if edit == SeedEdit::Randomize {
+            if let Some(point) = self.seed.random(|p| self.derivatives(p), self.dt) {
+                self.seed.point = point;
+            }
+        }
+        if edit != SeedEdit::None {
+            self.reset();
+            changed = true;
+        }
+
         if ui.button("🔄 Reset").clicked() {
             self.reset();
             changed = true;
@@ -148,8 +224,8 @@ impl Simulation3D for DadrasAttractor {
 
     fn reset(&mut self) {
         self.points.clear();
-        self.x = 0.1;
-        self.y = 0.1;
-        self.z = 0.1;
+        self.emitter.clear();
+        self.section.clear();
+        [self.x, self.y, self.z] = self.seed.start(|p| self.derivatives(p));
     }
 }
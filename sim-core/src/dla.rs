@@ -1,5 +1,14 @@
-use crate::{Color, ColorScheme, Simulation2D};
-use rand::Rng;
+use crate::color_ui::{color_scheme_combo, PaletteTransform};
+use crate::grid_render::{grid_filter_ui, render_grid, GridFilter};
+use crate::nudge::Nudge;
+use crate::readout;
+use crate::step_runner::StepRunner;
+use crate::{Color, ColorScheme, RenderCtx, Simulation2D};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+/// Walkers a "Run N" releases per frame
+const RUN_BATCH: u64 = 100;
 
 pub struct DLA {
     pub grid_width: usize,
@@ -9,12 +18,24 @@ pub struct DLA {
     pub spawn_radius_ratio: f32,
     pub color_by_age: bool,
     pub color_scheme: ColorScheme,
+    pub palette_transform: PaletteTransform,
     pub particle_size: f32,
+    pub steps_per_second: f32,
+    pub grid_filter: GridFilter,
+    /// Seed for the walkers' random numbers; the same seed and start grow
+    /// the same cluster
+    pub rng_seed: u64,
 
     grid: Vec<Option<usize>>,  // Some(age) if particle stuck, None if empty
     particles_stuck: usize,
     max_radius: f32,
     paused: bool,
+    time_accumulator: f32,
+    rng: StdRng,
+    /// Walkers released since the last reset, stuck or escaped
+    steps: u64,
+    /// Exact step counts queued from the UI
+    runner: StepRunner,
 }
 
 impl Default for DLA {
@@ -34,11 +55,19 @@ impl Default for DLA {
             spawn_radius_ratio: 0.8,
             color_by_age: true,
             color_scheme: ColorScheme::Ice,
+            palette_transform: PaletteTransform::default(),
             particle_size: 1.5,
+            steps_per_second: 300.0,
+            grid_filter: GridFilter::Sharp,
+            rng_seed: 0,
             grid,
             particles_stuck: 1,
             max_radius: 1.0,
             paused: false,
+            time_accumulator: 0.0,
+            rng: StdRng::seed_from_u64(0),
+            steps: 0,
+            runner: StepRunner::new(RUN_BATCH),
         }
     }
 }
@@ -49,11 +78,12 @@ impl DLA {
     }
 
     fn step(&mut self) -> bool {
-        if self.paused || self.particles_stuck >= self.num_particles {
+        if self.particles_stuck >= self.num_particles {
             return false;
         }
+        self.steps += 1;
 
-        let mut rng = rand::thread_rng();
+        let rng = &mut self.rng;
 
         // Spawn radius - outside the structure
         let spawn_radius = (self.max_radius + 10.0).max(50.0);
@@ -137,21 +167,13 @@ impl DLA {
         true
     }
 
-    pub fn reset(&mut self) {
-        // Resize grid if dimensions changed
-        let required_size = self.grid_width * self.grid_height;
-        if self.grid.len() != required_size {
-            self.grid = vec![None; required_size];
-        } else {
-            self.grid.fill(None);
-        }
-
-        // Seed with center particle
-        let center_idx = self.grid_height / 2 * self.grid_width + self.grid_width / 2;
-        self.grid[center_idx] = Some(0);
-        self.particles_stuck = 1;
-        self.max_radius = 1.0;
-        self.paused = false;
+    /// Rewind the walkers' random numbers and the step count, so a run from
+    /// a fresh start repeats exactly
+    fn restart_walkers(&mut self) {
+        self.rng = StdRng::seed_from_u64(self.rng_seed);
+        self.steps = 0;
+        self.time_accumulator = 0.0;
+        self.runner.cancel();
     }
 
     pub fn reset_with_seed(&mut self, seed: &str) {
@@ -203,6 +225,7 @@ impl DLA {
 
         self.max_radius = 15.0;
         self.paused = false;
+        self.restart_walkers();
     }
 }
 
@@ -212,39 +235,38 @@ impl Simulation2D for DLA {
     }
 
     fn compute(&self, width: usize, height: usize) -> Vec<Color> {
-        let mut colors = Vec::with_capacity(width * height);
-
-        let cell_width = width / self.grid_width;
-        let cell_height = height / self.grid_height;
-
-        for py in 0..height {
-            for px in 0..width {
-                let gx = px / cell_width.max(1);
-                let gy = py / cell_height.max(1);
-
-                if gx < self.grid_width && gy < self.grid_height {
-                    let idx = gy * self.grid_width + gx;
-
-                    if let Some(age) = self.grid[idx] {
-                        let color = if self.color_by_age {
-                            let t = age as f32 / self.num_particles as f32;
-                            self.color_scheme.map(t, true)
-                        } else {
-                            Color::WHITE
-                        };
-
-                        // Draw larger particles for visibility
-                        colors.push(color);
-                    } else {
-                        colors.push(Color::BLACK);
-                    }
-                } else {
-                    colors.push(Color::BLACK);
+        self.compute_ctx(&RenderCtx::new(width, height))
+    }
+
+    fn compute_ctx(&self, ctx: &RenderCtx) -> Vec<Color> {
+        render_grid(self.grid_width, self.grid_height, ctx.width, ctx.height, self.grid_filter, |idx| {
+            match self.grid[idx] {
+                Some(age) if self.color_by_age => {
+                    let t = age as f32 / self.num_particles as f32;
+                    self.palette_transform.map(&self.color_scheme, ctx.cycle_palette(t), true)
                 }
+                Some(_) => Color::WHITE,
+                None => ctx.background,
             }
+        })
+    }
+
+    fn reset(&mut self) {
+        // Resize grid if dimensions changed
+        let required_size = self.grid_width * self.grid_height;
+        if self.grid.len() != required_size {
+            self.grid = vec![None; required_size];
+        } else {
+            self.grid.fill(None);
         }
 
-        colors
+        // Seed with center particle
+        let center_idx = self.grid_height / 2 * self.grid_width + self.grid_width / 2;
+        self.grid[center_idx] = Some(0);
+        self.particles_stuck = 1;
+        self.max_radius = 1.0;
+        self.paused = false;
+        self.restart_walkers();
     }
 
     fn ui_parameters(&mut self, ui: &mut egui::Ui) -> bool {
@@ -252,8 +274,8 @@ impl Simulation2D for DLA {
 
         ui.heading("Diffusion-Limited Aggregation");
 
-        ui.label(format!("Particles stuck: {} / {}",
-                        self.particles_stuck, self.num_particles));
+        ui.label(format!("Particles stuck: {}",
+                        readout::fraction(self.particles_stuck, self.num_particles)));
 
         let progress = self.particles_stuck as f32 / self.num_particles as f32;
         ui.add(egui::ProgressBar::new(progress).show_percentage());
@@ -264,13 +286,29 @@ impl Simulation2D for DLA {
             .default_open(true)
             .show(ui, |ui| {
                 if ui.add(egui::Slider::new(&mut self.num_particles, 100..=10000)
+                    .nudge(ui, 1.0)
                     .text("Max Particles")).changed() {
                     changed = true;
                 }
 
                 changed |= ui.add(egui::Slider::new(&mut self.stickiness, 0.1..=1.0)
+                    .nudge(ui, 0.005)
                     .text("Stickiness")).changed();
 
+                changed |= ui.add(egui::Slider::new(&mut self.steps_per_second, 10.0..=3000.0)
+                    .nudge(ui, 5.0)
+                    .logarithmic(true)
+                    .text("Steps per second")).changed();
+
+                ui.horizontal(|ui| {
+                    ui.label("Random Seed:");
+                    if ui.add(egui::DragValue::new(&mut self.rng_seed))
+                        .on_hover_text("Takes effect on the next reset")
+                        .changed() {
+                        changed = true;
+                    }
+                });
+
                 ui.label("Stickiness: chance to stick when");
                 ui.label("touching another particle.");
                 ui.label("Lower = more dendritic branches");
@@ -281,15 +319,9 @@ impl Simulation2D for DLA {
             .show(ui, |ui| {
                 changed |= ui.checkbox(&mut self.color_by_age, "Color by Age").changed();
 
-                egui::ComboBox::from_label("Color Scheme")
-                    .selected_text(self.color_scheme.name())
-                    .show_ui(ui, |ui| {
-                        for scheme in ColorScheme::all() {
-                            if ui.selectable_value(&mut self.color_scheme, scheme, scheme.name()).clicked() {
-                                changed = true;
-                            }
-                        }
-                    });
+                changed |= color_scheme_combo(ui, &mut self.color_scheme);
+                changed |= self.palette_transform.ui(ui);
+                changed |= grid_filter_ui(ui, &mut self.grid_filter);
             });
 
         egui::CollapsingHeader::new("🎯 Seeds")
@@ -325,6 +357,11 @@ impl Simulation2D for DLA {
             }
         });
 
+        ui.label(format!("Step: {}", readout::count(self.steps)));
+        if self.runner.ui(ui) {
+            self.paused = true;
+        }
+
         egui::CollapsingHeader::new("📊 Info")
             .show(ui, |ui| {
                 ui.label("DLA simulates particles randomly");
@@ -335,16 +372,36 @@ impl Simulation2D for DLA {
                 ui.label("river deltas in nature.");
             });
 
-        // Run simulation steps
-        if !self.paused {
-            for _ in 0..5 {
+        changed
+    }
+
+    fn update(&mut self, dt: f32, _width: usize, _height: usize) -> bool {
+        let batch = self.runner.take();
+        if batch > 0 {
+            for _ in 0..batch {
                 if !self.step() {
+                    self.runner.cancel();
                     break;
                 }
             }
-            changed = true;
+            self.time_accumulator = 0.0;
+            return true;
         }
 
-        changed
+        if self.paused {
+            return false;
+        }
+
+        // Run simulation steps at a fixed rate, independent of frame rate
+        self.time_accumulator += dt * self.steps_per_second;
+
+        while self.time_accumulator >= 1.0 {
+            self.time_accumulator -= 1.0;
+            if !self.step() {
+                self.time_accumulator = 0.0;
+                break;
+            }
+        }
+        true
     }
 }
@@ -1,4 +1,5 @@
 use crate::Simulation3D;
+use crate::nudge::Nudge;
 
 pub struct DNAHelix {
     pub radius: f32,
@@ -159,24 +160,28 @@ impl Simulation3D for DNAHelix {
             .default_open(true)
             .show(ui, |ui| {
                 if ui.add(egui::Slider::new(&mut self.radius, 2.0..=10.0)
+                    .nudge(ui, 0.05)
                     .text("Radius")).changed() {
                     self.regenerate();
                     changed = true;
                 }
 
                 if ui.add(egui::Slider::new(&mut self.helix_height, 20.0..=80.0)
+                    .nudge(ui, 0.5)
                     .text("Height")).changed() {
                     self.regenerate();
                     changed = true;
                 }
 
                 if ui.add(egui::Slider::new(&mut self.twist_rate, 1.0..=10.0)
+                    .nudge(ui, 0.05)
                     .text("Twist Rate")).changed() {
                     self.regenerate();
                     changed = true;
                 }
 
                 if ui.add(egui::Slider::new(&mut self.num_base_pairs, 5..=50)
+                    .nudge(ui, 1.0)
                     .text("Base Pairs")).changed() {
                     self.regenerate();
                     changed = true;
@@ -197,9 +202,11 @@ impl Simulation3D for DNAHelix {
                 }
 
                 ui.add(egui::Slider::new(&mut self.animation_speed, 0.0..=5.0)
+                    .nudge(ui, 0.05)
                     .text("Animation Speed"));
 
                 if ui.add(egui::Slider::new(&mut self.points_per_turn, 20..=100)
+                    .nudge(ui, 1.0)
                     .text("Detail Level")).changed() {
                     self.regenerate();
                     changed = true;
@@ -1,4 +1,148 @@
-use crate::{Color, ColorScheme, Simulation2D};
+use crate::color_ui::color_scheme_combo;
+use crate::nudge::Nudge;
+use crate::{Color, ColorScheme, RenderCtx, Simulation2D};
+
+/// Angles and angular velocities of both arms
+#[derive(Clone, Copy)]
+struct PendulumState {
+    angle1: f32,
+    angle2: f32,
+    velocity1: f32,
+    velocity2: f32,
+}
+
+impl PendulumState {
+    fn new(angle1: f32, angle2: f32, velocity1: f32, velocity2: f32) -> Self {
+        Self { angle1, angle2, velocity1, velocity2 }
+    }
+
+    fn is_finite(&self) -> bool {
+        [self.angle1, self.angle2, self.velocity1, self.velocity2].iter().all(|v| v.is_finite())
+    }
+
+    /// `self + rate * dt`, for the RK4 stages
+    fn offset(&self, rate: &PendulumState, dt: f32) -> Self {
+        Self {
+            angle1: self.angle1 + rate.angle1 * dt,
+            angle2: self.angle2 + rate.angle2 * dt,
+            velocity1: self.velocity1 + rate.velocity1 * dt,
+            velocity2: self.velocity2 + rate.velocity2 * dt,
+        }
+    }
+}
+
+/// Physical constants for one step, with the arm lengths in pixels
+#[derive(Clone, Copy)]
+struct Physics {
+    g: f32,
+    m1: f32,
+    m2: f32,
+    l1: f32,
+    l2: f32,
+}
+
+impl Physics {
+    /// Angular accelerations of both arms (Lagrangian mechanics)
+    fn accelerations(&self, s: &PendulumState) -> (f32, f32) {
+        let Physics { g, m1, m2, l1, l2 } = *self;
+        let (a1, a2, v1, v2) = (s.angle1, s.angle2, s.velocity1, s.velocity2);
+
+        let num1 = -g * (2.0 * m1 + m2) * a1.sin()
+                   - m2 * g * (a1 - 2.0 * a2).sin()
+                   - 2.0 * (a1 - a2).sin() * m2 * (v2 * v2 * l2 + v1 * v1 * l1 * (a1 - a2).cos());
+        let den1 = l1 * (2.0 * m1 + m2 - m2 * (2.0 * a1 - 2.0 * a2).cos());
+
+        let num2 = 2.0 * (a1 - a2).sin()
+                   * (v1 * v1 * l1 * (m1 + m2) + g * (m1 + m2) * a1.cos() + v2 * v2 * l2 * m2 * (a1 - a2).cos());
+        let den2 = l2 * (2.0 * m1 + m2 - m2 * (2.0 * a1 - 2.0 * a2).cos());
+
+        (num1 / den1, num2 / den2)
+    }
+
+    /// Time derivative of the whole state
+    fn derivative(&self, s: &PendulumState) -> PendulumState {
+        let (acc1, acc2) = self.accelerations(s);
+        PendulumState::new(s.velocity1, s.velocity2, acc1, acc2)
+    }
+
+    /// Second bob relative to the pivot
+    fn bob2(&self, s: &PendulumState) -> (f32, f32) {
+        (self.l1 * s.angle1.sin() + self.l2 * s.angle2.sin(),
+         self.l1 * s.angle1.cos() + self.l2 * s.angle2.cos())
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Integrator {
+    /// Explicit Euler: positions move with the old velocities. Gains energy
+    /// every step, so the swing grows until it spins wildly.
+    Euler,
+    /// Semi-implicit Euler: velocities first, then positions with the new
+    /// velocities. Keeps energy bounded; the sim's normal integrator.
+    SymplecticEuler,
+    /// Classic fourth-order Runge-Kutta: much more accurate per step, but
+    /// slowly bleeds energy over long runs
+    RungeKutta4,
+}
+
+impl Integrator {
+    pub fn all() -> Vec<Integrator> {
+        vec![Integrator::Euler, Integrator::SymplecticEuler, Integrator::RungeKutta4]
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            Integrator::Euler => "Euler",
+            Integrator::SymplecticEuler => "Symplectic Euler",
+            Integrator::RungeKutta4 => "RK4",
+        }
+    }
+
+    /// Trace color in comparison mode
+    fn color(&self) -> Color {
+        match self {
+            Integrator::Euler => Color::from_rgb(255, 90, 90),
+            Integrator::SymplecticEuler => Color::from_rgb(90, 160, 255),
+            Integrator::RungeKutta4 => Color::from_rgb(90, 230, 120),
+        }
+    }
+
+    fn step(&self, s: &PendulumState, physics: &Physics, dt: f32) -> PendulumState {
+        match self {
+            Integrator::Euler => {
+                let rate = physics.derivative(s);
+                s.offset(&rate, dt)
+            }
+            Integrator::SymplecticEuler => {
+                let (acc1, acc2) = physics.accelerations(s);
+                let velocity1 = s.velocity1 + acc1 * dt;
+                let velocity2 = s.velocity2 + acc2 * dt;
+                PendulumState::new(s.angle1 + velocity1 * dt, s.angle2 + velocity2 * dt, velocity1, velocity2)
+            }
+            Integrator::RungeKutta4 => {
+                let k1 = physics.derivative(s);
+                let k2 = physics.derivative(&s.offset(&k1, dt / 2.0));
+                let k3 = physics.derivative(&s.offset(&k2, dt / 2.0));
+                let k4 = physics.derivative(&s.offset(&k3, dt));
+                let blend = |a: f32, b: f32, c: f32, d: f32| (a + 2.0 * b + 2.0 * c + d) / 6.0;
+                let rate = PendulumState::new(
+                    blend(k1.angle1, k2.angle1, k3.angle1, k4.angle1),
+                    blend(k1.angle2, k2.angle2, k3.angle2, k4.angle2),
+                    blend(k1.velocity1, k2.velocity1, k3.velocity1, k4.velocity1),
+                    blend(k1.velocity2, k2.velocity2, k3.velocity2, k4.velocity2),
+                );
+                s.offset(&rate, dt)
+            }
+        }
+    }
+}
+
+/// One integrator's copy of the pendulum in comparison mode
+struct ComparisonRun {
+    integrator: Integrator,
+    state: PendulumState,
+    trace: Vec<(f32, f32)>,
+}
 
 pub struct DoublePendulum {
     pub length1: f32,
@@ -11,11 +155,14 @@ pub struct DoublePendulum {
     pub show_trace: bool,
     pub color_scheme: ColorScheme,
     pub scale: f32,
-    angle1: f32,
-    angle2: f32,
-    velocity1: f32,
-    velocity2: f32,
+    /// Run a copy of the pendulum per integrator from the same start and
+    /// overlay their traces instead of showing the single pendulum
+    pub compare_integrators: bool,
+    /// Which comparison runs are drawn, in `Integrator::all()` order
+    pub integrators_shown: [bool; 3],
+    state: PendulumState,
     trace: Vec<(f32, f32)>,
+    comparison: Vec<ComparisonRun>,
 }
 
 impl Default for DoublePendulum {
@@ -31,11 +178,11 @@ impl Default for DoublePendulum {
             show_trace: true,
             color_scheme: ColorScheme::Rainbow,
             scale: 1.0,  // Overall scale multiplier
-            angle1: std::f32::consts::PI / 2.0,
-            angle2: std::f32::consts::PI / 2.0,
-            velocity1: 0.0,
-            velocity2: 0.0,
+            compare_integrators: false,
+            integrators_shown: [true; 3],
+            state: PendulumState::new(std::f32::consts::PI / 2.0, std::f32::consts::PI / 2.0, 0.0, 0.0),
             trace: Vec::new(),
+            comparison: Vec::new(),
         }
     }
 }
@@ -45,48 +192,93 @@ impl DoublePendulum {
         Self::default()
     }
 
-    fn update(&mut self, dt: f32, canvas_scale: f32) {
-        let g = self.gravity;
-        let m1 = self.mass1;
-        let m2 = self.mass2;
+    fn physics(&self, canvas_scale: f32) -> Physics {
         // Use actual pixel lengths for physics
-        let l1 = self.length1 * canvas_scale * self.scale;
-        let l2 = self.length2 * canvas_scale * self.scale;
-        let a1 = self.angle1;
-        let a2 = self.angle2;
-        let v1 = self.velocity1;
-        let v2 = self.velocity2;
-
-        // Double pendulum equations (Lagrangian mechanics)
-        let num1 = -g * (2.0 * m1 + m2) * a1.sin()
-                   - m2 * g * (a1 - 2.0 * a2).sin()
-                   - 2.0 * (a1 - a2).sin() * m2 * (v2 * v2 * l2 + v1 * v1 * l1 * (a1 - a2).cos());
-        let den1 = l1 * (2.0 * m1 + m2 - m2 * (2.0 * a1 - 2.0 * a2).cos());
-        let acc1 = num1 / den1;
+        Physics {
+            g: self.gravity,
+            m1: self.mass1,
+            m2: self.mass2,
+            l1: self.length1 * canvas_scale * self.scale,
+            l2: self.length2 * canvas_scale * self.scale,
+        }
+    }
 
-        let num2 = 2.0 * (a1 - a2).sin()
-                   * (v1 * v1 * l1 * (m1 + m2) + g * (m1 + m2) * a1.cos() + v2 * v2 * l2 * m2 * (a1 - a2).cos());
-        let den2 = l2 * (2.0 * m1 + m2 - m2 * (2.0 * a1 - 2.0 * a2).cos());
-        let acc2 = num2 / den2;
+    fn advance(&mut self, dt: f32, canvas_scale: f32) {
+        let physics = self.physics(canvas_scale);
+        let damping = self.damping;
+        let trace_length = self.trace_length;
+
+        // One step with damping, then record the second bob
+        let advance = |integrator: Integrator, state: &mut PendulumState, trace: &mut Vec<(f32, f32)>| {
+            let next = integrator.step(state, &physics, dt);
+            // Explicit Euler can run away to infinity; freeze it instead
+            if !next.is_finite() {
+                return;
+            }
+            *state = next;
+            state.velocity1 *= damping;
+            state.velocity2 *= damping;
 
-        self.velocity1 += acc1 * dt;
-        self.velocity2 += acc2 * dt;
+            trace.push(physics.bob2(state));
+            if trace.len() > trace_length {
+                trace.remove(0);
+            }
+        };
 
-        // Apply damping
-        self.velocity1 *= self.damping;
-        self.velocity2 *= self.damping;
+        if self.compare_integrators {
+            // Every run steps, shown or not, so toggling one back on shows
+            // it in step with the others
+            for run in &mut self.comparison {
+                advance(run.integrator, &mut run.state, &mut run.trace);
+            }
+        } else {
+            advance(Integrator::SymplecticEuler, &mut self.state, &mut self.trace);
+        }
+    }
 
-        self.angle1 += self.velocity1 * dt;
-        self.angle2 += self.velocity2 * dt;
+    /// Start the pendulum over from `state`, restarting every comparison
+    /// run from it too
+    fn set_state(&mut self, state: PendulumState) {
+        self.state = state;
+        self.trace.clear();
+        self.restart_comparison();
+    }
+
+    /// One run per integrator, all from the pendulum's current state
+    fn restart_comparison(&mut self) {
+        self.comparison = Integrator::all()
+            .into_iter()
+            .map(|integrator| ComparisonRun {
+                integrator,
+                state: self.state,
+                trace: Vec::new(),
+            })
+            .collect();
+    }
 
-        // Calculate second bob position for trace
-        let x2 = l1 * self.angle1.sin() + l2 * self.angle2.sin();
-        let y2 = l1 * self.angle1.cos() + l2 * self.angle2.cos();
+    /// Rods and bobs for one pendulum hanging from `(cx, cy)`
+    #[allow(clippy::too_many_arguments)]
+    fn draw_pendulum(&self, pixels: &mut [Color], width: usize, height: usize,
+                     cx: f32, cy: f32, physics: &Physics, state: &PendulumState,
+                     rod: Color, bob1: Color, bob2: Color) {
+        let x1 = cx + physics.l1 * state.angle1.sin();
+        let y1 = cy + physics.l1 * state.angle1.cos();
 
-        self.trace.push((x2, y2));
-        if self.trace.len() > self.trace_length {
-            self.trace.remove(0);
-        }
+        let x2 = x1 + physics.l2 * state.angle2.sin();
+        let y2 = y1 + physics.l2 * state.angle2.cos();
+
+        // Draw rods
+        self.draw_line(pixels, width, height,
+            cx as i32, cy as i32, x1 as i32, y1 as i32, rod);
+        self.draw_line(pixels, width, height,
+            x1 as i32, y1 as i32, x2 as i32, y2 as i32, rod);
+
+        // Draw bobs
+        let bob1_radius = (self.mass1 * 2.0).sqrt();
+        let bob2_radius = (self.mass2 * 2.0).sqrt();
+
+        self.draw_circle(pixels, width, height, x1, y1, bob1_radius, bob1);
+        self.draw_circle(pixels, width, height, x2, y2, bob2_radius, bob2);
     }
 
     fn draw_circle(&self, pixels: &mut [Color], width: usize, height: usize,
@@ -148,7 +340,20 @@ impl Simulation2D for DoublePendulum {
         "Double Pendulum"
     }
 
+    fn equations(&self) -> Option<&str> {
+        Some(
+            "Δ = θ₁ − θ₂,  M = 2m₁ + m₂ − m₂ cos 2Δ\n\
+             L₁ M θ₁'' = −g (2m₁ + m₂) sin θ₁ − m₂ g sin(θ₁ − 2θ₂) − 2 m₂ sin Δ (θ₂'² L₂ + θ₁'² L₁ cos Δ)\n\
+             L₂ M θ₂'' = 2 sin Δ (θ₁'² L₁ (m₁ + m₂) + g (m₁ + m₂) cos θ₁ + θ₂'² L₂ m₂ cos Δ)",
+        )
+    }
+
     fn compute(&self, width: usize, height: usize) -> Vec<Color> {
+        self.compute_ctx(&RenderCtx::new(width, height))
+    }
+
+    fn compute_ctx(&self, ctx: &RenderCtx) -> Vec<Color> {
+        let (width, height) = (ctx.width, ctx.height);
         let mut pixels = vec![Color::BLACK; width * height];
 
         let cx = width as f32 / 2.0;
@@ -156,10 +361,33 @@ impl Simulation2D for DoublePendulum {
 
         // Calculate canvas scale - use the smaller dimension to ensure it fits
         let canvas_scale = width.min(height) as f32;
+        let physics = self.physics(canvas_scale);
+
+        if self.compare_integrators {
+            let shown = self.comparison.iter()
+                .zip(self.integrators_shown)
+                .filter(|(_, shown)| *shown)
+                .map(|(run, _)| run);
+
+            // Each trace fades in toward its integrator's color
+            for run in shown.clone() {
+                let color = run.integrator.color();
+                for (i, &(x, y)) in run.trace.iter().enumerate() {
+                    let t = (i + 1) as f32 / run.trace.len() as f32;
+                    let faded = Color::lerp(Color::BLACK, color, 0.2 + 0.8 * t);
+                    self.draw_circle(&mut pixels, width, height, cx + x, cy + y, 1.5, faded);
+                }
+            }
 
-        // Actual lengths in pixels (ratio * canvas_scale * user scale)
-        let len1_px = self.length1 * canvas_scale * self.scale;
-        let len2_px = self.length2 * canvas_scale * self.scale;
+            self.draw_circle(&mut pixels, width, height, cx, cy, 5.0, Color::from_rgb(100, 100, 100));
+            for run in shown {
+                let color = run.integrator.color();
+                let rod = Color::lerp(Color::BLACK, color, 0.6);
+                self.draw_pendulum(&mut pixels, width, height, cx, cy, &physics, &run.state, rod, color, color);
+            }
+
+            return pixels;
+        }
 
         // Draw trace
         if self.show_trace {
@@ -169,34 +397,21 @@ impl Simulation2D for DoublePendulum {
 
                 if px >= 0.0 && px < width as f32 && py >= 0.0 && py < height as f32 {
                     let t = i as f32 / self.trace.len() as f32;
-                    let color = self.color_scheme.map(t, true);
+                    let color = self.color_scheme.map(ctx.cycle_palette(t), true);
                     self.draw_circle(&mut pixels, width, height, px, py, 2.0, color);
                 }
             }
         }
 
-        // Calculate bob positions
-        let x1 = cx + len1_px * self.angle1.sin();
-        let y1 = cy + len1_px * self.angle1.cos();
-
-        let x2 = x1 + len2_px * self.angle2.sin();
-        let y2 = y1 + len2_px * self.angle2.cos();
-
-        // Draw rods
-        self.draw_line(&mut pixels, width, height,
-            cx as i32, cy as i32, x1 as i32, y1 as i32, Color::WHITE);
-        self.draw_line(&mut pixels, width, height,
-            x1 as i32, y1 as i32, x2 as i32, y2 as i32, Color::WHITE);
-
-        // Draw bobs
-        let bob1_radius = (self.mass1 * 2.0).sqrt();
-        let bob2_radius = (self.mass2 * 2.0).sqrt();
-
         self.draw_circle(&mut pixels, width, height, cx, cy, 5.0, Color::from_rgb(100, 100, 100));
-        self.draw_circle(&mut pixels, width, height, x1, y1, bob1_radius, Color::from_rgb(255, 100, 100));
-        self.draw_circle(&mut pixels, width, height, x2, y2, bob2_radius, Color::from_rgb(100, 100, 255));
+        self.draw_pendulum(&mut pixels, width, height, cx, cy, &physics, &self.state,
+            Color::WHITE, Color::from_rgb(255, 100, 100), Color::from_rgb(100, 100, 255));
 
-        pixels
+        ctx.over_background(pixels)
+    }
+
+    fn reset(&mut self) {
+        self.set_state(PendulumState::new(std::f32::consts::PI / 2.0, std::f32::consts::PI / 2.0, 0.0, 0.0));
     }
 
     fn ui_parameters(&mut self, ui: &mut egui::Ui) -> bool {
@@ -208,21 +423,50 @@ impl Simulation2D for DoublePendulum {
             .default_open(true)
             .show(ui, |ui| {
                 changed |= ui.add(egui::Slider::new(&mut self.length1, 0.05..=0.4)
+                    .nudge(ui, 0.002)
                     .text("Length 1 (ratio)")).changed();
                 changed |= ui.add(egui::Slider::new(&mut self.length2, 0.05..=0.4)
+                    .nudge(ui, 0.002)
                     .text("Length 2 (ratio)")).changed();
 
                 changed |= ui.add(egui::Slider::new(&mut self.mass1, 1.0..=50.0)
+                    .nudge(ui, 0.2)
                     .text("Mass 1")).changed();
                 changed |= ui.add(egui::Slider::new(&mut self.mass2, 1.0..=50.0)
+                    .nudge(ui, 0.2)
                     .text("Mass 2")).changed();
 
                 changed |= ui.add(egui::Slider::new(&mut self.gravity, 0.1..=3.0)
+                    .nudge(ui, 0.02)
                     .text("Gravity")).changed();
                 changed |= ui.add(egui::Slider::new(&mut self.damping, 0.99..=1.0)
+                    .nudge(ui, 0.0001)
                     .text("Damping")).changed();
             });
 
+        egui::CollapsingHeader::new("🧮 Integrator Comparison")
+            .show(ui, |ui| {
+                if ui.checkbox(&mut self.compare_integrators, "Compare Integrators")
+                    .on_hover_text("Run the pendulum once per integrator from the same start and overlay the second bob's paths")
+                    .changed() {
+                    self.restart_comparison();
+                    changed = true;
+                }
+
+                ui.add_enabled_ui(self.compare_integrators, |ui| {
+                    for (integrator, shown) in Integrator::all().into_iter().zip(&mut self.integrators_shown) {
+                        let color = integrator.color();
+                        let label = egui::RichText::new(integrator.name())
+                            .color(egui::Color32::from_rgb(color.r, color.g, color.b));
+                        changed |= ui.checkbox(shown, label).changed();
+                    }
+                    if ui.button("Restart Comparison").clicked() {
+                        self.restart_comparison();
+                        changed = true;
+                    }
+                });
+            });
+
         egui::CollapsingHeader::new("🎨 Visual Settings")
             .default_open(true)
             .show(ui, |ui| {
@@ -230,68 +474,46 @@ impl Simulation2D for DoublePendulum {
 
                 if self.show_trace {
                     changed |= ui.add(egui::Slider::new(&mut self.trace_length, 10..=2000)
+                        .nudge(ui, 1.0)
                         .text("Trace Length")).changed();
                 }
 
                 changed |= ui.add(egui::Slider::new(&mut self.scale, 0.3..=1.5)
+                    .nudge(ui, 0.01)
                     .text("Scale")).changed();
 
-                egui::ComboBox::from_label("Color Scheme")
-                    .selected_text(self.color_scheme.name())
-                    .show_ui(ui, |ui| {
-                        for scheme in ColorScheme::all() {
-                            if ui.selectable_value(&mut self.color_scheme, scheme, scheme.name()).clicked() {
-                                changed = true;
-                            }
-                        }
-                    });
+                changed |= color_scheme_combo(ui, &mut self.color_scheme);
             });
 
         egui::CollapsingHeader::new("🎯 Presets")
             .show(ui, |ui| {
                 if ui.button("Classic").clicked() {
-                    self.angle1 = std::f32::consts::PI / 2.0;
-                    self.angle2 = std::f32::consts::PI / 2.0;
-                    self.velocity1 = 0.0;
-                    self.velocity2 = 0.0;
-                    self.trace.clear();
+                    self.set_state(PendulumState::new(std::f32::consts::PI / 2.0, std::f32::consts::PI / 2.0, 0.0, 0.0));
                     changed = true;
                 }
                 if ui.button("Chaotic Start").clicked() {
-                    self.angle1 = std::f32::consts::PI / 2.0 + 0.1;
-                    self.angle2 = std::f32::consts::PI / 2.0;
-                    self.velocity1 = 0.0;
-                    self.velocity2 = 0.0;
-                    self.trace.clear();
+                    self.set_state(PendulumState::new(std::f32::consts::PI / 2.0 + 0.1, std::f32::consts::PI / 2.0, 0.0, 0.0));
                     changed = true;
                 }
                 if ui.button("High Energy").clicked() {
-                    self.angle1 = std::f32::consts::PI;
-                    self.angle2 = 0.0;
-                    self.velocity1 = 0.2;
-                    self.velocity2 = 0.1;
-                    self.trace.clear();
+                    self.set_state(PendulumState::new(std::f32::consts::PI, 0.0, 0.2, 0.1));
                     changed = true;
                 }
             });
 
         if ui.button("🔄 Reset").clicked() {
-            self.angle1 = std::f32::consts::PI / 2.0;
-            self.angle2 = std::f32::consts::PI / 2.0;
-            self.velocity1 = 0.0;
-            self.velocity2 = 0.0;
-            self.trace.clear();
+            self.reset();
         }
 
-        // Update simulation
-        let dt = ui.input(|i| i.stable_dt);
-        let size = ui.available_size();
-        let canvas_scale = size.x.min(size.y);
+        changed
+    }
+
+    fn update(&mut self, dt: f32, width: usize, height: usize) -> bool {
+        // Same scale as compute_ctx, so the physics matches what is drawn
+        let canvas_scale = width.min(height) as f32;
         for _ in 0..3 {
-            self.update(dt * 10.0, canvas_scale);
+            self.advance(dt * 10.0, canvas_scale);
         }
-        changed = true;
-
-        changed
+        true
     }
 }
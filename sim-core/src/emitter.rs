@@ -0,0 +1,151 @@
+//! Configurable particle source shared by the 3D particle sims.
+
+use crate::nudge::Nudge;
+use rand::Rng;
+
+/// Region new particles are placed in. Ring and disk lie in the horizontal
+/// (XZ) plane.
+#[derive(Clone, Copy, PartialEq)]
+pub enum EmitterShape {
+    Point,
+    Ring,
+    Disk,
+    Sphere,
+    Box,
+}
+
+impl EmitterShape {
+    pub fn all() -> Vec<EmitterShape> {
+        vec![
+            EmitterShape::Point,
+            EmitterShape::Ring,
+            EmitterShape::Disk,
+            EmitterShape::Sphere,
+            EmitterShape::Box,
+        ]
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            EmitterShape::Point => "Point",
+            EmitterShape::Ring => "Ring",
+            EmitterShape::Disk => "Disk",
+            EmitterShape::Sphere => "Sphere",
+            EmitterShape::Box => "Box",
+        }
+    }
+}
+
+pub struct Emitter {
+    pub shape: EmitterShape,
+    pub position: [f32; 3],
+    /// Radius, or half-width for the box
+    pub size: f32,
+    /// Extra random offset on each axis, to thicken thin shapes
+    pub spread: f32,
+    /// Particles per second
+    pub rate: f32,
+    accumulator: f32,
+}
+
+impl Emitter {
+    pub fn new(shape: EmitterShape, size: f32, spread: f32, rate: f32) -> Self {
+        Self {
+            shape,
+            position: [0.0; 3],
+            size,
+            spread,
+            rate,
+            accumulator: 0.0,
+        }
+    }
+
+    /// A random spawn position
+    pub fn sample(&self, rng: &mut impl Rng) -> [f32; 3] {
+        let tau = std::f32::consts::TAU;
+        let offset = match self.shape {
+            EmitterShape::Point => [0.0; 3],
+            EmitterShape::Ring => {
+                let theta = rng.gen_range(0.0..tau);
+                [self.size * theta.cos(), 0.0, self.size * theta.sin()]
+            }
+            EmitterShape::Disk => {
+                // sqrt keeps the density uniform over the area
+                let theta = rng.gen_range(0.0..tau);
+                let r = self.size * rng.gen::<f32>().sqrt();
+                [r * theta.cos(), 0.0, r * theta.sin()]
+            }
+            EmitterShape::Sphere => {
+                // Uniform direction, cube-root radius for uniform volume
+                let z: f32 = rng.gen_range(-1.0..1.0);
+                let theta = rng.gen_range(0.0..tau);
+                let s = (1.0 - z * z).sqrt();
+                let r = self.size * rng.gen::<f32>().cbrt();
+                [r * s * theta.cos(), r * z, r * s * theta.sin()]
+            }
+            EmitterShape::Box => {
+                let h = self.size;
+                [rng.gen_range(-h..=h), rng.gen_range(-h..=h), rng.gen_range(-h..=h)]
+            }
+        };
+
+        let mut jitter = [0.0; 3];
+        if self.spread > 0.0 {
+            for j in &mut jitter {
+                *j = rng.gen_range(-self.spread..=self.spread);
+            }
+        }
+
+        [
+            self.position[0] + offset[0] + jitter[0],
+            self.position[1] + offset[1] + jitter[1],
+            self.position[2] + offset[2] + jitter[2],
+        ]
+    }
+
+    /// How many particles to emit after `dt` seconds, at most `room`.
+    /// Fractional particles carry over between calls so low rates still emit
+    /// on average; emission that doesn't fit is dropped rather than queued,
+    /// so a full system doesn't release a burst when space frees up.
+    pub fn emit_count(&mut self, dt: f32, room: usize) -> usize {
+        self.accumulator += self.rate.max(0.0) * dt.max(0.0);
+        let count = self.accumulator.floor();
+        self.accumulator -= count;
+        (count as usize).min(room)
+    }
+
+    /// Collapsible shape, position and rate controls. Changes only affect
+    /// particles emitted from now on.
+    pub fn ui(&mut self, ui: &mut egui::Ui) {
+        egui::CollapsingHeader::new("⛲ Emitter")
+            .show(ui, |ui| {
+                egui::ComboBox::from_label("Shape")
+                    .selected_text(self.shape.name())
+                    .show_ui(ui, |ui| {
+                        for shape in EmitterShape::all() {
+                            ui.selectable_value(&mut self.shape, shape, shape.name());
+                        }
+                    });
+
+                ui.horizontal(|ui| {
+                    ui.label("Position:");
+                    for (value, axis) in self.position.iter_mut().zip(["x", "y", "z"]) {
+                        ui.add(egui::DragValue::new(value).speed(0.5).prefix(format!("{axis}: ")));
+                    }
+                });
+
+                if self.shape != EmitterShape::Point {
+                    ui.add(egui::Slider::new(&mut self.size, 1.0..=80.0)
+                        .nudge(ui, 0.5)
+                        .text("Size"));
+                }
+                ui.add(egui::Slider::new(&mut self.spread, 0.0..=20.0)
+                    .nudge(ui, 0.2)
+                    .text("Spread"));
+                ui.add(egui::Slider::new(&mut self.rate, 1.0..=500.0)
+                    .nudge(ui, 0.5)
+                    .logarithmic(true)
+                    .text("Rate (per second)"));
+            });
+    }
+}
@@ -0,0 +1,343 @@
+//! Shared pieces for escape-time fractals: a per-pixel iteration cache so
+//! recoloring doesn't re-iterate, and iteration-count statistics for tuning
+//! `max_iterations`.
+
+use crate::Color;
+use crate::nudge::Nudge;
+use crate::readout;
+use crate::state::read;
+use std::sync::{Arc, Mutex};
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+const HISTOGRAM_BUCKETS: usize = 32;
+
+/// Deepest zoom offered. Near here neighboring pixels are only a few f64
+/// steps apart, and any further the image turns blocky.
+pub const MAX_ZOOM: f64 = 1e12;
+
+/// Zoom after a scroll of `delta` points. Each tick scales the zoom by the
+/// same factor at any depth, and equal ticks in and out cancel.
+pub fn scroll_zoom(zoom: f64, delta: f64) -> f64 {
+    (zoom * (delta * 0.002).exp()).clamp(0.1, MAX_ZOOM)
+}
+
+/// Everything that changes the iteration counts (but not the colors). A cached
+/// buffer is reused as long as the key matches.
+#[derive(Clone, PartialEq)]
+pub struct EscapeKey {
+    pub width: usize,
+    pub height: usize,
+    pub params: Vec<f64>,
+}
+
+/// Summary of the iteration counts of the current view
+#[derive(Clone)]
+pub struct IterationStats {
+    pub min: u32,
+    pub max: u32,
+    pub mean: f32,
+    /// Fraction of pixels that never escaped
+    pub inside_fraction: f32,
+    pub max_iterations: u32,
+    /// Escaped-pixel counts over `min..=max`
+    pub histogram: Vec<u32>,
+}
+
+impl IterationStats {
+    fn from_iterations(iterations: &[(u32, f64)], max_iterations: u32) -> Self {
+        let mut min = u32::MAX;
+        let mut max = 0;
+        let mut sum = 0u64;
+        let mut escaped = 0u64;
+
+        for &(i, _) in iterations {
+            if i < max_iterations {
+                min = min.min(i);
+                max = max.max(i);
+                sum += i as u64;
+                escaped += 1;
+            }
+        }
+
+        let mut histogram = vec![0; HISTOGRAM_BUCKETS];
+        if escaped == 0 {
+            min = 0;
+        } else {
+            // Buckets span the observed range, not 0..max_iterations, so a deep
+            // zoom where everything escapes late still gets a useful spread
+            let span = (max - min + 1) as usize;
+            for &(i, _) in iterations {
+                if i < max_iterations {
+                    let bucket = (i - min) as usize * HISTOGRAM_BUCKETS / span;
+                    histogram[bucket.min(HISTOGRAM_BUCKETS - 1)] += 1;
+                }
+            }
+        }
+
+        let total = iterations.len().max(1) as f32;
+        Self {
+            min,
+            max,
+            mean: if escaped > 0 { sum as f32 / escaped as f32 } else { 0.0 },
+            inside_fraction: (iterations.len() as u64 - escaped) as f32 / total,
+            max_iterations,
+            histogram,
+        }
+    }
+}
+
+#[derive(Clone, Default)]
+struct CacheInner {
+    key: Option<EscapeKey>,
+    iterations: Arc<Vec<(u32, f64)>>,
+    stats: Option<IterationStats>,
+}
+
+/// Per-pixel `(iterations, smooth_iterations)` for the last computed view.
+/// Lives behind a mutex because `Simulation2D::compute` takes `&self`.
+#[derive(Default)]
+pub struct EscapeTimeCache {
+    inner: Mutex<CacheInner>,
+}
+
+impl EscapeTimeCache {
+    /// Return the cached buffer if `key` matches, otherwise run `iterate` for
+    /// every pixel (row-parallel when available) and cache the result
+    pub fn get_or_compute<F>(&self, key: EscapeKey, max_iterations: u32, iterate: F) -> Arc<Vec<(u32, f64)>>
+    where
+        F: Fn(usize, usize) -> (u32, f64) + Sync,
+    {
+        self.get_or_compute_rows(key, max_iterations, |y, row| {
+            for (x, out) in row.iter_mut().enumerate() {
+                *out = iterate(x, y);
+            }
+        })
+    }
+
+    /// Like `get_or_compute`, but `iterate_row` fills a whole row at once, for
+    /// iterators that work on several pixels together
+    pub fn get_or_compute_rows<F>(&self, key: EscapeKey, max_iterations: u32, iterate_row: F) -> Arc<Vec<(u32, f64)>>
+    where
+        F: Fn(usize, &mut [(u32, f64)]) + Sync,
+    {
+        let mut inner = self.inner.lock().unwrap();
+        if inner.key.as_ref() == Some(&key) {
+            return inner.iterations.clone();
+        }
+
+        let mut iterations = vec![(0, 0.0); key.width * key.height];
+        for_each_row(&mut iterations, key.width, iterate_row);
+
+        inner.stats = Some(IterationStats::from_iterations(&iterations, max_iterations));
+        inner.iterations = Arc::new(iterations);
+        inner.key = Some(key);
+        inner.iterations.clone()
+    }
+
+    pub fn stats(&self) -> Option<IterationStats> {
+        self.inner.lock().unwrap().stats.clone()
+    }
+
+    /// Take over `other`'s buffer, key and statistics. The buffer is shared,
+    /// not copied.
+    pub fn copy_from(&self, other: &EscapeTimeCache) {
+        let theirs = other.inner.lock().unwrap().clone();
+        *self.inner.lock().unwrap() = theirs;
+    }
+
+    /// Rough work for a render with `key`, in iterations, for
+    /// `Simulation2D::render_cost`. A cache hit only recolors, one unit per
+    /// pixel. A miss is priced from the last view's statistics, assuming the
+    /// new view escapes like the old one but interior pixels now run to the
+    /// new cap; with no statistics every pixel runs to the cap.
+    pub fn render_cost(&self, key: &EscapeKey, max_iterations: u32) -> f64 {
+        let inner = self.inner.lock().unwrap();
+        let pixels = (key.width * key.height) as f64;
+        if inner.key.as_ref() == Some(key) {
+            return pixels;
+        }
+
+        let per_pixel = match &inner.stats {
+            Some(stats) => {
+                let inside = stats.inside_fraction as f64;
+                let escaped = (stats.mean as f64).min(max_iterations as f64);
+                inside * max_iterations as f64 + (1.0 - inside) * escaped
+            }
+            None => max_iterations as f64,
+        };
+        pixels * per_pixel.max(1.0)
+    }
+}
+
+/// Run `fill` on every `width`-long row of `buffer` with its row index, in
+/// parallel when the `parallel` feature is on (it is off for wasm, which has
+/// no threads)
+fn for_each_row<T, F>(buffer: &mut [T], width: usize, fill: F)
+where
+    T: Send,
+    F: Fn(usize, &mut [T]) + Sync,
+{
+    if width == 0 {
+        return;
+    }
+
+    #[cfg(feature = "parallel")]
+    let rows = buffer.par_chunks_mut(width);
+    #[cfg(not(feature = "parallel"))]
+    let rows = buffer.chunks_mut(width);
+
+    rows.enumerate().for_each(|(y, row)| fill(y, row));
+}
+
+/// Color a cached iteration buffer, row-parallel when available. With non-zero `offsets`, each RGB channel
+/// is taken from a pixel pushed radially outward by that many pixels (at the
+/// image edge), like lateral chromatic aberration. Flat regions look the same
+/// either way; the fringes show up where iteration counts change quickly,
+/// i.e. along the set boundary.
+pub fn colorize<F>(iterations: &[(u32, f64)], width: usize, height: usize, offsets: [f32; 3], color: F) -> Vec<Color>
+where
+    F: Fn(u32, f64) -> Color + Sync,
+{
+    let mut out = vec![Color::BLACK; iterations.len()];
+    if offsets == [0.0; 3] || width == 0 || height == 0 {
+        for_each_row(&mut out, width, |y, row| {
+            for (pixel, &(i, smooth)) in row.iter_mut().zip(&iterations[y * width..]) {
+                *pixel = color(i, smooth);
+            }
+        });
+        return out;
+    }
+
+    let cx = width as f32 / 2.0;
+    let cy = height as f32 / 2.0;
+    let half = cx.min(cy).max(1.0);
+    let sample = |x: usize, y: usize, offset: f32| -> Color {
+        let k = 1.0 + offset / half;
+        let sx = (cx + (x as f32 - cx) * k).round().clamp(0.0, width as f32 - 1.0) as usize;
+        let sy = (cy + (y as f32 - cy) * k).round().clamp(0.0, height as f32 - 1.0) as usize;
+        let (i, smooth) = iterations[sy * width + sx];
+        color(i, smooth)
+    };
+
+    for_each_row(&mut out, width, |y, row| {
+        for (x, pixel) in row.iter_mut().enumerate() {
+            *pixel = Color::from_rgb(
+                sample(x, y, offsets[0]).r,
+                sample(x, y, offsets[1]).g,
+                sample(x, y, offsets[2]).b,
+            );
+        }
+    });
+    out
+}
+
+/// Read `channel_offsets` written by `save_state`, each pulled into the range
+/// of its slider
+pub fn read_channel_offsets(state: &serde_json::Value, offsets: &mut [f32; 3]) {
+    read(state, "channel_offsets", offsets);
+    for offset in offsets {
+        *offset = if offset.is_nan() { 0.0 } else { offset.clamp(-8.0, 8.0) };
+    }
+}
+
+/// Sliders for the per-channel offsets used by `colorize`
+pub fn channel_offsets_ui(ui: &mut egui::Ui, offsets: &mut [f32; 3]) -> bool {
+    let mut changed = false;
+
+    egui::CollapsingHeader::new("🌈 Chromatic Aberration")
+        .show(ui, |ui| {
+            for (offset, name) in offsets.iter_mut().zip(["Red Shift", "Green Shift", "Blue Shift"]) {
+                changed |= ui.add(egui::Slider::new(offset, -8.0..=8.0)
+                    .nudge(ui, 0.1)
+                    .text(name)).changed();
+            }
+            if ui.button("Clear").clicked() {
+                *offsets = [0.0; 3];
+                changed = true;
+            }
+        });
+
+    changed
+}
+
+/// Center and magnification of the view, with as many digits as the zoom
+/// resolves, and a button copying them as `(x, y, zoom)` for a location list
+pub fn location_ui(ui: &mut egui::Ui, center_x: f64, center_y: f64, zoom: f64) {
+    let digits = (zoom.max(1.0).log10().ceil() as usize + 4).min(16);
+    let sign = if center_y < 0.0 { '-' } else { '+' };
+    ui.label(format!("Center: {center_x:.digits$} {sign} {:.digits$}i", center_y.abs()));
+    ui.horizontal(|ui| {
+        let magnification = if zoom < 1e4 { readout::decimal(zoom, 2) } else { format!("{zoom:.2e}") };
+        ui.label(format!("Magnification: {magnification}×"));
+        if ui.small_button("📋 Copy").on_hover_text("Copy as (x, y, zoom)").clicked() {
+            ui.output_mut(|o| o.copied_text = format!("({center_x:.digits$}, {center_y:.digits$}, {zoom:.3e})"));
+        }
+    });
+}
+
+/// Collapsible min/max/mean readout and histogram of the cached view
+pub fn iteration_histogram_ui(ui: &mut egui::Ui, cache: &EscapeTimeCache) {
+    egui::CollapsingHeader::new("📊 Iteration Histogram")
+        .show(ui, |ui| {
+            let Some(stats) = cache.stats() else {
+                ui.label("No data yet");
+                return;
+            };
+
+            ui.label(format!("Min: {}   Max: {}   Mean: {}", readout::count(stats.min), readout::count(stats.max), readout::decimal(stats.mean, 1)));
+            ui.label(format!("Inside set: {}", readout::percent(stats.inside_fraction, 1)));
+
+            let peak = stats.histogram.iter().copied().max().unwrap_or(0).max(1);
+            let (rect, _) = ui.allocate_exact_size(egui::vec2(ui.available_width(), 60.0), egui::Sense::hover());
+            let painter = ui.painter_at(rect);
+            painter.rect_filled(rect, 2.0, egui::Color32::from_gray(20));
+
+            let bar_width = rect.width() / stats.histogram.len() as f32;
+            for (i, &count) in stats.histogram.iter().enumerate() {
+                if count == 0 {
+                    continue;
+                }
+                let h = rect.height() * count as f32 / peak as f32;
+                let x = rect.left() + i as f32 * bar_width;
+                painter.rect_filled(
+                    egui::Rect::from_min_max(
+                        egui::pos2(x, rect.bottom() - h),
+                        egui::pos2(x + bar_width - 1.0, rect.bottom()),
+                    ),
+                    0.0,
+                    egui::Color32::from_rgb(100, 180, 255),
+                );
+            }
+
+            ui.horizontal(|ui| {
+                ui.label(readout::count(stats.min));
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    ui.label(readout::count(stats.max));
+                });
+            });
+
+            // Lots of interior pixels and escapes piling up at the cap both mean
+            // detail is being cut off
+            if stats.max + 1 >= stats.max_iterations && stats.inside_fraction > 0.0 {
+                ui.label("Escapes reach the iteration cap; try raising Max Iterations");
+            }
+        });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scroll_zoom_is_multiplicative_and_reversible() {
+        let zoom = scroll_zoom(scroll_zoom(250.0, 40.0), -40.0);
+        assert!((zoom - 250.0).abs() < 1e-9);
+        // The same tick is the same factor at any depth
+        let shallow = scroll_zoom(2.0, 30.0) / 2.0;
+        let deep = scroll_zoom(2e9, 30.0) / 2e9;
+        assert!((shallow - deep).abs() < 1e-12);
+        assert_eq!(scroll_zoom(MAX_ZOOM, 100.0), MAX_ZOOM);
+    }
+}
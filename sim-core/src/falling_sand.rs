@@ -1,7 +1,8 @@
+use crate::nudge::Nudge;
 use crate::{Color, Simulation2D};
 use rand::Rng;
 
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 enum Material {
     Empty,
     Sand,
@@ -15,35 +16,75 @@ pub struct FallingSand {
     pub brush_size: usize,
     pub gravity: f32,
     pub current_material: usize,
+    /// Grains leaving one side come back on the other instead of stopping
+    /// at a solid wall
+    pub wrap_walls: bool,
     grid: Vec<Material>,
     width: usize,
     height: usize,
     velocities: Vec<(f32, f32)>,
     temperatures: Vec<f32>,
+    /// Alternates the row scan direction each step, so neither side is
+    /// favoured when grains compete for the same cell
+    scan_right_to_left: bool,
+    /// Cells whose contents already moved or ignited this step, so a grain
+    /// carried ahead of the scan isn't updated again
+    moved: Vec<bool>,
 }
 
 impl Default for FallingSand {
     fn default() -> Self {
-        let width = 200;
-        let height = 150;
+        Self::with_size(200, 150)
+    }
+}
+
+impl FallingSand {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn with_size(width: usize, height: usize) -> Self {
         let size = width * height;
 
         Self {
             brush_size: 3,
             gravity: 0.5,
             current_material: 0,
+            wrap_walls: false,
             grid: vec![Material::Empty; size],
             width,
             height,
             velocities: vec![(0.0, 0.0); size],
             temperatures: vec![0.0; size],
+            scan_right_to_left: false,
+            moved: vec![false; size],
         }
     }
-}
 
-impl FallingSand {
-    pub fn new() -> Self {
-        Self::default()
+    /// Column `dx` steps from `x`, or `None` past a solid wall
+    fn neighbor_x(&self, x: usize, dx: i32) -> Option<usize> {
+        let nx = x as i32 + dx;
+        if self.wrap_walls {
+            Some(nx.rem_euclid(self.width as i32) as usize)
+        } else if nx >= 0 && (nx as usize) < self.width {
+            Some(nx as usize)
+        } else {
+            None
+        }
+    }
+
+    fn swap_cells(&mut self, a: usize, b: usize) {
+        self.grid.swap(a, b);
+        self.velocities.swap(a, b);
+        self.temperatures.swap(a, b);
+        self.moved[a] = true;
+        self.moved[b] = true;
+    }
+
+    /// Random left/right order for sideways moves; the other side is tried
+    /// if the first is blocked
+    fn sideways() -> [i32; 2] {
+        if rand::thread_rng().gen_bool(0.5) { [-1, 1] } else { [1, -1] }
     }
 
     fn material_color(&self, material: Material, temp: f32) -> Color {
@@ -88,6 +129,9 @@ impl FallingSand {
 
     fn update_particle(&mut self, x: usize, y: usize) {
         let idx = y * self.width + x;
+        if self.moved[idx] {
+            return;
+        }
         let material = self.grid[idx];
 
         match material {
@@ -100,31 +144,28 @@ impl FallingSand {
     }
 
     fn update_sand(&mut self, x: usize, y: usize) {
+        // The bottom row is the floor
         if y >= self.height - 1 {
             return;
         }
 
         let idx = y * self.width + x;
         let below = (y + 1) * self.width + x;
+        let passable = |m: Material| m == Material::Empty || m == Material::Water;
 
         // Fall straight down
-        if self.grid[below] == Material::Empty || self.grid[below] == Material::Water {
-            self.grid.swap(idx, below);
-            self.velocities.swap(idx, below);
-            self.temperatures.swap(idx, below);
+        if passable(self.grid[below]) {
+            self.swap_cells(idx, below);
+            return;
         }
-        // Fall diagonally
-        else {
-            let mut rng = rand::thread_rng();
-            let dx = if rng.gen_bool(0.5) { -1 } else { 1 };
-            let new_x = (x as i32 + dx) as usize;
-
-            if new_x < self.width {
-                let diag = (y + 1) * self.width + new_x;
-                if self.grid[diag] == Material::Empty || self.grid[diag] == Material::Water {
-                    self.grid.swap(idx, diag);
-                    self.velocities.swap(idx, diag);
-                    self.temperatures.swap(idx, diag);
+
+        // Blocked: slide diagonally, which piles sand at 45°
+        for dx in Self::sideways() {
+            if let Some(nx) = self.neighbor_x(x, dx) {
+                let diag = (y + 1) * self.width + nx;
+                if passable(self.grid[diag]) {
+                    self.swap_cells(idx, diag);
+                    return;
                 }
             }
         }
@@ -140,22 +181,17 @@ impl FallingSand {
 
         // Fall down
         if self.grid[below] == Material::Empty {
-            self.grid.swap(idx, below);
-            self.velocities.swap(idx, below);
-            self.temperatures.swap(idx, below);
+            self.swap_cells(idx, below);
+            return;
         }
-        // Spread sideways
-        else {
-            let mut rng = rand::thread_rng();
-            let dx = if rng.gen_bool(0.5) { -1 } else { 1 };
-            let new_x = (x as i32 + dx) as usize;
 
-            if new_x < self.width {
-                let side = y * self.width + new_x;
+        // Spread sideways
+        for dx in Self::sideways() {
+            if let Some(nx) = self.neighbor_x(x, dx) {
+                let side = y * self.width + nx;
                 if self.grid[side] == Material::Empty {
-                    self.grid.swap(idx, side);
-                    self.velocities.swap(idx, side);
-                    self.temperatures.swap(idx, side);
+                    self.swap_cells(idx, side);
+                    return;
                 }
             }
         }
@@ -177,8 +213,7 @@ impl FallingSand {
         if y > 0 {
             let above = (y - 1) * self.width + x;
             if self.grid[above] == Material::Empty && rng.gen_bool(0.3) {
-                self.grid.swap(idx, above);
-                self.temperatures.swap(idx, above);
+                self.swap_cells(idx, above);
             }
         }
 
@@ -196,6 +231,9 @@ impl FallingSand {
                 if self.grid[nidx] == Material::Wood && rng.gen_bool(0.1) {
                     self.grid[nidx] = Material::Fire;
                     self.temperatures[nidx] = 100.0;
+                    // New fire burns from the next step, so a row of wood
+                    // can't catch all at once along the scan
+                    self.moved[nidx] = true;
                 }
             }
         }
@@ -204,6 +242,48 @@ impl FallingSand {
     fn update_wood(&mut self, _x: usize, _y: usize) {
         // Wood is static unless ignited by fire
     }
+
+    /// One physics pass, bottom row first. The moved mask makes sure each
+    /// grain moves at most once, including water carried sideways along the
+    /// scan and fire rising into a row not yet scanned.
+    fn step(&mut self) {
+        let right_to_left = self.scan_right_to_left;
+        self.scan_right_to_left = !right_to_left;
+        self.moved.fill(false);
+
+        for y in (0..self.height).rev() {
+            for i in 0..self.width {
+                let x = if right_to_left { self.width - 1 - i } else { i };
+                self.update_particle(x, y);
+            }
+        }
+    }
+
+    /// Drop the current material along the top edge. Only empty cells are
+    /// filled, so existing grains are never overwritten.
+    fn spawn(&mut self) {
+        let mut rng = rand::thread_rng();
+        let x = rng.gen_range(0..self.width);
+        let material = Self::index_to_material(self.current_material);
+
+        for dy in 0..self.brush_size {
+            for dx in 0..self.brush_size {
+                let px = x.saturating_add(dx).saturating_sub(self.brush_size / 2);
+                let py = dy;
+
+                if px < self.width && py < self.height {
+                    let idx = py * self.width + px;
+                    if self.grid[idx] != Material::Empty {
+                        continue;
+                    }
+                    self.grid[idx] = material;
+                    if material == Material::Fire {
+                        self.temperatures[idx] = 100.0;
+                    }
+                }
+            }
+        }
+    }
 }
 
 impl Simulation2D for FallingSand {
@@ -231,6 +311,27 @@ impl Simulation2D for FallingSand {
         pixels
     }
 
+    /// How many cells of material are stacked in the column from each cell
+    /// down, counting the cell itself; empty cells are 0. Pile tops are the
+    /// lowest values, so the relief rises into the bulk of each pile.
+    fn scalar_field(&self) -> Option<(Vec<f32>, usize, usize)> {
+        let mut depth = vec![0.0; self.width * self.height];
+        for x in 0..self.width {
+            let mut stacked = 0.0;
+            for y in (0..self.height).rev() {
+                let idx = y * self.width + x;
+                stacked = if self.grid[idx] == Material::Empty { 0.0 } else { stacked + 1.0 };
+                depth[idx] = stacked;
+            }
+        }
+        Some((depth, self.width, self.height))
+    }
+
+    fn reset(&mut self) {
+        self.grid.fill(Material::Empty);
+        self.temperatures.fill(0.0);
+    }
+
     fn ui_parameters(&mut self, ui: &mut egui::Ui) -> bool {
         ui.heading("Falling Sand");
 
@@ -238,6 +339,7 @@ impl Simulation2D for FallingSand {
             .default_open(true)
             .show(ui, |ui| {
                 ui.add(egui::Slider::new(&mut self.brush_size, 1..=10)
+                    .nudge(ui, 1.0)
                     .text("Brush Size"));
 
                 ui.label("Material:");
@@ -254,44 +356,169 @@ impl Simulation2D for FallingSand {
             .default_open(true)
             .show(ui, |ui| {
                 ui.add(egui::Slider::new(&mut self.gravity, 0.1..=2.0)
+                    .nudge(ui, 0.01)
                     .text("Gravity"));
+
+                ui.horizontal(|ui| {
+                    ui.label("Side Walls:");
+                    ui.selectable_value(&mut self.wrap_walls, false, "Solid");
+                    ui.selectable_value(&mut self.wrap_walls, true, "Wrap");
+                });
             });
 
         if ui.button("🗑 Clear").clicked() {
-            self.grid.fill(Material::Empty);
-            self.temperatures.fill(0.0);
+            self.reset();
+        }
+
+        true
+    }
+
+    fn update(&mut self, dt: f32, _width: usize, _height: usize) -> bool {
+        // One step per frame while the clock runs
+        if dt <= 0.0 {
+            return false;
         }
 
         // Spawn materials from top to create continuous falling effect
-        let mut rng = rand::thread_rng();
-        if rng.gen_bool(0.3) {
-            let x = rng.gen_range(0..self.width);
-            let y = 0;
-
-            // Place material
-            for dy in 0..self.brush_size {
-                for dx in 0..self.brush_size {
-                    let px = x.saturating_add(dx).saturating_sub(self.brush_size / 2);
-                    let py = y + dy;
-
-                    if px < self.width && py < self.height {
-                        let idx = py * self.width + px;
-                        self.grid[idx] = Self::index_to_material(self.current_material);
-                        if self.current_material == 3 { // Fire
-                            self.temperatures[idx] = 100.0;
-                        }
-                    }
-                }
+        if rand::thread_rng().gen_bool(0.3) {
+            self.spawn();
+        }
+
+        self.step();
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn place(sim: &mut FallingSand, x: usize, y: usize, material: Material) {
+        sim.grid[y * sim.width + x] = material;
+    }
+
+    fn count(sim: &FallingSand, material: Material) -> usize {
+        sim.grid.iter().filter(|&&m| m == material).count()
+    }
+
+    /// Height of the sand column at each x
+    fn column_heights(sim: &FallingSand) -> Vec<usize> {
+        (0..sim.width)
+            .map(|x| (0..sim.height).filter(|&y| sim.grid[y * sim.width + x] == Material::Sand).count())
+            .collect()
+    }
+
+    #[test]
+    fn single_grain_lands_on_floor() {
+        let mut sim = FallingSand::with_size(10, 10);
+        place(&mut sim, 4, 0, Material::Sand);
+
+        for _ in 0..50 {
+            sim.step();
+        }
+
+        assert_eq!(count(&sim, Material::Sand), 1);
+        assert_eq!(sim.grid[9 * 10 + 4], Material::Sand);
+    }
+
+    #[test]
+    fn dropped_grains_form_a_45_degree_pile() {
+        let (width, height) = (41, 30);
+        let mut sim = FallingSand::with_size(width, height);
+        let grains = 100;
+
+        for _ in 0..grains {
+            place(&mut sim, width / 2, 0, Material::Sand);
+            for _ in 0..height * 2 {
+                sim.step();
             }
         }
 
-        // Update physics
-        for y in (0..self.height).rev() {
-            for x in 0..self.width {
-                self.update_particle(x, y);
+        assert_eq!(count(&sim, Material::Sand), grains);
+
+        // Settled sand can't have a neighbour column more than one cell
+        // lower, or the top grain would still slide
+        let heights = column_heights(&sim);
+        for pair in heights.windows(2) {
+            assert!(pair[0].abs_diff(pair[1]) <= 1, "slope steeper than 45°: {heights:?}");
+        }
+
+        // ...and it's a pile, not a flat layer
+        let peak = heights[width / 2];
+        assert!(peak >= 5, "pile too flat: {heights:?}");
+        for (x, &h) in heights.iter().enumerate() {
+            assert!(h <= peak, "column {x} higher than the peak: {heights:?}");
+        }
+    }
+
+    #[test]
+    fn solid_walls_hold_grains_in_the_corner() {
+        let mut sim = FallingSand::with_size(5, 5);
+        place(&mut sim, 0, 4, Material::Stone);
+        place(&mut sim, 0, 3, Material::Sand);
+        place(&mut sim, 1, 4, Material::Stone);
+
+        for _ in 0..10 {
+            sim.step();
+        }
+
+        // Blocked below and to the right, and the wall is solid
+        assert_eq!(sim.grid[3 * 5], Material::Sand);
+    }
+
+    #[test]
+    fn wrapping_walls_let_grains_slide_across() {
+        let mut sim = FallingSand::with_size(5, 5);
+        sim.wrap_walls = true;
+        place(&mut sim, 0, 4, Material::Stone);
+        place(&mut sim, 1, 4, Material::Stone);
+        place(&mut sim, 0, 3, Material::Sand);
+
+        for _ in 0..10 {
+            sim.step();
+        }
+
+        assert_eq!(count(&sim, Material::Sand), 1);
+        assert_eq!(sim.grid[4 * 5 + 4], Material::Sand);
+    }
+
+    #[test]
+    fn water_moves_at_most_one_cell_per_step() {
+        // On a stone floor against the left wall, water can only spread
+        // right. Scanning left to right must not carry it further along the
+        // row in the same step, which would happen half the time per cell.
+        for _ in 0..50 {
+            let mut sim = FallingSand::with_size(6, 2);
+            for x in 0..6 {
+                place(&mut sim, x, 1, Material::Stone);
             }
+            place(&mut sim, 0, 0, Material::Water);
+
+            sim.step();
+            assert_eq!(sim.grid[1], Material::Water);
         }
+    }
 
-        true
+    #[test]
+    fn diagonal_slides_are_unbiased() {
+        let trials = 2000;
+        let mut left = 0;
+
+        for _ in 0..trials {
+            // A grain balanced on a single stone slides to one side
+            let mut sim = FallingSand::with_size(3, 3);
+            place(&mut sim, 1, 2, Material::Stone);
+            place(&mut sim, 1, 1, Material::Sand);
+            sim.step();
+
+            if sim.grid[2 * 3] == Material::Sand {
+                left += 1;
+            } else {
+                assert_eq!(sim.grid[2 * 3 + 2], Material::Sand);
+            }
+        }
+
+        // Binomial(2000, 0.5) has a standard deviation of ~22
+        assert!((800..=1200).contains(&left), "{left} of {trials} slid left");
     }
 }
@@ -1,4 +1,6 @@
 use crate::Simulation3D;
+use crate::nudge::Nudge;
+use crate::readout;
 use rand::Rng;
 
 struct Particle {
@@ -232,20 +234,23 @@ impl Simulation3D for FluidSPH {
 
         ui.heading("Fluid Simulation (SPH)");
 
-        ui.label(format!("Active particles: {}", self.particles.len()));
+        ui.label(format!("Active particles: {}", readout::count(self.particles.len())));
 
         egui::CollapsingHeader::new("💧 Particle Settings")
             .default_open(true)
             .show(ui, |ui| {
                 if ui.add(egui::Slider::new(&mut self.particle_count, 50..=1000)
+                    .nudge(ui, 1.0)
                     .text("Particle Count")).changed() {
                     changed = true;
                 }
 
                 ui.add(egui::Slider::new(&mut self.smoothing_radius, 0.5..=5.0)
+                    .nudge(ui, 0.02)
                     .text("Smoothing Radius"));
 
                 ui.add(egui::Slider::new(&mut self.particle_mass, 0.1..=5.0)
+                    .nudge(ui, 0.02)
                     .text("Particle Mass"));
             });
 
@@ -253,21 +258,27 @@ impl Simulation3D for FluidSPH {
             .default_open(true)
             .show(ui, |ui| {
                 ui.add(egui::Slider::new(&mut self.gravity, 0.0..=20.0)
+                    .nudge(ui, 0.2)
                     .text("Gravity"));
 
                 ui.add(egui::Slider::new(&mut self.gas_constant, 500.0..=5000.0)
+                    .nudge(ui, 20.0)
                     .text("Pressure"));
 
                 ui.add(egui::Slider::new(&mut self.viscosity, 0.0..=2.0)
+                    .nudge(ui, 0.02)
                     .text("Viscosity"));
 
                 ui.add(egui::Slider::new(&mut self.damping, 0.5..=0.99)
+                    .nudge(ui, 0.002)
                     .text("Damping"));
 
                 ui.add(egui::Slider::new(&mut self.speed, 0.1..=3.0)
+                    .nudge(ui, 0.02)
                     .text("Speed"));
 
                 ui.add(egui::Slider::new(&mut self.boundary_size, 10.0..=50.0)
+                    .nudge(ui, 0.2)
                     .text("Boundary Size"));
             });
 
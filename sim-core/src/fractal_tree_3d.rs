@@ -1,4 +1,6 @@
 use crate::Simulation3D;
+use crate::nudge::Nudge;
+use crate::readout;
 use rand::Rng;
 
 #[derive(Clone)]
@@ -173,27 +175,21 @@ impl Simulation3D for FractalTree3D {
     }
 
     fn get_points(&self) -> Vec<[f32; 3]> {
-        let mut points = Vec::new();
-
-        for branch in &self.branches {
-            // Number of points based on thickness
-            let point_count = (branch.thickness * 3.0).max(1.0) as usize;
-
-            for _ in 0..point_count {
-                // Add points along the branch
-                let steps = 5;
-                for i in 0..=steps {
-                    let t = i as f32 / steps as f32;
-                    points.push([
-                        branch.start[0] * (1.0 - t) + branch.end[0] * t,
-                        branch.start[1] * (1.0 - t) + branch.end[1] * t,
-                        branch.start[2] * (1.0 - t) + branch.end[2] * t,
-                    ]);
-                }
-            }
-        }
+        // The joints: the branches themselves are drawn as segments
+        self.branches
+            .first()
+            .map(|trunk| trunk.start)
+            .into_iter()
+            .chain(self.branches.iter().map(|b| b.end))
+            .collect()
+    }
+
+    fn get_segments(&self) -> Vec<([f32; 3], [f32; 3])> {
+        self.branches.iter().map(|b| (b.start, b.end)).collect()
+    }
 
-        points
+    fn get_segment_widths(&self) -> Option<Vec<f32>> {
+        Some(self.branches.iter().map(|b| b.thickness).collect())
     }
 
     fn ui_parameters(&mut self, ui: &mut egui::Ui) -> bool {
@@ -201,27 +197,30 @@ impl Simulation3D for FractalTree3D {
 
         ui.heading("Fractal Tree 3D");
 
-        ui.label(format!("Branches: {} / {}", self.branches.len(), self.target_branches.len()));
+        ui.label(format!("Branches: {}", readout::fraction(self.branches.len(), self.target_branches.len())));
         if self.animated_growth {
-            ui.label(format!("Growth: {:.0}%", self.growth_progress * 100.0));
+            ui.label(format!("Growth: {}", readout::percent(self.growth_progress, 0)));
         }
 
         egui::CollapsingHeader::new("🌳 Tree Structure")
             .default_open(true)
             .show(ui, |ui| {
                 if ui.add(egui::Slider::new(&mut self.max_depth, 4..=12)
+                    .nudge(ui, 1.0)
                     .text("Max Depth")).changed() {
                     self.generate_tree();
                     changed = true;
                 }
 
                 if ui.add(egui::Slider::new(&mut self.branch_factor, 2.0..=5.0)
+                    .nudge(ui, 0.02)
                     .text("Branch Factor")).changed() {
                     self.generate_tree();
                     changed = true;
                 }
 
                 if ui.add(egui::Slider::new(&mut self.length_decay, 0.5..=0.9)
+                    .nudge(ui, 0.002)
                     .text("Length Decay")).changed() {
                     self.generate_tree();
                     changed = true;
@@ -232,18 +231,21 @@ impl Simulation3D for FractalTree3D {
             .default_open(true)
             .show(ui, |ui| {
                 if ui.add(egui::Slider::new(&mut self.branch_angle, 10.0..=50.0)
+                    .nudge(ui, 0.2)
                     .text("Branch Angle")).changed() {
                     self.generate_tree();
                     changed = true;
                 }
 
                 if ui.add(egui::Slider::new(&mut self.twist_angle, 0.0..=180.0)
+                    .nudge(ui, 1.0)
                     .text("Twist Angle")).changed() {
                     self.generate_tree();
                     changed = true;
                 }
 
                 if ui.add(egui::Slider::new(&mut self.randomness, 0.0..=0.5)
+                    .nudge(ui, 0.005)
                     .text("Randomness")).changed() {
                     self.generate_tree();
                     changed = true;
@@ -266,9 +268,11 @@ impl Simulation3D for FractalTree3D {
 
                 if self.animated_growth {
                     ui.add(egui::Slider::new(&mut self.growth_speed, 0.1..=2.0)
+                        .nudge(ui, 0.01)
                         .text("Growth Speed"));
 
                     ui.add(egui::Slider::new(&mut self.speed, 0.1..=3.0)
+                        .nudge(ui, 0.02)
                         .text("Speed"));
                 }
             });
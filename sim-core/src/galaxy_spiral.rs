@@ -1,4 +1,6 @@
-use crate::Simulation3D;
+use crate::{Color, Simulation3D};
+use crate::nudge::Nudge;
+use crate::readout;
 
 #[allow(dead_code)]
 struct Star {
@@ -8,8 +10,15 @@ struct Star {
     orbit_angle: f32,
     orbit_speed: f32,
     z_offset: f32,
+    color: Color,
 }
 
+/// Old yellow stars of the bulge
+const CORE_COLOR: Color = Color { r: 255, g: 214, b: 150 };
+/// Arm stars shade from yellow-white near the core to young blue at the rim
+const INNER_ARM_COLOR: Color = Color { r: 255, g: 240, b: 215 };
+const OUTER_ARM_COLOR: Color = Color { r: 140, g: 180, b: 255 };
+
 pub struct GalaxySpiral {
     pub num_arms: usize,
     pub stars_per_arm: usize,
@@ -104,6 +113,7 @@ impl GalaxySpiral {
                     orbit_angle: final_angle,
                     orbit_speed,
                     z_offset: z,
+                    color: Color::lerp(INNER_ARM_COLOR, OUTER_ARM_COLOR, t),
                 });
             }
         }
@@ -138,6 +148,7 @@ impl GalaxySpiral {
                     orbit_angle: theta,
                     orbit_speed: self.rotation_speed * 2.0,
                     z_offset: z,
+                    color: CORE_COLOR,
                 });
             }
         }
@@ -171,47 +182,57 @@ impl Simulation3D for GalaxySpiral {
         self.stars.iter().map(|s| s.position).collect()
     }
 
+    fn get_colors(&self) -> Option<Vec<Color>> {
+        Some(self.stars.iter().map(|s| s.color).collect())
+    }
+
     fn ui_parameters(&mut self, ui: &mut egui::Ui) -> bool {
         let mut changed = false;
 
         ui.heading("Galaxy Spiral");
 
-        ui.label(format!("Total stars: {}", self.stars.len()));
+        ui.label(format!("Total stars: {}", readout::count(self.stars.len())));
 
         egui::CollapsingHeader::new("🌌 Galaxy Structure")
             .default_open(true)
             .show(ui, |ui| {
                 if ui.add(egui::Slider::new(&mut self.num_arms, 2..=8)
+                    .nudge(ui, 1.0)
                     .text("Number of Arms")).changed() {
                     self.regenerate();
                     changed = true;
                 }
 
                 if ui.add(egui::Slider::new(&mut self.stars_per_arm, 50..=500)
+                    .nudge(ui, 1.0)
                     .text("Stars per Arm")).changed() {
                     self.regenerate();
                     changed = true;
                 }
 
                 if ui.add(egui::Slider::new(&mut self.arm_spread, 0.1..=1.0)
+                    .nudge(ui, 0.005)
                     .text("Arm Spread")).changed() {
                     self.regenerate();
                     changed = true;
                 }
 
                 if ui.add(egui::Slider::new(&mut self.core_radius, 2.0..=15.0)
+                    .nudge(ui, 0.1)
                     .text("Core Radius")).changed() {
                     self.regenerate();
                     changed = true;
                 }
 
                 if ui.add(egui::Slider::new(&mut self.max_radius, 20.0..=80.0)
+                    .nudge(ui, 0.5)
                     .text("Max Radius")).changed() {
                     self.regenerate();
                     changed = true;
                 }
 
                 if ui.add(egui::Slider::new(&mut self.disk_thickness, 2.0..=20.0)
+                    .nudge(ui, 0.1)
                     .text("Disk Thickness")).changed() {
                     self.regenerate();
                     changed = true;
@@ -222,9 +243,11 @@ impl Simulation3D for GalaxySpiral {
             .default_open(true)
             .show(ui, |ui| {
                 ui.add(egui::Slider::new(&mut self.rotation_speed, 0.0..=2.0)
+                    .nudge(ui, 0.02)
                     .text("Rotation Speed"));
 
                 if ui.add(egui::Slider::new(&mut self.orbital_velocity_falloff, 0.0..=2.0)
+                    .nudge(ui, 0.02)
                     .text("Velocity Falloff")).changed() {
                     self.regenerate();
                     changed = true;
@@ -1,4 +1,12 @@
-use crate::{Color, Simulation2D};
+use crate::grid_render::{cell_at, grid_filter_ui, render_grid, GridFilter};
+use crate::nudge::Nudge;
+use crate::readout;
+use crate::step_runner::StepRunner;
+use crate::timeline::Timeline;
+use crate::{Color, RenderCtx, Simulation2D};
+
+/// Generations a "Run N" takes per frame
+const RUN_BATCH: u64 = 100;
 
 #[derive(Clone, Copy, PartialEq)]
 pub enum LifeRule {
@@ -8,6 +16,20 @@ pub enum LifeRule {
     LifeWithoutDeath, // B3/S012345678
     DayAndNight, // B3678/S34678
     Maze,        // B3/S12345
+    /// Any B/S rule; bit `n` of each mask is set when `n` live neighbors
+    /// cause a birth or let a cell survive
+    Custom { birth: u16, survive: u16 },
+}
+
+/// Mask with a bit set for each listed neighbor count
+const fn counts(list: &[u8]) -> u16 {
+    let mut mask = 0;
+    let mut i = 0;
+    while i < list.len() {
+        mask |= 1 << list[i];
+        i += 1;
+    }
+    mask
 }
 
 impl LifeRule {
@@ -30,35 +52,43 @@ impl LifeRule {
             LifeRule::LifeWithoutDeath => "Life Without Death (B3/S012345678)",
             LifeRule::DayAndNight => "Day & Night (B3678/S34678)",
             LifeRule::Maze => "Maze (B3/S12345)",
+            LifeRule::Custom { .. } => "Custom",
         }
     }
 
-    pub fn should_live(&self, alive: bool, neighbors: u8) -> bool {
-        match self {
-            LifeRule::Conway => match (alive, neighbors) {
-                (true, 2..=3) => true,
-                (false, 3) => true,
-                _ => false,
-            },
-            LifeRule::HighLife => match (alive, neighbors) {
-                (true, 2..=3) => true,
-                (false, 3 | 6) => true,
-                _ => false,
-            },
-            LifeRule::Seeds => !alive && neighbors == 2,
-            LifeRule::LifeWithoutDeath => alive || neighbors == 3,
-            LifeRule::DayAndNight => match (alive, neighbors) {
-                (true, 3..=4 | 6..=8) => true,
-                (false, 3 | 6..=8) => true,
-                _ => false,
-            },
-            LifeRule::Maze => match (alive, neighbors) {
-                (true, 1..=5) => true,
-                (false, 3) => true,
-                _ => false,
-            },
+    /// `(birth, survive)` neighbor-count bitmasks
+    pub fn masks(&self) -> (u16, u16) {
+        match *self {
+            LifeRule::Conway => (counts(&[3]), counts(&[2, 3])),
+            LifeRule::HighLife => (counts(&[3, 6]), counts(&[2, 3])),
+            LifeRule::Seeds => (counts(&[2]), 0),
+            LifeRule::LifeWithoutDeath => (counts(&[3]), counts(&[0, 1, 2, 3, 4, 5, 6, 7, 8])),
+            LifeRule::DayAndNight => (counts(&[3, 6, 7, 8]), counts(&[3, 4, 6, 7, 8])),
+            LifeRule::Maze => (counts(&[3]), counts(&[1, 2, 3, 4, 5])),
+            LifeRule::Custom { birth, survive } => (birth, survive),
         }
     }
+
+    /// The named preset with these masks, or a custom rule
+    pub fn from_masks(birth: u16, survive: u16) -> LifeRule {
+        Self::all()
+            .into_iter()
+            .find(|rule| rule.masks() == (birth, survive))
+            .unwrap_or(LifeRule::Custom { birth, survive })
+    }
+
+    /// Rule in B/S notation, e.g. "B36/S23"
+    pub fn notation(&self) -> String {
+        let (birth, survive) = self.masks();
+        let digits = |mask: u16| (0..=8).filter(|n| mask >> n & 1 == 1).map(|n| n.to_string()).collect::<String>();
+        format!("B{}/S{}", digits(birth), digits(survive))
+    }
+
+    pub fn should_live(&self, alive: bool, neighbors: u8) -> bool {
+        let (birth, survive) = self.masks();
+        let mask = if alive { survive } else { birth };
+        mask >> neighbors & 1 == 1
+    }
 }
 
 pub struct GameOfLife {
@@ -70,8 +100,19 @@ pub struct GameOfLife {
     time_accumulator: f32,
     pub rule: LifeRule,
     pub show_age: bool,
+    pub grid_filter: GridFilter,
     pub paused: bool,
-    generation: u64,
+    /// Cells were painted since the last update; stepping waits until the
+    /// pointer is released, so cells don't move under it
+    painting: bool,
+    /// Exact step counts queued from the UI
+    runner: StepRunner,
+    /// `(cells, cell_age)` history; its step count is the generation
+    timeline: Timeline<(Vec<bool>, Vec<u32>)>,
+    /// RLE text being edited in the Pattern Library
+    rle_text: String,
+    /// Why the last RLE load failed
+    rle_error: Option<String>,
 }
 
 impl Default for GameOfLife {
@@ -84,6 +125,8 @@ impl Default for GameOfLife {
         // Initialize with a glider gun pattern
         Self::add_glider_gun(&mut cells, width, 10, 10);
 
+        let timeline = Timeline::new((cells.clone(), cell_age.clone()));
+
         Self {
             grid_width: width,
             grid_height: height,
@@ -93,8 +136,13 @@ impl Default for GameOfLife {
             time_accumulator: 0.0,
             rule: LifeRule::Conway,
             show_age: false,
+            grid_filter: GridFilter::Sharp,
             paused: false,
-            generation: 0,
+            painting: false,
+            runner: StepRunner::new(RUN_BATCH),
+            timeline,
+            rle_text: String::new(),
+            rle_error: None,
         }
     }
 }
@@ -178,7 +226,17 @@ impl GameOfLife {
 
         self.cells = new_cells;
         self.cell_age = new_age;
-        self.generation += 1;
+        self.timeline.record(|| (self.cells.clone(), self.cell_age.clone()));
+    }
+
+    pub fn generation(&self) -> u64 {
+        self.timeline.step()
+    }
+
+    /// Make the current cells generation 0
+    fn restart_timeline(&mut self) {
+        self.runner.cancel();
+        self.timeline.restart((self.cells.clone(), self.cell_age.clone()));
     }
 
     pub fn add_pattern(&mut self, pattern: &str) {
@@ -195,7 +253,23 @@ impl GameOfLife {
             "Acorn" => Self::add_acorn(&mut self.cells, self.grid_width, cx, cy),
             _ => {}
         }
-        self.generation = 0;
+        self.restart_timeline();
+    }
+
+    /// Replace the grid with a pattern in run-length-encoded Life format,
+    /// centered. Patterns that don't fit the grid are rejected and leave
+    /// the cells untouched.
+    pub fn load_rle(&mut self, text: &str) -> Result<(), String> {
+        let RlePattern { width, height, live } = parse_rle(text, self.grid_width, self.grid_height)?;
+
+        self.clear();
+        let x0 = (self.grid_width - width) / 2;
+        let y0 = (self.grid_height - height) / 2;
+        for (dx, dy) in live {
+            self.cells[(y0 + dy) * self.grid_width + (x0 + dx)] = true;
+        }
+        self.restart_timeline();
+        Ok(())
     }
 
     fn add_glider(cells: &mut Vec<bool>, width: usize, x: usize, y: usize) {
@@ -280,6 +354,20 @@ impl GameOfLife {
     pub fn clear(&mut self) {
         self.cells.fill(false);
     }
+
+    /// Set one cell, as when painting with the pointer. An edit starts a
+    /// new history from the edited grid, as loading a pattern does.
+    pub fn set_cell(&mut self, gx: usize, gy: usize, alive: bool) {
+        if gx >= self.grid_width || gy >= self.grid_height {
+            return;
+        }
+        let idx = gy * self.grid_width + gx;
+        if self.cells[idx] != alive {
+            self.cells[idx] = alive;
+            self.cell_age[idx] = 0;
+            self.restart_timeline();
+        }
+    }
 }
 
 impl Simulation2D for GameOfLife {
@@ -288,36 +376,73 @@ impl Simulation2D for GameOfLife {
     }
 
     fn compute(&self, width: usize, height: usize) -> Vec<Color> {
-        let mut colors = Vec::with_capacity(width * height);
-
-        let cell_width = width / self.grid_width;
-        let cell_height = height / self.grid_height;
-
-        for py in 0..height {
-            for px in 0..width {
-                let gx = px / cell_width.max(1);
-                let gy = py / cell_height.max(1);
-
-                if gx < self.grid_width && gy < self.grid_height {
-                    let idx = gy * self.grid_width + gx;
-                    colors.push(if self.cells[idx] {
-                        if self.show_age {
-                            // Color by age
-                            let age = self.cell_age[idx].min(50) as f32 / 50.0;
-                            Color::from_hsv(age * 240.0, 0.8, 0.9)
-                        } else {
-                            Color { r: 0, g: 255, b: 100 }
-                        }
-                    } else {
-                        Color::BLACK
-                    });
+        self.compute_ctx(&RenderCtx::new(width, height))
+    }
+
+    fn compute_ctx(&self, ctx: &RenderCtx) -> Vec<Color> {
+        render_grid(self.grid_width, self.grid_height, ctx.width, ctx.height, self.grid_filter, |idx| {
+            if self.cells[idx] {
+                if self.show_age {
+                    // Color by age
+                    let age = self.cell_age[idx].min(50) as f32 / 50.0;
+                    Color::from_hsv(age * 240.0, 0.8, 0.9)
                 } else {
-                    colors.push(Color::BLACK);
+                    Color { r: 0, g: 255, b: 100 }
                 }
+            } else {
+                ctx.background
             }
+        })
+    }
+
+    /// Live cells as 1 plus their age in generations (capped at 50), dead
+    /// cells 0, so long-lived structures stand out
+    fn scalar_field(&self) -> Option<(Vec<f32>, usize, usize)> {
+        let field = self.cells.iter()
+            .zip(&self.cell_age)
+            .map(|(&alive, &age)| if alive { 1.0 + age.min(50) as f32 } else { 0.0 })
+            .collect();
+        Some((field, self.grid_width, self.grid_height))
+    }
+
+    fn supports_seek(&self) -> bool {
+        true
+    }
+
+    fn step_count(&self) -> u64 {
+        self.generation()
+    }
+
+    fn seek(&mut self, step: u64) {
+        self.runner.cancel();
+        if let Some((cells, cell_age)) = self.timeline.rewind(step) {
+            self.cells = cells;
+            self.cell_age = cell_age;
+        }
+        while self.generation() < step {
+            self.step();
+        }
+        self.time_accumulator = 0.0;
+    }
+
+    fn paint_at(&mut self, x: f32, y: f32, width: usize, height: usize, erase: bool) -> bool {
+        self.painting = true;
+        let Some((gx, gy)) = cell_at(x, y, self.grid_width, self.grid_height, width, height) else {
+            return false;
+        };
+        let alive = !erase;
+        if self.cells[gy * self.grid_width + gx] == alive {
+            return false;
         }
+        self.set_cell(gx, gy, alive);
+        true
+    }
 
-        colors
+    fn reset(&mut self) {
+        // Back to the opening glider gun
+        self.cell_age.fill(0);
+        self.time_accumulator = 0.0;
+        self.add_pattern("Glider Gun");
     }
 
     fn ui_parameters(&mut self, ui: &mut egui::Ui) -> bool {
@@ -340,23 +465,32 @@ impl Simulation2D for GameOfLife {
 
                     if ui.button("🔄 Clear").clicked() {
                         self.clear();
-                        self.generation = 0;
+                        self.restart_timeline();
                         changed = true;
                     }
                 });
 
                 ui.add(egui::Slider::new(&mut self.speed, 1.0..=60.0)
+                    .nudge(ui, 0.5)
                     .text("Steps/Second"));
 
-                ui.label(format!("Generation: {}", self.generation));
-                ui.label(format!("Live Cells: {}", self.cells.iter().filter(|&&c| c).count()));
+                if self.runner.ui(ui) {
+                    self.paused = true;
+                }
+
+                ui.label(format!("Generation: {}", readout::count(self.generation())));
+                ui.label(format!("Live Cells: {}", readout::count(self.cells.iter().filter(|&&c| c).count())));
             });
 
         egui::CollapsingHeader::new("📐 Rules")
             .default_open(true)
             .show(ui, |ui| {
+                let selected = match self.rule {
+                    LifeRule::Custom { .. } => format!("Custom ({})", self.rule.notation()),
+                    rule => rule.name().to_string(),
+                };
                 egui::ComboBox::from_label("Rule Set")
-                    .selected_text(self.rule.name())
+                    .selected_text(selected)
                     .show_ui(ui, |ui| {
                         for rule in LifeRule::all() {
                             if ui.selectable_value(&mut self.rule, rule, rule.name()).clicked() {
@@ -364,6 +498,25 @@ impl Simulation2D for GameOfLife {
                             }
                         }
                     });
+
+                // Neighbor counts that give birth / let a cell survive
+                let (mut birth, mut survive) = self.rule.masks();
+                let mut edited = false;
+                for (label, mask) in [("Birth", &mut birth), ("Survive", &mut survive)] {
+                    ui.horizontal(|ui| {
+                        ui.label(label);
+                        for n in 0..=8 {
+                            if ui.selectable_label(*mask >> n & 1 == 1, n.to_string()).clicked() {
+                                *mask ^= 1 << n;
+                                edited = true;
+                            }
+                        }
+                    });
+                }
+                if edited {
+                    self.rule = LifeRule::from_masks(birth, survive);
+                    changed = true;
+                }
             });
 
         egui::CollapsingHeader::new("🎨 Visualization")
@@ -372,6 +525,8 @@ impl Simulation2D for GameOfLife {
                 if ui.checkbox(&mut self.show_age, "Color by Age").changed() {
                     changed = true;
                 }
+
+                changed |= grid_filter_ui(ui, &mut self.grid_filter);
             });
 
         egui::CollapsingHeader::new("🧬 Pattern Library")
@@ -402,23 +557,218 @@ impl Simulation2D for GameOfLife {
                 }
                 if ui.button("Random Soup").clicked() {
                     self.randomize();
-                    self.generation = 0;
+                    self.restart_timeline();
                     changed = true;
                 }
+
+                ui.separator();
+                ui.label("Paste an RLE pattern:");
+                ui.add(egui::TextEdit::multiline(&mut self.rle_text)
+                    .code_editor()
+                    .desired_rows(4)
+                    .hint_text("x = 3, y = 3\nbo$2bo$3o!"));
+                if ui.button("Load Pattern").clicked() {
+                    let text = std::mem::take(&mut self.rle_text);
+                    self.rle_error = self.load_rle(&text).err();
+                    self.rle_text = text;
+                    changed |= self.rle_error.is_none();
+                }
+                if let Some(error) = &self.rle_error {
+                    ui.label(error);
+                }
             });
 
-        // Auto-stepping
-        if !self.paused {
-            let dt = ui.input(|i| i.stable_dt);
-            self.time_accumulator += dt * self.speed;
+        changed
+    }
 
-            while self.time_accumulator >= 1.0 {
+    fn update(&mut self, dt: f32, _width: usize, _height: usize) -> bool {
+        let batch = self.runner.take();
+        if batch > 0 {
+            for _ in 0..batch {
                 self.step();
-                self.time_accumulator -= 1.0;
-                changed = true;
             }
+            self.time_accumulator = 0.0;
+            return true;
+        }
+
+        if std::mem::take(&mut self.painting) || self.paused {
+            return false;
+        }
+
+        let mut changed = false;
+        self.time_accumulator += dt * self.speed;
+
+        while self.time_accumulator >= 1.0 {
+            self.step();
+            self.time_accumulator -= 1.0;
+            changed = true;
         }
 
         changed
     }
 }
+
+/// A parsed RLE pattern: its bounding size and live cell offsets
+struct RlePattern {
+    width: usize,
+    height: usize,
+    live: Vec<(usize, usize)>,
+}
+
+/// Parse run-length-encoded Life: `#` comment lines, an optional
+/// `x = .., y = ..` header, then runs of `b` (dead), `o` (alive) and `$`
+/// (end of row), finished by `!`. A pattern that doesn't fit in
+/// `max_width`x`max_height` is rejected as soon as that shows, so a huge
+/// run count costs nothing.
+fn parse_rle(text: &str, max_width: usize, max_height: usize) -> Result<RlePattern, String> {
+    let too_big = || format!("Pattern doesn't fit the {max_width}x{max_height} grid");
+    let mut header = (0, 0);
+    let mut live = Vec::new();
+    let (mut x, mut y) = (0, 0);
+    let mut run: usize = 0;
+    let mut finished = false;
+
+    for line in text.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if line.starts_with('x') {
+            for field in line.split(',') {
+                let Some((key, value)) = field.split_once('=') else {
+                    continue;
+                };
+                let value = value.trim();
+                match key.trim() {
+                    "x" => header.0 = value.parse().map_err(|_| format!("Bad pattern width '{value}'"))?,
+                    "y" => header.1 = value.parse().map_err(|_| format!("Bad pattern height '{value}'"))?,
+                    _ => {}
+                }
+            }
+            if header.0 > max_width || header.1 > max_height {
+                return Err(format!(
+                    "Pattern is {}x{} but the grid is only {max_width}x{max_height}",
+                    header.0, header.1
+                ));
+            }
+            continue;
+        }
+
+        for c in line.chars() {
+            match c {
+                '0'..='9' => {
+                    // No run longer than the grid can fit, which also keeps
+                    // x and y from overflowing
+                    run = run
+                        .checked_mul(10)
+                        .and_then(|run| run.checked_add(c.to_digit(10).unwrap() as usize))
+                        .filter(|&run| run <= max_width.max(max_height))
+                        .ok_or_else(too_big)?;
+                    continue;
+                }
+                'b' => {
+                    x += run.max(1);
+                    if x > max_width {
+                        return Err(too_big());
+                    }
+                }
+                'o' => {
+                    let end = x + run.max(1);
+                    if end > max_width || y >= max_height {
+                        return Err(too_big());
+                    }
+                    live.extend((x..end).map(|cx| (cx, y)));
+                    x = end;
+                }
+                '$' => {
+                    y += run.max(1);
+                    x = 0;
+                    if y > max_height {
+                        return Err(too_big());
+                    }
+                }
+                '!' => {
+                    finished = true;
+                    break;
+                }
+                c if c.is_whitespace() => {}
+                c => return Err(format!("Unexpected '{c}' in pattern")),
+            }
+            run = 0;
+        }
+        if finished {
+            break;
+        }
+    }
+
+    if !finished {
+        return Err("Pattern is missing its closing '!'".to_string());
+    }
+    if live.is_empty() {
+        return Err("Pattern has no live cells".to_string());
+    }
+    let width = live.iter().map(|&(x, _)| x + 1).max().unwrap_or(0).max(header.0);
+    let height = live.iter().map(|&(_, y)| y + 1).max().unwrap_or(0).max(header.1);
+    Ok(RlePattern { width, height, live })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GLIDER: &str = "#N Glider\nx = 3, y = 3, rule = B3/S23\nbob$2bo$3o!";
+
+    #[test]
+    fn rle_glider_is_stamped_centered() {
+        let mut life = GameOfLife::new();
+        life.load_rle(GLIDER).unwrap();
+
+        let live: Vec<usize> = (0..life.cells.len()).filter(|&i| life.cells[i]).collect();
+        let (x0, y0) = ((life.grid_width - 3) / 2, (life.grid_height - 3) / 2);
+        let expected: Vec<usize> = [(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)]
+            .iter()
+            .map(|&(dx, dy)| (y0 + dy) * life.grid_width + x0 + dx)
+            .collect();
+        assert_eq!(live, expected);
+    }
+
+    #[test]
+    fn named_rules_round_trip_through_their_masks() {
+        for rule in LifeRule::all() {
+            let (birth, survive) = rule.masks();
+            assert!(rule.name().contains(&rule.notation()), "{} vs {}", rule.name(), rule.notation());
+            assert!(LifeRule::from_masks(birth, survive) == rule);
+        }
+
+        let custom = LifeRule::from_masks(counts(&[3, 5]), counts(&[2, 3, 6]));
+        assert_eq!(custom.notation(), "B35/S236");
+        assert!(custom.should_live(false, 5) && custom.should_live(true, 6));
+        assert!(!custom.should_live(false, 2) && !custom.should_live(true, 4));
+    }
+
+    #[test]
+    fn rle_larger_than_the_grid_is_rejected_untouched() {
+        let mut life = GameOfLife::new();
+        let before = life.cells.clone();
+        let wide = format!("x = {}, y = 1\n{}o!", life.grid_width + 1, life.grid_width + 1);
+
+        assert!(life.load_rle(&wide).is_err());
+        assert_eq!(life.cells, before);
+    }
+
+    #[test]
+    fn rle_with_a_huge_run_count_is_rejected_without_allocating() {
+        let (width, height) = (64, 48);
+        for text in ["3000000000o!", "99999999999999999999999999o!", "b3000000000$o!", "o4000000000$o!"] {
+            assert!(parse_rle(text, width, height).is_err(), "{text}");
+        }
+        // A row exactly as wide as the grid still fits
+        assert_eq!(parse_rle("64o!", width, height).unwrap().live.len(), 64);
+    }
+
+    #[test]
+    fn unterminated_or_garbage_rle_is_rejected() {
+        for text in ["bob$2bo$3o", "x = 3, y = 3\nbo?b!", "hello world!", "x = abc, y = 3\no!", ""] {
+            assert!(parse_rle(text, 64, 48).is_err(), "{text:?}");
+        }
+    }
+}
@@ -1,5 +1,10 @@
+use crate::color_ui::color_scheme_combo;
+use crate::nudge::Nudge;
+use crate::readout;
+use crate::state::{read, read_clamped};
 use crate::{Color, ColorScheme, Simulation2D};
 use rand::Rng;
+use serde_json::{json, Value};
 
 pub struct Boid {
     x: f32,
@@ -46,9 +51,11 @@ pub struct Boids {
     predators: Vec<Boid>,
     mouse_x: f32,
     mouse_y: f32,
+    polarization: f32,
+    mean_neighbors: f32,
 }
 
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
 pub enum FlockMode {
     Normal,
     Chaotic,
@@ -114,6 +121,100 @@ impl Default for Boids {
             predators: Vec::new(),
             mouse_x: 0.0,
             mouse_y: 0.0,
+            polarization: 0.0,
+            mean_neighbors: 0.0,
+        }
+    }
+}
+
+/// Shortest offset from `from` to `to` along one axis. On a torus that may
+/// go the other way around (minimum-image convention).
+fn wrap_delta(wrap: bool, from: f32, to: f32, size: f32) -> f32 {
+    let d = to - from;
+    if wrap && size > 0.0 {
+        d - size * (d / size).round()
+    } else {
+        d
+    }
+}
+
+/// Uniform grid of boid indices, with cells at least as wide as the largest
+/// interaction radius, so every boid within that radius of a point is in the
+/// point's cell or one of the 8 around it. Rebuilt from the positions every
+/// step, which keeps the neighbor search linear in the boid count.
+struct SpatialGrid {
+    columns: usize,
+    rows: usize,
+    cell_width: f32,
+    cell_height: f32,
+    /// Boid indices grouped by cell
+    indices: Vec<usize>,
+    /// Where each cell's run in `indices` starts; one extra entry at the end
+    starts: Vec<usize>,
+}
+
+impl SpatialGrid {
+    fn build(positions: &[(f32, f32, f32, f32)], cell_size: f32, width: usize, height: usize) -> Self {
+        let cell_size = cell_size.max(1.0);
+        let columns = ((width as f32 / cell_size) as usize).max(1);
+        let rows = ((height as f32 / cell_size) as usize).max(1);
+        let mut grid = Self {
+            columns,
+            rows,
+            cell_width: width.max(1) as f32 / columns as f32,
+            cell_height: height.max(1) as f32 / rows as f32,
+            indices: vec![0; positions.len()],
+            starts: vec![0; columns * rows + 1],
+        };
+
+        // Counting sort by cell
+        let cells: Vec<usize> = positions.iter().map(|&(x, y, _, _)| grid.cell(x, y)).collect();
+        for &cell in &cells {
+            grid.starts[cell + 1] += 1;
+        }
+        for cell in 0..columns * rows {
+            grid.starts[cell + 1] += grid.starts[cell];
+        }
+        let mut next = grid.starts.clone();
+        for (i, &cell) in cells.iter().enumerate() {
+            grid.indices[next[cell]] = i;
+            next[cell] += 1;
+        }
+        grid
+    }
+
+    /// Cell holding (x, y); points off the grid go to the nearest edge cell
+    fn cell(&self, x: f32, y: f32) -> usize {
+        let column = ((x / self.cell_width).max(0.0) as usize).min(self.columns - 1);
+        let row = ((y / self.cell_height).max(0.0) as usize).min(self.rows - 1);
+        row * self.columns + column
+    }
+
+    /// A cell and its neighbors along one axis of `count` cells, each once.
+    /// With `wrap` the neighbors of an edge cell include the far edge.
+    fn span(index: usize, count: usize, wrap: bool) -> impl Iterator<Item = usize> {
+        let range = if wrap && count > 3 {
+            index + count - 1..index + count + 2
+        } else if wrap {
+            0..count
+        } else {
+            index.saturating_sub(1)..(index + 2).min(count)
+        };
+        range.map(move |i| i % count)
+    }
+
+    /// Call `f` with every boid in the 3x3 block of cells around (x, y): a
+    /// superset of the boids within one cell size of it
+    fn for_each_near(&self, x: f32, y: f32, wrap: bool, mut f: impl FnMut(usize)) {
+        let cell = self.cell(x, y);
+        let (column, row) = (cell % self.columns, cell / self.columns);
+        for r in Self::span(row, self.rows, wrap) {
+            for c in Self::span(column, self.columns, wrap) {
+                let cell = r * self.columns + c;
+                for &i in &self.indices[self.starts[cell]..self.starts[cell + 1]] {
+                    f(i);
+                }
+            }
         }
     }
 }
@@ -132,7 +233,7 @@ impl Boids {
 
         for _ in 0..self.boid_count {
             let angle = rng.gen_range(0.0..std::f32::consts::TAU);
-            let speed = rng.gen_range(1.0..self.max_speed);
+            let speed = rng.gen_range(1.0..=self.max_speed);
 
             self.boids.push(Boid {
                 x: rng.gen_range(0.0..width as f32),
@@ -140,7 +241,8 @@ impl Boids {
                 vx: angle.cos() * speed,
                 vy: angle.sin() * speed,
                 color: Color::WHITE,
-                size: rng.gen_range(self.boid_size_min..self.boid_size_max),
+                // The size sliders are independent, so min can pass max
+                size: rng.gen_range(self.boid_size_min.min(self.boid_size_max)..=self.boid_size_max),
             });
         }
 
@@ -171,13 +273,22 @@ impl Boids {
             .collect();
 
         // Get parameters to avoid borrow issues
-        let color_scheme = self.color_scheme;
+        let color_scheme = self.color_scheme.clone();
         let hue_shift = self.hue_shift;
         let saturation = self.saturation;
         let brightness = self.brightness;
+        let wrap_edges = self.wrap_edges;
+
+        let reach = self.separation_radius.max(self.alignment_radius).max(self.cohesion_radius);
+        let grid = SpatialGrid::build(&boid_positions, reach, width, height);
+
+        let mut heading_x = 0.0;
+        let mut heading_y = 0.0;
+        let mut neighbor_total = 0usize;
 
         // Update regular boids
-        for (i, boid) in self.boids.iter_mut().enumerate() {
+        for i in 0..self.boids.len() {
+            let (bx, by) = (self.boids[i].x, self.boids[i].y);
             let mut sep_x = 0.0;
             let mut sep_y = 0.0;
             let mut sep_count = 0;
@@ -191,13 +302,14 @@ impl Boids {
             let mut coh_count = 0;
 
             // Calculate flocking forces
-            for (j, &(ox, oy, ovx, ovy)) in boid_positions.iter().enumerate() {
+            grid.for_each_near(bx, by, wrap_edges, |j| {
                 if i == j {
-                    continue;
+                    return;
                 }
+                let (ox, oy, ovx, ovy) = boid_positions[j];
 
-                let dx = ox - boid.x;
-                let dy = oy - boid.y;
+                let dx = wrap_delta(wrap_edges, bx, ox, width as f32);
+                let dy = wrap_delta(wrap_edges, by, oy, height as f32);
                 let dist_sq = dx * dx + dy * dy;
 
                 // Separation
@@ -215,13 +327,17 @@ impl Boids {
                     align_count += 1;
                 }
 
-                // Cohesion
+                // Cohesion (via the wrapped offset, so the centroid of a flock
+                // straddling an edge isn't pulled across the whole screen)
                 if dist_sq < self.cohesion_radius * self.cohesion_radius {
-                    coh_x += ox;
-                    coh_y += oy;
+                    coh_x += bx + dx;
+                    coh_y += by + dy;
                     coh_count += 1;
                 }
-            }
+            });
+
+            neighbor_total += align_count;
+            let boid = &mut self.boids[i];
 
             let mut ax = 0.0;
             let mut ay = 0.0;
@@ -291,8 +407,8 @@ impl Boids {
 
             // Avoid predators
             for &(px, py) in &predator_positions {
-                let dx = px - boid.x;
-                let dy = py - boid.y;
+                let dx = wrap_delta(wrap_edges, bx, px, width as f32);
+                let dy = wrap_delta(wrap_edges, by, py, height as f32);
                 let dist_sq = dx * dx + dy * dy;
 
                 if dist_sq < self.predator_radius * self.predator_radius && dist_sq > 0.0 {
@@ -375,41 +491,45 @@ impl Boids {
                 i as f32 / self.boid_count as f32
             };
 
-            let mut color = color_scheme.map(t, true);
-
-            // Apply color adjustments inline
-            let (h, s, v) = Color::rgb_to_hsv_static(color);
-            let new_h = (h + hue_shift * 360.0) % 360.0;
-            let new_s = (s * saturation).clamp(0.0, 1.0);
-            let new_v = (v * brightness).clamp(0.0, 1.0);
-            color = Color::from_hsv(new_h, new_s, new_v);
+            let color = color_scheme.map(t, true).adjust_hsv(hue_shift, saturation, brightness);
 
             boid.color = color;
+
+            let speed = (boid.vx * boid.vx + boid.vy * boid.vy).sqrt();
+            if speed > 0.0 {
+                heading_x += boid.vx / speed;
+                heading_y += boid.vy / speed;
+            }
         }
 
+        // Order parameter: 1 when every boid heads the same way, ~0 when random
+        let n = self.boids.len().max(1) as f32;
+        self.polarization = (heading_x * heading_x + heading_y * heading_y).sqrt() / n;
+        self.mean_neighbors = neighbor_total as f32 / n;
+
         // Update predators (simple chase behavior)
         for predator in &mut self.predators {
-            // Find nearest boid
+            // Find nearest boid, measured the same way boids measure each other
             let mut nearest_dist = f32::MAX;
-            let mut nearest_x = 0.0;
-            let mut nearest_y = 0.0;
+            let mut nearest_dx = 0.0;
+            let mut nearest_dy = 0.0;
 
             for &(bx, by, _, _) in &boid_positions {
-                let dx = bx - predator.x;
-                let dy = by - predator.y;
+                let dx = wrap_delta(wrap_edges, predator.x, bx, width as f32);
+                let dy = wrap_delta(wrap_edges, predator.y, by, height as f32);
                 let dist_sq = dx * dx + dy * dy;
 
                 if dist_sq < nearest_dist {
                     nearest_dist = dist_sq;
-                    nearest_x = bx;
-                    nearest_y = by;
+                    nearest_dx = dx;
+                    nearest_dy = dy;
                 }
             }
 
             // Chase nearest boid
             if nearest_dist < f32::MAX {
-                let dx = nearest_x - predator.x;
-                let dy = nearest_y - predator.y;
+                let dx = nearest_dx;
+                let dy = nearest_dy;
                 let dist = nearest_dist.sqrt();
 
                 if dist > 0.0 {
@@ -437,42 +557,6 @@ impl Boids {
         }
     }
 
-    #[allow(dead_code)]
-    fn apply_color_adjustments(&self, color: Color) -> Color {
-        let (h, s, v) = self.rgb_to_hsv(color);
-        let new_h = (h + self.hue_shift * 360.0) % 360.0;
-        let new_s = (s * self.saturation).clamp(0.0, 1.0);
-        let new_v = (v * self.brightness).clamp(0.0, 1.0);
-
-        Color::from_hsv(new_h, new_s, new_v)
-    }
-
-    #[allow(dead_code)]
-    fn rgb_to_hsv(&self, color: Color) -> (f32, f32, f32) {
-        let r = color.r as f32 / 255.0;
-        let g = color.g as f32 / 255.0;
-        let b = color.b as f32 / 255.0;
-
-        let max = r.max(g).max(b);
-        let min = r.min(g).min(b);
-        let delta = max - min;
-
-        let h = if delta == 0.0 {
-            0.0
-        } else if max == r {
-            60.0 * (((g - b) / delta) % 6.0)
-        } else if max == g {
-            60.0 * (((b - r) / delta) + 2.0)
-        } else {
-            60.0 * (((r - g) / delta) + 4.0)
-        };
-
-        let s = if max == 0.0 { 0.0 } else { delta / max };
-        let v = max;
-
-        (h, s, v)
-    }
-
     fn draw_triangle(&self, pixels: &mut [Color], width: usize, height: usize,
                      x: f32, y: f32, vx: f32, vy: f32, size: f32, color: Color) {
         let angle = vy.atan2(vx);
@@ -618,6 +702,13 @@ impl Simulation2D for Boids {
         pixels
     }
 
+    fn reset(&mut self) {
+        // update respawns the flock at the canvas size once it sees the
+        // counts no longer match
+        self.boids.clear();
+        self.predators.clear();
+    }
+
     fn ui_parameters(&mut self, ui: &mut egui::Ui) -> bool {
         let mut changed = false;
 
@@ -626,21 +717,27 @@ impl Simulation2D for Boids {
         egui::CollapsingHeader::new("🐦 Boid Settings")
             .default_open(true)
             .show(ui, |ui| {
-                if ui.add(egui::Slider::new(&mut self.boid_count, 10..=500)
+                if ui.add(egui::Slider::new(&mut self.boid_count, 10..=2000)
+                    .nudge(ui, 1.0)
+                    .logarithmic(true)
                     .text("Boid Count")).changed() {
                     changed = true;
                 }
 
                 changed |= ui.add(egui::Slider::new(&mut self.max_speed, 1.0..=10.0)
+                    .nudge(ui, 0.05)
                     .text("Max Speed")).changed();
 
                 changed |= ui.add(egui::Slider::new(&mut self.max_force, 0.1..=2.0)
+                    .nudge(ui, 0.01)
                     .text("Max Force")).changed();
 
                 changed |= ui.add(egui::Slider::new(&mut self.boid_size_min, 1.0..=10.0)
+                    .nudge(ui, 0.05)
                     .text("Min Size")).changed();
 
                 changed |= ui.add(egui::Slider::new(&mut self.boid_size_max, 1.0..=15.0)
+                    .nudge(ui, 0.1)
                     .text("Max Size")).changed();
             });
 
@@ -648,23 +745,29 @@ impl Simulation2D for Boids {
             .default_open(true)
             .show(ui, |ui| {
                 changed |= ui.add(egui::Slider::new(&mut self.separation_radius, 5.0..=100.0)
+                    .nudge(ui, 0.5)
                     .text("Separation Radius")).changed();
 
                 changed |= ui.add(egui::Slider::new(&mut self.alignment_radius, 10.0..=150.0)
+                    .nudge(ui, 1.0)
                     .text("Alignment Radius")).changed();
 
                 changed |= ui.add(egui::Slider::new(&mut self.cohesion_radius, 10.0..=150.0)
+                    .nudge(ui, 1.0)
                     .text("Cohesion Radius")).changed();
 
                 ui.separator();
 
                 changed |= ui.add(egui::Slider::new(&mut self.separation_strength, 0.0..=5.0)
+                    .nudge(ui, 0.05)
                     .text("Separation")).changed();
 
                 changed |= ui.add(egui::Slider::new(&mut self.alignment_strength, 0.0..=5.0)
+                    .nudge(ui, 0.05)
                     .text("Alignment")).changed();
 
                 changed |= ui.add(egui::Slider::new(&mut self.cohesion_strength, 0.0..=5.0)
+                    .nudge(ui, 0.05)
                     .text("Cohesion")).changed();
 
                 ui.separator();
@@ -683,27 +786,22 @@ impl Simulation2D for Boids {
         egui::CollapsingHeader::new("🎨 Color Settings")
             .default_open(true)
             .show(ui, |ui| {
-                egui::ComboBox::from_label("Color Scheme")
-                    .selected_text(self.color_scheme.name())
-                    .show_ui(ui, |ui| {
-                        for scheme in ColorScheme::all() {
-                            if ui.selectable_value(&mut self.color_scheme, scheme, scheme.name()).clicked() {
-                                changed = true;
-                            }
-                        }
-                    });
+                changed |= color_scheme_combo(ui, &mut self.color_scheme);
 
                 changed |= ui.checkbox(&mut self.color_by_velocity, "Color by Velocity").changed();
                 changed |= ui.checkbox(&mut self.color_by_direction, "Color by Direction").changed();
                 changed |= ui.checkbox(&mut self.color_by_neighbors, "Color by Neighbors").changed();
 
                 changed |= ui.add(egui::Slider::new(&mut self.hue_shift, 0.0..=1.0)
+                    .nudge(ui, 0.01)
                     .text("Hue Shift")).changed();
 
                 changed |= ui.add(egui::Slider::new(&mut self.saturation, 0.0..=2.0)
+                    .nudge(ui, 0.02)
                     .text("Saturation")).changed();
 
                 changed |= ui.add(egui::Slider::new(&mut self.brightness, 0.0..=2.0)
+                    .nudge(ui, 0.02)
                     .text("Brightness")).changed();
             });
 
@@ -717,6 +815,7 @@ impl Simulation2D for Boids {
 
                 if self.background_trails {
                     changed |= ui.add(egui::Slider::new(&mut self.trail_fade, 0.0..=1.0)
+                        .nudge(ui, 0.01)
                         .text("Trail Fade")).changed();
                 }
 
@@ -729,9 +828,11 @@ impl Simulation2D for Boids {
 
                 if self.avoid_mouse {
                     changed |= ui.add(egui::Slider::new(&mut self.mouse_avoidance_radius, 20.0..=200.0)
+                        .nudge(ui, 1.0)
                         .text("Avoidance Radius")).changed();
 
                     changed |= ui.add(egui::Slider::new(&mut self.mouse_avoidance_strength, 0.5..=5.0)
+                        .nudge(ui, 0.02)
                         .text("Avoidance Strength")).changed();
                 }
             });
@@ -739,19 +840,30 @@ impl Simulation2D for Boids {
         egui::CollapsingHeader::new("👹 Predators")
             .show(ui, |ui| {
                 if ui.add(egui::Slider::new(&mut self.predator_count, 0..=10)
+                    .nudge(ui, 1.0)
                     .text("Predator Count")).changed() {
                     changed = true;
                 }
 
                 if self.predator_count > 0 {
                     changed |= ui.add(egui::Slider::new(&mut self.predator_radius, 50.0..=200.0)
+                        .nudge(ui, 1.0)
                         .text("Fear Radius")).changed();
 
                     changed |= ui.add(egui::Slider::new(&mut self.predator_strength, 1.0..=10.0)
+                        .nudge(ui, 0.05)
                         .text("Fear Strength")).changed();
                 }
             });
 
+        egui::CollapsingHeader::new("📊 Flock Metrics")
+            .show(ui, |ui| {
+                ui.add(egui::ProgressBar::new(self.polarization)
+                    .text(format!("Polarization: {}", readout::decimal(self.polarization, 2))));
+                ui.label(format!("Mean neighbors: {}", readout::decimal(self.mean_neighbors, 1)));
+                ui.label(format!("World: {}", if self.wrap_edges { "Toroidal" } else { "Bounded" }));
+            });
+
         egui::CollapsingHeader::new("🎯 Presets")
             .show(ui, |ui| {
                 if ui.button("Classic Flock").clicked() {
@@ -778,22 +890,169 @@ impl Simulation2D for Boids {
                 }
             });
 
-        // Update boid positions
-        let dt = ui.input(|i| i.stable_dt);
+        changed
+    }
+
+    fn update(&mut self, dt: f32, width: usize, height: usize) -> bool {
+        let (width, height) = (width.max(800), height.max(600));
 
         // Reinitialize if count changed
         let current_count = self.boids.len();
         let current_predator_count = self.predators.len();
         if current_count != self.boid_count || current_predator_count != self.predator_count {
-            let size = ui.available_size();
-            self.init_boids(size.x as usize, size.y as usize);
+            self.init_boids(width, height);
         }
 
-        // Update positions
-        let size = ui.available_size();
-        self.update_boids(size.x.max(800.0) as usize, size.y.max(600.0) as usize, dt);
-        changed = true;
+        self.update_boids(width, height, dt);
+        true
+    }
 
-        changed
+    fn save_state(&self) -> Value {
+        json!({
+            "boid_count": self.boid_count,
+            "separation_radius": self.separation_radius,
+            "alignment_radius": self.alignment_radius,
+            "cohesion_radius": self.cohesion_radius,
+            "separation_strength": self.separation_strength,
+            "alignment_strength": self.alignment_strength,
+            "cohesion_strength": self.cohesion_strength,
+            "max_speed": self.max_speed,
+            "max_force": self.max_force,
+            "boid_size_min": self.boid_size_min,
+            "boid_size_max": self.boid_size_max,
+            "color_scheme": self.color_scheme,
+            "color_by_velocity": self.color_by_velocity,
+            "color_by_neighbors": self.color_by_neighbors,
+            "color_by_direction": self.color_by_direction,
+            "show_velocity_lines": self.show_velocity_lines,
+            "show_perception_radius": self.show_perception_radius,
+            "wrap_edges": self.wrap_edges,
+            "avoid_mouse": self.avoid_mouse,
+            "mouse_avoidance_radius": self.mouse_avoidance_radius,
+            "mouse_avoidance_strength": self.mouse_avoidance_strength,
+            "hue_shift": self.hue_shift,
+            "saturation": self.saturation,
+            "brightness": self.brightness,
+            "background_trails": self.background_trails,
+            "trail_fade": self.trail_fade,
+            "draw_triangles": self.draw_triangles,
+            "flock_mode": self.flock_mode,
+            "predator_count": self.predator_count,
+            "predator_radius": self.predator_radius,
+            "predator_strength": self.predator_strength,
+        })
+    }
+
+    /// The flock itself isn't saved; a changed boid or predator count
+    /// respawns it on the next update, as the sliders do
+    fn load_state(&mut self, state: &Value) {
+        read_clamped(state, "boid_count", &mut self.boid_count, 10..=2000);
+        read_clamped(state, "separation_radius", &mut self.separation_radius, 5.0..=100.0);
+        read_clamped(state, "alignment_radius", &mut self.alignment_radius, 10.0..=150.0);
+        read_clamped(state, "cohesion_radius", &mut self.cohesion_radius, 10.0..=150.0);
+        read_clamped(state, "separation_strength", &mut self.separation_strength, 0.0..=5.0);
+        read_clamped(state, "alignment_strength", &mut self.alignment_strength, 0.0..=5.0);
+        read_clamped(state, "cohesion_strength", &mut self.cohesion_strength, 0.0..=5.0);
+        read_clamped(state, "max_speed", &mut self.max_speed, 1.0..=10.0);
+        read_clamped(state, "max_force", &mut self.max_force, 0.1..=2.0);
+        read_clamped(state, "boid_size_min", &mut self.boid_size_min, 1.0..=10.0);
+        read_clamped(state, "boid_size_max", &mut self.boid_size_max, 1.0..=15.0);
+        read(state, "color_scheme", &mut self.color_scheme);
+        read(state, "color_by_velocity", &mut self.color_by_velocity);
+        read(state, "color_by_neighbors", &mut self.color_by_neighbors);
+        read(state, "color_by_direction", &mut self.color_by_direction);
+        read(state, "show_velocity_lines", &mut self.show_velocity_lines);
+        read(state, "show_perception_radius", &mut self.show_perception_radius);
+        read(state, "wrap_edges", &mut self.wrap_edges);
+        read(state, "avoid_mouse", &mut self.avoid_mouse);
+        read_clamped(state, "mouse_avoidance_radius", &mut self.mouse_avoidance_radius, 20.0..=200.0);
+        read_clamped(state, "mouse_avoidance_strength", &mut self.mouse_avoidance_strength, 0.5..=5.0);
+        read_clamped(state, "hue_shift", &mut self.hue_shift, 0.0..=1.0);
+        read_clamped(state, "saturation", &mut self.saturation, 0.0..=2.0);
+        read_clamped(state, "brightness", &mut self.brightness, 0.0..=2.0);
+        read(state, "background_trails", &mut self.background_trails);
+        read_clamped(state, "trail_fade", &mut self.trail_fade, 0.0..=1.0);
+        read(state, "draw_triangles", &mut self.draw_triangles);
+        read(state, "flock_mode", &mut self.flock_mode);
+        read_clamped(state, "predator_count", &mut self.predator_count, 0..=10);
+        read_clamped(state, "predator_radius", &mut self.predator_radius, 50.0..=200.0);
+        read_clamped(state, "predator_strength", &mut self.predator_strength, 1.0..=10.0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    /// Indices within `radius` of boid `i`, sorted
+    fn brute_force(positions: &[(f32, f32, f32, f32)], i: usize, radius: f32, wrap: bool, width: usize, height: usize) -> Vec<usize> {
+        let (x, y, _, _) = positions[i];
+        (0..positions.len())
+            .filter(|&j| {
+                let dx = wrap_delta(wrap, x, positions[j].0, width as f32);
+                let dy = wrap_delta(wrap, y, positions[j].1, height as f32);
+                j != i && dx * dx + dy * dy < radius * radius
+            })
+            .collect()
+    }
+
+    fn from_grid(grid: &SpatialGrid, positions: &[(f32, f32, f32, f32)], i: usize, radius: f32, wrap: bool, width: usize, height: usize) -> Vec<usize> {
+        let (x, y, _, _) = positions[i];
+        let mut found = Vec::new();
+        grid.for_each_near(x, y, wrap, |j| {
+            let dx = wrap_delta(wrap, x, positions[j].0, width as f32);
+            let dy = wrap_delta(wrap, y, positions[j].1, height as f32);
+            if j != i && dx * dx + dy * dy < radius * radius {
+                found.push(j);
+            }
+        });
+        found.sort_unstable();
+        found
+    }
+
+    #[test]
+    fn grid_finds_the_same_neighbors_as_brute_force() {
+        let mut rng = StdRng::seed_from_u64(7);
+        // The small world has fewer than 3 cells across, where the wrapped
+        // 3x3 block would visit a cell twice
+        for (width, height, radius) in [(800, 600, 50.0), (800, 600, 97.5), (120, 90, 50.0)] {
+            let positions: Vec<(f32, f32, f32, f32)> = (0..200)
+                .map(|_| (rng.gen_range(0.0..width as f32), rng.gen_range(0.0..height as f32), 0.0, 0.0))
+                .collect();
+            let grid = SpatialGrid::build(&positions, radius, width, height);
+
+            for wrap in [true, false] {
+                for i in 0..positions.len() {
+                    assert_eq!(
+                        from_grid(&grid, &positions, i, radius, wrap, width, height),
+                        brute_force(&positions, i, radius, wrap, width, height),
+                        "boid {i} in {width}x{height}, radius {radius}, wrap {wrap}",
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn oversized_loaded_state_is_clamped_to_the_sliders() {
+        let mut boids = Boids::new();
+        boids.load_state(&serde_json::json!({
+            "boid_count": 1_000_000_000u64,
+            "predator_count": 1_000_000u64,
+            "separation_radius": 1e30,
+            "cohesion_strength": -1e30,
+            "max_speed": 0.0,
+        }));
+        assert_eq!(boids.boid_count, 2000);
+        assert_eq!(boids.predator_count, 10);
+        assert_eq!(boids.separation_radius, 100.0);
+        assert_eq!(boids.cohesion_strength, 0.0);
+        assert_eq!(boids.max_speed, 1.0);
+
+        // The respawn on the next update builds the clamped flock
+        boids.update(0.016, 800, 600);
+        assert_eq!(boids.boids.len(), 2000);
     }
 }
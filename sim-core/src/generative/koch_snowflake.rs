@@ -1,4 +1,6 @@
-use crate::{Color, ColorScheme, Simulation2D};
+use crate::color_ui::color_scheme_combo;
+use crate::nudge::Nudge;
+use crate::{Color, ColorScheme, RenderCtx, Simulation2D};
 
 #[derive(Clone, Copy, PartialEq)]
 pub enum DrawMode {
@@ -104,14 +106,8 @@ impl KochSnowflake {
         points
     }
 
-    fn apply_color_adjustments(&self, mut color: Color) -> Color {
-        // Apply hue shift, saturation, brightness
-        let (h, s, v) = self.rgb_to_hsv(color);
-        let new_h = (h + self.hue_shift * 360.0) % 360.0;
-        let new_s = (s * self.saturation).clamp(0.0, 1.0);
-        let new_v = (v * self.brightness).clamp(0.0, 1.0);
-
-        color = Color::from_hsv(new_h, new_s, new_v);
+    fn apply_color_adjustments(&self, color: Color) -> Color {
+        let mut color = color.adjust_hsv(self.hue_shift, self.saturation, self.brightness);
 
         if self.invert_colors {
             color = Color::from_rgb(255 - color.r, 255 - color.g, 255 - color.b);
@@ -119,31 +115,6 @@ impl KochSnowflake {
 
         color
     }
-
-    fn rgb_to_hsv(&self, color: Color) -> (f32, f32, f32) {
-        let r = color.r as f32 / 255.0;
-        let g = color.g as f32 / 255.0;
-        let b = color.b as f32 / 255.0;
-
-        let max = r.max(g).max(b);
-        let min = r.min(g).min(b);
-        let delta = max - min;
-
-        let h = if delta == 0.0 {
-            0.0
-        } else if max == r {
-            60.0 * (((g - b) / delta) % 6.0)
-        } else if max == g {
-            60.0 * (((b - r) / delta) + 2.0)
-        } else {
-            60.0 * (((r - g) / delta) + 4.0)
-        };
-
-        let s = if max == 0.0 { 0.0 } else { delta / max };
-        let v = max;
-
-        (h, s, v)
-    }
 }
 
 impl Simulation2D for KochSnowflake {
@@ -152,6 +123,11 @@ impl Simulation2D for KochSnowflake {
     }
 
     fn compute(&self, width: usize, height: usize) -> Vec<Color> {
+        self.compute_ctx(&RenderCtx::new(width, height))
+    }
+
+    fn compute_ctx(&self, ctx: &RenderCtx) -> Vec<Color> {
+        let (width, height) = (ctx.width, ctx.height);
         let mut pixels = vec![Color::BLACK; width * height];
 
         let size = (width.min(height) as f32 * self.scale).max(50.0);
@@ -194,7 +170,7 @@ impl Simulation2D for KochSnowflake {
                     i as f32 / 3.0
                 };
 
-                let mut color = self.color_scheme.map(color_t, true);
+                let mut color = self.color_scheme.map(ctx.cycle_palette(color_t), true);
                 color = self.apply_color_adjustments(color);
 
                 // Draw line using Bresenham's algorithm
@@ -202,7 +178,7 @@ impl Simulation2D for KochSnowflake {
             }
         }
 
-        pixels
+        ctx.over_background(pixels)
     }
 
     fn ui_parameters(&mut self, ui: &mut egui::Ui) -> bool {
@@ -214,41 +190,40 @@ impl Simulation2D for KochSnowflake {
             .default_open(true)
             .show(ui, |ui| {
                 changed |= ui.add(egui::Slider::new(&mut self.iterations, 0..=7)
+                    .nudge(ui, 1.0)
                     .text("Iterations")).changed();
 
                 changed |= ui.add(egui::Slider::new(&mut self.scale, 0.1..=1.0)
+                    .nudge(ui, 0.005)
                     .text("Scale")).changed();
 
                 changed |= ui.add(egui::Slider::new(&mut self.rotation, 0.0..=360.0)
+                    .nudge(ui, 2.0)
                     .text("Rotation (°)")).changed();
             });
 
         egui::CollapsingHeader::new("🎨 Color & Style")
             .default_open(true)
             .show(ui, |ui| {
-                egui::ComboBox::from_label("Color Scheme")
-                    .selected_text(self.color_scheme.name())
-                    .show_ui(ui, |ui| {
-                        for scheme in ColorScheme::all() {
-                            if ui.selectable_value(&mut self.color_scheme, scheme, scheme.name()).clicked() {
-                                changed = true;
-                            }
-                        }
-                    });
+                changed |= color_scheme_combo(ui, &mut self.color_scheme);
 
                 changed |= ui.checkbox(&mut self.color_by_depth, "Color by Depth").changed();
                 changed |= ui.checkbox(&mut self.invert_colors, "Invert Colors").changed();
 
                 changed |= ui.add(egui::Slider::new(&mut self.hue_shift, 0.0..=1.0)
+                    .nudge(ui, 0.01)
                     .text("Hue Shift")).changed();
 
                 changed |= ui.add(egui::Slider::new(&mut self.saturation, 0.0..=2.0)
+                    .nudge(ui, 0.02)
                     .text("Saturation")).changed();
 
                 changed |= ui.add(egui::Slider::new(&mut self.brightness, 0.0..=2.0)
+                    .nudge(ui, 0.02)
                     .text("Brightness")).changed();
 
                 changed |= ui.add(egui::Slider::new(&mut self.alpha, 0.0..=1.0)
+                    .nudge(ui, 0.01)
                     .text("Opacity")).changed();
             });
 
@@ -266,6 +241,7 @@ impl Simulation2D for KochSnowflake {
                     });
 
                 changed |= ui.add(egui::Slider::new(&mut self.line_width, 0.5..=5.0)
+                    .nudge(ui, 0.02)
                     .text("Line Width")).changed();
 
                 changed |= ui.checkbox(&mut self.show_construction, "Show Construction").changed();
@@ -280,6 +256,7 @@ impl Simulation2D for KochSnowflake {
 
                 if self.animate {
                     ui.add(egui::Slider::new(&mut self.animation_speed, 0.1..=5.0)
+                        .nudge(ui, 0.02)
                         .text("Speed"));
                 }
             });
@@ -287,9 +264,11 @@ impl Simulation2D for KochSnowflake {
         egui::CollapsingHeader::new("📍 Position")
             .show(ui, |ui| {
                 changed |= ui.add(egui::Slider::new(&mut self.center_x, 0.0..=1.0)
+                    .nudge(ui, 0.01)
                     .text("Center X")).changed();
 
                 changed |= ui.add(egui::Slider::new(&mut self.center_y, 0.0..=1.0)
+                    .nudge(ui, 0.01)
                     .text("Center Y")).changed();
 
                 if ui.button("Reset Position").clicked() {
@@ -319,14 +298,14 @@ impl Simulation2D for KochSnowflake {
                 }
             });
 
-        // Handle animation
+        changed
+    }
+
+    fn update(&mut self, dt: f32, _width: usize, _height: usize) -> bool {
         if self.animate {
-            let dt = ui.input(|i| i.stable_dt);
             self.animation_time += dt;
-            changed = true;
         }
-
-        changed
+        self.animate
     }
 }
 
@@ -4,9 +4,11 @@ pub mod phyllotaxis;
 pub mod perlin_flow;
 pub mod boids;
 pub mod strange_attractors;
+pub mod symmetric_icons;
 
 pub use koch_snowflake::KochSnowflake;
 pub use phyllotaxis::Phyllotaxis;
 pub use perlin_flow::PerlinFlow;
 pub use boids::Boids;
 pub use strange_attractors::{DeJongAttractor, CliffordAttractor};
+pub use symmetric_icons::SymmetricIcons;
@@ -1,4 +1,7 @@
-use crate::{Color, ColorScheme, Simulation2D};
+use crate::color_ui::color_scheme_combo;
+use crate::nudge::Nudge;
+use crate::readout;
+use crate::{Color, ColorScheme, RenderCtx, Simulation2D};
 use noise::{NoiseFn, Perlin};
 use rand::Rng;
 
@@ -31,7 +34,13 @@ pub struct PerlinFlow {
     pub show_trails: bool,
     pub trail_length: usize,
     pub spawn_mode: SpawnMode,
+    pub emission: Emission,
+    /// Particles released by each burst in `Emission::Burst`
+    pub burst_count: usize,
     pub wrap_edges: bool,
+    /// Sample the noise on a torus, so the flow field repeats across the
+    /// edges and the image tiles
+    pub seamless_noise: bool,
     pub hue_shift: f32,
     pub saturation: f32,
     pub brightness: f32,
@@ -49,7 +58,10 @@ pub struct PerlinFlow {
     trail_history: Vec<Vec<(f32, f32)>>,
     last_width: usize,
     last_height: usize,
+    /// Respawn every particle on the next update
     needs_init: bool,
+    /// Release a burst on the next update
+    emit_requested: bool,
 }
 
 #[derive(Clone, Copy, PartialEq)]
@@ -78,6 +90,61 @@ impl SpawnMode {
             SpawnMode::Grid => "Grid",
         }
     }
+
+    /// Where the `i`th of `count` new particles starts
+    fn position(&self, rng: &mut impl Rng, width: usize, height: usize, i: usize, count: usize) -> (f32, f32) {
+        match self {
+            SpawnMode::Random => {
+                (rng.gen_range(0.0..width as f32), rng.gen_range(0.0..height as f32))
+            }
+            SpawnMode::Center => {
+                let cx = width as f32 / 2.0;
+                let cy = height as f32 / 2.0;
+                let radius = rng.gen_range(0.0..50.0);
+                let angle = rng.gen_range(0.0..std::f32::consts::TAU);
+                (cx + radius * angle.cos(), cy + radius * angle.sin())
+            }
+            SpawnMode::Edges => {
+                if rng.gen_bool(0.5) {
+                    (rng.gen_range(0.0..width as f32), if rng.gen_bool(0.5) { 0.0 } else { height as f32 })
+                } else {
+                    (if rng.gen_bool(0.5) { 0.0 } else { width as f32 }, rng.gen_range(0.0..height as f32))
+                }
+            }
+            SpawnMode::Grid => {
+                let cols = (count as f32).sqrt().max(1.0) as usize;
+                let rows = count.div_ceil(cols);
+                let col = i % cols;
+                let row = i / cols;
+                (
+                    (col as f32 + 0.5) * width as f32 / cols as f32,
+                    (row as f32 + 0.5) * height as f32 / rows as f32
+                )
+            }
+        }
+    }
+}
+
+/// How particles are released
+#[derive(Clone, Copy, PartialEq)]
+pub enum Emission {
+    /// Keep `particle_count` alive, respawning each one as it dies
+    Continuous,
+    /// Release `burst_count` at once and let them die out
+    Burst,
+}
+
+impl Emission {
+    pub fn all() -> Vec<Emission> {
+        vec![Emission::Continuous, Emission::Burst]
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            Emission::Continuous => "Continuous",
+            Emission::Burst => "Burst",
+        }
+    }
 }
 
 impl Default for PerlinFlow {
@@ -100,7 +167,10 @@ impl Default for PerlinFlow {
             show_trails: false,
             trail_length: 10,
             spawn_mode: SpawnMode::Random,
+            emission: Emission::Continuous,
+            burst_count: 500,
             wrap_edges: true,
+            seamless_noise: false,
             hue_shift: 0.0,
             saturation: 1.0,
             brightness: 1.0,
@@ -119,6 +189,7 @@ impl Default for PerlinFlow {
             last_width: 800,
             last_height: 600,
             needs_init: true,
+            emit_requested: false,
         }
     }
 }
@@ -134,42 +205,22 @@ impl PerlinFlow {
         self.last_width = width;
         self.last_height = height;
         self.needs_init = false;
-        let mut rng = rand::thread_rng();
         self.particles.clear();
         self.trail_history.clear();
 
-        for _ in 0..self.particle_count {
-            let (x, y) = match self.spawn_mode {
-                SpawnMode::Random => {
-                    (rng.gen_range(0.0..width as f32), rng.gen_range(0.0..height as f32))
-                }
-                SpawnMode::Center => {
-                    let cx = width as f32 / 2.0;
-                    let cy = height as f32 / 2.0;
-                    let radius = rng.gen_range(0.0..50.0);
-                    let angle = rng.gen_range(0.0..std::f32::consts::TAU);
-                    (cx + radius * angle.cos(), cy + radius * angle.sin())
-                }
-                SpawnMode::Edges => {
-                    if rng.gen_bool(0.5) {
-                        (rng.gen_range(0.0..width as f32), if rng.gen_bool(0.5) { 0.0 } else { height as f32 })
-                    } else {
-                        (if rng.gen_bool(0.5) { 0.0 } else { width as f32 }, rng.gen_range(0.0..height as f32))
-                    }
-                }
-                SpawnMode::Grid => {
-                    let cols = (self.particle_count as f32).sqrt() as usize;
-                    let rows = (self.particle_count + cols - 1) / cols;
-                    let i = self.particles.len();
-                    let col = i % cols;
-                    let row = i / cols;
-                    (
-                        (col as f32 + 0.5) * width as f32 / cols as f32,
-                        (row as f32 + 0.5) * height as f32 / rows as f32
-                    )
-                }
-            };
+        let count = match self.emission {
+            Emission::Continuous => self.particle_count,
+            Emission::Burst => self.burst_count,
+        };
+        self.spawn(count, width, height);
+    }
 
+    /// Add `count` new particles alongside any still alive
+    fn spawn(&mut self, count: usize, width: usize, height: usize) {
+        let mut rng = rand::thread_rng();
+
+        for i in 0..count {
+            let (x, y) = self.spawn_mode.position(&mut rng, width, height, i, count);
             let lifetime = rng.gen_range(self.particle_lifetime_min..self.particle_lifetime_max);
             let size = rng.gen_range(self.particle_size_min..self.particle_size_max);
 
@@ -190,6 +241,27 @@ impl PerlinFlow {
         }
     }
 
+    /// Flow noise at `(x, y)`. Seamless noise walks a circle through 4D noise
+    /// along each axis, one lap per view width or height, with the same
+    /// feature size as the plain field; time slides the first circle's
+    /// center, which keeps both laps closed.
+    fn flow_noise(&self, x: f32, y: f32, time: f32, width: usize, height: usize) -> f64 {
+        let scale = self.noise_scale as f64;
+        if !self.seamless_noise {
+            return self.noise.get([x as f64 * scale, y as f64 * scale, time as f64]);
+        }
+
+        let tau = std::f64::consts::TAU;
+        let (u, v) = (x as f64 / width as f64 * tau, y as f64 / height as f64 * tau);
+        let (ru, rv) = (width as f64 * scale / tau, height as f64 * scale / tau);
+        self.noise.get([
+            ru * u.cos() + time as f64,
+            ru * u.sin(),
+            rv * v.cos(),
+            rv * v.sin(),
+        ])
+    }
+
     fn update_particles(&mut self, width: usize, height: usize, dt: f32) {
         let mut rng = rand::thread_rng();
         let time_offset = if self.animate {
@@ -199,18 +271,17 @@ impl PerlinFlow {
         };
 
         // Get parameters to avoid borrow issues
-        let color_scheme = self.color_scheme;
+        let color_scheme = self.color_scheme.clone();
         let hue_shift = self.hue_shift;
         let saturation = self.saturation;
         let brightness = self.brightness;
+        let count = self.particles.len();
 
-        for (i, particle) in self.particles.iter_mut().enumerate() {
+        // Taken out for the loop, which samples the noise through `self`
+        let mut particles = std::mem::take(&mut self.particles);
+        for (i, particle) in particles.iter_mut().enumerate() {
             // Get flow direction from noise
-            let noise_val = self.noise.get([
-                particle.x as f64 * self.noise_scale as f64,
-                particle.y as f64 * self.noise_scale as f64,
-                time_offset as f64,
-            ]) as f32;
+            let noise_val = self.flow_noise(particle.x, particle.y, time_offset, width, height) as f32;
 
             let angle = noise_val * std::f32::consts::TAU;
 
@@ -250,27 +321,11 @@ impl PerlinFlow {
             // Update lifetime
             particle.lifetime -= dt;
 
-            // Respawn if dead
-            if particle.lifetime <= 0.0 {
+            // Respawn if dead; a burst's particles are removed below instead
+            if particle.lifetime <= 0.0 && self.emission == Emission::Continuous {
                 let (x, y) = match self.spawn_mode {
-                    SpawnMode::Random => {
-                        (rng.gen_range(0.0..width as f32), rng.gen_range(0.0..height as f32))
-                    }
-                    SpawnMode::Center => {
-                        let cx = width as f32 / 2.0;
-                        let cy = height as f32 / 2.0;
-                        let radius = rng.gen_range(0.0..50.0);
-                        let angle = rng.gen_range(0.0..std::f32::consts::TAU);
-                        (cx + radius * angle.cos(), cy + radius * angle.sin())
-                    }
-                    SpawnMode::Edges => {
-                        if rng.gen_bool(0.5) {
-                            (rng.gen_range(0.0..width as f32), if rng.gen_bool(0.5) { 0.0 } else { height as f32 })
-                        } else {
-                            (if rng.gen_bool(0.5) { 0.0 } else { width as f32 }, rng.gen_range(0.0..height as f32))
-                        }
-                    }
                     SpawnMode::Grid => (particle.x, particle.y),
+                    mode => mode.position(&mut rng, width, height, i, count),
                 };
 
                 particle.x = x;
@@ -295,84 +350,60 @@ impl PerlinFlow {
             } else if self.color_by_lifetime {
                 particle.lifetime / particle.max_lifetime
             } else {
-                i as f32 / self.particle_count as f32
+                i as f32 / count as f32
             };
 
-            let mut color = color_scheme.map(t, true);
-
-            // Apply color adjustments inline
-            let (h, s, v) = Color::rgb_to_hsv_static(color);
-            let new_h = (h + hue_shift * 360.0) % 360.0;
-            let new_s = (s * saturation).clamp(0.0, 1.0);
-            let new_v = (v * brightness).clamp(0.0, 1.0);
-            color = Color::from_hsv(new_h, new_s, new_v);
+            let color = color_scheme.map(t, true).adjust_hsv(hue_shift, saturation, brightness);
 
             particle.color = color;
         }
-    }
+        self.particles = particles;
 
-    #[allow(dead_code)]
-    fn apply_color_adjustments(&self, color: Color) -> Color {
-        let (h, s, v) = self.rgb_to_hsv(color);
-        let new_h = (h + self.hue_shift * 360.0) % 360.0;
-        let new_s = (s * self.saturation).clamp(0.0, 1.0);
-        let new_v = (v * self.brightness).clamp(0.0, 1.0);
-
-        Color::from_hsv(new_h, new_s, new_v)
+        if self.emission == Emission::Burst {
+            self.remove_dead();
+        }
     }
 
-    #[allow(dead_code)]
-    fn rgb_to_hsv(&self, color: Color) -> (f32, f32, f32) {
-        let r = color.r as f32 / 255.0;
-        let g = color.g as f32 / 255.0;
-        let b = color.b as f32 / 255.0;
-
-        let max = r.max(g).max(b);
-        let min = r.min(g).min(b);
-        let delta = max - min;
-
-        let h = if delta == 0.0 {
-            0.0
-        } else if max == r {
-            60.0 * (((g - b) / delta) % 6.0)
-        } else if max == g {
-            60.0 * (((b - r) / delta) + 2.0)
-        } else {
-            60.0 * (((r - g) / delta) + 4.0)
-        };
-
-        let s = if max == 0.0 { 0.0 } else { delta / max };
-        let v = max;
-
-        (h, s, v)
+    /// Drop particles whose lifetime has run out, along with their trails
+    fn remove_dead(&mut self) {
+        if self.trail_history.len() == self.particles.len() {
+            let mut alive = self.particles.iter().map(|p| p.lifetime > 0.0);
+            self.trail_history.retain(|_| alive.next().unwrap_or(false));
+        }
+        self.particles.retain(|p| p.lifetime > 0.0);
     }
 
-    fn draw_circle(&self, pixels: &mut [Color], width: usize, height: usize,
+    /// Draw into a float canvas, 255 per channel per unit of white, so glow
+    /// can add past white until the tone curve brings it back
+    fn draw_circle(&self, canvas: &mut [[f32; 3]], width: usize, height: usize,
                    cx: f32, cy: f32, radius: f32, color: Color) {
         let r_sq = radius * radius;
-        let min_x = (cx - radius).max(0.0) as usize;
-        let max_x = (cx + radius).min(width as f32 - 1.0) as usize;
-        let min_y = (cy - radius).max(0.0) as usize;
-        let max_y = (cy + radius).min(height as f32 - 1.0) as usize;
-
-        for y in min_y..=max_y {
-            for x in min_x..=max_x {
-                let dx = x as f32 - cx;
-                let dy = y as f32 - cy;
+        let (w, h) = (width as isize, height as isize);
+
+        // With wrapping edges a particle on an edge shows on the far side too
+        for iy in (cy - radius).ceil() as isize..=(cy + radius).floor() as isize {
+            for ix in (cx - radius).ceil() as isize..=(cx + radius).floor() as isize {
+                let (x, y) = if self.wrap_edges {
+                    (ix.rem_euclid(w), iy.rem_euclid(h))
+                } else if (0..w).contains(&ix) && (0..h).contains(&iy) {
+                    (ix, iy)
+                } else {
+                    continue;
+                };
+                let dx = ix as f32 - cx;
+                let dy = iy as f32 - cy;
                 let dist_sq = dx * dx + dy * dy;
 
                 if dist_sq <= r_sq {
-                    let idx = y * width + x;
+                    let pixel = &mut canvas[y as usize * width + x as usize];
+                    let color = [color.r as f32, color.g as f32, color.b as f32];
                     if self.particle_glow {
                         // Additive blending for glow effect
-                        let old = pixels[idx];
-                        pixels[idx] = Color::from_rgb(
-                            (old.r as u16 + color.r as u16).min(255) as u8,
-                            (old.g as u16 + color.g as u16).min(255) as u8,
-                            (old.b as u16 + color.b as u16).min(255) as u8,
-                        );
+                        for (channel, value) in pixel.iter_mut().zip(color) {
+                            *channel += value;
+                        }
                     } else {
-                        pixels[idx] = color;
+                        *pixel = color;
                     }
                 }
             }
@@ -386,17 +417,18 @@ impl Simulation2D for PerlinFlow {
     }
 
     fn compute(&self, width: usize, height: usize) -> Vec<Color> {
-        let mut pixels = vec![Color::BLACK; width * height];
+        self.compute_ctx(&RenderCtx::new(width, height))
+    }
+
+    fn compute_ctx(&self, ctx: &RenderCtx) -> Vec<Color> {
+        let (width, height) = (ctx.width, ctx.height);
+        let mut pixels = vec![[0.0f32; 3]; width * height];
 
         // Apply background fade for trail effect
         if self.show_trails && self.background_alpha < 1.0 {
-            for pixel in pixels.iter_mut() {
-                let fade = (255.0 * self.background_alpha) as u8;
-                *pixel = Color::from_rgb(
-                    ((pixel.r as u16 * fade as u16) / 255) as u8,
-                    ((pixel.g as u16 * fade as u16) / 255) as u8,
-                    ((pixel.b as u16 * fade as u16) / 255) as u8,
-                );
+            let fade = (255.0 * self.background_alpha) as u8 as f32;
+            for channel in pixels.iter_mut().flatten() {
+                *channel = (*channel * fade / 255.0).floor();
             }
         }
 
@@ -442,41 +474,78 @@ impl Simulation2D for PerlinFlow {
             self.draw_circle(&mut pixels, width, height, particle.x, particle.y, particle.size, color);
         }
 
-        pixels
+        // Opaque drawing only ever holds whole bytes; glow goes through the
+        // viewer's exposure and tone curve
+        if self.particle_glow {
+            pixels.into_iter().map(|pixel| ctx.glow(pixel)).collect()
+        } else {
+            pixels.into_iter().map(|[r, g, b]| Color::from_rgb(r as u8, g as u8, b as u8)).collect()
+        }
+    }
+
+    fn reset(&mut self) {
+        self.needs_init = true;
     }
 
     fn ui_parameters(&mut self, ui: &mut egui::Ui) -> bool {
         let mut changed = false;
 
-        // Check if we need to reinitialize with actual canvas size
-        // This happens on the first frame when we get the real dimensions
-        if self.needs_init {
-            // Will be initialized on first compute() with actual canvas size
-        }
-
         ui.heading("Perlin Flow Field");
 
         egui::CollapsingHeader::new("⚙ Particle Settings")
             .default_open(true)
             .show(ui, |ui| {
-                if ui.add(egui::Slider::new(&mut self.particle_count, 10..=5000)
-                    .text("Particle Count")).changed() {
-                    changed = true;
+                egui::ComboBox::from_label("Emission")
+                    .selected_text(self.emission.name())
+                    .show_ui(ui, |ui| {
+                        for emission in Emission::all() {
+                            if ui.selectable_value(&mut self.emission, emission, emission.name()).clicked() {
+                                self.needs_init = true;
+                                changed = true;
+                            }
+                        }
+                    });
+
+                match self.emission {
+                    Emission::Continuous => {
+                        if ui.add(egui::Slider::new(&mut self.particle_count, 10..=5000)
+                            .nudge(ui, 1.0)
+                            .text("Particle Count")).changed() {
+                            changed = true;
+                        }
+                    }
+                    Emission::Burst => {
+                        changed |= ui.add(egui::Slider::new(&mut self.burst_count, 10..=5000)
+                            .nudge(ui, 1.0)
+                            .text("Burst Count")).changed();
+                        ui.horizontal(|ui| {
+                            if ui.button("🎆 Re-emit").on_hover_text("Release another burst alongside any particles still alive").clicked() {
+                                self.emit_requested = true;
+                                changed = true;
+                            }
+                            ui.label(format!("Alive: {}", readout::count(self.particles.len())));
+                        });
+                    }
                 }
 
                 changed |= ui.add(egui::Slider::new(&mut self.particle_speed, 0.1..=5.0)
+                    .nudge(ui, 0.02)
                     .text("Speed")).changed();
 
                 changed |= ui.add(egui::Slider::new(&mut self.particle_size_min, 0.5..=10.0)
+                    .nudge(ui, 0.05)
                     .text("Min Size")).changed();
 
                 changed |= ui.add(egui::Slider::new(&mut self.particle_size_max, 0.5..=20.0)
+                    .nudge(ui, 0.1)
                     .text("Max Size")).changed();
 
                 changed |= ui.add(egui::Slider::new(&mut self.particle_lifetime_min, 0.5..=10.0)
+                    .nudge(ui, 0.05)
                     .text("Min Lifetime")).changed();
 
                 changed |= ui.add(egui::Slider::new(&mut self.particle_lifetime_max, 0.5..=20.0)
+                    .nudge(ui, 0.1)
                     .text("Max Lifetime")).changed();
 
                 egui::ComboBox::from_label("Spawn Mode")
@@ -494,35 +563,35 @@ impl Simulation2D for PerlinFlow {
             .default_open(true)
             .show(ui, |ui| {
                 changed |= ui.add(egui::Slider::new(&mut self.noise_scale, 0.0001..=0.01)
+                    .nudge(ui, 0.00005)
                     .text("Noise Scale")).changed();
 
                 changed |= ui.add(egui::Slider::new(&mut self.flow_strength, 0.1..=10.0)
+                    .nudge(ui, 0.05)
                     .text("Flow Strength")).changed();
 
                 changed |= ui.add(egui::Slider::new(&mut self.velocity_damping, 0.8..=0.99)
+                    .nudge(ui, 0.001)
                     .text("Damping")).changed();
 
                 changed |= ui.add(egui::Slider::new(&mut self.noise_octaves, 1..=4)
+                    .nudge(ui, 1.0)
                     .text("Noise Octaves")).changed();
 
                 changed |= ui.add(egui::Slider::new(&mut self.noise_persistence, 0.1..=1.0)
+                    .nudge(ui, 0.005)
                     .text("Persistence")).changed();
 
                 changed |= ui.checkbox(&mut self.wrap_edges, "Wrap Edges").changed();
+                changed |= ui.checkbox(&mut self.seamless_noise, "Seamless Noise")
+                    .on_hover_text("Make the flow field repeat across the edges so, with Wrap Edges, the image tiles. Check it with the viewer's Tiling Preview.")
+                    .changed();
             });
 
         egui::CollapsingHeader::new("🎨 Color Settings")
             .default_open(true)
             .show(ui, |ui| {
-                egui::ComboBox::from_label("Color Scheme")
-                    .selected_text(self.color_scheme.name())
-                    .show_ui(ui, |ui| {
-                        for scheme in ColorScheme::all() {
-                            if ui.selectable_value(&mut self.color_scheme, scheme, scheme.name()).clicked() {
-                                changed = true;
-                            }
-                        }
-                    });
+                changed |= color_scheme_combo(ui, &mut self.color_scheme);
 
                 changed |= ui.checkbox(&mut self.color_by_velocity, "Color by Velocity").changed();
                 changed |= ui.checkbox(&mut self.color_by_direction, "Color by Direction").changed();
@@ -530,12 +599,15 @@ impl Simulation2D for PerlinFlow {
                 changed |= ui.checkbox(&mut self.fade_by_lifetime, "Fade by Lifetime").changed();
 
                 changed |= ui.add(egui::Slider::new(&mut self.hue_shift, 0.0..=1.0)
+                    .nudge(ui, 0.01)
                     .text("Hue Shift")).changed();
 
                 changed |= ui.add(egui::Slider::new(&mut self.saturation, 0.0..=2.0)
+                    .nudge(ui, 0.02)
                     .text("Saturation")).changed();
 
                 changed |= ui.add(egui::Slider::new(&mut self.brightness, 0.0..=2.0)
+                    .nudge(ui, 0.02)
                     .text("Brightness")).changed();
             });
 
@@ -546,9 +618,11 @@ impl Simulation2D for PerlinFlow {
 
                 if self.show_trails {
                     changed |= ui.add(egui::Slider::new(&mut self.trail_length, 2..=50)
+                        .nudge(ui, 1.0)
                         .text("Trail Length")).changed();
 
                     changed |= ui.add(egui::Slider::new(&mut self.background_alpha, 0.0..=1.0)
+                        .nudge(ui, 0.01)
                         .text("Background Fade")).changed();
                 }
 
@@ -562,9 +636,11 @@ impl Simulation2D for PerlinFlow {
 
                 if self.animate {
                     ui.add(egui::Slider::new(&mut self.animation_speed, 0.1..=5.0)
+                        .nudge(ui, 0.02)
                         .text("Speed"));
                 } else {
                     changed |= ui.add(egui::Slider::new(&mut self.noise_z_offset, 0.0..=100.0)
+                        .nudge(ui, 1.0)
                         .text("Noise Offset")).changed();
                 }
             });
@@ -592,6 +668,20 @@ impl Simulation2D for PerlinFlow {
                     self.particle_glow = true;
                     changed = true;
                 }
+                if ui.button("Fireworks").clicked() {
+                    *self = Self::default();
+                    self.emission = Emission::Burst;
+                    self.burst_count = 1500;
+                    self.spawn_mode = SpawnMode::Center;
+                    self.color_scheme = ColorScheme::Fire;
+                    self.color_by_direction = false;
+                    self.color_by_lifetime = true;
+                    self.flow_strength = 6.0;
+                    self.velocity_damping = 0.98;
+                    self.particle_glow = true;
+                    self.emit_requested = true;
+                    changed = true;
+                }
                 if ui.button("Cosmic Dust").clicked() {
                     self.color_scheme = ColorScheme::Galaxy;
                     self.particle_count = 5000;
@@ -602,24 +692,25 @@ impl Simulation2D for PerlinFlow {
                 }
             });
 
-        // Handle animation and particle updates
-        let dt = ui.input(|i| i.stable_dt);
+        changed
+    }
+
+    fn update(&mut self, dt: f32, width: usize, height: usize) -> bool {
+        let (width, height) = (width.max(800), height.max(600));
         if self.animate {
             self.animation_time += dt;
         }
 
-        // Reinitialize particles if count changed
-        let current_count = self.particles.len();
-        if current_count != self.particle_count {
-            let size = ui.available_size();
-            self.init_particles(size.x as usize, size.y as usize);
+        // Reinitialize particles if count changed. A burst is left to die
+        // out, so its count only changes on request.
+        let count_changed = self.emission == Emission::Continuous && self.particles.len() != self.particle_count;
+        if self.needs_init || count_changed {
+            self.init_particles(width, height);
+        } else if std::mem::take(&mut self.emit_requested) {
+            self.spawn(self.burst_count, width, height);
         }
 
-        // Update particles every frame
-        let size = ui.available_size();
-        self.update_particles(size.x.max(800.0) as usize, size.y.max(600.0) as usize, dt);
-        changed = true;
-
-        changed
+        self.update_particles(width, height, dt);
+        true
     }
 }
@@ -1,4 +1,6 @@
-use crate::{Color, ColorScheme, Simulation2D};
+use crate::color_ui::color_scheme_combo;
+use crate::nudge::Nudge;
+use crate::{Color, ColorScheme, RenderCtx, Simulation2D};
 
 pub struct Phyllotaxis {
     pub dot_count: usize,
@@ -61,40 +63,6 @@ impl Phyllotaxis {
         Self::default()
     }
 
-    fn apply_color_adjustments(&self, color: Color) -> Color {
-        let (h, s, v) = self.rgb_to_hsv(color);
-        let new_h = (h + self.hue_shift * 360.0) % 360.0;
-        let new_s = (s * self.saturation).clamp(0.0, 1.0);
-        let new_v = (v * self.brightness).clamp(0.0, 1.0);
-
-        Color::from_hsv(new_h, new_s, new_v)
-    }
-
-    fn rgb_to_hsv(&self, color: Color) -> (f32, f32, f32) {
-        let r = color.r as f32 / 255.0;
-        let g = color.g as f32 / 255.0;
-        let b = color.b as f32 / 255.0;
-
-        let max = r.max(g).max(b);
-        let min = r.min(g).min(b);
-        let delta = max - min;
-
-        let h = if delta == 0.0 {
-            0.0
-        } else if max == r {
-            60.0 * (((g - b) / delta) % 6.0)
-        } else if max == g {
-            60.0 * (((b - r) / delta) + 2.0)
-        } else {
-            60.0 * (((r - g) / delta) + 4.0)
-        };
-
-        let s = if max == 0.0 { 0.0 } else { delta / max };
-        let v = max;
-
-        (h, s, v)
-    }
-
     fn draw_circle(&self, pixels: &mut [Color], width: usize, height: usize,
                    cx: f32, cy: f32, radius: f32, color: Color) {
         let r_sq = radius * radius;
@@ -123,6 +91,11 @@ impl Simulation2D for Phyllotaxis {
     }
 
     fn compute(&self, width: usize, height: usize) -> Vec<Color> {
+        self.compute_ctx(&RenderCtx::new(width, height))
+    }
+
+    fn compute_ctx(&self, ctx: &RenderCtx) -> Vec<Color> {
+        let (width, height) = (ctx.width, ctx.height);
         let mut pixels = vec![Color::BLACK; width * height];
 
         let size = width.min(height) as f32;
@@ -160,8 +133,8 @@ impl Simulation2D for Phyllotaxis {
                 n as f32 / max_dots as f32
             };
 
-            let mut color = self.color_scheme.map(t, true);
-            color = self.apply_color_adjustments(color);
+            let mut color = self.color_scheme.map(ctx.cycle_palette(t), true);
+            color = color.adjust_hsv(self.hue_shift, self.saturation, self.brightness);
 
             if self.fade_by_age {
                 let age_factor = n as f32 / max_dots as f32;
@@ -184,7 +157,7 @@ impl Simulation2D for Phyllotaxis {
             self.draw_circle(&mut pixels, width, height, x, y, dot_size, color);
         }
 
-        pixels
+        ctx.over_background(pixels)
     }
 
     fn ui_parameters(&mut self, ui: &mut egui::Ui) -> bool {
@@ -196,9 +169,11 @@ impl Simulation2D for Phyllotaxis {
             .default_open(true)
             .show(ui, |ui| {
                 changed |= ui.add(egui::Slider::new(&mut self.dot_count, 10..=2000)
+                    .nudge(ui, 1.0)
                     .text("Dot Count")).changed();
 
                 changed |= ui.add(egui::Slider::new(&mut self.angle, 120.0..=150.0)
+                    .nudge(ui, 0.2)
                     .text("Angle (°)")).changed();
 
                 ui.label(format!("Golden Angle: 137.508°"));
@@ -208,36 +183,33 @@ impl Simulation2D for Phyllotaxis {
                 }
 
                 changed |= ui.add(egui::Slider::new(&mut self.c_value, 1.0..=10.0)
+                    .nudge(ui, 0.05)
                     .text("Spiral Tightness")).changed();
 
                 changed |= ui.add(egui::Slider::new(&mut self.scale, 0.1..=2.0)
+                    .nudge(ui, 0.01)
                     .text("Scale")).changed();
             });
 
         egui::CollapsingHeader::new("🎨 Color Settings")
             .default_open(true)
             .show(ui, |ui| {
-                egui::ComboBox::from_label("Color Scheme")
-                    .selected_text(self.color_scheme.name())
-                    .show_ui(ui, |ui| {
-                        for scheme in ColorScheme::all() {
-                            if ui.selectable_value(&mut self.color_scheme, scheme, scheme.name()).clicked() {
-                                changed = true;
-                            }
-                        }
-                    });
+                changed |= color_scheme_combo(ui, &mut self.color_scheme);
 
                 changed |= ui.checkbox(&mut self.color_by_distance, "Color by Distance").changed();
                 changed |= ui.checkbox(&mut self.color_by_angle, "Color by Angle").changed();
                 changed |= ui.checkbox(&mut self.fade_by_age, "Fade by Age").changed();
 
                 changed |= ui.add(egui::Slider::new(&mut self.hue_shift, 0.0..=1.0)
+                    .nudge(ui, 0.01)
                     .text("Hue Shift")).changed();
 
                 changed |= ui.add(egui::Slider::new(&mut self.saturation, 0.0..=2.0)
+                    .nudge(ui, 0.02)
                     .text("Saturation")).changed();
 
                 changed |= ui.add(egui::Slider::new(&mut self.brightness, 0.0..=2.0)
+                    .nudge(ui, 0.02)
                     .text("Brightness")).changed();
             });
 
@@ -245,9 +217,11 @@ impl Simulation2D for Phyllotaxis {
             .default_open(true)
             .show(ui, |ui| {
                 changed |= ui.add(egui::Slider::new(&mut self.dot_size_min, 0.5..=10.0)
+                    .nudge(ui, 0.05)
                     .text("Min Size")).changed();
 
                 changed |= ui.add(egui::Slider::new(&mut self.dot_size_max, 0.5..=20.0)
+                    .nudge(ui, 0.1)
                     .text("Max Size")).changed();
 
                 changed |= ui.checkbox(&mut self.size_by_age, "Size by Age").changed();
@@ -261,6 +235,7 @@ impl Simulation2D for Phyllotaxis {
 
                 if self.animate_rotation || self.animate_growth {
                     ui.add(egui::Slider::new(&mut self.animation_speed, 0.1..=5.0)
+                        .nudge(ui, 0.02)
                         .text("Speed"));
                 }
             });
@@ -268,13 +243,16 @@ impl Simulation2D for Phyllotaxis {
         egui::CollapsingHeader::new("📍 Position & Rotation")
             .show(ui, |ui| {
                 changed |= ui.add(egui::Slider::new(&mut self.center_x, 0.0..=1.0)
+                    .nudge(ui, 0.01)
                     .text("Center X")).changed();
 
                 changed |= ui.add(egui::Slider::new(&mut self.center_y, 0.0..=1.0)
+                    .nudge(ui, 0.01)
                     .text("Center Y")).changed();
 
                 if !self.animate_rotation {
                     changed |= ui.add(egui::Slider::new(&mut self.rotation, 0.0..=360.0)
+                        .nudge(ui, 2.0)
                         .text("Rotation")).changed();
                 }
 
@@ -312,13 +290,29 @@ impl Simulation2D for Phyllotaxis {
                 }
             });
 
-        // Handle animation
-        if self.animate_rotation || self.animate_growth {
-            let dt = ui.input(|i| i.stable_dt);
+        changed
+    }
+
+    fn update(&mut self, dt: f32, _width: usize, _height: usize) -> bool {
+        let animating = self.animate_rotation || self.animate_growth;
+        if animating {
             self.animation_time += dt;
-            changed = true;
         }
+        animating
+    }
 
-        changed
+    fn supports_auto_fit(&self) -> bool {
+        true
+    }
+
+    fn auto_fit(&mut self, width: usize, height: usize) {
+        // The pattern is a disc around the center whose outermost dot sits at
+        // c·sqrt(n-1)·scale, plus that dot's radius
+        let outer = self.c_value * ((self.dot_count.max(2) - 1) as f32).sqrt();
+        let available = width.min(height) as f32 * 0.5 * 0.95 - self.dot_size_max;
+
+        self.center_x = 0.5;
+        self.center_y = 0.5;
+        self.scale = (available / outer).clamp(0.1, 2.0);
     }
 }
@@ -1,4 +1,361 @@
-use crate::{Color, ColorScheme, Simulation2D};
+use crate::color_ui::color_scheme_combo;
+use crate::nudge::Nudge;
+use crate::readout;
+use crate::{Color, ColorScheme, PlaneView, RenderCtx, Simulation2D};
+use rand::Rng;
+
+/// Rotation (cos, sin) and mirror flag for each copy of a point under
+/// n-fold rotational symmetry, optionally with mirror (dihedral) symmetry.
+/// The identity comes first, so symmetry 1 without mirror is a no-op.
+fn symmetry_folds(symmetry: u32, mirror: bool) -> Vec<(f32, f32, bool)> {
+    let n = symmetry.max(1);
+    let mut folds = Vec::with_capacity(n as usize * 2);
+    for flip in [false, true] {
+        if flip && !mirror {
+            break;
+        }
+        for k in 0..n {
+            let angle = k as f32 * std::f32::consts::TAU / n as f32;
+            folds.push((angle.cos(), angle.sin(), flip));
+        }
+    }
+    folds
+}
+
+/// Apply a fold from `symmetry_folds` around the attractor origin
+fn apply_fold(x: f32, y: f32, (cos, sin, flip): (f32, f32, bool)) -> (f32, f32) {
+    let y = if flip { -y } else { y };
+    (x * cos - y * sin, x * sin + y * cos)
+}
+
+/// Iterations sampled by auto-fit; enough to trace the attractor's extent
+const AUTO_FIT_SAMPLES: usize = 20000;
+
+/// Zoom and normalized center that frame `bounds` ([min_x, min_y, max_x, max_y]
+/// in attractor space) with a small margin, clamped to the slider ranges
+fn fit_view(bounds: [f32; 4], width: usize, height: usize) -> (f32, f32, f32) {
+    let [min_x, min_y, max_x, max_y] = bounds;
+    let span_x = (max_x - min_x).max(1e-6);
+    let span_y = (max_y - min_y).max(1e-6);
+
+    let zoom = (width as f32 * 0.9 / span_x)
+        .min(height as f32 * 0.9 / span_y)
+        .clamp(50.0, 500.0);
+
+    let mid_x = (min_x + max_x) * 0.5;
+    let mid_y = (min_y + max_y) * 0.5;
+    let center_x = (0.5 - mid_x * zoom / width as f32).clamp(0.0, 1.0);
+    let center_y = (0.5 - mid_y * zoom / height as f32).clamp(0.0, 1.0);
+
+    (zoom, center_x, center_y)
+}
+
+/// Default for "Skip First N": enough for the orbit from the start point to
+/// settle onto the attractor
+const DEFAULT_SKIP: usize = 100;
+
+/// Map parameters (a, b, c, d)
+type MapParams = (f32, f32, f32, f32);
+
+/// One iteration of the De Jong map
+fn de_jong_step((a, b, c, d): MapParams, x: f32, y: f32) -> (f32, f32) {
+    // x' = sin(a*y) - cos(b*x), y' = sin(c*x) - cos(d*y)
+    ((a * y).sin() - (b * x).cos(), (c * x).sin() - (d * y).cos())
+}
+
+/// One iteration of the Clifford map
+fn clifford_step((a, b, c, d): MapParams, x: f32, y: f32) -> (f32, f32) {
+    // x' = sin(a*y) + c*cos(a*x), y' = sin(b*x) + d*cos(b*y)
+    ((a * y).sin() + c * (a * x).cos(), (b * x).sin() + d * (b * y).cos())
+}
+
+/// Run `skip` iterations of `step` from `(x, y)` without plotting, so the
+/// transient from the start point doesn't leave stray points off the attractor
+fn skip_transient(
+    step: fn(MapParams, f32, f32) -> (f32, f32),
+    params: MapParams,
+    (mut x, mut y): (f32, f32),
+    skip: usize,
+) -> (f32, f32) {
+    for _ in 0..skip {
+        (x, y) = step(params, x, y);
+    }
+    (x, y)
+}
+
+/// Canvas area points are plotted into, as (left, top, width, height): the
+/// whole canvas, or with `lock_aspect` the largest centered square, so the
+/// attractor keeps its proportions and framing whatever the window shape
+fn plot_region(width: usize, height: usize, lock_aspect: bool) -> (usize, usize, usize, usize) {
+    if lock_aspect {
+        let side = width.min(height);
+        ((width - side) / 2, (height - side) / 2, side, side)
+    } else {
+        (0, 0, width, height)
+    }
+}
+
+/// Plane mapping of `compute_ctx`, which plots point `(x, y)` at
+/// `region origin + region size * center + (x, y) * zoom`
+fn attractor_plane_view(width: usize, height: usize, lock_aspect: bool,
+                        center_x: f32, center_y: f32, zoom: f32) -> Option<PlaneView> {
+    if zoom <= 0.0 {
+        return None;
+    }
+    let (left, top, w, h) = plot_region(width, height, lock_aspect);
+    let origin_x = left as f64 + w as f64 * center_x as f64;
+    let origin_y = top as f64 + h as f64 * center_y as f64;
+    Some(PlaneView {
+        center_x: (width as f64 / 2.0 - origin_x) / zoom as f64,
+        center_y: (height as f64 / 2.0 - origin_y) / zoom as f64,
+        units_per_pixel: 1.0 / zoom as f64,
+        complex: false,
+    })
+}
+
+/// Clear everything outside `region` to `background`, including the parts of
+/// points that spilled over its edge
+fn letterbox(pixels: &mut [Color], width: usize, (left, top, w, h): (usize, usize, usize, usize), background: Color) {
+    for (y, row) in pixels.chunks_mut(width).enumerate() {
+        if y < top || y >= top + h {
+            row.fill(background);
+        } else {
+            row[..left].fill(background);
+            row[left + w..].fill(background);
+        }
+    }
+}
+
+/// Side of the square density grid a randomize candidate is scored on
+const TRIAL_GRID: usize = 64;
+
+/// Orbit points plotted into the trial grid
+const TRIAL_POINTS: usize = 20_000;
+
+/// How visually rich the orbit of `step` from `start` is, from 0 to 1. The
+/// orbit is plotted into a small density grid framed on its own bounds, so
+/// the score doesn't depend on the view. It is the geometric mean of the
+/// fraction of cells hit and the density's entropy relative to a uniform
+/// spread: fixed points and short cycles hit a handful of cells, thin
+/// curves hit many cells but few of them, and orbits that run off to
+/// infinity score 0.
+fn richness(step: fn(MapParams, f32, f32) -> (f32, f32), params: MapParams, start: (f32, f32), skip: usize) -> f32 {
+    let (mut x, mut y) = skip_transient(step, params, start, skip);
+    let mut orbit = Vec::with_capacity(TRIAL_POINTS);
+    let mut bounds = [f32::MAX, f32::MAX, f32::MIN, f32::MIN];
+    for _ in 0..TRIAL_POINTS {
+        (x, y) = step(params, x, y);
+        if !x.is_finite() || !y.is_finite() || x.abs() > 1e6 || y.abs() > 1e6 {
+            return 0.0;
+        }
+        include_point(&mut bounds, x, y);
+        orbit.push((x, y));
+    }
+
+    let span = (bounds[2] - bounds[0]).max(bounds[3] - bounds[1]);
+    if span < 1e-4 {
+        return 0.0;
+    }
+    let mut density = vec![0u32; TRIAL_GRID * TRIAL_GRID];
+    let cell = |v: f32, min: f32| (((v - min) / span * TRIAL_GRID as f32) as usize).min(TRIAL_GRID - 1);
+    for &(x, y) in &orbit {
+        density[cell(y, bounds[1]) * TRIAL_GRID + cell(x, bounds[0])] += 1;
+    }
+
+    let cells = density.len() as f32;
+    let hit = density.iter().filter(|&&n| n > 0).count() as f32;
+    let total = orbit.len() as f32;
+    let entropy: f32 = density.iter()
+        .filter(|&&n| n > 0)
+        .map(|&n| {
+            let p = n as f32 / total;
+            -p * p.ln()
+        })
+        .sum();
+    ((hit / cells) * (entropy / cells.ln())).sqrt()
+}
+
+/// Random parameter search for the De Jong and Clifford maps. Candidates are
+/// drawn from the parameter sliders' range and scored with `richness` on a
+/// trial pass, until one reaches the threshold.
+pub struct Randomizer {
+    /// Score a candidate must reach, 0 to 1
+    pub threshold: f32,
+    /// Candidates tried before settling for the best one seen
+    pub max_tries: usize,
+    /// Score and tries of the last search, and whether it reached the threshold
+    last: Option<(f32, usize, bool)>,
+}
+
+impl Default for Randomizer {
+    fn default() -> Self {
+        Self {
+            threshold: 0.45,
+            max_tries: 200,
+            last: None,
+        }
+    }
+}
+
+impl Randomizer {
+    /// The first candidate scoring at least the threshold, or the best of
+    /// `max_tries` if none does
+    fn search(&mut self, step: fn(MapParams, f32, f32) -> (f32, f32), start: (f32, f32), skip: usize) -> MapParams {
+        let mut rng = rand::thread_rng();
+        let mut best = (f32::MIN, (0.0, 0.0, 0.0, 0.0));
+        for tries in 1..=self.max_tries.max(1) {
+            let params = (
+                rng.gen_range(-3.0..=3.0),
+                rng.gen_range(-3.0..=3.0),
+                rng.gen_range(-3.0..=3.0),
+                rng.gen_range(-3.0..=3.0),
+            );
+            let score = richness(step, params, start, skip);
+            if score >= self.threshold {
+                self.last = Some((score, tries, true));
+                return params;
+            }
+            if score > best.0 {
+                best = (score, params);
+            }
+        }
+        self.last = Some((best.0, self.max_tries.max(1), false));
+        best.1
+    }
+
+    /// Button and search settings; returns true when the button is clicked
+    fn ui(&mut self, ui: &mut egui::Ui) -> bool {
+        let mut clicked = false;
+
+        egui::CollapsingHeader::new("🎲 Randomize")
+            .default_open(true)
+            .show(ui, |ui| {
+                clicked = ui.button("🎲 Randomize")
+                    .on_hover_text("Pick random parameters, drawing again until the attractor looks rich enough")
+                    .clicked();
+
+                ui.add(egui::Slider::new(&mut self.threshold, 0.0..=0.9)
+                    .nudge(ui, 0.01)
+                    .text("Min Score"))
+                    .on_hover_text("How much of its area the attractor must fill, and how evenly. Dots and thin curves score low; around 0.45 and up looks detailed.");
+                ui.add(egui::Slider::new(&mut self.max_tries, 1..=1000)
+                    .nudge(ui, 1.0)
+                    .logarithmic(true)
+                    .text("Max Tries"));
+
+                match self.last {
+                    Some((score, tries, true)) => {
+                        ui.label(format!("Score {} on try {}", readout::decimal(score, 2), readout::count(tries)));
+                    }
+                    Some((score, tries, false)) => {
+                        ui.label(format!("Nothing reached the threshold in {} tries; kept the best (score {})", readout::count(tries), readout::decimal(score, 2)));
+                    }
+                    None => {}
+                }
+            });
+
+        clicked
+    }
+}
+
+fn include_point(bounds: &mut [f32; 4], x: f32, y: f32) {
+    bounds[0] = bounds[0].min(x);
+    bounds[1] = bounds[1].min(y);
+    bounds[2] = bounds[2].max(x);
+    bounds[3] = bounds[3].max(y);
+}
+
+/// Anti-aliased point drawing. Each point is laid over the pixels it
+/// touches with opacity equal to how much of each pixel it covers, so a point
+/// smaller than a pixel still adds its share instead of landing on or
+/// missing a pixel center. Pixel centers are at integer coordinates, as in
+/// `draw_circle`, and later points go on top just as they do there.
+struct Splats {
+    canvas: Vec<[f32; 3]>,
+    width: usize,
+    height: usize,
+    /// Add light instead of covering, like overlapping glows
+    additive: bool,
+}
+
+impl Splats {
+    fn new(width: usize, height: usize, background: Color, additive: bool) -> Self {
+        Self {
+            canvas: vec![[background.r as f32, background.g as f32, background.b as f32]; width * height],
+            width,
+            height,
+            additive,
+        }
+    }
+
+    fn add_at(&mut self, x: isize, y: isize, coverage: f32, color: Color) {
+        if coverage <= 0.0 || x < 0 || y < 0 || x as usize >= self.width || y as usize >= self.height {
+            return;
+        }
+        let pixel = &mut self.canvas[y as usize * self.width + x as usize];
+        for (channel, value) in pixel.iter_mut().zip([color.r, color.g, color.b]) {
+            if self.additive {
+                *channel += value as f32 * coverage;
+            } else {
+                *channel += (value as f32 - *channel) * coverage.min(1.0);
+            }
+        }
+    }
+
+    /// A disk of `radius` at (`cx`, `cy`). Below a pixel across, its area is
+    /// shared bilinearly among the four nearest pixels; larger disks get a
+    /// one-pixel soft edge. Either way it adds about its area in coverage.
+    fn add(&mut self, cx: f32, cy: f32, radius: f32, color: Color) {
+        if radius < 1.0 {
+            let area = std::f32::consts::PI * radius * radius;
+            let (x0, y0) = (cx.floor(), cy.floor());
+            let (fx, fy) = (cx - x0, cy - y0);
+            let (x0, y0) = (x0 as isize, y0 as isize);
+            self.add_at(x0, y0, area * (1.0 - fx) * (1.0 - fy), color);
+            self.add_at(x0 + 1, y0, area * fx * (1.0 - fy), color);
+            self.add_at(x0, y0 + 1, area * (1.0 - fx) * fy, color);
+            self.add_at(x0 + 1, y0 + 1, area * fx * fy, color);
+            return;
+        }
+
+        let reach = radius + 0.5;
+        for y in (cy - reach).floor() as isize..=(cy + reach).ceil() as isize {
+            for x in (cx - reach).floor() as isize..=(cx + reach).ceil() as isize {
+                let distance = ((x as f32 - cx).powi(2) + (y as f32 - cy).powi(2)).sqrt();
+                self.add_at(x, y, (reach - distance).min(1.0), color);
+            }
+        }
+    }
+
+    /// A hard-edged disk, pixel centers inside `radius` fully covered, as
+    /// `draw_circle` draws it
+    fn fill(&mut self, cx: f32, cy: f32, radius: f32, color: Color) {
+        let r_sq = radius * radius;
+        let min_x = (cx - radius).max(0.0) as isize;
+        let max_x = (cx + radius).min(self.width as f32 - 1.0) as isize;
+        let min_y = (cy - radius).max(0.0) as isize;
+        let max_y = (cy + radius).min(self.height as f32 - 1.0) as isize;
+
+        for y in min_y..=max_y {
+            for x in min_x..=max_x {
+                if (x as f32 - cx).powi(2) + (y as f32 - cy).powi(2) <= r_sq {
+                    self.add_at(x, y, 1.0, color);
+                }
+            }
+        }
+    }
+
+    /// Additive light goes through the viewer's exposure and tone curve
+    fn into_pixels(self, ctx: &RenderCtx) -> Vec<Color> {
+        if self.additive {
+            return self.canvas.into_iter().map(|pixel| ctx.glow(pixel)).collect();
+        }
+        let byte = |v: f32| v.round().clamp(0.0, 255.0) as u8;
+        self.canvas.into_iter()
+            .map(|[r, g, b]| Color::from_rgb(byte(r), byte(g), byte(b)))
+            .collect()
+    }
+}
 
 // De Jong (Peter de Jong) Attractor
 pub struct DeJongAttractor {
@@ -8,6 +365,8 @@ pub struct DeJongAttractor {
     pub d: f32,
     pub point_count: usize,
     pub point_size: f32,
+    /// Splat points with sub-pixel coverage instead of hard circles
+    pub antialias: bool,
     pub zoom: f32,
     pub center_x: f32,
     pub center_y: f32,
@@ -25,7 +384,16 @@ pub struct DeJongAttractor {
     pub start_y: f32,
     pub point_glow: bool,
     pub background_fade: f32,
+    pub symmetry: u32,
+    pub mirror: bool,
+    /// Iterations discarded before plotting
+    pub skip_first: usize,
+    /// Plot into a centered square instead of the full canvas
+    pub lock_aspect: bool,
+    pub randomizer: Randomizer,
     animation_time: f32,
+    /// Frame the attractor on the next update, after randomizing
+    fit_requested: bool,
 }
 
 impl Default for DeJongAttractor {
@@ -37,6 +405,7 @@ impl Default for DeJongAttractor {
             d: 2.0,
             point_count: 10000,
             point_size: 1.0,
+            antialias: false,
             zoom: 200.0,
             center_x: 0.5,
             center_y: 0.5,
@@ -54,7 +423,13 @@ impl Default for DeJongAttractor {
             start_y: 0.0,
             point_glow: false,
             background_fade: 0.0,
+            symmetry: 1,
+            mirror: false,
+            skip_first: DEFAULT_SKIP,
+            lock_aspect: false,
+            randomizer: Randomizer::default(),
             animation_time: 0.0,
+            fit_requested: false,
         }
     }
 }
@@ -67,6 +442,8 @@ pub struct CliffordAttractor {
     pub d: f32,
     pub point_count: usize,
     pub point_size: f32,
+    /// Splat points with sub-pixel coverage instead of hard circles
+    pub antialias: bool,
     pub zoom: f32,
     pub center_x: f32,
     pub center_y: f32,
@@ -84,7 +461,16 @@ pub struct CliffordAttractor {
     pub start_y: f32,
     pub point_glow: bool,
     pub background_fade: f32,
+    pub symmetry: u32,
+    pub mirror: bool,
+    /// Iterations discarded before plotting
+    pub skip_first: usize,
+    /// Plot into a centered square instead of the full canvas
+    pub lock_aspect: bool,
+    pub randomizer: Randomizer,
     animation_time: f32,
+    /// Frame the attractor on the next update, after randomizing
+    fit_requested: bool,
 }
 
 impl Default for CliffordAttractor {
@@ -96,6 +482,7 @@ impl Default for CliffordAttractor {
             d: 0.7,
             point_count: 10000,
             point_size: 1.0,
+            antialias: false,
             zoom: 150.0,
             center_x: 0.5,
             center_y: 0.5,
@@ -113,7 +500,13 @@ impl Default for CliffordAttractor {
             start_y: 0.0,
             point_glow: false,
             background_fade: 0.0,
+            symmetry: 1,
+            mirror: false,
+            skip_first: DEFAULT_SKIP,
+            lock_aspect: false,
+            randomizer: Randomizer::default(),
             animation_time: 0.0,
+            fit_requested: false,
         }
     }
 }
@@ -123,38 +516,19 @@ impl DeJongAttractor {
         Self::default()
     }
 
-    fn apply_color_adjustments(&self, color: Color) -> Color {
-        let (h, s, v) = self.rgb_to_hsv(color);
-        let new_h = (h + self.hue_shift * 360.0) % 360.0;
-        let new_s = (s * self.saturation).clamp(0.0, 1.0);
-        let new_v = (v * self.brightness).clamp(0.0, 1.0);
-
-        Color::from_hsv(new_h, new_s, new_v)
-    }
-
-    fn rgb_to_hsv(&self, color: Color) -> (f32, f32, f32) {
-        let r = color.r as f32 / 255.0;
-        let g = color.g as f32 / 255.0;
-        let b = color.b as f32 / 255.0;
-
-        let max = r.max(g).max(b);
-        let min = r.min(g).min(b);
-        let delta = max - min;
-
-        let h = if delta == 0.0 {
-            0.0
-        } else if max == r {
-            60.0 * (((g - b) / delta) % 6.0)
-        } else if max == g {
-            60.0 * (((b - r) / delta) + 2.0)
+    /// Parameters for this frame, including the animation offsets
+    fn animated_params(&self) -> (f32, f32, f32, f32) {
+        if self.animate_params {
+            let t = self.animation_time * self.animation_speed;
+            (
+                self.a + (t * 0.5).sin() * 0.5,
+                self.b + (t * 0.7).cos() * 0.5,
+                self.c + (t * 0.3).sin() * 0.3,
+                self.d + (t * 0.6).cos() * 0.3,
+            )
         } else {
-            60.0 * (((r - g) / delta) + 4.0)
-        };
-
-        let s = if max == 0.0 { 0.0 } else { delta / max };
-        let v = max;
-
-        (h, s, v)
+            (self.a, self.b, self.c, self.d)
+        }
     }
 
     fn draw_circle(&self, pixels: &mut [Color], width: usize, height: usize,
@@ -172,17 +546,7 @@ impl DeJongAttractor {
                 let dist_sq = dx * dx + dy * dy;
 
                 if dist_sq <= r_sq {
-                    let idx = y * width + x;
-                    if self.point_glow {
-                        let old = pixels[idx];
-                        pixels[idx] = Color::from_rgb(
-                            (old.r as u16 + color.r as u16).min(255) as u8,
-                            (old.g as u16 + color.g as u16).min(255) as u8,
-                            (old.b as u16 + color.b as u16).min(255) as u8,
-                        );
-                    } else {
-                        pixels[idx] = color;
-                    }
+                    pixels[y * width + x] = color;
                 }
             }
         }
@@ -194,38 +558,19 @@ impl CliffordAttractor {
         Self::default()
     }
 
-    fn apply_color_adjustments(&self, color: Color) -> Color {
-        let (h, s, v) = self.rgb_to_hsv(color);
-        let new_h = (h + self.hue_shift * 360.0) % 360.0;
-        let new_s = (s * self.saturation).clamp(0.0, 1.0);
-        let new_v = (v * self.brightness).clamp(0.0, 1.0);
-
-        Color::from_hsv(new_h, new_s, new_v)
-    }
-
-    fn rgb_to_hsv(&self, color: Color) -> (f32, f32, f32) {
-        let r = color.r as f32 / 255.0;
-        let g = color.g as f32 / 255.0;
-        let b = color.b as f32 / 255.0;
-
-        let max = r.max(g).max(b);
-        let min = r.min(g).min(b);
-        let delta = max - min;
-
-        let h = if delta == 0.0 {
-            0.0
-        } else if max == r {
-            60.0 * (((g - b) / delta) % 6.0)
-        } else if max == g {
-            60.0 * (((b - r) / delta) + 2.0)
+    /// Parameters for this frame, including the animation offsets
+    fn animated_params(&self) -> (f32, f32, f32, f32) {
+        if self.animate_params {
+            let t = self.animation_time * self.animation_speed;
+            (
+                self.a + (t * 0.4).sin() * 0.3,
+                self.b + (t * 0.5).cos() * 0.3,
+                self.c + (t * 0.6).sin() * 0.2,
+                self.d + (t * 0.7).cos() * 0.2,
+            )
         } else {
-            60.0 * (((r - g) / delta) + 4.0)
-        };
-
-        let s = if max == 0.0 { 0.0 } else { delta / max };
-        let v = max;
-
-        (h, s, v)
+            (self.a, self.b, self.c, self.d)
+        }
     }
 
     fn draw_circle(&self, pixels: &mut [Color], width: usize, height: usize,
@@ -243,17 +588,7 @@ impl CliffordAttractor {
                 let dist_sq = dx * dx + dy * dy;
 
                 if dist_sq <= r_sq {
-                    let idx = y * width + x;
-                    if self.point_glow {
-                        let old = pixels[idx];
-                        pixels[idx] = Color::from_rgb(
-                            (old.r as u16 + color.r as u16).min(255) as u8,
-                            (old.g as u16 + color.g as u16).min(255) as u8,
-                            (old.b as u16 + color.b as u16).min(255) as u8,
-                        );
-                    } else {
-                        pixels[idx] = color;
-                    }
+                    pixels[y * width + x] = color;
                 }
             }
         }
@@ -265,67 +600,81 @@ impl Simulation2D for DeJongAttractor {
         "De Jong Attractor"
     }
 
+    fn equations(&self) -> Option<&str> {
+        Some(
+            "xₙ₊₁ = sin(a yₙ) − cos(b xₙ)\n\
+             yₙ₊₁ = sin(c xₙ) − cos(d yₙ)",
+        )
+    }
+
     fn compute(&self, width: usize, height: usize) -> Vec<Color> {
-        let mut pixels = vec![Color::BLACK; width * height];
+        self.compute_ctx(&RenderCtx::new(width, height))
+    }
 
-        let cx = width as f32 * self.center_x;
-        let cy = height as f32 * self.center_y;
+    fn compute_ctx(&self, ctx: &RenderCtx) -> Vec<Color> {
+        let (width, height) = (ctx.width, ctx.height);
+        let mut pixels = vec![ctx.background; width * height];
 
-        let mut x = self.start_x;
-        let mut y = self.start_y;
+        let region = plot_region(width, height, self.lock_aspect);
+        let (left, top, w, h) = (region.0 as f32, region.1 as f32, region.2 as f32, region.3 as f32);
+        let cx = left + w * self.center_x;
+        let cy = top + h * self.center_y;
 
-        // Animated parameters
-        let (a, b, c, d) = if self.animate_params {
-            let t = self.animation_time * self.animation_speed;
-            (
-                self.a + (t * 0.5).sin() * 0.5,
-                self.b + (t * 0.7).cos() * 0.5,
-                self.c + (t * 0.3).sin() * 0.3,
-                self.d + (t * 0.6).cos() * 0.3,
-            )
-        } else {
-            (self.a, self.b, self.c, self.d)
-        };
+        let params = self.animated_params();
+        let (mut x, mut y) = skip_transient(de_jong_step, params, (self.start_x, self.start_y), self.skip_first);
+
+        let folds = symmetry_folds(self.symmetry, self.mirror);
+        // Glow sums light past white, so it needs the float canvas even
+        // without anti-aliasing
+        let mut splats = (self.antialias || self.point_glow).then(|| Splats::new(width, height, ctx.background, self.point_glow));
 
         for i in 0..self.point_count {
-            // De Jong attractor formula: x' = sin(a*y) - cos(b*x), y' = sin(c*x) - cos(d*y)
-            let x_new = (a * y).sin() - (b * x).cos();
-            let y_new = (c * x).sin() - (d * y).cos();
-
-            x = x_new;
-            y = y_new;
-
-            let px = cx + x * self.zoom;
-            let py = cy + y * self.zoom;
-
-            if px >= 0.0 && px < width as f32 && py >= 0.0 && py < height as f32 {
-                let t = if self.color_by_iteration {
-                    i as f32 / self.point_count as f32
-                } else if self.color_by_position {
-                    ((x + 2.0) / 4.0).clamp(0.0, 1.0)
-                } else if self.color_by_distance {
-                    let dist = (x * x + y * y).sqrt();
-                    (dist / 2.0).clamp(0.0, 1.0)
-                } else {
-                    0.5
-                };
-
-                let mut color = self.color_scheme.map(t, true);
-                color = self.apply_color_adjustments(color);
-
-                if self.fade_by_age {
-                    let age_t = i as f32 / self.point_count as f32;
-                    color = Color::from_rgb(
-                        (color.r as f32 * age_t) as u8,
-                        (color.g as f32 * age_t) as u8,
-                        (color.b as f32 * age_t) as u8,
-                    );
-                }
+            (x, y) = de_jong_step(params, x, y);
+
+            let t = if self.color_by_iteration {
+                i as f32 / self.point_count as f32
+            } else if self.color_by_position {
+                ((x + 2.0) / 4.0).clamp(0.0, 1.0)
+            } else if self.color_by_distance {
+                let dist = (x * x + y * y).sqrt();
+                (dist / 2.0).clamp(0.0, 1.0)
+            } else {
+                0.5
+            };
+
+            let mut color = self.color_scheme.map(ctx.cycle_palette(t), true);
+            color = color.adjust_hsv(self.hue_shift, self.saturation, self.brightness);
+
+            if self.fade_by_age {
+                // Old points fade into the canvas
+                let age_t = i as f32 / self.point_count as f32;
+                color = Color::lerp(ctx.background, color, age_t);
+            }
 
-                self.draw_circle(&mut pixels, width, height, px, py, self.point_size, color);
+            // Each symmetric copy gets the same color, so folds blend seamlessly
+            for &fold in &folds {
+                let (fx, fy) = apply_fold(x, y, fold);
+                let px = cx + fx * self.zoom;
+                let py = cy + fy * self.zoom;
+
+                if px >= left && px < left + w && py >= top && py < top + h {
+                    match &mut splats {
+                        Some(splats) if self.antialias => splats.add(px, py, self.point_size, color),
+                        Some(splats) => splats.fill(px, py, self.point_size, color),
+                        None => self.draw_circle(&mut pixels, width, height, px, py, self.point_size, color),
+                    }
+                }
             }
         }
 
+        if let Some(splats) = splats {
+            pixels = splats.into_pixels(ctx);
+        }
+
+        if self.lock_aspect {
+            letterbox(&mut pixels, width, region, ctx.background);
+        }
+
         pixels
     }
 
@@ -338,55 +687,79 @@ impl Simulation2D for DeJongAttractor {
             .default_open(true)
             .show(ui, |ui| {
                 changed |= ui.add(egui::Slider::new(&mut self.a, -3.0..=3.0)
+                    .nudge(ui, 0.05)
                     .text("Parameter A")).changed();
 
                 changed |= ui.add(egui::Slider::new(&mut self.b, -3.0..=3.0)
+                    .nudge(ui, 0.05)
                     .text("Parameter B")).changed();
 
                 changed |= ui.add(egui::Slider::new(&mut self.c, -3.0..=3.0)
+                    .nudge(ui, 0.05)
                     .text("Parameter C")).changed();
 
                 changed |= ui.add(egui::Slider::new(&mut self.d, -3.0..=3.0)
+                    .nudge(ui, 0.05)
                     .text("Parameter D")).changed();
             });
 
+        if self.randomizer.ui(ui) {
+            (self.a, self.b, self.c, self.d) = self.randomizer.search(de_jong_step, (self.start_x, self.start_y), self.skip_first);
+            self.fit_requested = true;
+            changed = true;
+        }
+
         egui::CollapsingHeader::new("🔍 View Settings")
             .default_open(true)
             .show(ui, |ui| {
                 changed |= ui.add(egui::Slider::new(&mut self.point_count, 100..=100000)
+                    .nudge(ui, 1.0)
                     .text("Point Count")).changed();
 
-                changed |= ui.add(egui::Slider::new(&mut self.point_size, 0.5..=5.0)
+                // Hard circles smaller than this can miss every pixel center
+                let min_size = if self.antialias { 0.2 } else { 0.5 };
+                changed |= ui.add(egui::Slider::new(&mut self.point_size, min_size..=5.0)
+                    .nudge(ui, 0.02)
                     .text("Point Size")).changed();
+                changed |= ui.checkbox(&mut self.antialias, "Anti-aliased Points")
+                    .on_hover_text("Spread each point over the pixels it covers, so dense attractors render smoothly even with sub-pixel points")
+                    .changed();
 
                 changed |= ui.add(egui::Slider::new(&mut self.zoom, 50.0..=500.0)
+                    .nudge(ui, 2.0)
                     .text("Zoom")).changed();
 
                 changed |= ui.add(egui::Slider::new(&mut self.center_x, 0.0..=1.0)
+                    .nudge(ui, 0.01)
                     .text("Center X")).changed();
 
                 changed |= ui.add(egui::Slider::new(&mut self.center_y, 0.0..=1.0)
+                    .nudge(ui, 0.01)
                     .text("Center Y")).changed();
 
+                changed |= ui.checkbox(&mut self.lock_aspect, "Lock Aspect (Square)")
+                    .on_hover_text("Plot into a centered square so the shape doesn't depend on the window")
+                    .changed();
+
                 changed |= ui.add(egui::Slider::new(&mut self.start_x, -2.0..=2.0)
+                    .nudge(ui, 0.02)
                     .text("Start X")).changed();
 
                 changed |= ui.add(egui::Slider::new(&mut self.start_y, -2.0..=2.0)
+                    .nudge(ui, 0.02)
                     .text("Start Y")).changed();
+
+                changed |= ui.add(egui::Slider::new(&mut self.skip_first, 0..=5000)
+                    .nudge(ui, 1.0)
+                    .text("Skip First N"))
+                    .on_hover_text("Iterations discarded before plotting, to hide the transient from the start point")
+                    .changed();
             });
 
         egui::CollapsingHeader::new("🎨 Color Settings")
             .default_open(true)
             .show(ui, |ui| {
-                egui::ComboBox::from_label("Color Scheme")
-                    .selected_text(self.color_scheme.name())
-                    .show_ui(ui, |ui| {
-                        for scheme in ColorScheme::all() {
-                            if ui.selectable_value(&mut self.color_scheme, scheme, scheme.name()).clicked() {
-                                changed = true;
-                            }
-                        }
-                    });
+                changed |= color_scheme_combo(ui, &mut self.color_scheme);
 
                 changed |= ui.checkbox(&mut self.color_by_iteration, "Color by Iteration").changed();
                 changed |= ui.checkbox(&mut self.color_by_position, "Color by Position").changed();
@@ -394,12 +767,15 @@ impl Simulation2D for DeJongAttractor {
                 changed |= ui.checkbox(&mut self.fade_by_age, "Fade by Age").changed();
 
                 changed |= ui.add(egui::Slider::new(&mut self.hue_shift, 0.0..=1.0)
+                    .nudge(ui, 0.01)
                     .text("Hue Shift")).changed();
 
                 changed |= ui.add(egui::Slider::new(&mut self.saturation, 0.0..=2.0)
+                    .nudge(ui, 0.02)
                     .text("Saturation")).changed();
 
                 changed |= ui.add(egui::Slider::new(&mut self.brightness, 0.0..=2.0)
+                    .nudge(ui, 0.02)
                     .text("Brightness")).changed();
             });
 
@@ -407,7 +783,13 @@ impl Simulation2D for DeJongAttractor {
             .show(ui, |ui| {
                 changed |= ui.checkbox(&mut self.point_glow, "Point Glow").changed();
                 changed |= ui.add(egui::Slider::new(&mut self.background_fade, 0.0..=1.0)
+                    .nudge(ui, 0.01)
                     .text("Background Fade")).changed();
+
+                changed |= ui.add(egui::Slider::new(&mut self.symmetry, 1..=12)
+                    .nudge(ui, 1.0)
+                    .text("Symmetry")).changed();
+                changed |= ui.checkbox(&mut self.mirror, "Mirror").changed();
             });
 
         egui::CollapsingHeader::new("🎬 Animation")
@@ -416,6 +798,7 @@ impl Simulation2D for DeJongAttractor {
 
                 if self.animate_params {
                     ui.add(egui::Slider::new(&mut self.animation_speed, 0.1..=5.0)
+                        .nudge(ui, 0.02)
                         .text("Speed"));
                 }
             });
@@ -451,20 +834,28 @@ impl Simulation2D for DeJongAttractor {
                 }
             });
 
-        // Handle animation
+        changed
+    }
+
+    fn update(&mut self, dt: f32, width: usize, height: usize) -> bool {
         if self.animate_params {
-            let dt = ui.input(|i| i.stable_dt);
             self.animation_time += dt;
-            changed = true;
         }
-
-        changed
+        let fit = std::mem::take(&mut self.fit_requested);
+        if fit {
+            self.auto_fit(width, height);
+        }
+        self.animate_params || fit
     }
 
     fn supports_zoom(&self) -> bool {
         true
     }
 
+    fn plane_view(&self, width: usize, height: usize) -> Option<PlaneView> {
+        attractor_plane_view(width, height, self.lock_aspect, self.center_x, self.center_y, self.zoom)
+    }
+
     fn adjust_center(&mut self, dx: f64, dy: f64, _width: usize, _height: usize) {
         self.center_x += (dx as f32) / self.zoom;
         self.center_y += (dy as f32) / self.zoom;
@@ -473,6 +864,33 @@ impl Simulation2D for DeJongAttractor {
     fn get_zoom(&self) -> f64 {
         self.zoom as f64
     }
+
+    fn supports_auto_fit(&self) -> bool {
+        true
+    }
+
+    fn auto_fit(&mut self, width: usize, height: usize) {
+        let params = self.animated_params();
+        let folds = symmetry_folds(self.symmetry, self.mirror);
+
+        // Dry run of the same orbit compute() plots
+        let mut bounds = [f32::MAX, f32::MAX, f32::MIN, f32::MIN];
+        let (mut x, mut y) = skip_transient(de_jong_step, params, (self.start_x, self.start_y), self.skip_first);
+        for _ in 0..self.point_count.min(AUTO_FIT_SAMPLES) {
+            (x, y) = de_jong_step(params, x, y);
+
+            for &fold in &folds {
+                let (fx, fy) = apply_fold(x, y, fold);
+                include_point(&mut bounds, fx, fy);
+            }
+        }
+
+        if bounds[0] <= bounds[2] && bounds.iter().all(|v| v.is_finite()) {
+            let (_, _, w, h) = plot_region(width, height, self.lock_aspect);
+            (self.zoom, self.center_x, self.center_y) = fit_view(bounds, w, h);
+        }
+    }
+
 }
 
 impl Simulation2D for CliffordAttractor {
@@ -480,67 +898,81 @@ impl Simulation2D for CliffordAttractor {
         "Clifford Attractor"
     }
 
+    fn equations(&self) -> Option<&str> {
+        Some(
+            "xₙ₊₁ = sin(a yₙ) + c cos(a xₙ)\n\
+             yₙ₊₁ = sin(b xₙ) + d cos(b yₙ)",
+        )
+    }
+
     fn compute(&self, width: usize, height: usize) -> Vec<Color> {
-        let mut pixels = vec![Color::BLACK; width * height];
+        self.compute_ctx(&RenderCtx::new(width, height))
+    }
 
-        let cx = width as f32 * self.center_x;
-        let cy = height as f32 * self.center_y;
+    fn compute_ctx(&self, ctx: &RenderCtx) -> Vec<Color> {
+        let (width, height) = (ctx.width, ctx.height);
+        let mut pixels = vec![ctx.background; width * height];
 
-        let mut x = self.start_x;
-        let mut y = self.start_y;
+        let region = plot_region(width, height, self.lock_aspect);
+        let (left, top, w, h) = (region.0 as f32, region.1 as f32, region.2 as f32, region.3 as f32);
+        let cx = left + w * self.center_x;
+        let cy = top + h * self.center_y;
 
-        // Animated parameters
-        let (a, b, c, d) = if self.animate_params {
-            let t = self.animation_time * self.animation_speed;
-            (
-                self.a + (t * 0.4).sin() * 0.3,
-                self.b + (t * 0.5).cos() * 0.3,
-                self.c + (t * 0.6).sin() * 0.2,
-                self.d + (t * 0.7).cos() * 0.2,
-            )
-        } else {
-            (self.a, self.b, self.c, self.d)
-        };
+        let params = self.animated_params();
+        let (mut x, mut y) = skip_transient(clifford_step, params, (self.start_x, self.start_y), self.skip_first);
+
+        let folds = symmetry_folds(self.symmetry, self.mirror);
+        // Glow sums light past white, so it needs the float canvas even
+        // without anti-aliasing
+        let mut splats = (self.antialias || self.point_glow).then(|| Splats::new(width, height, ctx.background, self.point_glow));
 
         for i in 0..self.point_count {
-            // Clifford attractor formula: x' = sin(a*y) + c*cos(a*x), y' = sin(b*x) + d*cos(b*y)
-            let x_new = (a * y).sin() + c * (a * x).cos();
-            let y_new = (b * x).sin() + d * (b * y).cos();
-
-            x = x_new;
-            y = y_new;
-
-            let px = cx + x * self.zoom;
-            let py = cy + y * self.zoom;
-
-            if px >= 0.0 && px < width as f32 && py >= 0.0 && py < height as f32 {
-                let t = if self.color_by_iteration {
-                    i as f32 / self.point_count as f32
-                } else if self.color_by_position {
-                    ((x + 2.0) / 4.0).clamp(0.0, 1.0)
-                } else if self.color_by_angle {
-                    let angle = y.atan2(x);
-                    (angle + std::f32::consts::PI) / std::f32::consts::TAU
-                } else {
-                    0.5
-                };
-
-                let mut color = self.color_scheme.map(t, true);
-                color = self.apply_color_adjustments(color);
-
-                if self.fade_by_age {
-                    let age_t = i as f32 / self.point_count as f32;
-                    color = Color::from_rgb(
-                        (color.r as f32 * age_t) as u8,
-                        (color.g as f32 * age_t) as u8,
-                        (color.b as f32 * age_t) as u8,
-                    );
-                }
+            (x, y) = clifford_step(params, x, y);
+
+            let t = if self.color_by_iteration {
+                i as f32 / self.point_count as f32
+            } else if self.color_by_position {
+                ((x + 2.0) / 4.0).clamp(0.0, 1.0)
+            } else if self.color_by_angle {
+                let angle = y.atan2(x);
+                (angle + std::f32::consts::PI) / std::f32::consts::TAU
+            } else {
+                0.5
+            };
+
+            let mut color = self.color_scheme.map(ctx.cycle_palette(t), true);
+            color = color.adjust_hsv(self.hue_shift, self.saturation, self.brightness);
+
+            if self.fade_by_age {
+                // Old points fade into the canvas
+                let age_t = i as f32 / self.point_count as f32;
+                color = Color::lerp(ctx.background, color, age_t);
+            }
 
-                self.draw_circle(&mut pixels, width, height, px, py, self.point_size, color);
+            // Each symmetric copy gets the same color, so folds blend seamlessly
+            for &fold in &folds {
+                let (fx, fy) = apply_fold(x, y, fold);
+                let px = cx + fx * self.zoom;
+                let py = cy + fy * self.zoom;
+
+                if px >= left && px < left + w && py >= top && py < top + h {
+                    match &mut splats {
+                        Some(splats) if self.antialias => splats.add(px, py, self.point_size, color),
+                        Some(splats) => splats.fill(px, py, self.point_size, color),
+                        None => self.draw_circle(&mut pixels, width, height, px, py, self.point_size, color),
+                    }
+                }
             }
         }
 
+        if let Some(splats) = splats {
+            pixels = splats.into_pixels(ctx);
+        }
+
+        if self.lock_aspect {
+            letterbox(&mut pixels, width, region, ctx.background);
+        }
+
         pixels
     }
 
@@ -553,55 +985,79 @@ impl Simulation2D for CliffordAttractor {
             .default_open(true)
             .show(ui, |ui| {
                 changed |= ui.add(egui::Slider::new(&mut self.a, -3.0..=3.0)
+                    .nudge(ui, 0.05)
                     .text("Parameter A")).changed();
 
                 changed |= ui.add(egui::Slider::new(&mut self.b, -3.0..=3.0)
+                    .nudge(ui, 0.05)
                     .text("Parameter B")).changed();
 
                 changed |= ui.add(egui::Slider::new(&mut self.c, -3.0..=3.0)
+                    .nudge(ui, 0.05)
                     .text("Parameter C")).changed();
 
                 changed |= ui.add(egui::Slider::new(&mut self.d, -3.0..=3.0)
+                    .nudge(ui, 0.05)
                     .text("Parameter D")).changed();
             });
 
+        if self.randomizer.ui(ui) {
+            (self.a, self.b, self.c, self.d) = self.randomizer.search(clifford_step, (self.start_x, self.start_y), self.skip_first);
+            self.fit_requested = true;
+            changed = true;
+        }
+
         egui::CollapsingHeader::new("🔍 View Settings")
             .default_open(true)
             .show(ui, |ui| {
                 changed |= ui.add(egui::Slider::new(&mut self.point_count, 100..=100000)
+                    .nudge(ui, 1.0)
                     .text("Point Count")).changed();
 
-                changed |= ui.add(egui::Slider::new(&mut self.point_size, 0.5..=5.0)
+                // Hard circles smaller than this can miss every pixel center
+                let min_size = if self.antialias { 0.2 } else { 0.5 };
+                changed |= ui.add(egui::Slider::new(&mut self.point_size, min_size..=5.0)
+                    .nudge(ui, 0.02)
                     .text("Point Size")).changed();
+                changed |= ui.checkbox(&mut self.antialias, "Anti-aliased Points")
+                    .on_hover_text("Spread each point over the pixels it covers, so dense attractors render smoothly even with sub-pixel points")
+                    .changed();
 
                 changed |= ui.add(egui::Slider::new(&mut self.zoom, 50.0..=500.0)
+                    .nudge(ui, 2.0)
                     .text("Zoom")).changed();
 
                 changed |= ui.add(egui::Slider::new(&mut self.center_x, 0.0..=1.0)
+                    .nudge(ui, 0.01)
                     .text("Center X")).changed();
 
                 changed |= ui.add(egui::Slider::new(&mut self.center_y, 0.0..=1.0)
+                    .nudge(ui, 0.01)
                     .text("Center Y")).changed();
 
+                changed |= ui.checkbox(&mut self.lock_aspect, "Lock Aspect (Square)")
+                    .on_hover_text("Plot into a centered square so the shape doesn't depend on the window")
+                    .changed();
+
                 changed |= ui.add(egui::Slider::new(&mut self.start_x, -2.0..=2.0)
+                    .nudge(ui, 0.02)
                     .text("Start X")).changed();
 
                 changed |= ui.add(egui::Slider::new(&mut self.start_y, -2.0..=2.0)
+                    .nudge(ui, 0.02)
                     .text("Start Y")).changed();
+
+                changed |= ui.add(egui::Slider::new(&mut self.skip_first, 0..=5000)
+                    .nudge(ui, 1.0)
+                    .text("Skip First N"))
+                    .on_hover_text("Iterations discarded before plotting, to hide the transient from the start point")
+                    .changed();
             });
 
         egui::CollapsingHeader::new("🎨 Color Settings")
             .default_open(true)
             .show(ui, |ui| {
-                egui::ComboBox::from_label("Color Scheme")
-                    .selected_text(self.color_scheme.name())
-                    .show_ui(ui, |ui| {
-                        for scheme in ColorScheme::all() {
-                            if ui.selectable_value(&mut self.color_scheme, scheme, scheme.name()).clicked() {
-                                changed = true;
-                            }
-                        }
-                    });
+                changed |= color_scheme_combo(ui, &mut self.color_scheme);
 
                 changed |= ui.checkbox(&mut self.color_by_iteration, "Color by Iteration").changed();
                 changed |= ui.checkbox(&mut self.color_by_position, "Color by Position").changed();
@@ -609,12 +1065,15 @@ impl Simulation2D for CliffordAttractor {
                 changed |= ui.checkbox(&mut self.fade_by_age, "Fade by Age").changed();
 
                 changed |= ui.add(egui::Slider::new(&mut self.hue_shift, 0.0..=1.0)
+                    .nudge(ui, 0.01)
                     .text("Hue Shift")).changed();
 
                 changed |= ui.add(egui::Slider::new(&mut self.saturation, 0.0..=2.0)
+                    .nudge(ui, 0.02)
                     .text("Saturation")).changed();
 
                 changed |= ui.add(egui::Slider::new(&mut self.brightness, 0.0..=2.0)
+                    .nudge(ui, 0.02)
                     .text("Brightness")).changed();
             });
 
@@ -622,7 +1081,13 @@ impl Simulation2D for CliffordAttractor {
             .show(ui, |ui| {
                 changed |= ui.checkbox(&mut self.point_glow, "Point Glow").changed();
                 changed |= ui.add(egui::Slider::new(&mut self.background_fade, 0.0..=1.0)
+                    .nudge(ui, 0.01)
                     .text("Background Fade")).changed();
+
+                changed |= ui.add(egui::Slider::new(&mut self.symmetry, 1..=12)
+                    .nudge(ui, 1.0)
+                    .text("Symmetry")).changed();
+                changed |= ui.checkbox(&mut self.mirror, "Mirror").changed();
             });
 
         egui::CollapsingHeader::new("🎬 Animation")
@@ -631,6 +1096,7 @@ impl Simulation2D for CliffordAttractor {
 
                 if self.animate_params {
                     ui.add(egui::Slider::new(&mut self.animation_speed, 0.1..=5.0)
+                        .nudge(ui, 0.02)
                         .text("Speed"));
                 }
             });
@@ -667,20 +1133,28 @@ impl Simulation2D for CliffordAttractor {
                 }
             });
 
-        // Handle animation
+        changed
+    }
+
+    fn update(&mut self, dt: f32, width: usize, height: usize) -> bool {
         if self.animate_params {
-            let dt = ui.input(|i| i.stable_dt);
             self.animation_time += dt;
-            changed = true;
         }
-
-        changed
+        let fit = std::mem::take(&mut self.fit_requested);
+        if fit {
+            self.auto_fit(width, height);
+        }
+        self.animate_params || fit
     }
 
     fn supports_zoom(&self) -> bool {
         true
     }
 
+    fn plane_view(&self, width: usize, height: usize) -> Option<PlaneView> {
+        attractor_plane_view(width, height, self.lock_aspect, self.center_x, self.center_y, self.zoom)
+    }
+
     fn adjust_center(&mut self, dx: f64, dy: f64, _width: usize, _height: usize) {
         self.center_x += (dx as f32) / self.zoom;
         self.center_y += (dy as f32) / self.zoom;
@@ -689,4 +1163,31 @@ impl Simulation2D for CliffordAttractor {
     fn get_zoom(&self) -> f64 {
         self.zoom as f64
     }
+
+    fn supports_auto_fit(&self) -> bool {
+        true
+    }
+
+    fn auto_fit(&mut self, width: usize, height: usize) {
+        let params = self.animated_params();
+        let folds = symmetry_folds(self.symmetry, self.mirror);
+
+        // Dry run of the same orbit compute() plots
+        let mut bounds = [f32::MAX, f32::MAX, f32::MIN, f32::MIN];
+        let (mut x, mut y) = skip_transient(clifford_step, params, (self.start_x, self.start_y), self.skip_first);
+        for _ in 0..self.point_count.min(AUTO_FIT_SAMPLES) {
+            (x, y) = clifford_step(params, x, y);
+
+            for &fold in &folds {
+                let (fx, fy) = apply_fold(x, y, fold);
+                include_point(&mut bounds, fx, fy);
+            }
+        }
+
+        if bounds[0] <= bounds[2] && bounds.iter().all(|v| v.is_finite()) {
+            let (_, _, w, h) = plot_region(width, height, self.lock_aspect);
+            (self.zoom, self.center_x, self.center_y) = fit_view(bounds, w, h);
+        }
+    }
+
 }
@@ -0,0 +1,474 @@
+//! Symmetric icons from Field & Golubitsky's "Symmetry in Chaos": the orbit
+//! of a planar map that commutes with rotation by 2π/n, so its attractor has
+//! exactly n-fold symmetry (and mirror symmetry too when ω = 0). The orbit is
+//! accumulated into a per-pixel hit count over many frames and log
+//! tone-mapped, so the faint filaments show next to the dense core.
+
+use crate::color_ui::color_scheme_combo;
+use crate::nudge::Nudge;
+use crate::readout;
+use crate::state::{read, read_clamped};
+use crate::{Color, ColorScheme, RenderCtx, Simulation2D};
+use serde_json::{json, Value};
+
+/// Where every orbit starts; off the origin, which is a fixed point
+const START: (f32, f32) = (0.01, 0.003);
+
+/// Iterations discarded from the start point before plotting
+const TRANSIENT: usize = 1000;
+
+/// Orbit points sampled to size the icon to the canvas
+const FIT_SAMPLES: usize = 20_000;
+
+/// Past this |z| the orbit has escaped and restarts from `START`
+const ESCAPE: f32 = 1e4;
+
+/// Points a render without accumulated counts (an export at another size,
+/// or a thumbnail) plots for itself
+const STANDALONE_POINTS: u64 = 2_000_000;
+
+/// Named (λ, α, β, γ, ω, degree) settings
+const PRESETS: [(&str, [f32; 5], u32); 10] = [
+    ("Sevenfold Bracelet", [-2.08, 1.0, -0.1, 0.167, 0.0], 7),
+    ("Hexagonal Lace", [-2.7, 5.0, 1.5, 1.0, 0.0], 6),
+    ("Fivefold Fan", [-2.34, 2.0, 0.2, 0.1, 0.0], 5),
+    ("Pentagon", [2.6, -2.0, 0.0, -0.5, 0.0], 5),
+    ("Swirling Pentagon", [-2.5, 5.0, -1.9, 1.0, 0.188], 5),
+    ("Sunflower", [2.409, -2.5, 0.0, 0.9, 0.0], 23),
+    ("Ninefold Rose", [-2.05, 3.0, -16.79, 1.0, 0.0], 9),
+    ("Triangle", [2.5, -2.5, 0.0, 0.9, 0.0], 3),
+    ("Twisted Square", [-1.86, 2.0, 0.0, 1.0, 0.1], 4),
+    ("Clam Shell", [1.56, -1.0, 0.1, -0.82, 0.12], 3),
+];
+
+/// Map parameters λ, α, β, γ, ω and the symmetry degree
+type IconParams = ([f32; 5], u32);
+
+/// One iteration of the map z' = (λ + α|z|² + β Re(zⁿ) + iω) z + γ z̄ⁿ⁻¹.
+/// Turning z by ρ with ρⁿ = 1 leaves |z|² and Re(zⁿ) alone and turns z̄ⁿ⁻¹
+/// by ρ̄ⁿ⁻¹ = ρ, so the whole step turns by ρ too: that is where the
+/// attractor's n-fold symmetry comes from.
+fn icon_step(([lambda, alpha, beta, gamma, omega], degree): IconParams, x: f32, y: f32) -> (f32, f32) {
+    // zⁿ⁻¹ by repeated multiplication, then zⁿ from it
+    let (mut px, mut py) = (1.0f32, 0.0f32);
+    for _ in 1..degree.max(1) {
+        (px, py) = (px * x - py * y, px * y + py * x);
+    }
+    let real_zn = px * x - py * y;
+
+    let p = lambda + alpha * (x * x + y * y) + beta * real_zn;
+    // z̄ⁿ⁻¹ is the conjugate of zⁿ⁻¹
+    (
+        p * x - omega * y + gamma * px,
+        p * y + omega * x - gamma * py,
+    )
+}
+
+/// Pixels per unit of z: an attractor reaching out to `extent` fills 95% of
+/// the shorter side at zoom 1
+fn fit_scale(width: usize, height: usize, extent: f32, zoom: f32) -> f32 {
+    width.min(height) as f32 * 0.5 * 0.95 / extent * zoom
+}
+
+/// The orbit after the transient, restarted whenever it escapes
+struct Orbit {
+    params: IconParams,
+    x: f32,
+    y: f32,
+}
+
+impl Orbit {
+    fn new(params: IconParams) -> Self {
+        let mut orbit = Self { params, x: START.0, y: START.1 };
+        for _ in 0..TRANSIENT {
+            orbit.next();
+        }
+        orbit
+    }
+
+    fn next(&mut self) -> (f32, f32) {
+        (self.x, self.y) = icon_step(self.params, self.x, self.y);
+        if !(self.x.is_finite() && self.y.is_finite()) || self.x.abs().max(self.y.abs()) > ESCAPE {
+            (self.x, self.y) = START;
+        }
+        (self.x, self.y)
+    }
+}
+
+/// Hit counts per pixel, accumulated across frames
+struct Density {
+    width: usize,
+    height: usize,
+    counts: Vec<u32>,
+    /// Points plotted so far
+    points: u64,
+    orbit: Orbit,
+}
+
+impl Density {
+    fn new(width: usize, height: usize, params: IconParams) -> Self {
+        Self {
+            width,
+            height,
+            counts: vec![0; width * height],
+            points: 0,
+            orbit: Orbit::new(params),
+        }
+    }
+
+    /// Plot `n` more orbit points, `scale` pixels per unit around the center
+    fn plot(&mut self, n: u64, scale: f32) {
+        let cx = self.width as f32 * 0.5;
+        let cy = self.height as f32 * 0.5;
+        for _ in 0..n {
+            let (x, y) = self.orbit.next();
+            let px = cx + x * scale;
+            let py = cy - y * scale;
+            if px >= 0.0 && py >= 0.0 && (px as usize) < self.width && (py as usize) < self.height {
+                let count = &mut self.counts[py as usize * self.width + px as usize];
+                *count = count.saturating_add(1);
+            }
+        }
+        self.points += n;
+    }
+}
+
+pub struct SymmetricIcons {
+    pub lambda: f32,
+    pub alpha: f32,
+    pub beta: f32,
+    pub gamma: f32,
+    pub omega: f32,
+    /// Order of the rotational symmetry
+    pub degree: u32,
+    /// Points added to the density each frame
+    pub points_per_frame: usize,
+    /// Stop accumulating once this many points are plotted
+    pub max_points: u64,
+    /// Magnification over the fitted size
+    pub zoom: f32,
+    /// Exponent on the log-scaled density; below 1 lifts faint regions
+    pub tone_gamma: f32,
+    pub color_scheme: ColorScheme,
+    density: Option<Density>,
+    /// Largest |z| on the attractor, measured when the parameters change
+    extent: f32,
+    /// Parameters `extent` and `density` belong to
+    fitted: Option<IconParams>,
+}
+
+impl Default for SymmetricIcons {
+    fn default() -> Self {
+        let ([lambda, alpha, beta, gamma, omega], degree) = (PRESETS[0].1, PRESETS[0].2);
+        Self {
+            lambda,
+            alpha,
+            beta,
+            gamma,
+            omega,
+            degree,
+            points_per_frame: 200_000,
+            max_points: 20_000_000,
+            zoom: 1.0,
+            tone_gamma: 0.6,
+            color_scheme: ColorScheme::Ocean,
+            density: None,
+            extent: 1.0,
+            fitted: None,
+        }
+    }
+}
+
+impl SymmetricIcons {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn params(&self) -> IconParams {
+        ([self.lambda, self.alpha, self.beta, self.gamma, self.omega], self.degree)
+    }
+
+    /// Largest |z| on the orbit under `params`
+    fn measure_extent(params: IconParams) -> f32 {
+        let mut orbit = Orbit::new(params);
+        let extent = (0..FIT_SAMPLES)
+            .map(|_| {
+                let (x, y) = orbit.next();
+                (x * x + y * y).sqrt()
+            })
+            .fold(0.0f32, f32::max);
+        if extent > 1e-6 { extent } else { 1.0 }
+    }
+
+    /// Start over with an empty density, e.g. after a change that moves
+    /// points
+    fn clear(&mut self) {
+        self.density = None;
+    }
+
+    fn tone_map(&self, counts: &[u32], ctx: &RenderCtx) -> Vec<Color> {
+        let peak = counts.iter().copied().max().unwrap_or(0);
+        if peak == 0 {
+            return vec![ctx.background; counts.len()];
+        }
+        let log_peak = (peak as f32).ln_1p();
+        counts.iter()
+            .map(|&n| {
+                if n == 0 {
+                    return ctx.background;
+                }
+                // Exposure pushes more of the density toward the palette's
+                // top end; the viewer's tone curve keeps the peak from
+                // flattening into one color
+                let t = ctx.tone_map.apply((n as f32).ln_1p() / log_peak * ctx.exposure);
+                self.color_scheme.map(ctx.cycle_palette(t.powf(self.tone_gamma)), true)
+            })
+            .collect()
+    }
+
+    fn load_params(&mut self, [lambda, alpha, beta, gamma, omega]: [f32; 5], degree: u32) {
+        self.lambda = lambda;
+        self.alpha = alpha;
+        self.beta = beta;
+        self.gamma = gamma;
+        self.omega = omega;
+        self.degree = degree;
+        self.zoom = 1.0;
+    }
+}
+
+impl Simulation2D for SymmetricIcons {
+    fn name(&self) -> &str {
+        "Symmetric Icons"
+    }
+
+    fn equations(&self) -> Option<&str> {
+        Some(
+            "zₙ₊₁ = (λ + α|z|² + β Re(zⁿ) + iω) z + γ z̄ⁿ⁻¹\n\
+             n = degree of rotational symmetry",
+        )
+    }
+
+    fn compute(&self, width: usize, height: usize) -> Vec<Color> {
+        self.compute_ctx(&RenderCtx::new(width, height))
+    }
+
+    fn compute_ctx(&self, ctx: &RenderCtx) -> Vec<Color> {
+        if let Some(density) = &self.density {
+            if (density.width, density.height) == (ctx.width, ctx.height) && density.points > 0 {
+                return self.tone_map(&density.counts, ctx);
+            }
+        }
+
+        // No counts at this size: plot a batch just for this render
+        let mut density = Density::new(ctx.width, ctx.height, self.params());
+        let extent = Self::measure_extent(self.params());
+        density.plot(STANDALONE_POINTS.min(self.max_points), fit_scale(ctx.width, ctx.height, extent, self.zoom));
+        self.tone_map(&density.counts, ctx)
+    }
+
+    fn update(&mut self, _dt: f32, width: usize, height: usize) -> bool {
+        let params = self.params();
+        if self.fitted != Some(params) {
+            self.extent = Self::measure_extent(params);
+            self.fitted = Some(params);
+            self.clear();
+        }
+        if self.density.as_ref().is_some_and(|d| (d.width, d.height) != (width, height)) {
+            self.clear();
+        }
+
+        let scale = fit_scale(width, height, self.extent, self.zoom);
+        let density = self.density.get_or_insert_with(|| Density::new(width, height, params));
+        if density.points >= self.max_points {
+            return false;
+        }
+        let n = (self.points_per_frame as u64).min(self.max_points - density.points);
+        density.plot(n, scale);
+        true
+    }
+
+    fn supports_auto_fit(&self) -> bool {
+        true
+    }
+
+    fn auto_fit(&mut self, _width: usize, _height: usize) {
+        self.zoom = 1.0;
+        self.clear();
+    }
+
+    fn reset(&mut self) {
+        self.clear();
+    }
+
+    fn ui_parameters(&mut self, ui: &mut egui::Ui) -> bool {
+        let mut changed = false;
+        let mut moved = false;
+
+        ui.heading("Symmetric Icons");
+
+        egui::CollapsingHeader::new("⚙ Map Parameters")
+            .default_open(true)
+            .show(ui, |ui| {
+                moved |= ui.add(egui::Slider::new(&mut self.lambda, -3.0..=3.0)
+                    .nudge(ui, 0.01)
+                    .text("Lambda (λ)")).changed();
+                moved |= ui.add(egui::Slider::new(&mut self.alpha, -12.0..=12.0)
+                    .nudge(ui, 0.05)
+                    .text("Alpha (α)")).changed();
+                moved |= ui.add(egui::Slider::new(&mut self.beta, -20.0..=20.0)
+                    .nudge(ui, 0.05)
+                    .text("Beta (β)")).changed();
+                moved |= ui.add(egui::Slider::new(&mut self.gamma, -2.0..=2.0)
+                    .nudge(ui, 0.005)
+                    .text("Gamma (γ)")).changed();
+                moved |= ui.add(egui::Slider::new(&mut self.omega, -0.5..=0.5)
+                    .nudge(ui, 0.002)
+                    .text("Omega (ω)"))
+                    .on_hover_text("Rotation per step. Nonzero ω breaks the mirror symmetry, leaving only the rotations.")
+                    .changed();
+                moved |= ui.add(egui::Slider::new(&mut self.degree, 2..=24)
+                    .nudge(ui, 1.0)
+                    .text("Degree (n)"))
+                    .on_hover_text("The icon looks the same after a turn of 360°/n")
+                    .changed();
+            });
+
+        egui::CollapsingHeader::new("🔍 Rendering")
+            .default_open(true)
+            .show(ui, |ui| {
+                moved |= ui.add(egui::Slider::new(&mut self.zoom, 0.25..=8.0)
+                    .nudge(ui, 0.02)
+                    .logarithmic(true)
+                    .text("Zoom")).changed();
+                ui.add(egui::Slider::new(&mut self.points_per_frame, 10_000..=2_000_000)
+                    .nudge(ui, 1.0)
+                    .logarithmic(true)
+                    .text("Points per Frame"));
+                changed |= ui.add(egui::Slider::new(&mut self.max_points, 1_000_000..=200_000_000)
+                    .nudge(ui, 1.0)
+                    .logarithmic(true)
+                    .text("Max Points"))
+                    .on_hover_text("Accumulation stops here; more points smooth out the faint regions")
+                    .changed();
+
+                let plotted = self.density.as_ref().map_or(0, |d| d.points);
+                ui.label(format!("Plotted: {}", readout::count(plotted)));
+                if ui.button("🔄 Restart Accumulation").clicked() {
+                    moved = true;
+                }
+            });
+
+        egui::CollapsingHeader::new("🎨 Colors")
+            .default_open(true)
+            .show(ui, |ui| {
+                changed |= color_scheme_combo(ui, &mut self.color_scheme);
+                changed |= ui.add(egui::Slider::new(&mut self.tone_gamma, 0.2..=2.0)
+                    .nudge(ui, 0.01)
+                    .text("Tone Gamma"))
+                    .on_hover_text("Colors follow the log of each pixel's hit count, raised to this power. Lower values bring out the faint filaments.")
+                    .changed();
+            });
+
+        egui::CollapsingHeader::new("🎯 Presets")
+            .show(ui, |ui| {
+                ui.horizontal_wrapped(|ui| {
+                    for (name, params, degree) in PRESETS {
+                        if ui.button(name).on_hover_text(format!("{}-fold", degree)).clicked() {
+                            self.load_params(params, degree);
+                            moved = true;
+                        }
+                    }
+                });
+            });
+
+        if moved {
+            self.clear();
+        }
+        changed || moved
+    }
+
+    fn preset_names(&self) -> Vec<&'static str> {
+        PRESETS.iter().map(|p| p.0).collect()
+    }
+
+    fn load_preset(&mut self, name: &str) -> bool {
+        let Some(&(_, params, degree)) = PRESETS.iter().find(|p| p.0 == name) else {
+            return false;
+        };
+        self.load_params(params, degree);
+        self.clear();
+        true
+    }
+
+    fn save_state(&self) -> Value {
+        json!({
+            "lambda": self.lambda,
+            "alpha": self.alpha,
+            "beta": self.beta,
+            "gamma": self.gamma,
+            "omega": self.omega,
+            "degree": self.degree,
+            "points_per_frame": self.points_per_frame,
+            "max_points": self.max_points,
+            "zoom": self.zoom,
+            "tone_gamma": self.tone_gamma,
+            "color_scheme": self.color_scheme,
+        })
+    }
+
+    fn load_state(&mut self, state: &Value) {
+        let view = (self.params(), self.zoom);
+
+        read_clamped(state, "lambda", &mut self.lambda, -3.0..=3.0);
+        read_clamped(state, "alpha", &mut self.alpha, -12.0..=12.0);
+        read_clamped(state, "beta", &mut self.beta, -20.0..=20.0);
+        read_clamped(state, "gamma", &mut self.gamma, -2.0..=2.0);
+        read_clamped(state, "omega", &mut self.omega, -0.5..=0.5);
+        read_clamped(state, "degree", &mut self.degree, 2..=24);
+        read_clamped(state, "points_per_frame", &mut self.points_per_frame, 10_000..=2_000_000);
+        read_clamped(state, "max_points", &mut self.max_points, 1_000_000..=200_000_000);
+        read_clamped(state, "zoom", &mut self.zoom, 0.25..=8.0);
+        read_clamped(state, "tone_gamma", &mut self.tone_gamma, 0.2..=2.0);
+        read(state, "color_scheme", &mut self.color_scheme);
+
+        if (self.params(), self.zoom) != view {
+            self.clear();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn step_commutes_with_rotation_by_the_degree() {
+        for (name, params, degree) in PRESETS {
+            let angle = std::f32::consts::TAU / degree as f32;
+            let (cos, sin) = (angle.cos(), angle.sin());
+            let (x, y) = (0.31, -0.17);
+
+            // f(ρz) against ρ f(z)
+            let (rx, ry) = icon_step((params, degree), x * cos - y * sin, x * sin + y * cos);
+            let (fx, fy) = icon_step((params, degree), x, y);
+            let (ex, ey) = (fx * cos - fy * sin, fx * sin + fy * cos);
+            assert!((rx - ex).abs() < 1e-4 && (ry - ey).abs() < 1e-4, "{name}: ({rx}, {ry}) vs ({ex}, {ey})");
+        }
+    }
+
+    #[test]
+    fn oversized_loaded_state_is_clamped_to_the_sliders() {
+        let mut icons = SymmetricIcons::new();
+        icons.load_state(&json!({
+            "degree": 4_000_000_000u32,
+            "points_per_frame": 1_000_000_000_000u64,
+            "max_points": u64::MAX,
+            "zoom": 0.0,
+        }));
+        assert_eq!(icons.degree, 24);
+        assert_eq!(icons.points_per_frame, 2_000_000);
+        assert_eq!(icons.max_points, 200_000_000);
+        assert_eq!(icons.zoom, 0.25);
+    }
+}
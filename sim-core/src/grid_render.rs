@@ -0,0 +1,141 @@
+//! Upscaling cell grids to the canvas. Cells are mapped with fractional
+//! sizes, so the grid always fills the canvas exactly and neighbouring cells
+//! differ by at most one pixel, instead of integer cell sizes that leave a
+//! black margin and uneven cells when the canvas isn't a multiple of the grid.
+
+use crate::Color;
+
+#[derive(Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+pub enum GridFilter {
+    Sharp,  // Nearest cell, crisp edges
+    Smooth, // Bilinear blend between cell centers
+}
+
+impl GridFilter {
+    pub fn all() -> Vec<GridFilter> {
+        vec![GridFilter::Sharp, GridFilter::Smooth]
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            GridFilter::Sharp => "Sharp",
+            GridFilter::Smooth => "Smooth",
+        }
+    }
+}
+
+/// Render a `grid_width`x`grid_height` grid to `width`x`height` pixels.
+/// `cell_color` is called once per cell with the cell's row-major index.
+pub fn render_grid<F>(
+    grid_width: usize,
+    grid_height: usize,
+    width: usize,
+    height: usize,
+    filter: GridFilter,
+    cell_color: F,
+) -> Vec<Color>
+where
+    F: Fn(usize) -> Color,
+{
+    if grid_width == 0 || grid_height == 0 || width == 0 || height == 0 {
+        return vec![Color::BLACK; width * height];
+    }
+
+    let cells: Vec<Color> = (0..grid_width * grid_height).map(cell_color).collect();
+    let sx = grid_width as f32 / width as f32;
+    let sy = grid_height as f32 / height as f32;
+    let mut colors = Vec::with_capacity(width * height);
+
+    match filter {
+        GridFilter::Sharp => {
+            // Cell under each pixel center
+            let columns: Vec<usize> = (0..width)
+                .map(|px| (((px as f32 + 0.5) * sx) as usize).min(grid_width - 1))
+                .collect();
+            for py in 0..height {
+                let gy = (((py as f32 + 0.5) * sy) as usize).min(grid_height - 1);
+                let row = &cells[gy * grid_width..(gy + 1) * grid_width];
+                colors.extend(columns.iter().map(|&gx| row[gx]));
+            }
+        }
+        GridFilter::Smooth => {
+            // Position in cell-center coordinates, clamped at the border
+            let sample = |p: usize, scale: f32, len: usize| -> (usize, usize, f32) {
+                let u = ((p as f32 + 0.5) * scale - 0.5).clamp(0.0, (len - 1) as f32);
+                let i = u as usize;
+                (i, (i + 1).min(len - 1), u - i as f32)
+            };
+            let columns: Vec<_> = (0..width).map(|px| sample(px, sx, grid_width)).collect();
+            for py in 0..height {
+                let (y0, y1, fy) = sample(py, sy, grid_height);
+                for &(x0, x1, fx) in &columns {
+                    let top = Color::lerp(cells[y0 * grid_width + x0], cells[y0 * grid_width + x1], fx);
+                    let bottom = Color::lerp(cells[y1 * grid_width + x0], cells[y1 * grid_width + x1], fx);
+                    colors.push(Color::lerp(top, bottom, fy));
+                }
+            }
+        }
+    }
+
+    colors
+}
+
+/// Cell under the canvas position (`x`, `y`), for a grid drawn by
+/// `render_grid` at `width`x`height`. `None` off the canvas.
+pub fn cell_at(x: f32, y: f32, grid_width: usize, grid_height: usize, width: usize, height: usize) -> Option<(usize, usize)> {
+    if x < 0.0 || y < 0.0 || width == 0 || height == 0 {
+        return None;
+    }
+    let gx = (x * grid_width as f32 / width as f32) as usize;
+    let gy = (y * grid_height as f32 / height as f32) as usize;
+    (gx < grid_width && gy < grid_height).then_some((gx, gy))
+}
+
+/// Sharp/Smooth selector for a sim's display settings
+pub fn grid_filter_ui(ui: &mut egui::Ui, filter: &mut GridFilter) -> bool {
+    let mut changed = false;
+    ui.horizontal(|ui| {
+        ui.label("Cell Filter:");
+        for option in GridFilter::all() {
+            changed |= ui.selectable_value(filter, option, option.name()).clicked();
+        }
+    });
+    changed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The cell index a pixel was colored from, encoded in its color
+    fn cell_of(c: Color) -> usize {
+        c.r as usize | (c.g as usize) << 8 | (c.b as usize) << 16
+    }
+
+    #[test]
+    fn sharp_grid_fills_canvases_that_are_not_a_multiple_of_it() {
+        for (grid_width, grid_height, width, height) in [(120, 90, 800, 600), (7, 5, 100, 33), (200, 150, 801, 599), (3, 4, 10, 10)] {
+            let pixels = render_grid(grid_width, grid_height, width, height, GridFilter::Sharp, |idx| {
+                Color::from_rgb(idx as u8, (idx >> 8) as u8, (idx >> 16) as u8)
+            });
+            assert_eq!(pixels.len(), width * height);
+
+            let mut seen = vec![false; grid_width * grid_height];
+            for (i, &c) in pixels.iter().enumerate() {
+                let cell = cell_of(c);
+                assert!(cell < seen.len(), "{grid_width}x{grid_height} on {width}x{height}: pixel {i} maps past the grid");
+                seen[cell] = true;
+            }
+            // No dead band at the right or bottom: every cell, the last
+            // row and column included, is on screen
+            assert!(seen.iter().all(|&s| s), "{grid_width}x{grid_height} on {width}x{height} leaves cells undrawn");
+
+            // Pointer positions map back to the cell drawn there
+            for (i, &c) in pixels.iter().enumerate() {
+                let (px, py) = (i % width, i / width);
+                let cell = cell_at(px as f32 + 0.5, py as f32 + 0.5, grid_width, grid_height, width, height);
+                assert_eq!(cell, Some((cell_of(c) % grid_width, cell_of(c) / grid_width)));
+            }
+        }
+    }
+}
@@ -1,4 +1,29 @@
-use crate::{ColorScheme, Simulation3D};
+use crate::color_ui::color_scheme_combo;
+use crate::nudge::Nudge;
+use crate::poincare::{PoincareSection, SectionAxis};
+use crate::seed::{SeedEdit, SeedPoint, SeedPreset};
+use crate::trajectory::{TrailEmitter, TrajectoryColoring};
+use crate::{Color, ColorScheme, Simulation3D};
+
+/// Seed points, with what the orbit does from each under the default
+/// parameters
+const SEEDS: [SeedPreset; 3] = [
+    SeedPreset {
+        name: "Near Origin",
+        point: [-1.0, 0.0, 0.0],
+        behavior: "Spirals out of the unstable origin onto the three lobes",
+    },
+    SeedPreset {
+        name: "Near the Diagonal",
+        point: [1.0, 1.0, 1.05],
+        behavior: "Dives along the x = y = z diagonal almost into the origin, then the small offset grows and flings it onto the lobes",
+    },
+    SeedPreset {
+        name: "Far Out",
+        point: [-5.0, 0.0, 0.0],
+        behavior: "Starts beyond the lobes and is captured almost at once",
+    },
+];
 
 pub struct HalvorsenAttractor {
     pub a: f32,
@@ -6,6 +31,10 @@ pub struct HalvorsenAttractor {
     pub point_count: usize,
     pub trail_length: usize,
     pub color_scheme: ColorScheme,
+    pub trajectory_coloring: TrajectoryColoring,
+    pub emitter: TrailEmitter,
+    pub section: PoincareSection,
+    pub seed: SeedPoint,
     pub scale: f32,
     points: Vec<[f32; 3]>,
     x: f32,
@@ -21,6 +50,10 @@ impl Default for HalvorsenAttractor {
             point_count: 5000,
             trail_length: 5000,
             color_scheme: ColorScheme::Plasma,
+            trajectory_coloring: TrajectoryColoring::Index,
+            emitter: TrailEmitter::new(),
+            section: PoincareSection::new(SectionAxis::Z, 0.0),
+            seed: SeedPoint::new(8.0, &SEEDS),
             scale: 20.0,
             points: Vec::new(),
             x: -1.0,
@@ -34,6 +67,15 @@ impl HalvorsenAttractor {
     pub fn new() -> Self {
         Self::default()
     }
+
+    /// Halvorsen equations
+    fn derivatives(&self, [x, y, z]: [f32; 3]) -> [f32; 3] {
+        [
+            -self.a * x - 4.0 * y - 4.0 * z - y * y,
+            -self.a * y - 4.0 * z - 4.0 * x - z * z,
+            -self.a * z - 4.0 * x - 4.0 * y - x * x,
+        ]
+    }
 }
 
 impl Simulation3D for HalvorsenAttractor {
@@ -41,26 +83,27 @@ impl Simulation3D for HalvorsenAttractor {
         "Halvorsen Attractor"
     }
 
+    fn equations(&self) -> Option<&str> {
+        Some(
+            "dx/dt = −a x − 4y − 4z − y²\n\
+             dy/dt = −a y − 4z − 4x − z²\n\
+             dz/dt = −a z − 4x − 4y − x²",
+        )
+    }
+
     fn step(&mut self, _dt: f32) {
         for _ in 0..10 {
-            // Halvorsen equations
-            let dx = -self.a * self.x - 4.0 * self.y - 4.0 * self.z - self.y * self.y;
-            let dy = -self.a * self.y - 4.0 * self.z - 4.0 * self.x - self.z * self.z;
-            let dz = -self.a * self.z - 4.0 * self.x - 4.0 * self.y - self.x * self.x;
+            let [dx, dy, dz] = self.derivatives([self.x, self.y, self.z]);
 
+            let prev = [self.x, self.y, self.z];
             self.x += dx * self.dt;
             self.y += dy * self.dt;
             self.z += dz * self.dt;
+            self.section.record(prev, [self.x, self.y, self.z]);
 
-            self.points.push([
-                self.x * self.scale,
-                self.y * self.scale,
-                self.z * self.scale,
-            ]);
-
-            if self.points.len() > self.trail_length {
-                self.points.remove(0);
-            }
+            let scale = self.scale;
+            let next = [self.x * scale, self.y * scale, self.z * scale];
+            self.emitter.push(&mut self.points, prev.map(|v| v * scale), next, self.trail_length);
         }
     }
 
@@ -68,6 +111,14 @@ impl Simulation3D for HalvorsenAttractor {
         self.points.clone()
     }
 
+    fn get_colors(&self) -> Option<Vec<Color>> {
+        self.emitter.colors(&self.points, self.trajectory_coloring, &self.color_scheme)
+    }
+
+    fn poincare_section(&self) -> Option<&PoincareSection> {
+        Some(&self.section)
+    }
+
     fn ui_parameters(&mut self, ui: &mut egui::Ui) -> bool {
         let mut changed = false;
 
@@ -77,8 +128,10 @@ impl Simulation3D for HalvorsenAttractor {
             .default_open(true)
             .show(ui, |ui| {
                 changed |= ui.add(egui::Slider::new(&mut self.a, 0.5..=3.0)
+                    .nudge(ui, 0.02)
                     .text("a")).changed();
                 changed |= ui.add(egui::Slider::new(&mut self.dt, 0.001..=0.02)
+                    .nudge(ui, 0.0001)
                     .text("Time Step")).changed();
             });
 
@@ -86,24 +139,31 @@ impl Simulation3D for HalvorsenAttractor {
             .default_open(true)
             .show(ui, |ui| {
                 changed |= ui.add(egui::Slider::new(&mut self.trail_length, 100..=10000)
+                    .nudge(ui, 1.0)
                     .text("Trail Length")).changed();
+                changed |= self.emitter.ui(ui, &mut self.points, self.trail_length);
                 changed |= ui.add(egui::Slider::new(&mut self.scale, 5.0..=50.0)
+                    .nudge(ui, 0.2)
                     .text("Scale")).changed();
             });
 
         egui::CollapsingHeader::new("🎨 Color Settings")
             .show(ui, |ui| {
-                egui::ComboBox::from_label("Color Scheme")
-                    .selected_text(self.color_scheme.name())
+                changed |= color_scheme_combo(ui, &mut self.color_scheme);
+
+                egui::ComboBox::from_label("Color By")
+                    .selected_text(self.trajectory_coloring.name())
                     .show_ui(ui, |ui| {
-                        for scheme in ColorScheme::all() {
-                            if ui.selectable_value(&mut self.color_scheme, scheme, scheme.name()).clicked() {
+                        for coloring in TrajectoryColoring::all() {
+                            if ui.selectable_value(&mut self.trajectory_coloring, coloring, coloring.name()).clicked() {
                                 changed = true;
                             }
                         }
                     });
             });
 
+        self.section.ui(ui, -15.0..=15.0, &self.points);
+
         egui::CollapsingHeader::new("🎯 Presets")
             .show(ui, |ui| {
                 if ui.button("Classic").clicked() {
@@ -122,6 +182,17 @@ impl Simulation3D for HalvorsenAttractor {
                 }
             });
 
+        let edit = self.seed.ui(ui);
+        if edit == SeedEdit::Randomize {
+            if let Some(point) = self.seed.random(|p| self.derivatives(p), self.dt) {
+                self.seed.point = point;
+            }
+        }
+        if edit != SeedEdit::None {
+            self.reset();
+            changed = true;
+        }
+
         if ui.button("🔄 Reset").clicked() {
             self.reset();
             changed = true;
@@ -132,8 +203,8 @@ impl Simulation3D for HalvorsenAttractor {
 
     fn reset(&mut self) {
         self.points.clear();
-        self.x = -1.0;
-        self.y = 0.0;
-        self.z = 0.0;
+        self.emitter.clear();
+        self.section.clear();
+        [self.x, self.y, self.z] = self.seed.start(|p| self.derivatives(p));
     }
 }
@@ -1,8 +1,53 @@
-use crate::{Color, ColorScheme, Simulation2D};
+use crate::color_ui::{color_scheme_combo, PaletteTransform};
+use crate::escape_time::{channel_offsets_ui, read_channel_offsets, colorize, iteration_histogram_ui, location_ui, scroll_zoom, EscapeKey, EscapeTimeCache, MAX_ZOOM};
+use crate::nudge::Nudge;
+use crate::state::{read, read_clamped};
+use crate::{Color, ColorScheme, PlaneView, RenderCtx, Simulation2D};
 use num_complex::Complex64;
+use serde_json::{json, Value};
+
+/// Named values of c as (real, imaginary)
+const PARAMETERS: [(&str, f64, f64); 5] = [
+    ("Dendrite", -0.4, 0.6),
+    ("San Marco Dragon", -0.75, 0.0),
+    ("Siegel Disk", -0.391, -0.587),
+    ("Douady's Rabbit", -0.123, 0.745),
+    ("Galaxy", 0.285, 0.01),
+];
+
+/// Closed curve that `c` follows when animating
+#[derive(Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+pub enum JuliaPath {
+    Circle,
+    Cardioid, // Boundary of the Mandelbrot main cardioid
+}
+
+impl JuliaPath {
+    pub fn all() -> Vec<JuliaPath> {
+        vec![JuliaPath::Circle, JuliaPath::Cardioid]
+    }
 
-#[cfg(feature = "parallel")]
-use rayon::prelude::*;
+    pub fn name(&self) -> &str {
+        match self {
+            JuliaPath::Circle => "Circle",
+            JuliaPath::Cardioid => "Main Cardioid",
+        }
+    }
+
+    /// Point on the unit-scale path at angle `t`
+    fn point(&self, t: f64) -> (f64, f64) {
+        match self {
+            JuliaPath::Circle => (t.cos(), t.sin()),
+            JuliaPath::Cardioid => {
+                // c = e^{it}/2 - e^{2it}/4
+                (
+                    t.cos() / 2.0 - (2.0 * t).cos() / 4.0,
+                    t.sin() / 2.0 - (2.0 * t).sin() / 4.0,
+                )
+            }
+        }
+    }
+}
 
 pub struct Julia {
     pub max_iterations: u32,
@@ -16,10 +61,17 @@ pub struct Julia {
     pub color_scheme: ColorScheme,
     pub smooth_coloring: bool,
     pub invert_colors: bool,
-    pub color_offset: f32,
+    pub palette_transform: PaletteTransform,
+    /// Per-channel radial shift in pixels; all zero is normal coloring
+    pub channel_offsets: [f32; 3],
     pub animate: bool,
+    pub animation_path: JuliaPath,
+    pub animation_radius: f64,
+    pub animation_center_real: f64,
+    pub animation_center_imag: f64,
+    pub animation_speed: f32,
     animation_time: f32,
-    animation_radius: f64,
+    iteration_cache: EscapeTimeCache,
 }
 
 impl Default for Julia {
@@ -36,10 +88,16 @@ impl Default for Julia {
             color_scheme: ColorScheme::Ultra,
             smooth_coloring: true,
             invert_colors: false,
-            color_offset: 0.0,
+            palette_transform: PaletteTransform::default(),
+            channel_offsets: [0.0; 3],
             animate: false,
-            animation_time: 0.0,
+            animation_path: JuliaPath::Circle,
             animation_radius: 0.7885,
+            animation_center_real: 0.0,
+            animation_center_imag: 0.0,
+            animation_speed: 0.3,
+            animation_time: 0.0,
+            iteration_cache: EscapeTimeCache::default(),
         }
     }
 }
@@ -57,12 +115,9 @@ impl Julia {
         for i in 0..self.max_iterations {
             let z_norm_sqr = z.norm_sqr();
             if z_norm_sqr > escape_sqr {
-                if self.smooth_coloring {
-                    let log_zn = z_norm_sqr.ln() / 2.0;
-                    let nu = (log_zn / self.escape_radius.ln()).ln() / 2_f64.ln();
-                    return (i, i as f64 + 1.0 - nu);
-                }
-                return (i, i as f64);
+                let log_zn = z_norm_sqr.ln() / 2.0;
+                let nu = (log_zn / self.escape_radius.ln()).ln() / 2_f64.ln();
+                return (i, i as f64 + 1.0 - nu);
             }
 
             if (self.power - 2.0).abs() < 0.001 {
@@ -74,6 +129,15 @@ impl Julia {
         (self.max_iterations, self.max_iterations as f64)
     }
 
+    /// Everything that changes the iteration counts of a `width`x`height` view
+    fn escape_key(&self, width: usize, height: usize) -> EscapeKey {
+        EscapeKey {
+            width,
+            height,
+            params: vec![self.c_real, self.c_imag, self.center_x, self.center_y, self.zoom, self.power, self.escape_radius, self.max_iterations as f64],
+        }
+    }
+
     fn pixel_to_complex(&self, x: usize, y: usize, width: usize, height: usize) -> Complex64 {
         let aspect = width as f64 / height as f64;
         let range = 4.0 / self.zoom;
@@ -84,13 +148,14 @@ impl Julia {
         Complex64::new(real, imag)
     }
 
-    fn iterations_to_color(&self, iterations: u32, smooth_iter: f64) -> Color {
+    fn iterations_to_color(&self, iterations: u32, smooth_iter: f64, ctx: &RenderCtx) -> Color {
         if iterations == self.max_iterations {
             return Color::BLACK;
         }
 
-        let t = ((smooth_iter / self.max_iterations as f64) as f32 + self.color_offset) % 1.0;
-        let color = self.color_scheme.map(t, self.smooth_coloring);
+        let smooth_iter = if self.smooth_coloring { smooth_iter } else { iterations as f64 };
+        let t = (smooth_iter / self.max_iterations as f64) as f32;
+        let color = self.palette_transform.map(&self.color_scheme, ctx.cycle_palette(t), self.smooth_coloring);
 
         if self.invert_colors {
             Color::from_rgb(255 - color.r, 255 - color.g, 255 - color.b)
@@ -105,22 +170,30 @@ impl Simulation2D for Julia {
         "Julia Set"
     }
 
+    fn equations(&self) -> Option<&str> {
+        Some(
+            "z ← zᵖ + c,  z₀ = pixel, c fixed\n\
+             Inside if |z| never exceeds the escape radius\n\
+             Color = iterations before escape",
+        )
+    }
+
     fn compute(&self, width: usize, height: usize) -> Vec<Color> {
-        #[cfg(feature = "parallel")]
-        let iter = (0..height).into_par_iter();
-        #[cfg(not(feature = "parallel"))]
-        let iter = (0..height).into_iter();
-
-        iter.flat_map(|y| {
-            (0..width)
-                .map(|x| {
-                    let z = self.pixel_to_complex(x, y, width, height);
-                    let (iterations, smooth_iter) = self.julia_iterations(z);
-                    self.iterations_to_color(iterations, smooth_iter)
-                })
-                .collect::<Vec<_>>()
-        })
-        .collect()
+        self.compute_ctx(&RenderCtx::new(width, height))
+    }
+
+    fn compute_ctx(&self, ctx: &RenderCtx) -> Vec<Color> {
+        let (width, height) = (ctx.width, ctx.height);
+        // Colors are cheap; only re-iterate when the geometry changes
+        let key = self.escape_key(width, height);
+        let iterations = self.iteration_cache.get_or_compute(key, self.max_iterations, |x, y| {
+            self.julia_iterations(self.pixel_to_complex(x, y, width, height))
+        });
+
+        let pixels = colorize(&iterations, width, height, self.channel_offsets, |i, smooth_iter| {
+            self.iterations_to_color(i, smooth_iter, ctx)
+        });
+        ctx.over_background(pixels)
     }
 
     fn ui_parameters(&mut self, ui: &mut egui::Ui) -> bool {
@@ -132,12 +205,15 @@ impl Simulation2D for Julia {
             .default_open(true)
             .show(ui, |ui| {
                 changed |= ui.add(egui::Slider::new(&mut self.max_iterations, 10..=1000)
+                    .nudge(ui, 1.0)
                     .text("Max Iterations")).changed();
 
                 changed |= ui.add(egui::Slider::new(&mut self.power, 2.0..=8.0)
+                    .nudge(ui, 0.05)
                     .text("Power (z^n)")).changed();
 
                 changed |= ui.add(egui::Slider::new(&mut self.escape_radius, 2.0..=10.0)
+                    .nudge(ui, 0.05)
                     .text("Escape Radius")).changed();
             });
 
@@ -163,37 +239,58 @@ impl Simulation2D for Julia {
                 }
 
                 if self.animate {
-                    ui.add(egui::Slider::new(&mut self.animation_radius, 0.1..=1.0)
+                    egui::ComboBox::from_label("Path")
+                        .selected_text(self.animation_path.name())
+                        .show_ui(ui, |ui| {
+                            for path in JuliaPath::all() {
+                                ui.selectable_value(&mut self.animation_path, path, path.name());
+                            }
+                        });
+
+                    ui.add(egui::Slider::new(&mut self.animation_radius, 0.1..=1.5)
+                        .nudge(ui, 0.01)
                         .text("Animation Radius"));
+                    ui.horizontal(|ui| {
+                        ui.label("Path Center:");
+                        ui.add(egui::DragValue::new(&mut self.animation_center_real)
+                            .speed(0.001)
+                            .range(-2.0..=2.0));
+                        ui.add(egui::DragValue::new(&mut self.animation_center_imag)
+                            .speed(0.001)
+                            .range(-2.0..=2.0)
+                            .suffix("i"));
+                    });
+                    ui.add(egui::Slider::new(&mut self.animation_speed, 0.01..=2.0)
+                        .nudge(ui, 0.005)
+                        .logarithmic(true)
+                        .text("Speed"));
                 }
             });
 
         egui::CollapsingHeader::new("🎨 Color Settings")
             .default_open(true)
             .show(ui, |ui| {
-                egui::ComboBox::from_label("Color Scheme")
-                    .selected_text(self.color_scheme.name())
-                    .show_ui(ui, |ui| {
-                        for scheme in ColorScheme::all() {
-                            if ui.selectable_value(&mut self.color_scheme, scheme, scheme.name()).clicked() {
-                                changed = true;
-                            }
-                        }
-                    });
+                changed |= color_scheme_combo(ui, &mut self.color_scheme);
 
                 changed |= ui.checkbox(&mut self.smooth_coloring, "Smooth Coloring").changed();
                 changed |= ui.checkbox(&mut self.invert_colors, "Invert Colors").changed();
-                changed |= ui.add(egui::Slider::new(&mut self.color_offset, 0.0..=1.0)
-                    .text("Color Offset")).changed();
+                changed |= self.palette_transform.ui(ui);
             });
 
+        changed |= channel_offsets_ui(ui, &mut self.channel_offsets);
+
+        iteration_histogram_ui(ui, &self.iteration_cache);
+
         egui::CollapsingHeader::new("🔍 Navigation")
             .default_open(true)
             .show(ui, |ui| {
-                changed |= ui.add(egui::Slider::new(&mut self.zoom, 0.1..=1000.0)
+                changed |= ui.add(egui::Slider::new(&mut self.zoom, 0.1..=MAX_ZOOM)
+                    .nudge(ui, 0.05)
                     .logarithmic(true)
                     .text("Zoom")).changed();
 
+                location_ui(ui, self.center_x, self.center_y, self.zoom);
+
                 if ui.button("🏠 Reset").clicked() {
                     *self = Self::default();
                     changed = true;
@@ -202,65 +299,155 @@ impl Simulation2D for Julia {
 
         egui::CollapsingHeader::new("📍 Interesting Parameters")
             .show(ui, |ui| {
-                if ui.button("Dendrite").clicked() {
-                    self.c_real = -0.4;
-                    self.c_imag = 0.6;
-                    changed = true;
-                }
-                if ui.button("San Marco Dragon").clicked() {
-                    self.c_real = -0.75;
-                    self.c_imag = 0.0;
-                    changed = true;
-                }
-                if ui.button("Siegel Disk").clicked() {
-                    self.c_real = -0.391;
-                    self.c_imag = -0.587;
-                    changed = true;
-                }
-                if ui.button("Douady's Rabbit").clicked() {
-                    self.c_real = -0.123;
-                    self.c_imag = 0.745;
-                    changed = true;
-                }
-                if ui.button("Galaxy").clicked() {
-                    self.c_real = 0.285;
-                    self.c_imag = 0.01;
-                    changed = true;
+                for (name, re, im) in PARAMETERS {
+                    if ui.button(name).clicked() {
+                        self.c_real = re;
+                        self.c_imag = im;
+                        changed = true;
+                    }
                 }
             });
 
-        // Handle animation
-        if self.animate {
-            let dt = ui.input(|i| i.stable_dt);
-            self.animation_time += dt * 0.3;
-            self.c_real = self.animation_radius * self.animation_time.cos() as f64;
-            self.c_imag = self.animation_radius * self.animation_time.sin() as f64;
-            changed = true;
+        changed
+    }
+
+    fn update(&mut self, dt: f32, _width: usize, _height: usize) -> bool {
+        if !self.animate {
+            return false;
         }
 
-        changed
+        // Wrap so the loop is seamless and the angle never loses f32 precision
+        self.animation_time = (self.animation_time + dt * self.animation_speed)
+            .rem_euclid(std::f32::consts::TAU);
+        let (re, im) = self.animation_path.point(self.animation_time as f64);
+        self.c_real = self.animation_center_real + self.animation_radius * re;
+        self.c_imag = self.animation_center_imag + self.animation_radius * im;
+        true
+    }
+
+    fn iteration_cache(&self) -> Option<&EscapeTimeCache> {
+        Some(&self.iteration_cache)
+    }
+
+    fn render_cost(&self, width: usize, height: usize) -> Option<f64> {
+        Some(self.iteration_cache.render_cost(&self.escape_key(width, height), self.max_iterations))
     }
 
     fn supports_zoom(&self) -> bool {
         true
     }
 
-    fn adjust_center(&mut self, dx: f64, dy: f64, width: usize, height: usize) {
-        // Convert pixel delta to world space delta
-        let aspect = width as f64 / height as f64;
-        let view_width = 4.0 / self.zoom;
-        let view_height = view_width / aspect;
+    fn plane_view(&self, _width: usize, height: usize) -> Option<PlaneView> {
+        // Matches pixel_to_complex: the view is 4 / zoom tall
+        Some(PlaneView {
+            center_x: self.center_x,
+            center_y: self.center_y,
+            units_per_pixel: 4.0 / self.zoom / height.max(1) as f64,
+            complex: true,
+        })
+    }
 
-        self.center_x -= dx * view_width / width as f64;
-        self.center_y -= dy * view_height / height as f64;
+    fn adjust_center(&mut self, dx: f64, dy: f64, _width: usize, height: usize) {
+        // Pixels are square, so both axes move by plane_view's units per pixel
+        let units_per_pixel = 4.0 / self.zoom / height.max(1) as f64;
+        self.center_x -= dx * units_per_pixel;
+        self.center_y -= dy * units_per_pixel;
     }
 
     fn adjust_zoom(&mut self, delta: f64) {
-        let zoom_factor = 1.0 + delta * 0.001;
-        self.zoom = (self.zoom * zoom_factor).clamp(0.1, 1000.0);
+        self.zoom = scroll_zoom(self.zoom, delta);
     }
 
     fn get_zoom(&self) -> f64 {
         self.zoom
     }
+
+    fn preset_names(&self) -> Vec<&'static str> {
+        PARAMETERS.iter().map(|p| p.0).collect()
+    }
+
+    fn load_preset(&mut self, name: &str) -> bool {
+        let Some(&(_, re, im)) = PARAMETERS.iter().find(|p| p.0 == name) else {
+            return false;
+        };
+        // A preset picks a specific c, so stop the animation from moving it
+        self.animate = false;
+        self.c_real = re;
+        self.c_imag = im;
+        true
+    }
+
+    fn save_state(&self) -> Value {
+        json!({
+            "max_iterations": self.max_iterations,
+            "c_real": self.c_real,
+            "c_imag": self.c_imag,
+            "zoom": self.zoom,
+            "center_x": self.center_x,
+            "center_y": self.center_y,
+            "power": self.power,
+            "escape_radius": self.escape_radius,
+            "color_scheme": self.color_scheme,
+            "smooth_coloring": self.smooth_coloring,
+            "invert_colors": self.invert_colors,
+            "color_offset": self.palette_transform.offset,
+            "reverse_palette": self.palette_transform.reversed,
+            "channel_offsets": self.channel_offsets,
+            "animate": self.animate,
+            "animation_path": self.animation_path,
+            "animation_radius": self.animation_radius,
+            "animation_center_real": self.animation_center_real,
+            "animation_center_imag": self.animation_center_imag,
+            "animation_speed": self.animation_speed,
+            "animation_time": self.animation_time,
+        })
+    }
+
+    fn load_state(&mut self, state: &Value) {
+        read_clamped(state, "max_iterations", &mut self.max_iterations, 10..=1000);
+        read_clamped(state, "c_real", &mut self.c_real, -2.0..=2.0);
+        read_clamped(state, "c_imag", &mut self.c_imag, -2.0..=2.0);
+        read_clamped(state, "zoom", &mut self.zoom, 0.1..=MAX_ZOOM);
+        read_clamped(state, "center_x", &mut self.center_x, -4.0..=4.0);
+        read_clamped(state, "center_y", &mut self.center_y, -4.0..=4.0);
+        read_clamped(state, "power", &mut self.power, 2.0..=8.0);
+        read_clamped(state, "escape_radius", &mut self.escape_radius, 2.0..=10.0);
+        read(state, "color_scheme", &mut self.color_scheme);
+        read(state, "smooth_coloring", &mut self.smooth_coloring);
+        read(state, "invert_colors", &mut self.invert_colors);
+        read_clamped(state, "color_offset", &mut self.palette_transform.offset, 0.0..=1.0);
+        read(state, "reverse_palette", &mut self.palette_transform.reversed);
+        read_channel_offsets(state, &mut self.channel_offsets);
+        read(state, "animate", &mut self.animate);
+        read(state, "animation_path", &mut self.animation_path);
+        read_clamped(state, "animation_radius", &mut self.animation_radius, 0.1..=1.5);
+        read_clamped(state, "animation_center_real", &mut self.animation_center_real, -2.0..=2.0);
+        read_clamped(state, "animation_center_imag", &mut self.animation_center_imag, -2.0..=2.0);
+        read_clamped(state, "animation_speed", &mut self.animation_speed, 0.01..=2.0);
+        read_clamped(state, "animation_time", &mut self.animation_time, 0.0..=std::f32::consts::TAU);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn smooth_count_is_continuous_where_the_band_changes() {
+        // Walk outward from just past the set's edge
+        let julia = Julia::new();
+        let samples: Vec<(u32, f64)> = (0..=4000)
+            .map(|k| julia.julia_iterations(Complex64::new(1.27 + k as f64 * 0.0005, 0.0)))
+            .collect();
+
+        let mut bands = 0;
+        for pair in samples.windows(2) {
+            let ((i0, s0), (i1, s1)) = (pair[0], pair[1]);
+            if i0 != i1 {
+                bands += 1;
+                assert!((s0 - s1).abs() < 0.3, "smooth count jumps from {s0} to {s1} where the band goes {i0} -> {i1}");
+            }
+        }
+        assert!(bands > 5);
+    }
 }
@@ -1,4 +1,7 @@
-use crate::{Color, ColorScheme, Simulation2D};
+use crate::color_ui::color_scheme_combo;
+use crate::nudge::Nudge;
+use crate::readout;
+use crate::{Color, ColorScheme, RenderCtx, Simulation2D};
 
 #[derive(Clone, Copy, PartialEq)]
 enum Direction {
@@ -141,21 +144,6 @@ impl LangtonsAnt {
 
         self.step_count += 1;
     }
-
-    pub fn reset(&mut self) {
-        // Resize grid if dimensions changed
-        let required_size = self.grid_width * self.grid_height;
-        if self.grid.len() != required_size {
-            self.grid = vec![false; required_size];
-        } else {
-            self.grid.fill(false);
-        }
-        self.ant_x = (self.grid_width / 2) as i32;
-        self.ant_y = (self.grid_height / 2) as i32;
-        self.direction = Direction::Up;
-        self.step_count = 0;
-        self.time_accumulator = 0.0;
-    }
 }
 
 impl Simulation2D for LangtonsAnt {
@@ -164,6 +152,11 @@ impl Simulation2D for LangtonsAnt {
     }
 
     fn compute(&self, width: usize, height: usize) -> Vec<Color> {
+        self.compute_ctx(&RenderCtx::new(width, height))
+    }
+
+    fn compute_ctx(&self, ctx: &RenderCtx) -> Vec<Color> {
+        let (width, height) = (ctx.width, ctx.height);
         let mut colors = Vec::with_capacity(width * height);
 
         let cell_width = width / self.grid_width;
@@ -190,7 +183,7 @@ impl Simulation2D for LangtonsAnt {
                             // Color based on position for rainbow effect
                             let t = (gx as f32 / self.grid_width as f32 +
                                     gy as f32 / self.grid_height as f32) / 2.0;
-                            colors.push(self.color_scheme.map(t, true));
+                            colors.push(self.color_scheme.map(ctx.cycle_palette(t), true));
                         } else {
                             colors.push(Color::WHITE);
                         }
@@ -203,7 +196,22 @@ impl Simulation2D for LangtonsAnt {
             }
         }
 
-        colors
+        ctx.over_background(colors)
+    }
+
+    fn reset(&mut self) {
+        // Resize grid if dimensions changed
+        let required_size = self.grid_width * self.grid_height;
+        if self.grid.len() != required_size {
+            self.grid = vec![false; required_size];
+        } else {
+            self.grid.fill(false);
+        }
+        self.ant_x = (self.grid_width / 2) as i32;
+        self.ant_y = (self.grid_height / 2) as i32;
+        self.direction = Direction::Up;
+        self.step_count = 0;
+        self.time_accumulator = 0.0;
     }
 
     fn ui_parameters(&mut self, ui: &mut egui::Ui) -> bool {
@@ -211,7 +219,7 @@ impl Simulation2D for LangtonsAnt {
 
         ui.heading("Langton's Ant");
 
-        ui.label(format!("Steps: {}", self.step_count));
+        ui.label(format!("Steps: {}", readout::count(self.step_count)));
 
         if self.step_count < 10000 {
             ui.label("Phase: Chaotic (building highway)");
@@ -225,18 +233,21 @@ impl Simulation2D for LangtonsAnt {
             .default_open(true)
             .show(ui, |ui| {
                 if ui.add(egui::Slider::new(&mut self.grid_width, 50..=400)
+                    .nudge(ui, 1.0)
                     .text("Grid Width")).changed() {
                     self.reset();
                     changed = true;
                 }
 
                 if ui.add(egui::Slider::new(&mut self.grid_height, 50..=300)
+                    .nudge(ui, 1.0)
                     .text("Grid Height")).changed() {
                     self.reset();
                     changed = true;
                 }
 
                 ui.add(egui::Slider::new(&mut self.speed, 1.0..=1000.0)
+                    .nudge(ui, 0.5)
                     .logarithmic(true)
                     .text("Steps per second"));
 
@@ -250,15 +261,7 @@ impl Simulation2D for LangtonsAnt {
                 changed |= ui.checkbox(&mut self.trail_color, "Colored Trail").changed();
 
                 if self.trail_color {
-                    egui::ComboBox::from_label("Color Scheme")
-                        .selected_text(self.color_scheme.name())
-                        .show_ui(ui, |ui| {
-                            for scheme in ColorScheme::all() {
-                                if ui.selectable_value(&mut self.color_scheme, scheme, scheme.name()).clicked() {
-                                    changed = true;
-                                }
-                            }
-                        });
+                    changed |= color_scheme_combo(ui, &mut self.color_scheme);
                 }
             });
 
@@ -279,8 +282,11 @@ impl Simulation2D for LangtonsAnt {
             changed = true;
         }
 
-        // Auto-stepping
-        let dt = ui.input(|i| i.stable_dt);
+        changed
+    }
+
+    fn update(&mut self, dt: f32, _width: usize, _height: usize) -> bool {
+        let mut changed = false;
         self.time_accumulator += dt * self.speed;
 
         while self.time_accumulator >= 1.0 {
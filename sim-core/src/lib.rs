@@ -4,6 +4,7 @@ pub mod game_of_life;
 pub mod lorenz;
 pub mod cellular_automaton;
 pub mod burning_ship;
+pub mod quaternion_julia;
 pub mod rossler;
 
 // Additional 3D attractors
@@ -12,6 +13,8 @@ pub mod halvorsen;
 pub mod dadras;
 pub mod thomas;
 pub mod chen;
+pub mod cyclic_symmetric;
+pub mod trajectory;
 
 // Animated 2D simulations
 pub mod double_pendulum;
@@ -51,8 +54,63 @@ pub mod vortex_turbulence;
 pub mod lightning_bolt;
 pub mod fractal_tree_3d;
 
+// 3D fractals
+pub mod mandelbulb;
+pub mod menger_sponge;
+
+// Offscreen rendering
+pub mod preview;
+
+// Poincaré sections of 3D trajectories
+pub mod poincare;
+
+// Starting points for the trajectory attractors
+pub mod seed;
+
+// Shared escape-time fractal helpers
+pub mod escape_time;
+
+// Parameter save/restore helpers
+pub mod state;
+
+// Exposure and tone curves for additive glow
+pub mod tone_map;
+
+// Particle sources for 3D particle sims
+pub mod emitter;
+
+// Color scheme picker with gradient swatches
+pub mod color_ui;
+
+// Color schemes as GIMP, CSS and hex palette files
+pub mod palette_export;
+
+// Fading particle trail colors
+pub mod trail;
+
+// Upscaling cell grids to the canvas
+pub mod grid_render;
+
+// Pause-aware time shared by all sims
+pub mod clock;
+
+// Step history for seekable sims
+pub mod timeline;
+
+// Exact step counts run in batches across frames
+pub mod step_runner;
+
+// Arrow-key steps for sliders
+pub mod nudge;
+
+// Shared number formatting for info readouts
+pub mod readout;
+
+// Constructors for every simulation, in menu order
+pub mod registry;
+
 /// Color representation in RGB format
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct Color {
     pub r: u8,
     pub g: u8,
@@ -105,6 +163,30 @@ impl Color {
         }
     }
 
+    /// Composite a color rendered on black over `background` (a screen
+    /// blend). Black becomes the background, full-intensity channels are kept,
+    /// and anything faded toward black fades toward the background instead.
+    pub fn over_background(self, background: Color) -> Self {
+        let screen = |c: u8, bg: u8| 255 - ((255 - c as u16) * (255 - bg as u16) / 255) as u8;
+        Color {
+            r: screen(self.r, background.r),
+            g: screen(self.g, background.g),
+            b: screen(self.b, background.b),
+        }
+    }
+
+    /// The color with its hue turned by `hue_shift` turns and its
+    /// saturation and value scaled by `saturation` and `brightness`, each
+    /// clamped to 1. The sims' Hue Shift, Saturation and Brightness sliders.
+    pub fn adjust_hsv(self, hue_shift: f32, saturation: f32, brightness: f32) -> Color {
+        let (h, s, v) = Color::rgb_to_hsv_static(self);
+        let new_h = (h + hue_shift * 360.0) % 360.0;
+        let new_s = (s * saturation).clamp(0.0, 1.0);
+        let new_v = (v * brightness).clamp(0.0, 1.0);
+        Color::from_hsv(new_h, new_s, new_v)
+    }
+
+    /// Hue in degrees (0 up to 360), saturation and value (0 to 1)
     pub fn rgb_to_hsv_static(color: Color) -> (f32, f32, f32) {
         let r = color.r as f32 / 255.0;
         let g = color.g as f32 / 255.0;
@@ -117,12 +199,14 @@ impl Color {
         let h = if delta == 0.0 {
             0.0
         } else if max == r {
-            60.0 * (((g - b) / delta) % 6.0)
+            60.0 * ((g - b) / delta)
         } else if max == g {
             60.0 * (((b - r) / delta) + 2.0)
         } else {
             60.0 * (((r - g) / delta) + 4.0)
         };
+        // Reds leaning toward blue come out negative
+        let h = h.rem_euclid(360.0);
 
         let s = if max == 0.0 { 0.0 } else { delta / max };
         let v = max;
@@ -132,7 +216,7 @@ impl Color {
 }
 
 /// Color schemes for fractal visualization
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 pub enum ColorScheme {
     Classic,
     Rainbow,
@@ -161,6 +245,18 @@ pub enum ColorScheme {
     Earth,
     Copper,
     Galaxy,
+    /// User-built gradient: color stops sorted by position (0 to 1), blended
+    /// in the given space
+    Custom(Vec<(f32, Color)>, GradientSpace),
+}
+
+/// How a custom gradient blends between neighbouring stops
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+pub enum GradientSpace {
+    Rgb,
+    /// Around the hue wheel the short way, so red to blue passes through
+    /// magenta rather than gray
+    Hsv,
 }
 
 impl ColorScheme {
@@ -223,6 +319,7 @@ impl ColorScheme {
             ColorScheme::Earth => "Earth",
             ColorScheme::Copper => "Copper",
             ColorScheme::Galaxy => "Galaxy",
+            ColorScheme::Custom(..) => "Custom",
         }
     }
 
@@ -392,8 +489,154 @@ impl ColorScheme {
                     Color::lerp(Color::from_rgb(130, 80, 200), Color::from_rgb(200, 150, 255), (t - 0.66) * 3.0)
                 }
             }
+            ColorScheme::Custom(stops, space) => Self::map_stops(stops, *space, t),
         }
     }
+
+    /// The scheme run backwards: `map(1 - t)`
+    pub fn map_reversed(&self, t: f32, smooth: bool) -> Color {
+        self.map(1.0 - t, smooth)
+    }
+
+    /// The scheme turned by `offset` palette lengths, wrapping past the end
+    /// back to the start. A whole turn lands on 1 rather than 0, so `t` = 1
+    /// keeps the last color with no offset and positions just below a wrap
+    /// blend smoothly into it.
+    pub fn map_rotated(&self, t: f32, offset: f32, smooth: bool) -> Color {
+        let u = t + offset;
+        let wrapped = u.rem_euclid(1.0);
+        let t = if wrapped == 0.0 && u > 0.0 { 1.0 } else { wrapped };
+        self.map(t, smooth)
+    }
+
+    /// A custom gradient at `t`: the end colors beyond the outer stops,
+    /// blended between the two stops around `t` elsewhere
+    fn map_stops(stops: &[(f32, Color)], space: GradientSpace, t: f32) -> Color {
+        let Some(&(first_t, first)) = stops.first() else {
+            return Color::BLACK;
+        };
+        if t <= first_t {
+            return first;
+        }
+
+        for pair in stops.windows(2) {
+            let ((t0, a), (t1, b)) = (pair[0], pair[1]);
+            if t <= t1 {
+                let f = if t1 > t0 { (t - t0) / (t1 - t0) } else { 1.0 };
+                return match space {
+                    GradientSpace::Rgb => Color::lerp(a, b, f),
+                    GradientSpace::Hsv => {
+                        let (h0, s0, v0) = Color::rgb_to_hsv_static(a);
+                        let (h1, s1, v1) = Color::rgb_to_hsv_static(b);
+                        // Shortest way around the wheel, from -180 to 180
+                        let dh = (h1 - h0 + 540.0).rem_euclid(360.0) - 180.0;
+                        Color::from_hsv((h0 + dh * f).rem_euclid(360.0), s0 + (s1 - s0) * f, v0 + (v1 - v0) * f)
+                    }
+                };
+            }
+        }
+        stops[stops.len() - 1].1
+    }
+}
+
+/// Everything a 2D render may depend on beyond the sim's own parameters
+#[derive(Debug, Clone, Copy)]
+pub struct RenderCtx {
+    pub width: usize,
+    pub height: usize,
+    /// Seconds since the viewer started
+    pub time: f64,
+    /// Shared seed for sims that want reproducible randomness per frame
+    pub seed: u64,
+    /// Canvas color behind the sim
+    pub background: Color,
+    /// Palette cycling offset, in palette lengths. Sims that color through a
+    /// `ColorScheme` at render time pass their palette position through
+    /// `cycle_palette`; 0 leaves colors unchanged.
+    pub palette_phase: f32,
+    /// Exposure and curve for glow and density renders, which sum light
+    /// past white; 1 and `Clamp` clip as before
+    pub exposure: f32,
+    pub tone_map: tone_map::ToneMap,
+}
+
+impl RenderCtx {
+    /// A context with only a size: time and seed zero, black background
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            time: 0.0,
+            seed: 0,
+            background: Color::BLACK,
+            palette_phase: 0.0,
+            exposure: 1.0,
+            tone_map: tone_map::ToneMap::Clamp,
+        }
+    }
+
+    /// Summed light, 255 per channel per unit of white, as a display color
+    /// through the exposure and tone curve
+    pub fn glow(&self, [r, g, b]: [f32; 3]) -> Color {
+        Color::from_rgb(
+            self.tone_map.byte(r, self.exposure),
+            self.tone_map.byte(g, self.exposure),
+            self.tone_map.byte(b, self.exposure),
+        )
+    }
+
+    /// Palette position `t` (0..1) shifted by `palette_phase`. Past either
+    /// end the palette is mirrored rather than wrapped, so cycling a scheme
+    /// like Fire never jumps from its last color straight to its first.
+    pub fn cycle_palette(&self, t: f32) -> f32 {
+        if self.palette_phase == 0.0 {
+            return t;
+        }
+        let u = (t + self.palette_phase).rem_euclid(2.0);
+        1.0 - (u - 1.0).abs()
+    }
+
+    /// Composite a sim's pixels over the background, for sims that draw on
+    /// black
+    pub fn over_background(&self, mut pixels: Vec<Color>) -> Vec<Color> {
+        if self.background != Color::BLACK {
+            for c in &mut pixels {
+                *c = c.over_background(self.background);
+            }
+        }
+        pixels
+    }
+}
+
+/// Uniform linear map between image pixels and a sim's plane coordinates
+/// (e.g. the complex plane of a fractal). Plane y grows with pixel y.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PlaneView {
+    /// Plane point at the center of the image
+    pub center_x: f64,
+    pub center_y: f64,
+    /// Plane units per image pixel, on both axes
+    pub units_per_pixel: f64,
+    /// The plane is the complex plane, so the vertical axis is imaginary
+    pub complex: bool,
+}
+
+impl PlaneView {
+    /// Plane point at pixel `(x, y)` of a `width`x`height` image
+    pub fn to_plane(&self, x: f64, y: f64, width: usize, height: usize) -> (f64, f64) {
+        (
+            self.center_x + (x - width as f64 / 2.0) * self.units_per_pixel,
+            self.center_y + (y - height as f64 / 2.0) * self.units_per_pixel,
+        )
+    }
+
+    /// Pixel position of plane point `(u, v)`
+    pub fn to_pixel(&self, u: f64, v: f64, width: usize, height: usize) -> (f64, f64) {
+        (
+            width as f64 / 2.0 + (u - self.center_x) / self.units_per_pixel,
+            height as f64 / 2.0 + (v - self.center_y) / self.units_per_pixel,
+        )
+    }
 }
 
 /// Trait for 2D simulations that produce colored images
@@ -404,9 +647,24 @@ pub trait Simulation2D: Send + Sync {
     /// Compute the simulation and return pixel data
     fn compute(&self, width: usize, height: usize) -> Vec<Color>;
 
+    /// Compute with the full render context. The default renders with
+    /// `compute` and composites the result over the background; sims that
+    /// draw the background themselves, or use the time or seed, override it.
+    fn compute_ctx(&self, ctx: &RenderCtx) -> Vec<Color> {
+        ctx.over_background(self.compute(ctx.width, ctx.height))
+    }
+
     /// Get UI parameters for egui controls
     fn ui_parameters(&mut self, ui: &mut egui::Ui) -> bool;
 
+    /// Advance by `dt` seconds of sim time (0 while paused) for a view of
+    /// `width`x`height`. Called once a frame whether or not the controls are
+    /// showing, so a sim runs without an egui context. Returns true if the
+    /// picture changed.
+    fn update(&mut self, _dt: f32, _width: usize, _height: usize) -> bool {
+        false
+    }
+
     /// Check if this simulation supports zoom/pan (default: false)
     fn supports_zoom(&self) -> bool {
         false
@@ -426,6 +684,114 @@ pub trait Simulation2D: Send + Sync {
     fn adjust_zoom(&mut self, _delta: f64) {
         // Default: do nothing
     }
+
+    /// Rough work for a `width`x`height` render, in iterations of the sim's
+    /// inner loop, so the app can warn before a render that would take many
+    /// seconds. Only sims whose settings can push the cost up by orders of
+    /// magnitude report one (default: `None`).
+    fn render_cost(&self, _width: usize, _height: usize) -> Option<f64> {
+        None
+    }
+
+    /// Whether `auto_fit` is implemented (default: false)
+    fn supports_auto_fit(&self) -> bool {
+        false
+    }
+
+    /// Set zoom/center so the pattern fills a `width`x`height` canvas.
+    /// Called on demand, not every frame.
+    fn auto_fit(&mut self, _width: usize, _height: usize) {
+        // Default: do nothing
+    }
+
+    /// Whether `step_count`/`seek` are implemented (default: false). Only
+    /// sims whose run replays exactly from its initial state support it.
+    fn supports_seek(&self) -> bool {
+        false
+    }
+
+    /// Steps taken since the initial state
+    fn step_count(&self) -> u64 {
+        0
+    }
+
+    /// Jump to `step`, replaying from the initial state (or a saved
+    /// checkpoint) with the current parameters. The result is the same as
+    /// running to that step.
+    fn seek(&mut self, _step: u64) {
+        // Default: do nothing
+    }
+
+    /// Raw scalar field behind the image as `(values, width, height)`, for
+    /// false-color heatmap display. Sims without one return `None`.
+    fn scalar_field(&self) -> Option<(Vec<f32>, usize, usize)> {
+        None
+    }
+
+    /// How a `width`x`height` render maps onto the sim's plane, for drawing
+    /// coordinate axes over it. Sims without a fixed plane return `None`.
+    fn plane_view(&self, _width: usize, _height: usize) -> Option<PlaneView> {
+        None
+    }
+
+    /// Governing equations in plain Unicode math, shown in a collapsible
+    /// panel for reference only. Sims without a compact formulation return
+    /// `None`.
+    fn equations(&self) -> Option<&str> {
+        None
+    }
+
+    /// The sim's escape-time iteration cache, if it has one. A copy rendered
+    /// on a worker thread hands its buffer back through it.
+    fn iteration_cache(&self) -> Option<&escape_time::EscapeTimeCache> {
+        None
+    }
+
+    /// Paint at a pointer position given in `compute` pixel coordinates.
+    /// `erase` is set while the secondary button is held. Only called for
+    /// sims that don't support zoom/pan. Returns true if the image needs
+    /// recomputing.
+    fn paint_at(&mut self, _x: f32, _y: f32, _width: usize, _height: usize, _erase: bool) -> bool {
+        false
+    }
+
+    /// Start over from a fresh initial state (re-seeded grid, respawned
+    /// particles, cleared accumulation), keeping the parameters. Sims whose
+    /// image is a pure function of their parameters, like Mandelbrot, leave
+    /// this empty.
+    fn reset(&mut self) {
+        // Default: nothing to reset
+    }
+
+    /// Called when the view is clicked at plane point `(u, v)`, for sims with
+    /// a `plane_view`. A sim can answer with the name of another sim and
+    /// state to load into it, and the app switches to that sim.
+    fn plane_click(&mut self, _u: f64, _v: f64) -> Option<(&'static str, serde_json::Value)> {
+        None
+    }
+
+    /// Names of the built-in presets accepted by `load_preset`
+    fn preset_names(&self) -> Vec<&'static str> {
+        Vec::new()
+    }
+
+    /// Apply a built-in preset by name, as if its button had been clicked.
+    /// Returns false for an unknown name.
+    fn load_preset(&mut self, _name: &str) -> bool {
+        false
+    }
+
+    /// Parameters as a JSON object, for scenes and bookmarks. Sims that
+    /// don't support it return `Null`.
+    fn save_state(&self) -> serde_json::Value {
+        serde_json::Value::Null
+    }
+
+    /// Restore parameters written by `save_state`. Missing or mistyped keys
+    /// keep their current values.
+    fn load_state(&mut self, _state: &serde_json::Value) {
+        // Default: do nothing
+    }
 }
 
 /// Trait for 3D simulations
@@ -439,12 +805,133 @@ pub trait Simulation3D: Send + Sync {
     /// Get current 3D points for visualization
     fn get_points(&self) -> Vec<[f32; 3]>;
 
+    /// Optional per-point colors matching `get_points`, used by the viewer's
+    /// Simulation color mode. Return `None` to let the viewer pick colors.
+    fn get_colors(&self) -> Option<Vec<Color>> {
+        None
+    }
+
+    /// Line segments drawn along with the points, in the same space as
+    /// `get_points`, for sims built from strokes such as branches
+    fn get_segments(&self) -> Vec<([f32; 3], [f32; 3])> {
+        Vec::new()
+    }
+
+    /// Optional world-space widths matching `get_segments`, so strokes can
+    /// taper. Return `None` to size them from the viewer's point size.
+    fn get_segment_widths(&self) -> Option<Vec<f32>> {
+        None
+    }
+
+    /// Poincaré section recorded by a trajectory sim, if it keeps one. The
+    /// viewer draws it as a 2D inset while it is enabled.
+    fn poincare_section(&self) -> Option<&poincare::PoincareSection> {
+        None
+    }
+
+    /// Text labels pinned to world positions (same space as `get_points`),
+    /// drawn over the view and following its rotation
+    fn labels(&self) -> Vec<([f32; 3], String)> {
+        Vec::new()
+    }
+
+    /// World positions of field sources the viewer lets you drag, such as
+    /// magnets or vortex centers. Indices match `move_source`.
+    fn sources(&self) -> Vec<[f32; 3]> {
+        Vec::new()
+    }
+
+    /// Move source `index` by `delta` in world space
+    fn move_source(&mut self, _index: usize, _delta: [f32; 3]) {
+        // Default: nothing to move
+    }
+
+    /// Governing equations, as for `Simulation2D::equations`
+    fn equations(&self) -> Option<&str> {
+        None
+    }
+
     /// Get UI parameters for egui controls
     fn ui_parameters(&mut self, ui: &mut egui::Ui) -> bool;
 
     /// Reset simulation to initial state
     fn reset(&mut self);
+
+    /// Names of the built-in presets accepted by `load_preset`
+    fn preset_names(&self) -> Vec<&'static str> {
+        Vec::new()
+    }
+
+    /// Apply a built-in preset by name, as if its button had been clicked.
+    /// Returns false for an unknown name.
+    fn load_preset(&mut self, _name: &str) -> bool {
+        false
+    }
+
+    /// Parameters as a JSON object, for scenes and bookmarks. Sims that
+    /// don't support it return `Null`.
+    fn save_state(&self) -> serde_json::Value {
+        serde_json::Value::Null
+    }
+
+    /// Restore parameters written by `save_state`. Missing or mistyped keys
+    /// keep their current values.
+    fn load_state(&mut self, _state: &serde_json::Value) {
+        // Default: do nothing
+    }
 }
 
 pub type Simulation2DBox = Box<dyn Simulation2D>;
 pub type Simulation3DBox = Box<dyn Simulation3D>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hsv_round_trips_primary_and_secondary_colors() {
+        for color in [Color::RED, Color::GREEN, Color::BLUE, Color::from_rgb(0, 255, 255), Color::from_rgb(255, 0, 255)] {
+            let (h, s, v) = Color::rgb_to_hsv_static(color);
+            assert!((0.0..360.0).contains(&h), "{color:?} has hue {h}");
+            assert_eq!(Color::from_hsv(h, s, v), color);
+        }
+    }
+
+    #[test]
+    fn adjust_hsv_shifts_hue_in_turns_and_scales_saturation_and_value() {
+        let orange = Color::from_rgb(255, 128, 0);
+        assert_eq!(orange.adjust_hsv(0.0, 1.0, 1.0), Color::from_rgb(255, 128, 0));
+        assert_eq!(Color::RED.adjust_hsv(1.0 / 3.0, 1.0, 1.0), Color::from_rgb(0, 255, 0));
+        assert_eq!(Color::BLUE.adjust_hsv(0.5, 1.0, 1.0), Color::from_rgb(255, 255, 0));
+        assert_eq!(orange.adjust_hsv(0.0, 0.0, 1.0), Color::WHITE);
+        assert_eq!(orange.adjust_hsv(0.0, 1.0, 0.5), Color::from_rgb(127, 64, 0));
+        // Scaling up clamps rather than overflowing
+        assert_eq!(Color::from_rgb(128, 64, 64).adjust_hsv(0.0, 4.0, 4.0), Color::RED);
+    }
+
+    #[test]
+    fn custom_gradient_blends_between_surrounding_stops() {
+        let stops = vec![(0.25, Color::RED), (0.75, Color::BLUE)];
+        let rgb = ColorScheme::Custom(stops.clone(), GradientSpace::Rgb);
+        assert_eq!(rgb.map(0.0, true), Color::RED);
+        assert_eq!(rgb.map(1.0, true), Color::BLUE);
+        assert_eq!(rgb.map(0.5, true), Color::from_rgb(127, 0, 127));
+
+        // Red to blue the short way round the hue wheel is through magenta
+        let hsv = ColorScheme::Custom(stops, GradientSpace::Hsv);
+        assert_eq!(hsv.map(0.5, true), Color::from_rgb(255, 0, 255));
+    }
+
+    #[test]
+    fn rotated_palette_wraps_without_a_jump() {
+        let scheme = ColorScheme::Grayscale;
+        assert_eq!(scheme.map_reversed(0.0, true), scheme.map(1.0, true));
+        assert_eq!(scheme.map_rotated(1.0, 0.0, true), scheme.map(1.0, true));
+        assert_eq!(scheme.map_rotated(0.75, 0.5, true), scheme.map(0.25, true));
+        assert_eq!(scheme.map_rotated(0.25, -0.5, true), scheme.map(0.75, true));
+
+        // Approaching the wrap from below ends on the last color
+        assert_eq!(scheme.map_rotated(0.5, 0.5, true), scheme.map(1.0, true));
+        assert_eq!(scheme.map_rotated(0.4999, 0.5, true), scheme.map(0.9999, true));
+    }
+}
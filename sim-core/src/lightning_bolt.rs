@@ -1,4 +1,6 @@
 use crate::Simulation3D;
+use crate::nudge::Nudge;
+use crate::readout;
 use rand::Rng;
 
 struct BoltSegment {
@@ -218,22 +220,26 @@ impl Simulation3D for LightningBolt {
 
         ui.heading("Lightning Bolt");
 
-        ui.label(format!("Active Branches: {}", self.branches.len()));
-        ui.label(format!("Next Strike: {:.1}s", (self.strike_frequency - self.time_since_strike).max(0.0)));
+        ui.label(format!("Active Branches: {}", readout::count(self.branches.len())));
+        ui.label(format!("Next Strike: {}", readout::seconds((self.strike_frequency - self.time_since_strike).max(0.0), 1)));
 
         egui::CollapsingHeader::new("⚡ Lightning Settings")
             .default_open(true)
             .show(ui, |ui| {
                 ui.add(egui::Slider::new(&mut self.strike_frequency, 0.5..=5.0)
+                    .nudge(ui, 0.02)
                     .text("Strike Frequency (s)"));
 
                 ui.add(egui::Slider::new(&mut self.segment_length, 1.0..=8.0)
+                    .nudge(ui, 0.05)
                     .text("Segment Length"));
 
                 ui.add(egui::Slider::new(&mut self.chaos, 0.5..=5.0)
+                    .nudge(ui, 0.02)
                     .text("Chaos"));
 
                 ui.add(egui::Slider::new(&mut self.downward_bias, 0.3..=1.0)
+                    .nudge(ui, 0.005)
                     .text("Downward Bias"));
             });
 
@@ -241,9 +247,11 @@ impl Simulation3D for LightningBolt {
             .default_open(true)
             .show(ui, |ui| {
                 ui.add(egui::Slider::new(&mut self.branch_probability, 0.0..=0.5)
+                    .nudge(ui, 0.005)
                     .text("Branch Probability"));
 
                 ui.add(egui::Slider::new(&mut self.max_branches, 5..=50)
+                    .nudge(ui, 1.0)
                     .text("Max Branches"));
             });
 
@@ -251,9 +259,11 @@ impl Simulation3D for LightningBolt {
             .default_open(true)
             .show(ui, |ui| {
                 ui.add(egui::Slider::new(&mut self.energy_decay, 0.8..=0.99)
+                    .nudge(ui, 0.001)
                     .text("Energy Decay"));
 
                 ui.add(egui::Slider::new(&mut self.speed, 0.1..=3.0)
+                    .nudge(ui, 0.02)
                     .text("Speed"));
             });
 
@@ -1,4 +1,45 @@
-use crate::{Color, ColorScheme, Simulation2D};
+use crate::color_ui::color_scheme_combo;
+use crate::nudge::Nudge;
+use crate::{Color, ColorScheme, RenderCtx, Simulation2D};
+use std::f32::consts::{FRAC_PI_2, FRAC_PI_4, TAU};
+
+/// Named (x frequency, y frequency, phase)
+const PRESETS: [(&str, f32, f32, f32); 5] = [
+    ("Circle", 1.0, 1.0, FRAC_PI_2),
+    ("Figure Eight", 1.0, 2.0, FRAC_PI_2),
+    ("Flower", 3.0, 4.0, FRAC_PI_2),
+    ("Star", 5.0, 4.0, 0.0),
+    ("Complex", 7.0, 9.0, FRAC_PI_4),
+];
+
+/// Frequency ratios offered as x:y buttons; the phase is left alone
+const RATIOS: [(f32, f32); 10] = [
+    (1.0, 2.0),
+    (1.0, 3.0),
+    (2.0, 3.0),
+    (3.0, 4.0),
+    (4.0, 5.0),
+    (5.0, 6.0),
+    (5.0, 8.0),
+    (7.0, 8.0),
+    (8.0, 9.0),
+    (9.0, 10.0),
+];
+
+/// Longest line segment, in pixels. The curve is sampled finely enough that
+/// its chords never get longer, whatever the frequencies.
+const MAX_SEGMENT: f32 = 2.0;
+
+/// Upper bound on the segments drawn, for huge canvases
+const MAX_SEGMENTS: usize = 40_000;
+
+/// Margin, in pixels, a later segment must be nearer by to take over a
+/// pixel's core color
+const TIE: f32 = 1e-3;
+
+/// How far toward white the glowing line's core is, like an overdriven
+/// oscilloscope trace
+const CORE_WHITENESS: f32 = 0.6;
 
 pub struct LissajousCurves {
     pub freq_x: f32,
@@ -12,9 +53,20 @@ pub struct LissajousCurves {
     pub animate_phase: bool,
     pub animate_frequency: bool,
     pub animation_speed: f32,
+    /// Radians per second the relative phase advances; negative turns the
+    /// figure the other way
+    pub phase_speed: f32,
+    /// Anti-aliased line with a soft halo instead of hard pixel lines
+    pub glow: bool,
+    /// Halo falloff distance in pixels
+    pub glow_radius: f32,
+    pub glow_strength: f32,
     pub show_points: bool,
     pub trail_fade: bool,
     animation_time: f32,
+    /// Accumulated phase animation, kept separately so changing the speed
+    /// doesn't make the figure jump
+    phase_offset: f32,
 }
 
 impl Default for LissajousCurves {
@@ -31,9 +83,14 @@ impl Default for LissajousCurves {
             animate_phase: true,
             animate_frequency: false,
             animation_speed: 1.0,
+            phase_speed: 0.6,
+            glow: false,
+            glow_radius: 6.0,
+            glow_strength: 0.7,
             show_points: false,
             trail_fade: false,
             animation_time: 0.0,
+            phase_offset: 0.0,
         }
     }
 }
@@ -43,6 +100,84 @@ impl LissajousCurves {
         Self::default()
     }
 
+    /// Segments needed over t in 0..2π for chords no longer than
+    /// `MAX_SEGMENT`: the curve moves at most `sqrt((ax fx)² + (ay fy)²)`
+    /// pixels per radian of t
+    fn segment_count(amp_x: f32, amp_y: f32, freq_x: f32, freq_y: f32) -> usize {
+        let speed = ((amp_x * freq_x).powi(2) + (amp_y * freq_y).powi(2)).sqrt();
+        ((TAU * speed / MAX_SEGMENT).ceil() as usize).clamp(2, MAX_SEGMENTS)
+    }
+
+    /// Draw the polyline `points` as an anti-aliased line with a halo. The
+    /// core is shaded by the distance to the nearest segment. The halo is the
+    /// curve blurred by a Gaussian, so it is even along the line and
+    /// brighter where the trace crosses or retraces itself, as on an
+    /// oscilloscope.
+    fn draw_glow(&self, ctx: &RenderCtx, points: &[(f32, f32)]) -> Vec<Color> {
+        let (width, height) = (ctx.width, ctx.height);
+        let half_width = self.line_width / 2.0;
+        let reach = half_width + 1.0;
+        let radius = self.glow_radius.max(1.0);
+        // Scales the blurred curve so a straight line's halo peaks at the
+        // glow strength: a unit-density line under a normalized Gaussian
+        // of sigma = radius / √2 peaks at 1 / (√π radius)
+        let gain = self.glow_strength * std::f32::consts::PI.sqrt() * radius;
+
+        // Distance to the nearest segment and that segment's color
+        let mut distance = vec![f32::INFINITY; width * height];
+        let mut nearest = vec![Color::BLACK; width * height];
+        // Each segment's color times its length, splatted at its midpoint
+        let mut halo = vec![[0.0f32; 3]; width * height];
+        let segments = points.len().saturating_sub(1).max(1) as f32;
+
+        for (i, pair) in points.windows(2).enumerate() {
+            let [(x0, y0), (x1, y1)] = [pair[0], pair[1]];
+            let (dx, dy) = (x1 - x0, y1 - y0);
+            let length_sq = (dx * dx + dy * dy).max(1e-6);
+            let color = self.color_scheme.map(ctx.cycle_palette(i as f32 / segments), true);
+
+            let weight = length_sq.sqrt() * gain;
+            let rgb = [color.r as f32 * weight, color.g as f32 * weight, color.b as f32 * weight];
+            splat(&mut halo, width, height, x0 + dx * 0.5, y0 + dy * 0.5, rgb);
+
+            let min_x = ((x0.min(x1) - reach).floor().max(0.0)) as usize;
+            let max_x = ((x0.max(x1) + reach).ceil().min(width as f32)) as usize;
+            let min_y = ((y0.min(y1) - reach).floor().max(0.0)) as usize;
+            let max_y = ((y0.max(y1) + reach).ceil().min(height as f32)) as usize;
+
+            for y in min_y..max_y {
+                for x in min_x..max_x {
+                    let (px, py) = (x as f32 + 0.5 - x0, y as f32 + 0.5 - y0);
+                    let s = ((px * dx + py * dy) / length_sq).clamp(0.0, 1.0);
+                    let (ex, ey) = (px - s * dx, py - s * dy);
+                    let d = (ex * ex + ey * ey).sqrt();
+
+                    // Ties go to the earlier segment, so a retraced stretch
+                    // doesn't flicker between two colors
+                    let idx = y * width + x;
+                    if d < distance[idx] - TIE {
+                        distance[idx] = d;
+                        nearest[idx] = color;
+                    }
+                }
+            }
+        }
+
+        if self.glow_strength > 0.0 {
+            gaussian_blur(&mut halo, width, height, radius / std::f32::consts::SQRT_2, points);
+        }
+
+        let mut pixels = vec![Color::BLACK; width * height];
+        for (idx, pixel) in pixels.iter_mut().enumerate() {
+            let core = (half_width + 0.5 - distance[idx]).clamp(0.0, 1.0);
+            let hot = Color::lerp(nearest[idx], Color::WHITE, CORE_WHITENESS * core);
+            let [r, g, b] = halo[idx];
+            let channel = |hot: u8, glow: f32| (hot as f32 * core + glow * (1.0 - core)).min(255.0) as u8;
+            *pixel = Color::from_rgb(channel(hot.r, r), channel(hot.g, g), channel(hot.b, b));
+        }
+        pixels
+    }
+
     fn draw_circle(&self, pixels: &mut [Color], width: usize, height: usize,
                    cx: f32, cy: f32, radius: f32, color: Color) {
         let r_sq = radius * radius;
@@ -97,12 +232,135 @@ impl LissajousCurves {
     }
 }
 
+/// Add `value` at (x, y) in pixel coordinates, shared bilinearly between
+/// the four nearest pixel centers
+fn splat(buffer: &mut [[f32; 3]], width: usize, height: usize, x: f32, y: f32, value: [f32; 3]) {
+    let (fx, fy) = (x - 0.5, y - 0.5);
+    let (x0, y0) = (fx.floor(), fy.floor());
+    let (tx, ty) = (fx - x0, fy - y0);
+
+    for (ox, oy, share) in [(0, 0, (1.0 - tx) * (1.0 - ty)), (1, 0, tx * (1.0 - ty)), (0, 1, (1.0 - tx) * ty), (1, 1, tx * ty)] {
+        let (px, py) = (x0 as i64 + ox, y0 as i64 + oy);
+        if px >= 0 && py >= 0 && (px as usize) < width && (py as usize) < height {
+            for (sum, v) in buffer[py as usize * width + px as usize].iter_mut().zip(value) {
+                *sum += v * share;
+            }
+        }
+    }
+}
+
+/// Approximate a normalized Gaussian blur of standard deviation `sigma` with
+/// three box blurs per axis, which costs the same at any radius. Only the
+/// box around `points` that the blur can reach is touched; everything else
+/// is black. Outside the image counts as black too.
+fn gaussian_blur(buffer: &mut [[f32; 3]], width: usize, height: usize, sigma: f32, points: &[(f32, f32)]) {
+    // Three boxes of width w have variance 3 (w² - 1) / 12
+    let box_width = (4.0 * sigma * sigma + 1.0).sqrt();
+    let half = ((box_width - 1.0) / 2.0).round().max(1.0) as usize;
+
+    // Splats land within a pixel of a point, and each pass spreads them by
+    // `half` more
+    let margin = 3.0 * half as f32 + 2.0;
+    let (min_x, max_x, min_y, max_y) = points.iter().fold(
+        (f32::INFINITY, f32::NEG_INFINITY, f32::INFINITY, f32::NEG_INFINITY),
+        |(x0, x1, y0, y1), &(x, y)| (x0.min(x), x1.max(x), y0.min(y), y1.max(y)),
+    );
+    let x0 = (min_x - margin).floor().clamp(0.0, width as f32) as usize;
+    let x1 = (max_x + margin).ceil().clamp(0.0, width as f32) as usize;
+    let y0 = (min_y - margin).floor().clamp(0.0, height as f32) as usize;
+    let y1 = (max_y + margin).ceil().clamp(0.0, height as f32) as usize;
+    if x0 >= x1 || y0 >= y1 {
+        return;
+    }
+
+    let (region_width, region_height) = (x1 - x0, y1 - y0);
+    let mut region: Vec<[f32; 3]> = (y0..y1)
+        .flat_map(|y| buffer[y * width + x0..y * width + x1].iter().copied())
+        .collect();
+
+    let mut scratch = Vec::new();
+    for _ in 0..3 {
+        for row in region.chunks_exact_mut(region_width) {
+            box_blur_row(row, half, &mut scratch);
+        }
+        box_blur_columns(&mut region, region_width, region_height, half, &mut scratch);
+    }
+
+    for (y, row) in (y0..y1).zip(region.chunks_exact(region_width)) {
+        buffer[y * width + x0..y * width + x1].copy_from_slice(row);
+    }
+}
+
+/// Box blur of half-width `half` along one row
+fn box_blur_row(row: &mut [[f32; 3]], half: usize, scratch: &mut Vec<[f32; 3]>) {
+    scratch.clear();
+    scratch.extend_from_slice(row);
+
+    let scale = 1.0 / (2 * half + 1) as f32;
+    let mut sum = [0.0f32; 3];
+    for v in scratch.iter().take(half) {
+        add(&mut sum, v, 1.0);
+    }
+    for (i, out) in row.iter_mut().enumerate() {
+        // The window is now i - half ..= i + half
+        if let Some(entering) = scratch.get(i + half) {
+            add(&mut sum, entering, 1.0);
+        }
+        *out = sum.map(|s| s * scale);
+        if i >= half {
+            add(&mut sum, &scratch[i - half], -1.0);
+        }
+    }
+}
+
+/// Box blur of half-width `half` down every column, a row at a time so the
+/// buffer is read in order
+fn box_blur_columns(buffer: &mut [[f32; 3]], width: usize, height: usize, half: usize, scratch: &mut Vec<[f32; 3]>) {
+    scratch.clear();
+    scratch.extend_from_slice(buffer);
+    let row = |y: usize| &scratch[y * width..(y + 1) * width];
+
+    let scale = 1.0 / (2 * half + 1) as f32;
+    let mut sums = vec![[0.0f32; 3]; width];
+    for y in 0..half.min(height) {
+        for (sum, v) in sums.iter_mut().zip(row(y)) {
+            add(sum, v, 1.0);
+        }
+    }
+    for y in 0..height {
+        if y + half < height {
+            for (sum, v) in sums.iter_mut().zip(row(y + half)) {
+                add(sum, v, 1.0);
+            }
+        }
+        for (out, sum) in buffer[y * width..(y + 1) * width].iter_mut().zip(&sums) {
+            *out = sum.map(|s| s * scale);
+        }
+        if y >= half {
+            for (sum, v) in sums.iter_mut().zip(row(y - half)) {
+                add(sum, v, -1.0);
+            }
+        }
+    }
+}
+
+fn add(sum: &mut [f32; 3], v: &[f32; 3], sign: f32) {
+    for (s, v) in sum.iter_mut().zip(v) {
+        *s += v * sign;
+    }
+}
+
 impl Simulation2D for LissajousCurves {
     fn name(&self) -> &str {
         "Lissajous Curves"
     }
 
     fn compute(&self, width: usize, height: usize) -> Vec<Color> {
+        self.compute_ctx(&RenderCtx::new(width, height))
+    }
+
+    fn compute_ctx(&self, ctx: &RenderCtx) -> Vec<Color> {
+        let (width, height) = (ctx.width, ctx.height);
         let mut pixels = vec![Color::BLACK; width * height];
 
         let cx = width as f32 / 2.0;
@@ -116,7 +374,7 @@ impl Simulation2D for LissajousCurves {
         let amp_y = self.amplitude_y * canvas_scale;
 
         let phase = if self.animate_phase {
-            self.phase + self.animation_time * self.animation_speed
+            self.phase + self.phase_offset
         } else {
             self.phase
         };
@@ -133,47 +391,49 @@ impl Simulation2D for LissajousCurves {
             self.freq_y
         };
 
-        let mut points = Vec::new();
-
-        for i in 0..self.point_count {
-            let t = (i as f32 / self.point_count as f32) * std::f32::consts::TAU;
+        let curve = |t: f32| (cx + amp_x * (freq_x * t).sin(), cy + amp_y * (freq_y * t + phase).sin());
 
-            let x = cx + amp_x * (freq_x * t).sin();
-            let y = cy + amp_y * (freq_y * t + phase).sin();
+        // The line is sampled to the curve's speed so fast, high-ratio
+        // figures don't turn into polygons, and runs to t = 2π inclusive so
+        // a closed figure has no gap where it meets its start
+        let segments = Self::segment_count(amp_x, amp_y, freq_x, freq_y).max(self.point_count);
+        let line: Vec<(f32, f32)> = (0..=segments)
+            .map(|i| curve(i as f32 / segments as f32 * TAU))
+            .collect();
 
-            points.push((x, y));
-        }
-
-        // Draw lines
-        for i in 0..points.len() - 1 {
-            let (x0, y0) = points[i];
-            let (x1, y1) = points[i + 1];
-
-            let t = i as f32 / points.len() as f32;
-            let color = self.color_scheme.map(t, true);
-
-            for w in 0..(self.line_width as i32) {
-                self.draw_line(&mut pixels, width, height,
-                    x0 as i32 + w, y0 as i32,
-                    x1 as i32 + w, y1 as i32,
-                    color);
-                self.draw_line(&mut pixels, width, height,
-                    x0 as i32, y0 as i32 + w,
-                    x1 as i32, y1 as i32 + w,
-                    color);
+        if self.glow {
+            pixels = self.draw_glow(ctx, &line);
+        } else {
+            for (i, pair) in line.windows(2).enumerate() {
+                let [(x0, y0), (x1, y1)] = [pair[0], pair[1]];
+
+                let t = i as f32 / segments as f32;
+                let color = self.color_scheme.map(ctx.cycle_palette(t), true);
+
+                for w in 0..(self.line_width as i32) {
+                    self.draw_line(&mut pixels, width, height,
+                        x0 as i32 + w, y0 as i32,
+                        x1 as i32 + w, y1 as i32,
+                        color);
+                    self.draw_line(&mut pixels, width, height,
+                        x0 as i32, y0 as i32 + w,
+                        x1 as i32, y1 as i32 + w,
+                        color);
+                }
             }
         }
 
         // Draw points
         if self.show_points {
-            for (i, &(x, y)) in points.iter().enumerate() {
-                let t = i as f32 / points.len() as f32;
-                let color = self.color_scheme.map(t, true);
+            for i in 0..self.point_count {
+                let t = i as f32 / self.point_count as f32;
+                let (x, y) = curve(t * TAU);
+                let color = self.color_scheme.map(ctx.cycle_palette(t), true);
                 self.draw_circle(&mut pixels, width, height, x, y, 3.0, color);
             }
         }
 
-        pixels
+        ctx.over_background(pixels)
     }
 
     fn ui_parameters(&mut self, ui: &mut egui::Ui) -> bool {
@@ -185,16 +445,21 @@ impl Simulation2D for LissajousCurves {
             .default_open(true)
             .show(ui, |ui| {
                 changed |= ui.add(egui::Slider::new(&mut self.freq_x, 1.0..=10.0)
+                    .nudge(ui, 0.05)
                     .text("Frequency X")).changed();
                 changed |= ui.add(egui::Slider::new(&mut self.freq_y, 1.0..=10.0)
+                    .nudge(ui, 0.05)
                     .text("Frequency Y")).changed();
 
                 changed |= ui.add(egui::Slider::new(&mut self.phase, 0.0..=std::f32::consts::TAU)
+                    .nudge(ui, 0.05)
                     .text("Phase")).changed();
 
                 changed |= ui.add(egui::Slider::new(&mut self.amplitude_x, 0.1..=0.5)
+                    .nudge(ui, 0.002)
                     .text("Amplitude X (ratio)")).changed();
                 changed |= ui.add(egui::Slider::new(&mut self.amplitude_y, 0.1..=0.5)
+                    .nudge(ui, 0.002)
                     .text("Amplitude Y (ratio)")).changed();
             });
 
@@ -202,76 +467,101 @@ impl Simulation2D for LissajousCurves {
             .default_open(true)
             .show(ui, |ui| {
                 changed |= ui.add(egui::Slider::new(&mut self.point_count, 100..=5000)
-                    .text("Point Count")).changed();
+                    .nudge(ui, 1.0)
+                    .text("Point Count"))
+                    .on_hover_text("Dots shown by Show Points; the line adds samples as needed to stay smooth")
+                    .changed();
                 changed |= ui.add(egui::Slider::new(&mut self.line_width, 1.0..=5.0)
+                    .nudge(ui, 0.02)
                     .text("Line Width")).changed();
 
+                changed |= ui.checkbox(&mut self.glow, "Glowing Line")
+                    .on_hover_text("Anti-aliased trace with a soft halo, like an oscilloscope")
+                    .changed();
+                if self.glow {
+                    changed |= ui.add(egui::Slider::new(&mut self.glow_radius, 2.0..=20.0)
+                        .nudge(ui, 0.1)
+                        .text("Glow Radius")).changed();
+                    changed |= ui.add(egui::Slider::new(&mut self.glow_strength, 0.0..=1.5)
+                        .nudge(ui, 0.01)
+                        .text("Glow Strength")).changed();
+                }
+
                 changed |= ui.checkbox(&mut self.show_points, "Show Points").changed();
 
-                egui::ComboBox::from_label("Color Scheme")
-                    .selected_text(self.color_scheme.name())
-                    .show_ui(ui, |ui| {
-                        for scheme in ColorScheme::all() {
-                            if ui.selectable_value(&mut self.color_scheme, scheme, scheme.name()).clicked() {
-                                changed = true;
-                            }
-                        }
-                    });
+                changed |= color_scheme_combo(ui, &mut self.color_scheme);
             });
 
         egui::CollapsingHeader::new("🎬 Animation")
             .default_open(true)
             .show(ui, |ui| {
-                changed |= ui.checkbox(&mut self.animate_phase, "Animate Phase").changed();
-                changed |= ui.checkbox(&mut self.animate_frequency, "Animate Frequency").changed();
+                changed |= ui.checkbox(&mut self.animate_phase, "Animate Phase")
+                    .on_hover_text("Drift the relative phase so the figure seems to tumble in 3D")
+                    .changed();
+                if self.animate_phase {
+                    ui.add(egui::Slider::new(&mut self.phase_speed, -3.0..=3.0)
+                        .nudge(ui, 0.01)
+                        .suffix(" rad/s")
+                        .text("Phase Speed"));
+                }
 
-                if self.animate_phase || self.animate_frequency {
+                changed |= ui.checkbox(&mut self.animate_frequency, "Animate Frequency").changed();
+                if self.animate_frequency {
                     ui.add(egui::Slider::new(&mut self.animation_speed, 0.1..=5.0)
-                        .text("Speed"));
+                        .nudge(ui, 0.02)
+                        .text("Frequency Speed"));
                 }
             });
 
         egui::CollapsingHeader::new("🎯 Presets")
             .show(ui, |ui| {
-                if ui.button("Circle").clicked() {
-                    self.freq_x = 1.0;
-                    self.freq_y = 1.0;
-                    self.phase = std::f32::consts::PI / 2.0;
-                    changed = true;
-                }
-                if ui.button("Figure Eight").clicked() {
-                    self.freq_x = 1.0;
-                    self.freq_y = 2.0;
-                    self.phase = std::f32::consts::PI / 2.0;
-                    changed = true;
-                }
-                if ui.button("Flower").clicked() {
-                    self.freq_x = 3.0;
-                    self.freq_y = 4.0;
-                    self.phase = std::f32::consts::PI / 2.0;
-                    changed = true;
-                }
-                if ui.button("Star").clicked() {
-                    self.freq_x = 5.0;
-                    self.freq_y = 4.0;
-                    self.phase = 0.0;
-                    changed = true;
-                }
-                if ui.button("Complex").clicked() {
-                    self.freq_x = 7.0;
-                    self.freq_y = 9.0;
-                    self.phase = std::f32::consts::PI / 4.0;
-                    changed = true;
+                for (name, freq_x, freq_y, phase) in PRESETS {
+                    if ui.button(name).clicked() {
+                        self.freq_x = freq_x;
+                        self.freq_y = freq_y;
+                        self.phase = phase;
+                        changed = true;
+                    }
                 }
+
+                ui.label("Frequency Ratio (x:y):");
+                ui.horizontal_wrapped(|ui| {
+                    for (freq_x, freq_y) in RATIOS {
+                        let selected = self.freq_x == freq_x && self.freq_y == freq_y;
+                        if ui.selectable_label(selected, format!("{freq_x}:{freq_y}")).clicked() {
+                            self.freq_x = freq_x;
+                            self.freq_y = freq_y;
+                            changed = true;
+                        }
+                    }
+                });
             });
 
-        // Update animation
-        if self.animate_phase || self.animate_frequency {
-            let dt = ui.input(|i| i.stable_dt);
+        changed
+    }
+
+    fn update(&mut self, dt: f32, _width: usize, _height: usize) -> bool {
+        let animating = self.animate_phase || self.animate_frequency;
+        if animating {
             self.animation_time += dt;
-            changed = true;
+            if self.animate_phase {
+                self.phase_offset = (self.phase_offset + dt * self.phase_speed).rem_euclid(TAU);
+            }
         }
+        animating
+    }
 
-        changed
+    fn preset_names(&self) -> Vec<&'static str> {
+        PRESETS.iter().map(|p| p.0).collect()
+    }
+
+    fn load_preset(&mut self, name: &str) -> bool {
+        let Some(&(_, freq_x, freq_y, phase)) = PRESETS.iter().find(|p| p.0 == name) else {
+            return false;
+        };
+        self.freq_x = freq_x;
+        self.freq_y = freq_y;
+        self.phase = phase;
+        true
     }
 }
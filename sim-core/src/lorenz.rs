@@ -1,4 +1,53 @@
-use crate::Simulation3D;
+use crate::color_ui::color_scheme_combo;
+use crate::nudge::Nudge;
+use crate::poincare::{PoincareSection, SectionAxis};
+use crate::seed::{SeedEdit, SeedPoint, SeedPreset};
+use crate::state::{read, read_clamped};
+use crate::trajectory::{TrailEmitter, TrajectoryColoring};
+use crate::{Color, ColorScheme, Simulation3D};
+use serde_json::{json, Value};
+
+/// Integration step used when re-tracing the whole trail at once
+const TRACE_DT: f32 = 0.005;
+
+/// Steps thrown away before re-tracing, so the start-up transient of the new
+/// parameters doesn't end up in the trail
+const TRACE_WARMUP: usize = 2000;
+
+/// Named (σ, ρ, β) settings
+const PRESETS: [(&str, f32, f32, f32); 6] = [
+    ("Classic Butterfly (ρ=28)", 10.0, 28.0, 8.0 / 3.0),
+    ("Transient Chaos (ρ=22)", 10.0, 22.0, 8.0 / 3.0),
+    ("Stable Spirals (ρ=14)", 10.0, 14.0, 8.0 / 3.0),
+    ("Torus Knot (ρ=99.96)", 10.0, 99.96, 8.0 / 3.0),
+    ("Periodic Window (ρ=100.5)", 10.0, 100.5, 8.0 / 3.0),
+    ("Periodic Window (ρ=160)", 10.0, 160.0, 8.0 / 3.0),
+];
+
+/// Seed points, with what the orbit does from each at ρ = 28. The origin
+/// and both wing centres are fixed points.
+const SEEDS: [SeedPreset; 4] = [
+    SeedPreset {
+        name: "Near Origin",
+        point: [0.1, 0.0, 0.0],
+        behavior: "Leaves the origin along its unstable direction and settles onto the butterfly within a few loops",
+    },
+    SeedPreset {
+        name: "Right Wing Centre",
+        point: [8.6, 8.6, 27.0],
+        behavior: "Circles the right wing's unstable centre in slowly widening loops for a long time before the first flip to the left wing",
+    },
+    SeedPreset {
+        name: "Above the Origin",
+        point: [0.0, 0.01, 20.0],
+        behavior: "Slides down the z axis almost to the origin, then is flung out onto the butterfly",
+    },
+    SeedPreset {
+        name: "Far Above",
+        point: [0.0, 1.0, 60.0],
+        behavior: "Falls in from well outside the attractor and is captured within a couple of loops",
+    },
+];
 
 pub struct LorenzAttractor {
     pub sigma: f32,
@@ -8,6 +57,11 @@ pub struct LorenzAttractor {
     current: [f32; 3],
     max_points: usize,
     pub speed: f32,
+    pub color_scheme: ColorScheme,
+    pub trajectory_coloring: TrajectoryColoring,
+    pub emitter: TrailEmitter,
+    pub section: PoincareSection,
+    pub seed: SeedPoint,
 }
 
 impl Default for LorenzAttractor {
@@ -20,13 +74,21 @@ impl Default for LorenzAttractor {
             current: [0.1, 0.0, 0.0],
             max_points: 5000,
             speed: 1.0,
+            color_scheme: ColorScheme::Rainbow,
+            trajectory_coloring: TrajectoryColoring::Index,
+            emitter: TrailEmitter::new(),
+            // The classic section through both wing centres, z = ρ - 1
+            section: PoincareSection::new(SectionAxis::Z, 27.0),
+            seed: SeedPoint::new(30.0, &SEEDS),
         }
     }
 }
 
 impl LorenzAttractor {
     pub fn new() -> Self {
-        Self::default()
+        let mut attractor = Self::default();
+        attractor.retrace();
+        attractor
     }
 
     fn compute_derivatives(&self, pos: [f32; 3]) -> [f32; 3] {
@@ -37,92 +99,194 @@ impl LorenzAttractor {
             x * y - self.beta * z,
         ]
     }
-}
 
-impl Simulation3D for LorenzAttractor {
-    fn name(&self) -> &str {
-        "Lorenz Attractor"
-    }
-
-    fn step(&mut self, dt: f32) {
-        let dt = dt * self.speed * 0.01;
-
-        // Runge-Kutta 4th order integration
-        let k1 = self.compute_derivatives(self.current);
+    /// One Runge-Kutta 4th order step of size `h` from `pos`
+    fn rk4(&self, pos: [f32; 3], h: f32) -> [f32; 3] {
+        let k1 = self.compute_derivatives(pos);
 
         let temp = [
-            self.current[0] + k1[0] * dt * 0.5,
-            self.current[1] + k1[1] * dt * 0.5,
-            self.current[2] + k1[2] * dt * 0.5,
+            pos[0] + k1[0] * h * 0.5,
+            pos[1] + k1[1] * h * 0.5,
+            pos[2] + k1[2] * h * 0.5,
         ];
         let k2 = self.compute_derivatives(temp);
 
         let temp = [
-            self.current[0] + k2[0] * dt * 0.5,
-            self.current[1] + k2[1] * dt * 0.5,
-            self.current[2] + k2[2] * dt * 0.5,
+            pos[0] + k2[0] * h * 0.5,
+            pos[1] + k2[1] * h * 0.5,
+            pos[2] + k2[2] * h * 0.5,
         ];
         let k3 = self.compute_derivatives(temp);
 
         let temp = [
-            self.current[0] + k3[0] * dt,
-            self.current[1] + k3[1] * dt,
-            self.current[2] + k3[2] * dt,
+            pos[0] + k3[0] * h,
+            pos[1] + k3[1] * h,
+            pos[2] + k3[2] * h,
         ];
         let k4 = self.compute_derivatives(temp);
 
-        self.current[0] += (k1[0] + 2.0 * k2[0] + 2.0 * k3[0] + k4[0]) * dt / 6.0;
-        self.current[1] += (k1[1] + 2.0 * k2[1] + 2.0 * k3[1] + k4[1]) * dt / 6.0;
-        self.current[2] += (k1[2] + 2.0 * k2[2] + 2.0 * k3[2] + k4[2]) * dt / 6.0;
+        [
+            pos[0] + (k1[0] + 2.0 * k2[0] + 2.0 * k3[0] + k4[0]) * h / 6.0,
+            pos[1] + (k1[1] + 2.0 * k2[1] + 2.0 * k3[1] + k4[1]) * h / 6.0,
+            pos[2] + (k1[2] + 2.0 * k2[2] + 2.0 * k3[2] + k4[2]) * h / 6.0,
+        ]
+    }
+
+    /// Integrate one step, append it to the trail and the section
+    fn advance(&mut self, h: f32) {
+        let next = self.rk4(self.current, h);
+        self.section.record(self.current, next);
+        self.emitter.push(&mut self.points, self.current, next, self.max_points);
+        self.current = next;
+    }
+
+    /// Rebuild the whole trail under the current parameters, continuing from
+    /// the current state so parameter sweeps morph the shape instead of
+    /// restarting it. A blown-up state restarts from the seed.
+    fn retrace(&mut self) {
+        if !self.current.iter().all(|v| v.is_finite() && v.abs() < 1e4) {
+            self.current = self.seed.start(|p| self.compute_derivatives(p));
+        }
+        for _ in 0..TRACE_WARMUP {
+            self.current = self.rk4(self.current, TRACE_DT);
+        }
 
-        self.points.push(self.current);
+        self.points.clear();
+        self.emitter.clear();
+        self.section.clear();
+        self.fill();
+    }
 
-        if self.points.len() > self.max_points {
-            self.points.remove(0);
+    /// Advance until the trail is full. One step is one point per time
+    /// step, but by arc length it may place several or none.
+    fn fill(&mut self) {
+        for _ in 0..self.max_points * 8 {
+            if self.points.len() >= self.max_points {
+                break;
+            }
+            self.advance(TRACE_DT);
         }
     }
 
+    /// Trace the trail from the seed point itself, without the warm-up, so
+    /// the transient on the way to the attractor stays in view
+    fn trace_from_seed(&mut self) {
+        self.current = self.seed.start(|p| self.compute_derivatives(p));
+        self.points.clear();
+        self.emitter.clear();
+        self.section.clear();
+        self.fill();
+    }
+}
+
+impl Simulation3D for LorenzAttractor {
+    fn name(&self) -> &str {
+        "Lorenz Attractor"
+    }
+
+    fn equations(&self) -> Option<&str> {
+        Some(
+            "dx/dt = σ (y − x)\n\
+             dy/dt = x (ρ − z) − y\n\
+             dz/dt = x y − β z",
+        )
+    }
+
+    fn step(&mut self, dt: f32) {
+        self.advance(dt * self.speed * 0.01);
+    }
+
     fn get_points(&self) -> Vec<[f32; 3]> {
         self.points.clone()
     }
 
+    fn get_colors(&self) -> Option<Vec<Color>> {
+        self.emitter.colors(&self.points, self.trajectory_coloring, &self.color_scheme)
+    }
+
+    fn poincare_section(&self) -> Option<&PoincareSection> {
+        Some(&self.section)
+    }
+
     fn ui_parameters(&mut self, ui: &mut egui::Ui) -> bool {
         let mut changed = false;
 
         ui.heading("Lorenz Attractor Parameters");
 
-        changed |= ui.add(egui::Slider::new(&mut self.sigma, 0.0..=20.0)
-            .text("Sigma (σ)")).changed();
+        let mut params_changed = false;
+        egui::CollapsingHeader::new("⚙ Parameters")
+            .default_open(true)
+            .show(ui, |ui| {
+                params_changed |= ui.add(egui::Slider::new(&mut self.sigma, 0.0..=20.0)
+                    .nudge(ui, 0.2)
+                    .text("Sigma (σ)")).changed();
+
+                params_changed |= ui.add(egui::Slider::new(&mut self.rho, 0.0..=200.0)
+                    .nudge(ui, 2.0)
+                    .text("Rho (ρ)")).changed();
+
+                params_changed |= ui.add(egui::Slider::new(&mut self.beta, 0.0..=10.0)
+                    .nudge(ui, 0.1)
+                    .text("Beta (β)")).changed();
 
-        changed |= ui.add(egui::Slider::new(&mut self.rho, 0.0..=50.0)
-            .text("Rho (ρ)")).changed();
+                ui.add(egui::Slider::new(&mut self.speed, 0.1..=5.0)
+                    .nudge(ui, 0.02)
+                    .text("Speed"));
 
-        changed |= ui.add(egui::Slider::new(&mut self.beta, 0.0..=10.0)
-            .text("Beta (β)")).changed();
+                params_changed |= ui.add(egui::Slider::new(&mut self.max_points, 100..=10000)
+                    .nudge(ui, 1.0)
+                    .text("Trail Length")).changed();
+                changed |= self.emitter.ui(ui, &mut self.points, self.max_points);
+            });
 
-        ui.add(egui::Slider::new(&mut self.speed, 0.1..=5.0)
-            .text("Speed"));
+        egui::CollapsingHeader::new("🎨 Colors")
+            .default_open(true)
+            .show(ui, |ui| {
+                changed |= color_scheme_combo(ui, &mut self.color_scheme);
 
-        ui.add(egui::Slider::new(&mut self.max_points, 100..=10000)
-            .text("Trail Length"));
+                egui::ComboBox::from_label("Color By")
+                    .selected_text(self.trajectory_coloring.name())
+                    .show_ui(ui, |ui| {
+                        for coloring in TrajectoryColoring::all() {
+                            if ui.selectable_value(&mut self.trajectory_coloring, coloring, coloring.name()).clicked() {
+                                changed = true;
+                            }
+                        }
+                    });
+            });
 
-        if ui.button("Reset").clicked() {
+        self.section.ui(ui, -50.0..=200.0, &self.points);
+
+        egui::CollapsingHeader::new("🎯 Presets")
+            .show(ui, |ui| {
+                for (name, sigma, rho, beta) in PRESETS {
+                    if ui.button(name).clicked() {
+                        self.sigma = sigma;
+                        self.rho = rho;
+                        self.beta = beta;
+                        params_changed = true;
+                    }
+                }
+            });
+
+        let edit = self.seed.ui(ui);
+        if edit == SeedEdit::Randomize {
+            if let Some(point) = self.seed.random(|p| self.compute_derivatives(p), TRACE_DT) {
+                self.seed.point = point;
+            }
+        }
+        if edit != SeedEdit::None {
             self.reset();
             changed = true;
         }
 
-        ui.separator();
-        ui.label("Interesting configurations:");
-        if ui.button("Classic Butterfly").clicked() {
-            self.sigma = 10.0;
-            self.rho = 28.0;
-            self.beta = 8.0 / 3.0;
+        if ui.button("🔄 Reset").clicked() {
+            self.reset();
             changed = true;
         }
-        if ui.button("Chaotic").clicked() {
-            self.sigma = 10.0;
-            self.rho = 99.96;
-            self.beta = 8.0 / 3.0;
+
+        if params_changed {
+            self.retrace();
             changed = true;
         }
 
@@ -130,7 +294,70 @@ impl Simulation3D for LorenzAttractor {
     }
 
     fn reset(&mut self) {
-        self.points.clear();
-        self.current = [0.1, 0.0, 0.0];
+        self.trace_from_seed();
+    }
+
+    fn preset_names(&self) -> Vec<&'static str> {
+        PRESETS.iter().map(|p| p.0).collect()
+    }
+
+    fn load_preset(&mut self, name: &str) -> bool {
+        let Some(&(_, sigma, rho, beta)) = PRESETS.iter().find(|p| p.0 == name) else {
+            return false;
+        };
+        self.sigma = sigma;
+        self.rho = rho;
+        self.beta = beta;
+        self.retrace();
+        true
+    }
+
+    fn save_state(&self) -> Value {
+        json!({
+            "sigma": self.sigma,
+            "rho": self.rho,
+            "beta": self.beta,
+            "speed": self.speed,
+            "max_points": self.max_points,
+            "color_scheme": self.color_scheme,
+            "trajectory_coloring": self.trajectory_coloring,
+            "emission": self.emitter.mode,
+            "spacing": self.emitter.spacing,
+            "section_enabled": self.section.enabled,
+            "section_axis": self.section.axis,
+            "section_offset": self.section.offset,
+            "seed": self.seed.point,
+        })
+    }
+
+    fn load_state(&mut self, state: &Value) {
+        let shape = (self.sigma, self.rho, self.beta, self.max_points, self.emitter.mode, self.emitter.spacing);
+        let plane = (self.section.axis, self.section.offset);
+        let seed = self.seed.point;
+
+        read_clamped(state, "sigma", &mut self.sigma, 0.0..=20.0);
+        read_clamped(state, "rho", &mut self.rho, 0.0..=200.0);
+        read_clamped(state, "beta", &mut self.beta, 0.0..=10.0);
+        read_clamped(state, "speed", &mut self.speed, 0.1..=5.0);
+        read_clamped(state, "max_points", &mut self.max_points, 100..=10000);
+        read(state, "color_scheme", &mut self.color_scheme);
+        read(state, "trajectory_coloring", &mut self.trajectory_coloring);
+        read(state, "emission", &mut self.emitter.mode);
+        read_clamped(state, "spacing", &mut self.emitter.spacing, 0.25..=4.0);
+        read(state, "section_enabled", &mut self.section.enabled);
+        read(state, "section_axis", &mut self.section.axis);
+        read_clamped(state, "section_offset", &mut self.section.offset, -50.0..=200.0);
+        read(state, "seed", &mut self.seed.point);
+
+        // Only re-trace when the orbit itself changed, from the seed so the
+        // same state always gives the same trail
+        if (self.sigma, self.rho, self.beta, self.max_points, self.emitter.mode, self.emitter.spacing) != shape {
+            self.current = self.seed.start(|p| self.compute_derivatives(p));
+            self.retrace();
+        } else if self.seed.point != seed {
+            self.trace_from_seed();
+        } else if (self.section.axis, self.section.offset) != plane {
+            self.section.clear();
+        }
     }
 }
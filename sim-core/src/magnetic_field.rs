@@ -1,4 +1,8 @@
-use crate::Simulation3D;
+use crate::emitter::{Emitter, EmitterShape};
+use crate::nudge::Nudge;
+use crate::readout;
+use crate::trail::{particle_color, TrailStyle};
+use crate::{Color, Simulation3D};
 use rand::Rng;
 
 struct Magnet {
@@ -22,8 +26,11 @@ pub struct MagneticField {
     pub show_trails: bool,
     pub damping: f32,
     pub speed: f32,
-    pub spawn_radius: f32,
+    /// Particles further than this from the origin are removed
+    pub escape_radius: f32,
     pub magnet_strength: f32,
+    pub emitter: Emitter,
+    pub trail_style: TrailStyle,
     magnets: Vec<Magnet>,
     particles: Vec<FieldParticle>,
 }
@@ -39,8 +46,10 @@ impl Default for MagneticField {
             show_trails: true,
             damping: 0.98,
             speed: 1.0,
-            spawn_radius: 30.0,
+            escape_radius: 60.0,
             magnet_strength: 100.0,
+            emitter: Emitter::new(EmitterShape::Sphere, 30.0, 0.0, 60.0),
+            trail_style: TrailStyle::default(),
             magnets: Vec::new(),
             particles: Vec::new(),
         }
@@ -126,22 +135,18 @@ impl MagneticField {
         let mut rng = rand::thread_rng();
 
         for _ in 0..self.particle_count {
-            let theta = rng.gen_range(0.0..std::f32::consts::TAU);
-            let phi = rng.gen_range(0.0..std::f32::consts::PI);
-            let radius = rng.gen_range(5.0..self.spawn_radius);
-
-            let x = radius * phi.sin() * theta.cos();
-            let y = radius * phi.sin() * theta.sin();
-            let z = radius * phi.cos();
-
-            self.particles.push(FieldParticle {
-                position: [x, y, z],
-                velocity: [0.0, 0.0, 0.0],
-                trail: Vec::new(),
-            });
+            self.spawn_particle(&mut rng);
         }
     }
 
+    fn spawn_particle(&mut self, rng: &mut impl Rng) {
+        self.particles.push(FieldParticle {
+            position: self.emitter.sample(rng),
+            velocity: [0.0, 0.0, 0.0],
+            trail: Vec::new(),
+        });
+    }
+
     fn compute_field(&self, position: [f32; 3]) -> [f32; 3] {
         let mut field = [0.0, 0.0, 0.0];
 
@@ -178,6 +183,8 @@ impl Simulation3D for MagneticField {
     }
 
     fn step(&mut self, dt: f32) {
+        // The emitter rate is per wall-clock second (scaled by Speed)
+        let emit_dt = dt * self.speed;
         let dt = dt * self.speed * 0.05;
 
         // Compute fields for all particles first
@@ -211,23 +218,20 @@ impl Simulation3D for MagneticField {
                 }
             }
 
-            // Reset particle if it goes too far
-            let dist_sq = particle.position[0] * particle.position[0] +
-                         particle.position[1] * particle.position[1] +
-                         particle.position[2] * particle.position[2];
-
-            if dist_sq > self.spawn_radius * self.spawn_radius * 4.0 {
-                let mut rng = rand::thread_rng();
-                let theta = rng.gen_range(0.0..std::f32::consts::TAU);
-                let phi = rng.gen_range(0.0..std::f32::consts::PI);
-                let radius = rng.gen_range(5.0..self.spawn_radius);
-
-                particle.position[0] = radius * phi.sin() * theta.cos();
-                particle.position[1] = radius * phi.sin() * theta.sin();
-                particle.position[2] = radius * phi.cos();
-                particle.velocity = [0.0, 0.0, 0.0];
-                particle.trail.clear();
-            }
+        }
+
+        // Drop particles that wandered off; the emitter refills at its rate
+        let escape_sq = self.escape_radius * self.escape_radius;
+        self.particles.retain(|p| {
+            let [x, y, z] = p.position;
+            x * x + y * y + z * z <= escape_sq
+        });
+        self.particles.truncate(self.particle_count);
+
+        let mut rng = rand::thread_rng();
+        let room = self.particle_count.saturating_sub(self.particles.len());
+        for _ in 0..self.emitter.emit_count(emit_dt, room) {
+            self.spawn_particle(&mut rng);
         }
     }
 
@@ -252,23 +256,63 @@ impl Simulation3D for MagneticField {
         points
     }
 
+    fn sources(&self) -> Vec<[f32; 3]> {
+        self.magnets.iter().map(|m| m.position).collect()
+    }
+
+    fn move_source(&mut self, index: usize, delta: [f32; 3]) {
+        if let Some(magnet) = self.magnets.get_mut(index) {
+            for (p, d) in magnet.position.iter_mut().zip(delta) {
+                *p += d;
+            }
+        }
+    }
+
+    fn get_colors(&self) -> Option<Vec<Color>> {
+        let mut colors = Vec::new();
+
+        // Same order as get_points; magnets by polarity
+        for magnet in &self.magnets {
+            colors.push(if magnet.polarity > 0.0 {
+                Color::from_rgb(255, 80, 80)
+            } else {
+                Color::from_rgb(80, 120, 255)
+            });
+        }
+
+        let count = self.particles.len();
+        for (i, particle) in self.particles.iter().enumerate() {
+            let head = particle_color(i, count);
+            colors.push(head);
+
+            if self.show_trails {
+                self.trail_style.push_colors(&mut colors, head, particle.trail.len(), self.trail_length);
+            }
+        }
+
+        Some(colors)
+    }
+
     fn ui_parameters(&mut self, ui: &mut egui::Ui) -> bool {
         let mut changed = false;
 
         ui.heading("Magnetic Field Lines");
 
-        ui.label(format!("Magnets: {} | Particles: {}", self.magnets.len(), self.particles.len()));
+        ui.label(format!("Magnets: {} | Particles: {}", readout::count(self.magnets.len()), readout::count(self.particles.len())));
 
         egui::CollapsingHeader::new("🧲 Magnet Settings")
             .default_open(true)
             .show(ui, |ui| {
                 if ui.add(egui::Slider::new(&mut self.magnet_count, 1..=4)
+                    .nudge(ui, 1.0)
                     .text("Magnet Configuration")).changed() {
                     self.init_magnets();
                     changed = true;
                 }
+                ui.label(egui::RichText::new("Drag a magnet in the view to move it").weak());
 
                 ui.add(egui::Slider::new(&mut self.magnet_strength, 10.0..=500.0)
+                    .nudge(ui, 2.0)
                     .text("Magnet Strength"));
 
                 ui.label("Configurations:");
@@ -280,20 +324,26 @@ impl Simulation3D for MagneticField {
             .default_open(true)
             .show(ui, |ui| {
                 if ui.add(egui::Slider::new(&mut self.particle_count, 50..=500)
+                    .nudge(ui, 1.0)
                     .text("Particle Count")).changed() {
                     changed = true;
                 }
 
                 ui.add(egui::Slider::new(&mut self.particle_speed, 0.1..=10.0)
+                    .nudge(ui, 0.05)
                     .text("Particle Speed"));
 
                 ui.add(egui::Slider::new(&mut self.field_strength, 10.0..=200.0)
+                    .nudge(ui, 1.0)
                     .text("Max Field Strength"));
 
-                ui.add(egui::Slider::new(&mut self.spawn_radius, 10.0..=60.0)
-                    .text("Spawn Radius"));
+                ui.add(egui::Slider::new(&mut self.escape_radius, 20.0..=120.0)
+                    .nudge(ui, 1.0)
+                    .text("Escape Radius"));
             });
 
+        self.emitter.ui(ui);
+
         egui::CollapsingHeader::new("🎨 Visual")
             .default_open(true)
             .show(ui, |ui| {
@@ -301,13 +351,17 @@ impl Simulation3D for MagneticField {
 
                 if self.show_trails {
                     ui.add(egui::Slider::new(&mut self.trail_length, 10..=200)
+                        .nudge(ui, 1.0)
                         .text("Trail Length"));
+                    self.trail_style.ui(ui);
                 }
 
                 ui.add(egui::Slider::new(&mut self.damping, 0.9..=0.99)
+                    .nudge(ui, 0.0005)
                     .text("Damping"));
 
                 ui.add(egui::Slider::new(&mut self.speed, 0.1..=3.0)
+                    .nudge(ui, 0.02)
                     .text("Speed"));
             });
 
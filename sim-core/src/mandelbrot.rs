@@ -1,8 +1,52 @@
-use crate::{Color, ColorScheme, Simulation2D};
+use crate::color_ui::{color_scheme_combo, PaletteTransform};
+use crate::escape_time::{channel_offsets_ui, read_channel_offsets, colorize, iteration_histogram_ui, location_ui, scroll_zoom, EscapeKey, EscapeTimeCache, MAX_ZOOM};
+use crate::nudge::Nudge;
+use crate::state::{read, read_clamped};
+use crate::{Color, ColorScheme, PlaneView, RenderCtx, Simulation2D};
 use num_complex::Complex64;
+use serde_json::{json, Value};
+
+#[cfg(feature = "simd")]
+use wide::{f64x4, CmpEq, CmpGt};
+
+/// Named views as (center x, center y, zoom)
+const LOCATIONS: [(&str, f64, f64, f64); 5] = [
+    ("Seahorse Valley", -0.75, 0.1, 100.0),
+    ("Elephant Valley", 0.3, 0.0, 50.0),
+    ("Spiral", -0.7269, 0.1889, 500.0),
+    ("Triple Spiral", -0.1011, 0.9563, 1000.0),
+    ("Mini Mandelbrot", -0.7453, 0.1127, 5000.0),
+];
+
+/// Shape an orbit is measured against in orbit-trap coloring
+#[derive(Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+pub enum TrapShape {
+    Point,  // The origin
+    Cross,  // The real and imaginary axes
+    Circle, // The unit circle
+}
+
+impl TrapShape {
+    pub fn all() -> Vec<TrapShape> {
+        vec![TrapShape::Point, TrapShape::Cross, TrapShape::Circle]
+    }
 
-#[cfg(feature = "parallel")]
-use rayon::prelude::*;
+    pub fn name(&self) -> &str {
+        match self {
+            TrapShape::Point => "Point",
+            TrapShape::Cross => "Cross",
+            TrapShape::Circle => "Circle",
+        }
+    }
+
+    fn distance(&self, z: Complex64) -> f64 {
+        match self {
+            TrapShape::Point => z.norm(),
+            TrapShape::Cross => z.re.abs().min(z.im.abs()),
+            TrapShape::Circle => (z.norm() - 1.0).abs(),
+        }
+    }
+}
 
 pub struct Mandelbrot {
     pub max_iterations: u32,
@@ -14,9 +58,24 @@ pub struct Mandelbrot {
     pub color_scheme: ColorScheme,
     pub smooth_coloring: bool,
     pub invert_colors: bool,
-    pub color_offset: f32,
+    pub palette_transform: PaletteTransform,
     pub color_cycling: bool,
+    /// Per-channel radial shift in pixels; all zero is normal coloring
+    pub channel_offsets: [f32; 3],
+    /// Color escaped points by how close their orbit came to `trap_shape`
+    /// instead of by escape time
+    pub orbit_trap: bool,
+    pub trap_shape: TrapShape,
+    /// Trap distance that maps to the end of the palette
+    pub trap_scale: f64,
+    /// Clicking the view opens the Julia set for the clicked `c`
+    pub pick_julia: bool,
+    /// Iterate four pixels at a time for z^2. Escape counts are identical to
+    /// the scalar path, so this only affects speed.
+    #[cfg(feature = "simd")]
+    pub simd: bool,
     cycle_time: f32,
+    iteration_cache: EscapeTimeCache,
 }
 
 impl Default for Mandelbrot {
@@ -31,9 +90,17 @@ impl Default for Mandelbrot {
             color_scheme: ColorScheme::Classic,
             smooth_coloring: true,
             invert_colors: false,
-            color_offset: 0.0,
+            palette_transform: PaletteTransform::default(),
             color_cycling: false,
+            channel_offsets: [0.0; 3],
+            orbit_trap: false,
+            trap_shape: TrapShape::Point,
+            trap_scale: 0.5,
+            pick_julia: false,
+            #[cfg(feature = "simd")]
+            simd: true,
             cycle_time: 0.0,
+            iteration_cache: EscapeTimeCache::default(),
         }
     }
 }
@@ -43,6 +110,15 @@ impl Mandelbrot {
         Self::default()
     }
 
+    /// z^power + c (generalized Mandelbrot)
+    fn iterate(&self, z: Complex64, c: Complex64) -> Complex64 {
+        if (self.power - 2.0).abs() < 0.001 {
+            z * z + c
+        } else {
+            z.powf(self.power) + c
+        }
+    }
+
     fn mandelbrot_iterations(&self, c: Complex64) -> (u32, f64) {
         let mut z = Complex64::new(0.0, 0.0);
         let escape_sqr = self.escape_radius * self.escape_radius;
@@ -50,23 +126,108 @@ impl Mandelbrot {
         for i in 0..self.max_iterations {
             let z_norm_sqr = z.norm_sqr();
             if z_norm_sqr > escape_sqr {
-                if self.smooth_coloring {
-                    // Smooth iteration count using continuous coloring
-                    let log_zn = z_norm_sqr.ln() / 2.0;
-                    let nu = (log_zn / self.escape_radius.ln()).ln() / 2_f64.ln();
-                    return (i, i as f64 + 1.0 - nu);
+                return (i, self.smooth_iterations(i, z_norm_sqr));
+            }
+            z = self.iterate(z, c);
+        }
+        (self.max_iterations, self.max_iterations as f64)
+    }
+
+    /// Escape count and the orbit's closest approach to the trap. The
+    /// starting z = 0 is skipped, since it sits on the point and cross traps
+    /// for every c.
+    fn trap_iterations(&self, c: Complex64) -> (u32, f64) {
+        let mut z = Complex64::new(0.0, 0.0);
+        let escape_sqr = self.escape_radius * self.escape_radius;
+        let mut nearest = f64::INFINITY;
+
+        for i in 0..self.max_iterations {
+            if z.norm_sqr() > escape_sqr {
+                return (i, nearest);
+            }
+            z = self.iterate(z, c);
+            nearest = nearest.min(self.trap_shape.distance(z));
+        }
+        (self.max_iterations, nearest)
+    }
+
+    /// Smooth iteration count using continuous coloring. Always computed so
+    /// toggling smooth coloring doesn't re-iterate.
+    fn smooth_iterations(&self, i: u32, z_norm_sqr: f64) -> f64 {
+        let log_zn = z_norm_sqr.ln() / 2.0;
+        let nu = (log_zn / self.escape_radius.ln()).ln() / 2_f64.ln();
+        i as f64 + 1.0 - nu
+    }
+
+    /// Fill one row of escape counts four pixels at a time. Only valid for
+    /// power 2. The arithmetic mirrors `z * z + c` on `Complex64` operation
+    /// for operation, so every lane escapes on the same iteration as the
+    /// scalar loop would.
+    #[cfg(feature = "simd")]
+    fn mandelbrot_row_simd(&self, y: usize, width: usize, height: usize, row: &mut [(u32, f64)]) {
+        let escape_sqr = f64x4::splat(self.escape_radius * self.escape_radius);
+        let max = self.max_iterations;
+
+        for (chunk_index, out) in row.chunks_mut(4).enumerate() {
+            let x0 = chunk_index * 4;
+            let mut cr = [0.0; 4];
+            let mut ci = [0.0; 4];
+            for lane in 0..4 {
+                // Pad a short final chunk by repeating its last pixel
+                let x = (x0 + lane).min(width - 1);
+                let c = self.pixel_to_complex(x, y, width, height);
+                cr[lane] = c.re;
+                ci[lane] = c.im;
+            }
+            let (cr, ci) = (f64x4::from(cr), f64x4::from(ci));
+
+            let mut zr = f64x4::ZERO;
+            let mut zi = f64x4::ZERO;
+            // All-ones while the lane is still iterating
+            let mut active = f64x4::ZERO.cmp_eq(f64x4::ZERO);
+            let mut counts = f64x4::ZERO;
+            let mut escape_norm = f64x4::ZERO;
+
+            for _ in 0..max {
+                let norm = zr * zr + zi * zi;
+                let escaped = norm.cmp_gt(escape_sqr) & active;
+                escape_norm = escaped.blend(norm, escape_norm);
+                active &= !escaped;
+                if active.none() {
+                    break;
                 }
-                return (i, i as f64);
+                counts += active & f64x4::ONE;
+
+                let re = zr * zr - zi * zi;
+                let im = zr * zi + zi * zr;
+                zr = re + cr;
+                zi = im + ci;
             }
 
-            // z = z^power + c (generalized Mandelbrot)
-            if (self.power - 2.0).abs() < 0.001 {
-                z = z * z + c;
-            } else {
-                z = z.powf(self.power) + c;
+            let counts = counts.to_array();
+            let escape_norm = escape_norm.to_array();
+            let active = active.to_array();
+            for (lane, out) in out.iter_mut().enumerate() {
+                *out = if active[lane] != 0.0 {
+                    (max, max as f64)
+                } else {
+                    let i = counts[lane] as u32;
+                    (i, self.smooth_iterations(i, escape_norm[lane]))
+                };
             }
         }
-        (self.max_iterations, self.max_iterations as f64)
+    }
+
+    /// Everything that changes the iteration counts of a `width`x`height` view
+    fn escape_key(&self, width: usize, height: usize) -> EscapeKey {
+        EscapeKey {
+            width,
+            height,
+            params: vec![
+                self.center_x, self.center_y, self.zoom, self.power, self.escape_radius, self.max_iterations as f64,
+                self.orbit_trap as u8 as f64, self.trap_shape as u8 as f64,
+            ],
+        }
     }
 
     fn pixel_to_complex(&self, x: usize, y: usize, width: usize, height: usize) -> Complex64 {
@@ -79,21 +240,27 @@ impl Mandelbrot {
         Complex64::new(real, imag)
     }
 
-    fn iterations_to_color(&self, iterations: u32, smooth_iter: f64) -> Color {
+    /// `value` is the smooth iteration count, or the trap distance with
+    /// `orbit_trap` on
+    fn iterations_to_color(&self, iterations: u32, value: f64, ctx: &RenderCtx) -> Color {
         if iterations == self.max_iterations {
             return Color::BLACK;
         }
 
-        let mut t = (smooth_iter / self.max_iterations as f64) as f32;
+        let mut t = if self.orbit_trap {
+            // The square root spreads out the thin filaments right at the trap
+            (value / self.trap_scale).min(1.0).sqrt() as f32
+        } else {
+            let smooth_iter = if self.smooth_coloring { value } else { iterations as f64 };
+            (smooth_iter / self.max_iterations as f64) as f32
+        };
 
-        // Apply color offset/cycling
+        // Apply color cycling
         if self.color_cycling {
             t = (t + self.cycle_time) % 1.0;
-        } else {
-            t = (t + self.color_offset) % 1.0;
         }
 
-        let color = self.color_scheme.map(t, self.smooth_coloring);
+        let color = self.palette_transform.map(&self.color_scheme, ctx.cycle_palette(t), self.smooth_coloring || self.orbit_trap);
 
         if self.invert_colors {
             Color::from_rgb(255 - color.r, 255 - color.g, 255 - color.b)
@@ -108,22 +275,45 @@ impl Simulation2D for Mandelbrot {
         "Mandelbrot Set"
     }
 
+    fn equations(&self) -> Option<&str> {
+        Some(
+            "z ← zᵖ + c,  z₀ = 0, c = pixel\n\
+             Inside if |z| never exceeds the escape radius\n\
+             Color = iterations before escape",
+        )
+    }
+
     fn compute(&self, width: usize, height: usize) -> Vec<Color> {
-        #[cfg(feature = "parallel")]
-        let iter = (0..height).into_par_iter();
-        #[cfg(not(feature = "parallel"))]
-        let iter = (0..height).into_iter();
-
-        iter.flat_map(|y| {
-            (0..width)
-                .map(|x| {
-                    let c = self.pixel_to_complex(x, y, width, height);
-                    let (iterations, smooth_iter) = self.mandelbrot_iterations(c);
-                    self.iterations_to_color(iterations, smooth_iter)
-                })
-                .collect::<Vec<_>>()
-        })
-        .collect()
+        self.compute_ctx(&RenderCtx::new(width, height))
+    }
+
+    fn compute_ctx(&self, ctx: &RenderCtx) -> Vec<Color> {
+        let (width, height) = (ctx.width, ctx.height);
+        // Colors are cheap; only re-iterate when the geometry changes
+        let key = self.escape_key(width, height);
+        let iterations = self.iteration_cache.get_or_compute_rows(key, self.max_iterations, |y, row| {
+            if self.orbit_trap {
+                for (x, out) in row.iter_mut().enumerate() {
+                    *out = self.trap_iterations(self.pixel_to_complex(x, y, width, height));
+                }
+                return;
+            }
+
+            #[cfg(feature = "simd")]
+            if self.simd && (self.power - 2.0).abs() < 0.001 {
+                self.mandelbrot_row_simd(y, width, height, row);
+                return;
+            }
+
+            for (x, out) in row.iter_mut().enumerate() {
+                *out = self.mandelbrot_iterations(self.pixel_to_complex(x, y, width, height));
+            }
+        });
+
+        let pixels = colorize(&iterations, width, height, self.channel_offsets, |i, smooth_iter| {
+            self.iterations_to_color(i, smooth_iter, ctx)
+        });
+        ctx.over_background(pixels)
     }
 
     fn ui_parameters(&mut self, ui: &mut egui::Ui) -> bool {
@@ -135,45 +325,68 @@ impl Simulation2D for Mandelbrot {
             .default_open(true)
             .show(ui, |ui| {
                 changed |= ui.add(egui::Slider::new(&mut self.max_iterations, 10..=1000)
+                    .nudge(ui, 1.0)
                     .text("Max Iterations")).changed();
 
                 changed |= ui.add(egui::Slider::new(&mut self.power, 2.0..=8.0)
+                    .nudge(ui, 0.05)
                     .text("Power (z^n)")).changed();
 
                 changed |= ui.add(egui::Slider::new(&mut self.escape_radius, 2.0..=10.0)
+                    .nudge(ui, 0.05)
                     .text("Escape Radius")).changed();
+
+                #[cfg(feature = "simd")]
+                ui.checkbox(&mut self.simd, "SIMD")
+                    .on_hover_text("Iterate four pixels at once (power 2 only); same image, faster");
             });
 
         egui::CollapsingHeader::new("🎨 Color Settings")
             .default_open(true)
             .show(ui, |ui| {
-                egui::ComboBox::from_label("Color Scheme")
-                    .selected_text(self.color_scheme.name())
-                    .show_ui(ui, |ui| {
-                        for scheme in ColorScheme::all() {
-                            if ui.selectable_value(&mut self.color_scheme, scheme, scheme.name()).clicked() {
-                                changed = true;
-                            }
-                        }
-                    });
+                changed |= color_scheme_combo(ui, &mut self.color_scheme);
 
                 changed |= ui.checkbox(&mut self.smooth_coloring, "Smooth Coloring").changed();
                 changed |= ui.checkbox(&mut self.invert_colors, "Invert Colors").changed();
 
-                if ui.add(egui::Slider::new(&mut self.color_offset, 0.0..=1.0)
-                    .text("Color Offset")).changed() {
-                    changed = true;
-                }
+                changed |= self.palette_transform.ui(ui);
 
                 if ui.checkbox(&mut self.color_cycling, "Animate Colors").changed() {
                     changed = true;
                 }
             });
 
+        egui::CollapsingHeader::new("⭕ Orbit Trap")
+            .show(ui, |ui| {
+                changed |= ui.checkbox(&mut self.orbit_trap, "Color by Orbit Trap")
+                    .on_hover_text("Color escaped points by how close their orbit came to the trap; the inside stays dark")
+                    .changed();
+
+                ui.add_enabled_ui(self.orbit_trap, |ui| {
+                    egui::ComboBox::from_label("Trap Shape")
+                        .selected_text(self.trap_shape.name())
+                        .show_ui(ui, |ui| {
+                            for shape in TrapShape::all() {
+                                changed |= ui.selectable_value(&mut self.trap_shape, shape, shape.name()).changed();
+                            }
+                        });
+
+                    changed |= ui.add(egui::Slider::new(&mut self.trap_scale, 0.01..=2.0)
+                        .nudge(ui, 0.01)
+                        .logarithmic(true)
+                        .text("Trap Scale")).changed();
+                });
+            });
+
+        changed |= channel_offsets_ui(ui, &mut self.channel_offsets);
+
+        iteration_histogram_ui(ui, &self.iteration_cache);
+
         egui::CollapsingHeader::new("🔍 Navigation")
             .default_open(true)
             .show(ui, |ui| {
-                changed |= ui.add(egui::Slider::new(&mut self.zoom, 0.1..=10000.0)
+                changed |= ui.add(egui::Slider::new(&mut self.zoom, 0.1..=MAX_ZOOM)
+                    .nudge(ui, 0.05)
                     .logarithmic(true)
                     .text("Zoom")).changed();
 
@@ -189,6 +402,11 @@ impl Simulation2D for Mandelbrot {
                         .speed(0.01 / self.zoom)).changed();
                 });
 
+                ui.checkbox(&mut self.pick_julia, "Pick Julia constant")
+                    .on_hover_text("Click a point to open the Julia set for that c");
+
+                location_ui(ui, self.center_x, self.center_y, self.zoom);
+
                 if ui.button("🏠 Reset View").clicked() {
                     *self = Self::default();
                     changed = true;
@@ -197,69 +415,194 @@ impl Simulation2D for Mandelbrot {
 
         egui::CollapsingHeader::new("📍 Interesting Locations")
             .show(ui, |ui| {
-                if ui.button("Seahorse Valley").clicked() {
-                    self.center_x = -0.75;
-                    self.center_y = 0.1;
-                    self.zoom = 100.0;
-                    changed = true;
-                }
-                if ui.button("Elephant Valley").clicked() {
-                    self.center_x = 0.3;
-                    self.center_y = 0.0;
-                    self.zoom = 50.0;
-                    changed = true;
-                }
-                if ui.button("Spiral").clicked() {
-                    self.center_x = -0.7269;
-                    self.center_y = 0.1889;
-                    self.zoom = 500.0;
-                    changed = true;
-                }
-                if ui.button("Triple Spiral").clicked() {
-                    self.center_x = -0.1011;
-                    self.center_y = 0.9563;
-                    self.zoom = 1000.0;
-                    changed = true;
-                }
-                if ui.button("Mini Mandelbrot").clicked() {
-                    self.center_x = -0.7453;
-                    self.center_y = 0.1127;
-                    self.zoom = 5000.0;
-                    changed = true;
+                for (name, x, y, zoom) in LOCATIONS {
+                    if ui.button(name).clicked() {
+                        self.center_x = x;
+                        self.center_y = y;
+                        self.zoom = zoom;
+                        changed = true;
+                    }
                 }
             });
 
-        // Handle color cycling animation
+        changed
+    }
+
+    fn update(&mut self, dt: f32, _width: usize, _height: usize) -> bool {
         if self.color_cycling {
-            let dt = ui.input(|i| i.stable_dt);
             self.cycle_time = (self.cycle_time + dt * 0.1) % 1.0;
-            changed = true;
         }
+        self.color_cycling
+    }
 
-        changed
+    fn iteration_cache(&self) -> Option<&EscapeTimeCache> {
+        Some(&self.iteration_cache)
+    }
+
+    fn render_cost(&self, width: usize, height: usize) -> Option<f64> {
+        Some(self.iteration_cache.render_cost(&self.escape_key(width, height), self.max_iterations))
     }
 
     fn supports_zoom(&self) -> bool {
         true
     }
 
-    fn adjust_center(&mut self, dx: f64, dy: f64, width: usize, height: usize) {
-        // Convert pixel delta to world space delta
-        let aspect = width as f64 / height as f64;
-        let view_width = 4.0 / self.zoom;
-        let view_height = view_width / aspect;
+    fn plane_view(&self, _width: usize, height: usize) -> Option<PlaneView> {
+        // Matches pixel_to_complex: the view is 4 / zoom tall
+        Some(PlaneView {
+            center_x: self.center_x,
+            center_y: self.center_y,
+            units_per_pixel: 4.0 / self.zoom / height.max(1) as f64,
+            complex: true,
+        })
+    }
 
-        self.center_x -= dx * view_width / width as f64;
-        self.center_y -= dy * view_height / height as f64;
+    fn plane_click(&mut self, u: f64, v: f64) -> Option<(&'static str, Value)> {
+        if !self.pick_julia {
+            return None;
+        }
+        // The whole Julia set, colored like this view
+        Some(("Julia Set", json!({
+            "c_real": u,
+            "c_imag": v,
+            "animate": false,
+            "center_x": 0.0,
+            "center_y": 0.0,
+            "zoom": 1.0,
+            "power": self.power,
+            "color_scheme": self.color_scheme,
+            "smooth_coloring": self.smooth_coloring,
+            "invert_colors": self.invert_colors,
+            "color_offset": self.palette_transform.offset,
+            "reverse_palette": self.palette_transform.reversed,
+        })))
+    }
+
+    fn adjust_center(&mut self, dx: f64, dy: f64, _width: usize, height: usize) {
+        // Pixels are square, so both axes move by plane_view's units per pixel
+        let units_per_pixel = 4.0 / self.zoom / height.max(1) as f64;
+        self.center_x -= dx * units_per_pixel;
+        self.center_y -= dy * units_per_pixel;
     }
 
     fn adjust_zoom(&mut self, delta: f64) {
-        // Adjust zoom with exponential scaling for smooth mousewheel control
-        let zoom_factor = 1.0 + delta * 0.001;
-        self.zoom = (self.zoom * zoom_factor).clamp(0.1, 10000.0);
+        self.zoom = scroll_zoom(self.zoom, delta);
     }
 
     fn get_zoom(&self) -> f64 {
         self.zoom
     }
+
+    fn preset_names(&self) -> Vec<&'static str> {
+        LOCATIONS.iter().map(|l| l.0).collect()
+    }
+
+    fn load_preset(&mut self, name: &str) -> bool {
+        let Some(&(_, x, y, zoom)) = LOCATIONS.iter().find(|l| l.0 == name) else {
+            return false;
+        };
+        self.center_x = x;
+        self.center_y = y;
+        self.zoom = zoom;
+        true
+    }
+
+    fn save_state(&self) -> Value {
+        json!({
+            "max_iterations": self.max_iterations,
+            "center_x": self.center_x,
+            "center_y": self.center_y,
+            "zoom": self.zoom,
+            "power": self.power,
+            "escape_radius": self.escape_radius,
+            "color_scheme": self.color_scheme,
+            "smooth_coloring": self.smooth_coloring,
+            "invert_colors": self.invert_colors,
+            "color_offset": self.palette_transform.offset,
+            "reverse_palette": self.palette_transform.reversed,
+            "color_cycling": self.color_cycling,
+            "channel_offsets": self.channel_offsets,
+            "orbit_trap": self.orbit_trap,
+            "trap_shape": self.trap_shape,
+            "trap_scale": self.trap_scale,
+            "cycle_time": self.cycle_time,
+        })
+    }
+
+    fn load_state(&mut self, state: &Value) {
+        read_clamped(state, "max_iterations", &mut self.max_iterations, 10..=1000);
+        read_clamped(state, "center_x", &mut self.center_x, -4.0..=4.0);
+        read_clamped(state, "center_y", &mut self.center_y, -4.0..=4.0);
+        read_clamped(state, "zoom", &mut self.zoom, 0.1..=MAX_ZOOM);
+        read_clamped(state, "power", &mut self.power, 2.0..=8.0);
+        read_clamped(state, "escape_radius", &mut self.escape_radius, 2.0..=10.0);
+        read(state, "color_scheme", &mut self.color_scheme);
+        read(state, "smooth_coloring", &mut self.smooth_coloring);
+        read(state, "invert_colors", &mut self.invert_colors);
+        read_clamped(state, "color_offset", &mut self.palette_transform.offset, 0.0..=1.0);
+        read(state, "reverse_palette", &mut self.palette_transform.reversed);
+        read(state, "color_cycling", &mut self.color_cycling);
+        read_channel_offsets(state, &mut self.channel_offsets);
+        read(state, "orbit_trap", &mut self.orbit_trap);
+        read(state, "trap_shape", &mut self.trap_shape);
+        read_clamped(state, "trap_scale", &mut self.trap_scale, 0.01..=2.0);
+        read_clamped(state, "cycle_time", &mut self.cycle_time, 0.0..=1.0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "simd")]
+    #[test]
+    fn simd_rows_match_the_scalar_loop() {
+        // The whole set, and a deep view of Seahorse Valley where almost
+        // every pixel sits close to the boundary. The width isn't a multiple
+        // of four, so the padded final chunk is covered too.
+        let (width, height) = (37, 23);
+        for (center_x, center_y, zoom) in [(-0.5, 0.0, 1.0), (-0.743643, 0.131825, 5000.0)] {
+            let mandelbrot = Mandelbrot { center_x, center_y, zoom, max_iterations: 500, ..Mandelbrot::new() };
+            for y in 0..height {
+                let mut row = vec![(0, 0.0); width];
+                mandelbrot.mandelbrot_row_simd(y, width, height, &mut row);
+                for (x, &(i, smooth)) in row.iter().enumerate() {
+                    let (expected, expected_smooth) = mandelbrot.mandelbrot_iterations(mandelbrot.pixel_to_complex(x, y, width, height));
+                    assert_eq!(i, expected, "pixel ({x}, {y}) at zoom {zoom}");
+                    assert!((smooth - expected_smooth).abs() < 1e-9, "pixel ({x}, {y}) at zoom {zoom}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn smooth_count_is_continuous_where_the_band_changes() {
+        // Along the real axis past the cusp at 0.25 everything escapes, with
+        // the integer count falling one band at a time
+        let mandelbrot = Mandelbrot::new();
+        let samples: Vec<(u32, f64)> = (0..=4000)
+            .map(|k| mandelbrot.mandelbrot_iterations(Complex64::new(0.3 + k as f64 * 0.0004, 0.0)))
+            .collect();
+
+        let mut bands = 0;
+        for pair in samples.windows(2) {
+            let ((i0, s0), (i1, s1)) = (pair[0], pair[1]);
+            if i0 != i1 {
+                bands += 1;
+                assert!((s0 - s1).abs() < 0.3, "smooth count jumps from {s0} to {s1} where the band goes {i0} -> {i1}");
+            }
+        }
+        assert!(bands > 5);
+    }
+
+    #[test]
+    fn trap_distance_is_the_closest_approach_of_the_orbit() {
+        // c = 2: the orbit 0 -> 2 -> 6 escapes after two steps, along the real axis
+        let c = Complex64::new(2.0, 0.0);
+        for (shape, nearest) in [(TrapShape::Point, 2.0), (TrapShape::Cross, 0.0), (TrapShape::Circle, 1.0)] {
+            let mandelbrot = Mandelbrot { trap_shape: shape, ..Mandelbrot::new() };
+            assert_eq!(mandelbrot.trap_iterations(c), (2, nearest), "{}", shape.name());
+            assert_eq!(mandelbrot.trap_iterations(c).0, mandelbrot.mandelbrot_iterations(c).0);
+        }
+    }
 }
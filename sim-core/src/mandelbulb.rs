@@ -0,0 +1,198 @@
+use crate::Simulation3D;
+use crate::nudge::Nudge;
+use crate::readout;
+use rand::seq::SliceRandom;
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// Half-width of the sampled cube; the power-8 bulb fits inside |p| < 1.2
+const BOUNDS: f32 = 1.3;
+
+/// Detail is the grid resolution per axis, so cost grows with its cube
+const MAX_DETAIL: usize = 96;
+
+pub struct Mandelbulb {
+    pub power: f32,
+    pub detail: usize,
+    pub max_iterations: u32,
+    pub max_points: usize,
+    pub scale: f32,
+    points: Vec<[f32; 3]>,
+}
+
+impl Default for Mandelbulb {
+    fn default() -> Self {
+        Self {
+            power: 8.0,
+            detail: 64,
+            max_iterations: 8,
+            max_points: 5000,
+            scale: 40.0,
+            points: Vec::new(),
+        }
+    }
+}
+
+impl Mandelbulb {
+    pub fn new() -> Self {
+        let mut bulb = Self::default();
+        bulb.regenerate();
+        bulb
+    }
+
+    /// Distance estimate to the bulb surface (negative or zero inside)
+    fn distance_estimate(&self, p: [f32; 3]) -> f32 {
+        let mut z = p;
+        let mut dr = 1.0;
+        let mut r = 0.0;
+
+        for _ in 0..self.max_iterations {
+            r = (z[0] * z[0] + z[1] * z[1] + z[2] * z[2]).sqrt();
+            if r > 2.0 {
+                break;
+            }
+
+            // Spherical coordinates, raised to the power
+            let theta = (z[2] / r.max(1e-6)).clamp(-1.0, 1.0).acos() * self.power;
+            let phi = z[1].atan2(z[0]) * self.power;
+            dr = r.powf(self.power - 1.0) * self.power * dr + 1.0;
+
+            let zr = r.powf(self.power);
+            z = [
+                zr * theta.sin() * phi.cos() + p[0],
+                zr * theta.sin() * phi.sin() + p[1],
+                zr * theta.cos() + p[2],
+            ];
+        }
+
+        if r <= 0.0 {
+            return 0.0;
+        }
+        0.5 * r.ln() * r / dr
+    }
+
+    /// Sample a detail³ grid and keep cells whose distance estimate puts them
+    /// within one cell of the surface
+    fn regenerate(&mut self) {
+        self.detail = self.detail.clamp(16, MAX_DETAIL);
+        let n = self.detail;
+        let cell = 2.0 * BOUNDS / n as f32;
+
+        let slice = |k: usize| -> Vec<[f32; 3]> {
+            let z = -BOUNDS + (k as f32 + 0.5) * cell;
+            let mut out = Vec::new();
+            for j in 0..n {
+                let y = -BOUNDS + (j as f32 + 0.5) * cell;
+                for i in 0..n {
+                    let x = -BOUNDS + (i as f32 + 0.5) * cell;
+                    let de = self.distance_estimate([x, y, z]);
+                    if de >= 0.0 && de < cell {
+                        out.push([x * self.scale, y * self.scale, z * self.scale]);
+                    }
+                }
+            }
+            out
+        };
+
+        #[cfg(feature = "parallel")]
+        let slices: Vec<Vec<[f32; 3]>> = (0..n).into_par_iter().map(slice).collect();
+        #[cfg(not(feature = "parallel"))]
+        let slices: Vec<Vec<[f32; 3]>> = (0..n).map(slice).collect();
+
+        let mut points: Vec<[f32; 3]> = slices.into_iter().flatten().collect();
+
+        // Grid order would put one slab first; shuffle so a truncated cloud
+        // (and the viewer's bounding-box sample) covers the whole bulb
+        points.shuffle(&mut rand::thread_rng());
+        points.truncate(self.max_points);
+        self.points = points;
+    }
+}
+
+impl Simulation3D for Mandelbulb {
+    fn name(&self) -> &str {
+        "Mandelbulb"
+    }
+
+    fn equations(&self) -> Option<&str> {
+        Some(
+            "v ← vⁿ + c,  v₀ = c = sample point\n\
+             vⁿ = rⁿ (sin nθ cos nφ, sin nθ sin nφ, cos nθ)\n\
+             r = |v|, θ = acos(v_z / r), φ = atan2(v_y, v_x)\n\
+             Inside if |v| stays ≤ 2",
+        )
+    }
+
+    fn step(&mut self, _dt: f32) {
+        // Static surface; the viewer provides rotation
+    }
+
+    fn get_points(&self) -> Vec<[f32; 3]> {
+        self.points.clone()
+    }
+
+    fn ui_parameters(&mut self, ui: &mut egui::Ui) -> bool {
+        let mut changed = false;
+
+        ui.heading("Mandelbulb");
+
+        egui::CollapsingHeader::new("⚙ Fractal Parameters")
+            .default_open(true)
+            .show(ui, |ui| {
+                changed |= ui.add(egui::Slider::new(&mut self.power, 2.0..=16.0)
+                    .nudge(ui, 0.1)
+                    .text("Power")).changed();
+                changed |= ui.add(egui::Slider::new(&mut self.max_iterations, 2..=20)
+                    .nudge(ui, 1.0)
+                    .text("Iterations")).changed();
+            });
+
+        egui::CollapsingHeader::new("🔍 Display Settings")
+            .default_open(true)
+            .show(ui, |ui| {
+                changed |= ui.add(egui::Slider::new(&mut self.detail, 16..=MAX_DETAIL)
+                    .nudge(ui, 1.0)
+                    .text("Detail")).changed();
+                changed |= ui.add(egui::Slider::new(&mut self.max_points, 1000..=20000)
+                    .nudge(ui, 1.0)
+                    .text("Max Points")).changed();
+                changed |= ui.add(egui::Slider::new(&mut self.scale, 10.0..=80.0)
+                    .nudge(ui, 0.5)
+                    .text("Scale")).changed();
+
+                ui.label(format!("Surface points: {}", readout::count(self.points.len())));
+            });
+
+        egui::CollapsingHeader::new("🎯 Presets")
+            .show(ui, |ui| {
+                if ui.button("Classic (Power 8)").clicked() {
+                    self.power = 8.0;
+                    changed = true;
+                }
+                if ui.button("Smooth Bulb (Power 3)").clicked() {
+                    self.power = 3.0;
+                    changed = true;
+                }
+                if ui.button("Spiky (Power 12)").clicked() {
+                    self.power = 12.0;
+                    changed = true;
+                }
+            });
+
+        if ui.button("🔄 Reset").clicked() {
+            *self = Self::default();
+            changed = true;
+        }
+
+        if changed {
+            self.regenerate();
+        }
+
+        changed
+    }
+
+    fn reset(&mut self) {
+        self.regenerate();
+    }
+}
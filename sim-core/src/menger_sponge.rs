@@ -0,0 +1,218 @@
+//! The Menger sponge and the Sierpinski tetrahedron as point clouds: one
+//! point at the center of every cell that survives `level` rounds of
+//! subdivision. The sponge keeps 20 of a cube's 27 sub-cubes per round and
+//! the tetrahedron 4 corner copies at half size, so the clouds grow as 20ⁿ
+//! and 4ⁿ; the level is capped per kind to keep that within `MAX_POINTS`.
+
+use crate::color_ui::color_scheme_combo;
+use crate::nudge::Nudge;
+use crate::readout;
+use crate::{Color, ColorScheme, Simulation3D};
+
+/// Largest cloud a level may produce, in line with the viewer's point cap
+const MAX_POINTS: usize = 20_000;
+
+/// Corners of a regular tetrahedron centered on the origin, inscribed in
+/// the cube [-1, 1]³
+const TETRAHEDRON: [[f32; 3]; 4] = [
+    [1.0, 1.0, 1.0],
+    [1.0, -1.0, -1.0],
+    [-1.0, 1.0, -1.0],
+    [-1.0, -1.0, 1.0],
+];
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum SolidKind {
+    Sponge,
+    Tetrahedron,
+}
+
+impl SolidKind {
+    pub fn all() -> Vec<SolidKind> {
+        vec![SolidKind::Sponge, SolidKind::Tetrahedron]
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            SolidKind::Sponge => "Menger Sponge",
+            SolidKind::Tetrahedron => "Sierpinski Tetrahedron",
+        }
+    }
+
+    /// Cells each cell is replaced by in one round
+    fn branching(&self) -> usize {
+        match self {
+            SolidKind::Sponge => 20,
+            SolidKind::Tetrahedron => 4,
+        }
+    }
+
+    /// Deepest level whose cloud fits in `MAX_POINTS`
+    pub fn max_level(&self) -> u32 {
+        let mut level = 0;
+        while self.branching().pow(level + 1) <= MAX_POINTS {
+            level += 1;
+        }
+        level
+    }
+}
+
+pub struct MengerSponge {
+    pub kind: SolidKind,
+    /// Rounds of subdivision
+    pub level: u32,
+    /// Edge length of the level-0 cube (the tetrahedron's bounding cube)
+    pub size: f32,
+    pub color_scheme: ColorScheme,
+    points: Vec<[f32; 3]>,
+    /// Distance of each point from the center, 0 to 1, for coloring
+    depths: Vec<f32>,
+}
+
+impl Default for MengerSponge {
+    fn default() -> Self {
+        Self {
+            kind: SolidKind::Sponge,
+            level: 3,
+            size: 60.0,
+            color_scheme: ColorScheme::Ocean,
+            points: Vec::new(),
+            depths: Vec::new(),
+        }
+    }
+}
+
+impl MengerSponge {
+    pub fn new() -> Self {
+        let mut sponge = Self::default();
+        sponge.regenerate();
+        sponge
+    }
+
+    fn regenerate(&mut self) {
+        self.level = self.level.min(self.kind.max_level());
+        let half = self.size / 2.0;
+
+        // Each round replaces every cell (center, half size) by its children
+        let mut cells = vec![([0.0f32; 3], half)];
+        for _ in 0..self.level {
+            let mut next = Vec::with_capacity(cells.len() * self.kind.branching());
+            for &(c, h) in &cells {
+                match self.kind {
+                    SolidKind::Sponge => {
+                        let step = h * 2.0 / 3.0;
+                        for i in -1i32..=1 {
+                            for j in -1i32..=1 {
+                                for k in -1i32..=1 {
+                                    // Dropped: the center and the middle of each face,
+                                    // the sub-cubes with two or more zero offsets
+                                    if (i == 0) as u8 + (j == 0) as u8 + (k == 0) as u8 >= 2 {
+                                        continue;
+                                    }
+                                    let offset = [i as f32 * step, j as f32 * step, k as f32 * step];
+                                    next.push(([c[0] + offset[0], c[1] + offset[1], c[2] + offset[2]], h / 3.0));
+                                }
+                            }
+                        }
+                    }
+                    SolidKind::Tetrahedron => {
+                        for v in TETRAHEDRON {
+                            let offset = v.map(|v| v * h / 2.0);
+                            next.push(([c[0] + offset[0], c[1] + offset[1], c[2] + offset[2]], h / 2.0));
+                        }
+                    }
+                }
+            }
+            cells = next;
+        }
+
+        // Corners of the bounding cube are the farthest any center can be
+        let reach = half * 3f32.sqrt();
+        self.points = cells.iter().map(|&(c, _)| c).collect();
+        self.depths = self.points.iter()
+            .map(|p| ((p[0] * p[0] + p[1] * p[1] + p[2] * p[2]).sqrt() / reach).min(1.0))
+            .collect();
+    }
+}
+
+impl Simulation3D for MengerSponge {
+    fn name(&self) -> &str {
+        "Menger Sponge"
+    }
+
+    fn equations(&self) -> Option<&str> {
+        Some(match self.kind {
+            SolidKind::Sponge => {
+                "Split each cube into 3×3×3 and drop the center and the 6 face centers\n\
+                 Cubes after n rounds: 20ⁿ, dimension log 20 / log 3 ≈ 2.727"
+            }
+            SolidKind::Tetrahedron => {
+                "Replace each tetrahedron by its 4 corner copies at half size\n\
+                 Tetrahedra after n rounds: 4ⁿ, dimension log 4 / log 2 = 2"
+            }
+        })
+    }
+
+    fn step(&mut self, _dt: f32) {
+        // Static solid; the viewer provides rotation
+    }
+
+    fn get_points(&self) -> Vec<[f32; 3]> {
+        self.points.clone()
+    }
+
+    fn get_colors(&self) -> Option<Vec<Color>> {
+        Some(self.depths.iter().map(|&t| self.color_scheme.map(t, true)).collect())
+    }
+
+    fn ui_parameters(&mut self, ui: &mut egui::Ui) -> bool {
+        let mut changed = false;
+
+        ui.heading("Menger Sponge");
+
+        egui::CollapsingHeader::new("⚙ Fractal Parameters")
+            .default_open(true)
+            .show(ui, |ui| {
+                egui::ComboBox::from_label("Fractal")
+                    .selected_text(self.kind.name())
+                    .show_ui(ui, |ui| {
+                        for kind in SolidKind::all() {
+                            changed |= ui.selectable_value(&mut self.kind, kind, kind.name()).changed();
+                        }
+                    });
+
+                // Capped so the cloud stays small enough to draw every frame
+                changed |= ui.add(egui::Slider::new(&mut self.level, 0..=self.kind.max_level())
+                    .nudge(ui, 1.0)
+                    .text("Level")).changed();
+                changed |= ui.add(egui::Slider::new(&mut self.size, 20.0..=120.0)
+                    .nudge(ui, 0.5)
+                    .text("Size")).changed();
+
+                ui.label(format!("Points: {}", readout::count(self.points.len())))
+                    .on_hover_text("Raise the view's Point Cap if it is lower, or the nearest cells are left out");
+            });
+
+        egui::CollapsingHeader::new("🎨 Colors")
+            .default_open(true)
+            .show(ui, |ui| {
+                changed |= color_scheme_combo(ui, &mut self.color_scheme);
+                ui.label(egui::RichText::new("Colored by depth: distance from the center").weak());
+            });
+
+        if ui.button("🔄 Reset").clicked() {
+            *self = Self::default();
+            changed = true;
+        }
+
+        if changed {
+            self.regenerate();
+        }
+
+        changed
+    }
+
+    fn reset(&mut self) {
+        self.regenerate();
+    }
+}
@@ -1,4 +1,6 @@
 use crate::Simulation3D;
+use crate::nudge::Nudge;
+use crate::readout;
 use rand::Rng;
 
 struct Body {
@@ -118,6 +120,19 @@ impl Simulation3D for NBodyGravity {
         "N-Body Gravity"
     }
 
+    fn labels(&self) -> Vec<([f32; 3], String)> {
+        // Name the heavy bodies; the rest are too many to label
+        let heavy: Vec<_> = self.bodies.iter().filter(|b| b.mass >= 10.0).collect();
+        match heavy.as_slice() {
+            [sun] => vec![(sun.position, "Sun".to_string())],
+            stars => stars
+                .iter()
+                .enumerate()
+                .map(|(i, b)| (b.position, format!("Star {}", i + 1)))
+                .collect(),
+        }
+    }
+
     fn step(&mut self, dt: f32) {
         let dt = dt * self.speed * 0.1;
 
@@ -174,23 +189,27 @@ impl Simulation3D for NBodyGravity {
 
         ui.heading("N-Body Gravity");
 
-        ui.label(format!("Active bodies: {}", self.bodies.len()));
+        ui.label(format!("Active bodies: {}", readout::count(self.bodies.len())));
 
         egui::CollapsingHeader::new("🌍 Body Settings")
             .default_open(true)
             .show(ui, |ui| {
                 if ui.add(egui::Slider::new(&mut self.body_count, 10..=200)
+                    .nudge(ui, 1.0)
                     .text("Body Count")).changed() {
                     changed = true;
                 }
 
                 changed |= ui.add(egui::Slider::new(&mut self.central_mass, 10.0..=500.0)
+                    .nudge(ui, 2.0)
                     .text("Central Mass")).changed();
 
                 changed |= ui.add(egui::Slider::new(&mut self.spawn_radius, 10.0..=80.0)
+                    .nudge(ui, 0.5)
                     .text("Spawn Radius")).changed();
 
                 changed |= ui.add(egui::Slider::new(&mut self.initial_velocity, 0.5..=5.0)
+                    .nudge(ui, 0.02)
                     .text("Initial Velocity")).changed();
             });
 
@@ -198,12 +217,15 @@ impl Simulation3D for NBodyGravity {
             .default_open(true)
             .show(ui, |ui| {
                 ui.add(egui::Slider::new(&mut self.gravitational_constant, 0.1..=5.0)
+                    .nudge(ui, 0.02)
                     .text("Gravity Strength"));
 
                 ui.add(egui::Slider::new(&mut self.softening, 0.1..=2.0)
+                    .nudge(ui, 0.01)
                     .text("Softening"));
 
                 ui.add(egui::Slider::new(&mut self.speed, 0.1..=3.0)
+                    .nudge(ui, 0.02)
                     .text("Speed"));
             });
 
@@ -214,6 +236,7 @@ impl Simulation3D for NBodyGravity {
 
                 if self.show_trails {
                     ui.add(egui::Slider::new(&mut self.trail_length, 10..=200)
+                        .nudge(ui, 1.0)
                         .text("Trail Length"));
                 }
             });
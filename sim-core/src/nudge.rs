@@ -0,0 +1,34 @@
+//! Arrow-key nudging for sliders. egui moves a focused slider by one screen
+//! point per arrow press, which is a different (and usually useless) amount
+//! for every slider; `nudge` gives each one an explicit step instead.
+
+/// Step multiplier while Shift is held
+const SHIFT_MULTIPLIER: f64 = 10.0;
+
+pub trait Nudge {
+    /// Arrow keys move the focused slider by `step`, or ten times that with
+    /// Shift. Keyboard changes report `changed()` just like dragging.
+    fn nudge(self, ui: &egui::Ui, step: f64) -> Self;
+}
+
+impl Nudge for egui::Slider<'_> {
+    fn nudge(self, ui: &egui::Ui, step: f64) -> Self {
+        // The step is only set on frames with an arrow press. A step also
+        // snaps every value the slider writes, and with the default clamping
+        // the slider rewrites its value every frame, so setting it all the
+        // time would round off precise values typed in or loaded from presets.
+        let (arrow, shift) = ui.input(|i| {
+            let arrow = [egui::Key::ArrowLeft, egui::Key::ArrowRight, egui::Key::ArrowUp, egui::Key::ArrowDown]
+                .into_iter()
+                .any(|key| i.key_pressed(key));
+            (arrow, i.modifiers.shift)
+        });
+        if !arrow {
+            return self;
+        }
+
+        let step = if shift { step * SHIFT_MULTIPLIER } else { step };
+        self.step_by(step)
+            .clamping(egui::SliderClamping::Edits)
+    }
+}
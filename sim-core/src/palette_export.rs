@@ -0,0 +1,154 @@
+//! Exporting a color scheme as a palette file for use in other tools. The
+//! scheme is sampled at evenly spaced stops with `ColorScheme::map`, the
+//! same lookup the sims render with, so the swatches match the screen.
+//!
+//! Every color scheme picker offers the export. Copying works everywhere;
+//! saving needs a file dialog, which lives in the app, so the picker leaves
+//! the file with the context for the app to collect via `take_save_request`.
+
+use crate::nudge::Nudge;
+use crate::{Color, ColorScheme};
+
+/// Bounds on the number of stops sampled
+pub const MIN_STOPS: usize = 2;
+pub const MAX_STOPS: usize = 256;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum PaletteFormat {
+    Gimp,
+    Css,
+    Hex,
+}
+
+impl PaletteFormat {
+    pub fn all() -> Vec<PaletteFormat> {
+        vec![PaletteFormat::Gimp, PaletteFormat::Css, PaletteFormat::Hex]
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            PaletteFormat::Gimp => "GIMP Palette (.gpl)",
+            PaletteFormat::Css => "CSS Custom Properties (.css)",
+            PaletteFormat::Hex => "Hex List (.txt)",
+        }
+    }
+
+    pub fn extension(&self) -> &str {
+        match self {
+            PaletteFormat::Gimp => "gpl",
+            PaletteFormat::Css => "css",
+            PaletteFormat::Hex => "txt",
+        }
+    }
+}
+
+/// `scheme` at `stops` evenly spaced positions from 0 to 1, ends included
+pub fn sample(scheme: &ColorScheme, stops: usize) -> Vec<Color> {
+    let stops = stops.clamp(MIN_STOPS, MAX_STOPS);
+    (0..stops)
+        .map(|i| scheme.map(i as f32 / (stops - 1) as f32, true))
+        .collect()
+}
+
+/// The palette file for `scheme` sampled at `stops` positions
+pub fn export_palette(scheme: &ColorScheme, stops: usize, format: PaletteFormat) -> String {
+    let colors = sample(scheme, stops);
+    let name = scheme.name();
+    let slug = slug(name);
+    let hex = |c: &Color| format!("#{:02x}{:02x}{:02x}", c.r, c.g, c.b);
+
+    match format {
+        PaletteFormat::Gimp => {
+            let mut text = format!("GIMP Palette\nName: {name}\nColumns: {}\n#\n", colors.len().min(16));
+            for (i, c) in colors.iter().enumerate() {
+                text.push_str(&format!("{:3} {:3} {:3}\t{name} {i}\n", c.r, c.g, c.b));
+            }
+            text
+        }
+        PaletteFormat::Css => {
+            let mut text = format!("/* {name}, {} stops */\n:root {{\n", colors.len());
+            for (i, c) in colors.iter().enumerate() {
+                text.push_str(&format!("  --{slug}-{i}: {};\n", hex(c)));
+            }
+            text.push_str("}\n");
+            text
+        }
+        PaletteFormat::Hex => colors.iter().map(|c| hex(c) + "\n").collect(),
+    }
+}
+
+/// Suggested file name for an export
+pub fn file_name(scheme: &ColorScheme, format: PaletteFormat) -> String {
+    format!("{}.{}", slug(scheme.name()), format.extension())
+}
+
+/// Lowercase with dashes, for CSS names and file names
+fn slug(name: &str) -> String {
+    name.split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_ascii_lowercase)
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// A palette file waiting for the app to save it
+#[derive(Clone, Default)]
+pub struct SaveRequest {
+    pub file_name: String,
+    pub extension: String,
+    pub contents: String,
+}
+
+fn save_request_id() -> egui::Id {
+    egui::Id::new("palette_export_save")
+}
+
+/// The palette a picker asked to save since the last call, if any
+pub fn take_save_request(ctx: &egui::Context) -> Option<SaveRequest> {
+    ctx.data_mut(|d| d.remove_temp::<SaveRequest>(save_request_id()))
+}
+
+/// Export settings, shared by every picker
+#[derive(Clone)]
+struct ExportSettings {
+    format: PaletteFormat,
+    stops: usize,
+}
+
+/// Menu contents for exporting `scheme`
+pub(crate) fn export_menu(ui: &mut egui::Ui, scheme: &ColorScheme) {
+    let id = egui::Id::new("palette_export_settings");
+    let mut settings = ui.ctx().data(|d| d.get_temp::<ExportSettings>(id)).unwrap_or(ExportSettings {
+        format: PaletteFormat::Gimp,
+        stops: 16,
+    });
+
+    ui.label(format!("Export {}", scheme.name()));
+    for format in PaletteFormat::all() {
+        ui.radio_value(&mut settings.format, format, format.name());
+    }
+    ui.add(egui::Slider::new(&mut settings.stops, MIN_STOPS..=MAX_STOPS)
+        .nudge(ui, 1.0)
+        .logarithmic(true)
+        .text("Stops"));
+
+    ui.horizontal(|ui| {
+        if ui.button("📋 Copy").clicked() {
+            let text = export_palette(scheme, settings.stops, settings.format);
+            ui.output_mut(|o| o.copied_text = text);
+            ui.close_menu();
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        if ui.button("💾 Save…").clicked() {
+            let request = SaveRequest {
+                file_name: file_name(scheme, settings.format),
+                extension: settings.format.extension().to_string(),
+                contents: export_palette(scheme, settings.stops, settings.format),
+            };
+            ui.ctx().data_mut(|d| d.insert_temp(save_request_id(), request));
+            ui.close_menu();
+        }
+    });
+
+    ui.ctx().data_mut(|d| d.insert_temp(id, settings));
+}
@@ -1,4 +1,6 @@
 use crate::Simulation3D;
+use crate::nudge::Nudge;
+use crate::readout;
 
 #[allow(dead_code)]
 struct Particle {
@@ -187,26 +189,30 @@ impl Simulation3D for ParticleAttractor3D {
 
         ui.heading("Particle Lorenz Attractor");
 
-        ui.label(format!("Active particles: {}", self.particles.len()));
+        ui.label(format!("Active particles: {}", readout::count(self.particles.len())));
 
         egui::CollapsingHeader::new("🎨 Particle Settings")
             .default_open(true)
             .show(ui, |ui| {
                 if ui.add(egui::Slider::new(&mut self.num_particles, 10..=200)
+                    .nudge(ui, 1.0)
                     .text("Max Particles")).changed() {
                     changed = true;
                 }
 
                 ui.add(egui::Slider::new(&mut self.spawn_rate, 0.1..=20.0)
+                    .nudge(ui, 0.1)
                     .text("Spawn Rate"));
 
                 changed |= ui.add(egui::Slider::new(&mut self.particle_lifetime, 1.0..=30.0)
+                    .nudge(ui, 0.2)
                     .text("Particle Lifetime")).changed();
 
                 changed |= ui.checkbox(&mut self.particle_trails, "Show Trails").changed();
 
                 if self.particle_trails {
                     changed |= ui.add(egui::Slider::new(&mut self.trail_length, 10..=200)
+                        .nudge(ui, 1.0)
                         .text("Trail Length")).changed();
                 }
             });
@@ -215,15 +221,19 @@ impl Simulation3D for ParticleAttractor3D {
             .default_open(true)
             .show(ui, |ui| {
                 changed |= ui.add(egui::Slider::new(&mut self.sigma, 0.0..=20.0)
+                    .nudge(ui, 0.2)
                     .text("Sigma (σ)")).changed();
 
                 changed |= ui.add(egui::Slider::new(&mut self.rho, 0.0..=50.0)
+                    .nudge(ui, 0.5)
                     .text("Rho (ρ)")).changed();
 
                 changed |= ui.add(egui::Slider::new(&mut self.beta, 0.0..=10.0)
+                    .nudge(ui, 0.1)
                     .text("Beta (β)")).changed();
 
                 ui.add(egui::Slider::new(&mut self.speed, 0.1..=5.0)
+                    .nudge(ui, 0.02)
                     .text("Speed"));
             });
 
@@ -0,0 +1,230 @@
+//! Poincaré sections: the points where a trajectory pierces a fixed
+//! axis-aligned plane. For a chaotic flow they trace out a cross-section of
+//! the attractor that is much easier to read than the tangled 3D trail.
+//!
+//! A trajectory sim opts in by owning a `PoincareSection`, feeding each
+//! integration step to `record`, and returning it from
+//! `Simulation3D::poincare_section`; the 3D viewer draws it as an inset.
+
+use crate::nudge::Nudge;
+use crate::readout;
+use std::collections::VecDeque;
+
+/// Oldest crossings are dropped beyond this
+const MAX_CROSSINGS: usize = 20000;
+
+#[derive(Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+pub enum SectionAxis {
+    X,
+    Y,
+    Z,
+}
+
+impl SectionAxis {
+    pub fn all() -> Vec<SectionAxis> {
+        vec![SectionAxis::X, SectionAxis::Y, SectionAxis::Z]
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            SectionAxis::X => "X",
+            SectionAxis::Y => "Y",
+            SectionAxis::Z => "Z",
+        }
+    }
+
+    fn index(&self) -> usize {
+        match self {
+            SectionAxis::X => 0,
+            SectionAxis::Y => 1,
+            SectionAxis::Z => 2,
+        }
+    }
+
+    /// The two in-plane axes, used as the inset's horizontal and vertical
+    fn plane_axes(&self) -> (usize, usize) {
+        match self {
+            SectionAxis::X => (1, 2),
+            SectionAxis::Y => (0, 2),
+            SectionAxis::Z => (0, 1),
+        }
+    }
+}
+
+/// Accumulated crossings of the plane `axis = offset`
+pub struct PoincareSection {
+    pub enabled: bool,
+    pub axis: SectionAxis,
+    pub offset: f32,
+    crossings: VecDeque<[f32; 2]>,
+}
+
+impl PoincareSection {
+    pub fn new(axis: SectionAxis, offset: f32) -> Self {
+        Self {
+            enabled: false,
+            axis,
+            offset,
+            crossings: VecDeque::new(),
+        }
+    }
+
+    /// Record a crossing if the segment `from -> to` changes side of the
+    /// plane. The hit point is linearly interpolated onto the plane.
+    pub fn record(&mut self, from: [f32; 3], to: [f32; 3]) {
+        if !self.enabled {
+            return;
+        }
+
+        let axis = self.axis.index();
+        let d0 = from[axis] - self.offset;
+        let d1 = to[axis] - self.offset;
+        if d0 == d1 || (d0 < 0.0) == (d1 < 0.0) {
+            return;
+        }
+
+        let t = d0 / (d0 - d1);
+        let (u, v) = self.axis.plane_axes();
+        let hit = [
+            from[u] + (to[u] - from[u]) * t,
+            from[v] + (to[v] - from[v]) * t,
+        ];
+        if !hit[0].is_finite() || !hit[1].is_finite() {
+            return;
+        }
+
+        if self.crossings.len() >= MAX_CROSSINGS {
+            self.crossings.pop_front();
+        }
+        self.crossings.push_back(hit);
+    }
+
+    /// Record every crossing along a polyline
+    pub fn record_path(&mut self, points: &[[f32; 3]]) {
+        for pair in points.windows(2) {
+            self.record(pair[0], pair[1]);
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.crossings.clear();
+    }
+
+    /// Crossing points in plane coordinates, oldest first
+    pub fn crossings(&self) -> impl Iterator<Item = [f32; 2]> + '_ {
+        self.crossings.iter().copied()
+    }
+
+    /// Collapsible controls: enable toggle, plane selection and a clear
+    /// button. Moving the plane clears the section since old crossings belong
+    /// to a different plane. `offset_range` should cover the attractor along
+    /// every axis, in equation units. `trail` is the trajectory on screen,
+    /// which seeds the section when it is switched on or its plane moves.
+    pub fn ui(&mut self, ui: &mut egui::Ui, offset_range: std::ops::RangeInclusive<f32>, trail: &[[f32; 3]]) {
+        let step = (offset_range.end() - offset_range.start()) as f64 / 100.0;
+        egui::CollapsingHeader::new("✂ Poincaré Section")
+            .show(ui, |ui| {
+                let switched_on = ui.checkbox(&mut self.enabled, "Record Section")
+                    .on_hover_text("Plot where the trajectory crosses a plane, drawn over the 3D view")
+                    .changed() && self.enabled;
+
+                let mut plane_changed = false;
+                ui.horizontal(|ui| {
+                    ui.label("Plane:");
+                    for axis in SectionAxis::all() {
+                        plane_changed |= ui.selectable_value(&mut self.axis, axis, axis.name()).clicked();
+                    }
+                });
+                plane_changed |= ui.add(egui::Slider::new(&mut self.offset, offset_range)
+                    .nudge(ui, step)
+                    .text("Offset")).changed();
+
+                ui.horizontal(|ui| {
+                    ui.label(format!("Crossings: {}", readout::count(self.crossings.len())));
+                    if ui.button("Clear").clicked() {
+                        self.clear();
+                    }
+                });
+
+                if plane_changed {
+                    self.clear();
+                }
+                if switched_on || plane_changed {
+                    self.record_path(trail);
+                }
+            });
+    }
+
+    /// Paint the crossings into `rect` (background included), fitted to their
+    /// bounding box
+    pub fn paint(&self, painter: &egui::Painter, rect: egui::Rect) {
+        painter.rect_filled(rect, 2.0, egui::Color32::from_black_alpha(200));
+        painter.rect_stroke(rect, 2.0, egui::Stroke::new(1.0, egui::Color32::from_gray(80)));
+        painter.text(
+            rect.left_top() + egui::vec2(4.0, 2.0),
+            egui::Align2::LEFT_TOP,
+            format!("{} = {:.2}", self.axis.name(), self.offset),
+            egui::FontId::proportional(11.0),
+            egui::Color32::GRAY,
+        );
+
+        if self.crossings.is_empty() {
+            painter.text(
+                rect.center(),
+                egui::Align2::CENTER_CENTER,
+                "No crossings yet",
+                egui::FontId::proportional(11.0),
+                egui::Color32::GRAY,
+            );
+            return;
+        }
+
+        let (mut min, mut max) = ([f32::MAX; 2], [f32::MIN; 2]);
+        for p in &self.crossings {
+            for i in 0..2 {
+                min[i] = min[i].min(p[i]);
+                max[i] = max[i].max(p[i]);
+            }
+        }
+
+        // Uniform scale so the section isn't stretched
+        let inner = rect.shrink(6.0);
+        let span = (max[0] - min[0]).max(max[1] - min[1]).max(1e-6);
+        let scale = inner.width().min(inner.height()) / span;
+        let mid = [(min[0] + max[0]) * 0.5, (min[1] + max[1]) * 0.5];
+
+        let color = egui::Color32::from_rgb(255, 200, 80);
+        for p in &self.crossings {
+            let pos = egui::pos2(
+                inner.center().x + (p[0] - mid[0]) * scale,
+                inner.center().y - (p[1] - mid[1]) * scale,
+            );
+            painter.rect_filled(egui::Rect::from_center_size(pos, egui::vec2(1.5, 1.5)), 0.0, color);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn crossings_are_interpolated_onto_the_plane_and_capped() {
+        let mut section = PoincareSection::new(SectionAxis::Z, 1.0);
+        section.enabled = true;
+        section.record_path(&[[0.0, 0.0, 0.0], [2.0, 4.0, 2.0], [2.0, 4.0, 3.0], [0.0, 0.0, 0.5]]);
+        let crossings: Vec<[f32; 2]> = section.crossings().collect();
+        assert_eq!(crossings.len(), 2);
+        for (hit, expected) in crossings.iter().zip([[1.0, 2.0], [0.4, 0.8]]) {
+            assert!((hit[0] - expected[0]).abs() < 1e-6 && (hit[1] - expected[1]).abs() < 1e-6, "{hit:?}");
+        }
+
+        // Past the cap the oldest crossings go first
+        for i in 0..MAX_CROSSINGS {
+            let x = i as f32;
+            section.record([x, 0.0, 0.0], [x, 0.0, 2.0]);
+        }
+        assert_eq!(section.crossings().count(), MAX_CROSSINGS);
+        assert_eq!(section.crossings().next(), Some([0.0, 0.0]));
+    }
+}
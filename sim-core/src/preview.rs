@@ -0,0 +1,85 @@
+//! Offscreen rendering of 2D simulations into images, for thumbnails and
+//! command-line tools. Nothing here touches egui.
+
+use crate::{Color, RenderCtx, Simulation2DBox};
+use image::{Rgb, RgbImage};
+
+/// Render `sim` at exactly `width`x`height`. This is the same `compute_ctx` call
+/// the viewer makes, so a static sim on a black background matches the
+/// on-screen image pixel for pixel.
+pub fn render_preview(sim: &Simulation2DBox, width: u32, height: u32) -> RgbImage {
+    render_preview_supersampled(sim, width, height, 1)
+}
+
+/// Render `sim` at `factor` times the target size and box-filter it down.
+/// Averaging happens in linear light so thin bright features don't darken the
+/// way they do with a naive sRGB average.
+pub fn render_preview_supersampled(sim: &Simulation2DBox, width: u32, height: u32, factor: u32) -> RgbImage {
+    let factor = factor.max(1);
+    let (w, h) = (width as usize, height as usize);
+    let (sw, sh) = (w * factor as usize, h * factor as usize);
+
+    let pixels = sim.compute_ctx(&RenderCtx::new(sw, sh));
+    if factor == 1 {
+        return to_image(&pixels, width, height);
+    }
+
+    let lut = srgb_to_linear_lut();
+    let f = factor as usize;
+    let weight = 1.0 / (f * f) as f32;
+    let mut out = RgbImage::new(width, height);
+
+    for y in 0..h {
+        for x in 0..w {
+            let mut sum = [0.0f32; 3];
+            for sy in 0..f {
+                let row = (y * f + sy) * sw;
+                for sx in 0..f {
+                    let c = pixels.get(row + x * f + sx).copied().unwrap_or(Color::BLACK);
+                    sum[0] += lut[c.r as usize];
+                    sum[1] += lut[c.g as usize];
+                    sum[2] += lut[c.b as usize];
+                }
+            }
+            out.put_pixel(x as u32, y as u32, Rgb([
+                linear_to_srgb(sum[0] * weight),
+                linear_to_srgb(sum[1] * weight),
+                linear_to_srgb(sum[2] * weight),
+            ]));
+        }
+    }
+
+    out
+}
+
+fn to_image(pixels: &[Color], width: u32, height: u32) -> RgbImage {
+    let mut out = RgbImage::new(width, height);
+    for (i, px) in out.pixels_mut().enumerate() {
+        let c = pixels.get(i).copied().unwrap_or(Color::BLACK);
+        *px = Rgb([c.r, c.g, c.b]);
+    }
+    out
+}
+
+fn srgb_to_linear_lut() -> [f32; 256] {
+    let mut lut = [0.0; 256];
+    for (i, v) in lut.iter_mut().enumerate() {
+        let c = i as f32 / 255.0;
+        *v = if c <= 0.04045 {
+            c / 12.92
+        } else {
+            ((c + 0.055) / 1.055).powf(2.4)
+        };
+    }
+    lut
+}
+
+fn linear_to_srgb(v: f32) -> u8 {
+    let v = v.clamp(0.0, 1.0);
+    let c = if v <= 0.0031308 {
+        v * 12.92
+    } else {
+        1.055 * v.powf(1.0 / 2.4) - 0.055
+    };
+    (c * 255.0).round() as u8
+}
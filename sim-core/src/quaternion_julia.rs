@@ -0,0 +1,430 @@
+use crate::color_ui::{color_scheme_combo, PaletteTransform};
+use crate::escape_time::{channel_offsets_ui, read_channel_offsets, colorize, iteration_histogram_ui, EscapeKey, EscapeTimeCache};
+use crate::nudge::Nudge;
+use crate::state::{read, read_clamped};
+use crate::{Color, ColorScheme, PlaneView, RenderCtx, Simulation2D};
+use serde_json::{json, Value};
+
+/// Named values of c as (real, i, j, k)
+const PARAMETERS: [(&str, [f64; 4]); 5] = [
+    ("Cloud", [-0.08, 0.0, -0.8, -0.03]),
+    ("Twisted Rabbit", [-0.123, 0.745, 0.1, 0.0]),
+    ("Dendrite Shell", [-0.4, 0.6, 0.0, 0.2]),
+    ("Knotted", [-0.291, -0.399, 0.339, 0.437]),
+    ("Sea Horse", [-0.745, 0.113, 0.0, 0.05]),
+];
+
+/// Escape radius; a quaternion this far out always diverges for |c| < 2
+const ESCAPE_RADIUS: f64 = 4.0;
+
+/// A quaternion as (real, i, j, k)
+type Quaternion = [f64; 4];
+
+/// q² + c. For q = a + v with v the imaginary part, q² = a² - |v|² + 2av.
+fn square_add(q: Quaternion, c: Quaternion) -> Quaternion {
+    let [a, b, d, e] = q;
+    [
+        a * a - b * b - d * d - e * e + c[0],
+        2.0 * a * b + c[1],
+        2.0 * a * d + c[2],
+        2.0 * a * e + c[3],
+    ]
+}
+
+fn norm_sqr(q: Quaternion) -> f64 {
+    q.iter().map(|v| v * v).sum()
+}
+
+/// How the slice moves when sweeping
+#[derive(Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+pub enum SliceSweep {
+    /// Back and forth along the j axis
+    Z,
+    /// Back and forth along the k axis
+    W,
+    /// Round a circle in the j-k plane that starts at the center
+    Circle,
+}
+
+impl SliceSweep {
+    pub fn all() -> Vec<SliceSweep> {
+        vec![SliceSweep::Z, SliceSweep::W, SliceSweep::Circle]
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            SliceSweep::Z => "Along z",
+            SliceSweep::W => "Along w",
+            SliceSweep::Circle => "Circle in z-w",
+        }
+    }
+
+    /// Offset of the slice from its center at phase `t`; zero at `t = 0`,
+    /// so starting a sweep doesn't jump
+    fn offset(&self, t: f64) -> (f64, f64) {
+        match self {
+            SliceSweep::Z => (t.sin(), 0.0),
+            SliceSweep::W => (0.0, t.sin()),
+            SliceSweep::Circle => (t.sin(), 1.0 - t.cos()),
+        }
+    }
+}
+
+/// A 2D slice through a quaternion Julia set. The screen is the real-i
+/// plane at fixed j = `slice_z` and k = `slice_w`; each pixel starts
+/// q = x + yi + zj + wk and iterates q ← q² + c.
+pub struct QuaternionJulia {
+    pub max_iterations: u32,
+    pub c: Quaternion,
+    pub slice_z: f64,
+    pub slice_w: f64,
+    pub zoom: f64,
+    pub center_x: f64,
+    pub center_y: f64,
+    pub color_scheme: ColorScheme,
+    pub smooth_coloring: bool,
+    pub invert_colors: bool,
+    pub palette_transform: PaletteTransform,
+    /// Per-channel radial shift in pixels; all zero is normal coloring
+    pub channel_offsets: [f32; 3],
+    /// Move the slice through the set around (`sweep_center_z`, `sweep_center_w`)
+    pub sweep: bool,
+    pub sweep_path: SliceSweep,
+    pub sweep_amplitude: f64,
+    pub sweep_center_z: f64,
+    pub sweep_center_w: f64,
+    /// Radians per second
+    pub sweep_speed: f32,
+    sweep_phase: f32,
+    iteration_cache: EscapeTimeCache,
+}
+
+impl Default for QuaternionJulia {
+    fn default() -> Self {
+        Self {
+            max_iterations: 60,
+            c: PARAMETERS[0].1,
+            slice_z: 0.0,
+            slice_w: 0.0,
+            zoom: 1.5,
+            center_x: 0.0,
+            center_y: 0.0,
+            color_scheme: ColorScheme::Ultra,
+            smooth_coloring: true,
+            invert_colors: false,
+            palette_transform: PaletteTransform::default(),
+            channel_offsets: [0.0; 3],
+            sweep: false,
+            sweep_path: SliceSweep::W,
+            sweep_amplitude: 0.6,
+            sweep_center_z: 0.0,
+            sweep_center_w: 0.0,
+            sweep_speed: 0.4,
+            sweep_phase: 0.0,
+            iteration_cache: EscapeTimeCache::default(),
+        }
+    }
+}
+
+impl QuaternionJulia {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn iterations(&self, q0: Quaternion) -> (u32, f64) {
+        let mut q = q0;
+        let escape_sqr = ESCAPE_RADIUS * ESCAPE_RADIUS;
+
+        for i in 0..self.max_iterations {
+            let q_norm_sqr = norm_sqr(q);
+            if q_norm_sqr > escape_sqr {
+                // Same continuous count as the complex fractals: |q| grows
+                // quadratically just like |z| does
+                let log_qn = q_norm_sqr.ln() / 2.0;
+                let nu = (log_qn / ESCAPE_RADIUS.ln()).ln() / 2_f64.ln();
+                return (i, i as f64 + 1.0 - nu);
+            }
+            q = square_add(q, self.c);
+        }
+        (self.max_iterations, self.max_iterations as f64)
+    }
+
+    fn pixel_to_quaternion(&self, x: usize, y: usize, width: usize, height: usize) -> Quaternion {
+        let aspect = width as f64 / height as f64;
+        let range = 4.0 / self.zoom;
+
+        let real = (x as f64 / width as f64 - 0.5) * range * aspect + self.center_x;
+        let imag = (y as f64 / height as f64 - 0.5) * range + self.center_y;
+
+        [real, imag, self.slice_z, self.slice_w]
+    }
+
+    fn iterations_to_color(&self, iterations: u32, smooth_iter: f64, ctx: &RenderCtx) -> Color {
+        if iterations == self.max_iterations {
+            return Color::BLACK;
+        }
+
+        let smooth_iter = if self.smooth_coloring { smooth_iter } else { iterations as f64 };
+        let t = (smooth_iter / self.max_iterations as f64) as f32;
+        let color = self.palette_transform.map(&self.color_scheme, ctx.cycle_palette(t), self.smooth_coloring);
+
+        if self.invert_colors {
+            Color::from_rgb(255 - color.r, 255 - color.g, 255 - color.b)
+        } else {
+            color
+        }
+    }
+}
+
+impl Simulation2D for QuaternionJulia {
+    fn name(&self) -> &str {
+        "Quaternion Julia"
+    }
+
+    fn equations(&self) -> Option<&str> {
+        Some(
+            "q ← q² + c,  q, c quaternions a + bi + cj + dk\n\
+             q₀ = x + yi + zj + wk, with (z, w) the slice\n\
+             Color = iterations before |q| escapes",
+        )
+    }
+
+    fn compute(&self, width: usize, height: usize) -> Vec<Color> {
+        self.compute_ctx(&RenderCtx::new(width, height))
+    }
+
+    fn compute_ctx(&self, ctx: &RenderCtx) -> Vec<Color> {
+        let (width, height) = (ctx.width, ctx.height);
+        let key = EscapeKey {
+            width,
+            height,
+            params: vec![
+                self.c[0], self.c[1], self.c[2], self.c[3],
+                self.slice_z, self.slice_w,
+                self.center_x, self.center_y, self.zoom, self.max_iterations as f64,
+            ],
+        };
+        let iterations = self.iteration_cache.get_or_compute(key, self.max_iterations, |x, y| {
+            self.iterations(self.pixel_to_quaternion(x, y, width, height))
+        });
+
+        let pixels = colorize(&iterations, width, height, self.channel_offsets, |i, smooth_iter| {
+            self.iterations_to_color(i, smooth_iter, ctx)
+        });
+        ctx.over_background(pixels)
+    }
+
+    fn ui_parameters(&mut self, ui: &mut egui::Ui) -> bool {
+        let mut changed = false;
+
+        ui.heading("Quaternion Julia Set");
+
+        egui::CollapsingHeader::new("⚙ Calculation Settings")
+            .default_open(true)
+            .show(ui, |ui| {
+                changed |= ui.add(egui::Slider::new(&mut self.max_iterations, 10..=500)
+                    .nudge(ui, 1.0)
+                    .text("Max Iterations")).changed();
+            });
+
+        egui::CollapsingHeader::new("🌀 Quaternion Parameter (c)")
+            .default_open(true)
+            .show(ui, |ui| {
+                for (value, label) in self.c.iter_mut().zip(["Real:", "i:", "j:", "k:"]) {
+                    ui.horizontal(|ui| {
+                        ui.label(label);
+                        changed |= ui.add(egui::DragValue::new(value)
+                            .speed(0.001)
+                            .range(-2.0..=2.0)).changed();
+                    });
+                }
+            });
+
+        egui::CollapsingHeader::new("🔪 Slice")
+            .default_open(true)
+            .show(ui, |ui| {
+                ui.add_enabled_ui(!self.sweep, |ui| {
+                    changed |= ui.add(egui::Slider::new(&mut self.slice_z, -1.5..=1.5)
+                        .nudge(ui, 0.005)
+                        .text("z (j)")).changed();
+                    changed |= ui.add(egui::Slider::new(&mut self.slice_w, -1.5..=1.5)
+                        .nudge(ui, 0.005)
+                        .text("w (k)")).changed();
+                });
+
+                if ui.checkbox(&mut self.sweep, "Sweep Slice")
+                    .on_hover_text("Move the slice through the set to watch the cross-section change")
+                    .changed() {
+                    // Sweep around wherever the slice is now
+                    self.sweep_center_z = self.slice_z;
+                    self.sweep_center_w = self.slice_w;
+                    self.sweep_phase = 0.0;
+                    changed = true;
+                }
+
+                if self.sweep {
+                    egui::ComboBox::from_label("Sweep Path")
+                        .selected_text(self.sweep_path.name())
+                        .show_ui(ui, |ui| {
+                            for path in SliceSweep::all() {
+                                ui.selectable_value(&mut self.sweep_path, path, path.name());
+                            }
+                        });
+                    ui.add(egui::Slider::new(&mut self.sweep_amplitude, 0.05..=1.5)
+                        .nudge(ui, 0.01)
+                        .text("Amplitude"));
+                    ui.add(egui::Slider::new(&mut self.sweep_speed, 0.02..=2.0)
+                        .nudge(ui, 0.01)
+                        .logarithmic(true)
+                        .text("Speed"));
+                }
+            });
+
+        egui::CollapsingHeader::new("🎨 Color Settings")
+            .default_open(true)
+            .show(ui, |ui| {
+                changed |= color_scheme_combo(ui, &mut self.color_scheme);
+
+                changed |= ui.checkbox(&mut self.smooth_coloring, "Smooth Coloring").changed();
+                changed |= ui.checkbox(&mut self.invert_colors, "Invert Colors").changed();
+                changed |= self.palette_transform.ui(ui);
+            });
+
+        changed |= channel_offsets_ui(ui, &mut self.channel_offsets);
+
+        iteration_histogram_ui(ui, &self.iteration_cache);
+
+        egui::CollapsingHeader::new("🔍 Navigation")
+            .default_open(true)
+            .show(ui, |ui| {
+                changed |= ui.add(egui::Slider::new(&mut self.zoom, 0.1..=1000.0)
+                    .nudge(ui, 0.05)
+                    .logarithmic(true)
+                    .text("Zoom")).changed();
+
+                if ui.button("🏠 Reset").clicked() {
+                    *self = Self::default();
+                    changed = true;
+                }
+            });
+
+        egui::CollapsingHeader::new("📍 Interesting Parameters")
+            .show(ui, |ui| {
+                for (name, c) in PARAMETERS {
+                    if ui.button(name).clicked() {
+                        self.c = c;
+                        changed = true;
+                    }
+                }
+            });
+
+        changed
+    }
+
+    fn update(&mut self, dt: f32, _width: usize, _height: usize) -> bool {
+        if !self.sweep {
+            return false;
+        }
+
+        // Wrap so the loop is seamless and the angle never loses f32 precision
+        self.sweep_phase = (self.sweep_phase + dt * self.sweep_speed)
+            .rem_euclid(std::f32::consts::TAU);
+        let (dz, dw) = self.sweep_path.offset(self.sweep_phase as f64);
+        self.slice_z = self.sweep_center_z + self.sweep_amplitude * dz;
+        self.slice_w = self.sweep_center_w + self.sweep_amplitude * dw;
+        true
+    }
+
+    fn supports_zoom(&self) -> bool {
+        true
+    }
+
+    fn plane_view(&self, _width: usize, height: usize) -> Option<PlaneView> {
+        // Matches pixel_to_quaternion: the view is 4 / zoom tall
+        Some(PlaneView {
+            center_x: self.center_x,
+            center_y: self.center_y,
+            units_per_pixel: 4.0 / self.zoom / height.max(1) as f64,
+            complex: true,
+        })
+    }
+
+    fn adjust_center(&mut self, dx: f64, dy: f64, _width: usize, height: usize) {
+        // Pixels are square, so both axes move by plane_view's units per pixel
+        let units_per_pixel = 4.0 / self.zoom / height.max(1) as f64;
+        self.center_x -= dx * units_per_pixel;
+        self.center_y -= dy * units_per_pixel;
+    }
+
+    fn adjust_zoom(&mut self, delta: f64) {
+        let zoom_factor = 1.0 + delta * 0.001;
+        self.zoom = (self.zoom * zoom_factor).clamp(0.1, 1000.0);
+    }
+
+    fn get_zoom(&self) -> f64 {
+        self.zoom
+    }
+
+    fn preset_names(&self) -> Vec<&'static str> {
+        PARAMETERS.iter().map(|p| p.0).collect()
+    }
+
+    fn load_preset(&mut self, name: &str) -> bool {
+        let Some(&(_, c)) = PARAMETERS.iter().find(|p| p.0 == name) else {
+            return false;
+        };
+        self.c = c;
+        true
+    }
+
+    fn save_state(&self) -> Value {
+        json!({
+            "max_iterations": self.max_iterations,
+            "c": self.c,
+            "slice_z": self.slice_z,
+            "slice_w": self.slice_w,
+            "zoom": self.zoom,
+            "center_x": self.center_x,
+            "center_y": self.center_y,
+            "color_scheme": self.color_scheme,
+            "smooth_coloring": self.smooth_coloring,
+            "invert_colors": self.invert_colors,
+            "color_offset": self.palette_transform.offset,
+            "reverse_palette": self.palette_transform.reversed,
+            "channel_offsets": self.channel_offsets,
+            "sweep": self.sweep,
+            "sweep_path": self.sweep_path,
+            "sweep_amplitude": self.sweep_amplitude,
+            "sweep_center_z": self.sweep_center_z,
+            "sweep_center_w": self.sweep_center_w,
+            "sweep_speed": self.sweep_speed,
+            "sweep_phase": self.sweep_phase,
+        })
+    }
+
+    fn load_state(&mut self, state: &Value) {
+        read_clamped(state, "max_iterations", &mut self.max_iterations, 10..=500);
+        read(state, "c", &mut self.c);
+        for value in &mut self.c {
+            *value = if value.is_nan() { 0.0 } else { value.clamp(-2.0, 2.0) };
+        }
+        read_clamped(state, "slice_z", &mut self.slice_z, -1.5..=1.5);
+        read_clamped(state, "slice_w", &mut self.slice_w, -1.5..=1.5);
+        read_clamped(state, "zoom", &mut self.zoom, 0.1..=1000.0);
+        read_clamped(state, "center_x", &mut self.center_x, -4.0..=4.0);
+        read_clamped(state, "center_y", &mut self.center_y, -4.0..=4.0);
+        read(state, "color_scheme", &mut self.color_scheme);
+        read(state, "smooth_coloring", &mut self.smooth_coloring);
+        read(state, "invert_colors", &mut self.invert_colors);
+        read_clamped(state, "color_offset", &mut self.palette_transform.offset, 0.0..=1.0);
+        read(state, "reverse_palette", &mut self.palette_transform.reversed);
+        read_channel_offsets(state, &mut self.channel_offsets);
+        read(state, "sweep", &mut self.sweep);
+        read(state, "sweep_path", &mut self.sweep_path);
+        read_clamped(state, "sweep_amplitude", &mut self.sweep_amplitude, 0.05..=1.5);
+        read_clamped(state, "sweep_center_z", &mut self.sweep_center_z, -1.5..=1.5);
+        read_clamped(state, "sweep_center_w", &mut self.sweep_center_w, -1.5..=1.5);
+        read_clamped(state, "sweep_speed", &mut self.sweep_speed, 0.02..=2.0);
+        read_clamped(state, "sweep_phase", &mut self.sweep_phase, 0.0..=std::f32::consts::TAU);
+    }
+}
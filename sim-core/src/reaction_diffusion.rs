@@ -1,4 +1,77 @@
-use crate::{Color, ColorScheme, Simulation2D};
+use crate::color_ui::color_scheme_combo;
+use crate::nudge::Nudge;
+use crate::state::{read, read_clamped};
+use crate::{Color, ColorScheme, RenderCtx, Simulation2D};
+use noise::{NoiseFn, Perlin};
+use serde_json::{json, Value};
+
+/// Named (feed, kill) rates
+const PRESETS: [(&str, f32, f32); 5] = [
+    ("Coral Growth", 0.055, 0.062),
+    ("Spots", 0.035, 0.065),
+    ("Stripes", 0.025, 0.055),
+    ("Waves", 0.014, 0.054),
+    ("Maze", 0.029, 0.057),
+];
+
+/// Velocity field that carries both chemicals between reaction steps
+#[derive(Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+pub enum FlowMode {
+    None,    // Plain Gray-Scott
+    Uniform, // Same velocity everywhere
+    Noise,   // Direction follows a Perlin field
+}
+
+impl FlowMode {
+    pub fn all() -> Vec<FlowMode> {
+        vec![FlowMode::None, FlowMode::Uniform, FlowMode::Noise]
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            FlowMode::None => "None",
+            FlowMode::Uniform => "Uniform",
+            FlowMode::Noise => "Noise",
+        }
+    }
+}
+
+/// What the Laplacian and the flow see past the edges of the grid
+#[derive(Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+pub enum BoundaryMode {
+    Periodic,  // Edges wrap around, so the grid tiles seamlessly
+    Neumann,   // Zero flux: the edge cell is mirrored, nothing crosses the edge
+    Dirichlet, // Outside is held at the unreacted state and absorbs B
+}
+
+impl BoundaryMode {
+    pub fn all() -> Vec<BoundaryMode> {
+        vec![BoundaryMode::Periodic, BoundaryMode::Neumann, BoundaryMode::Dirichlet]
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            BoundaryMode::Periodic => "Periodic",
+            BoundaryMode::Neumann => "Zero Flux (Neumann)",
+            BoundaryMode::Dirichlet => "Absorbing (Dirichlet)",
+        }
+    }
+
+    fn description(&self) -> &str {
+        match self {
+            BoundaryMode::Periodic => "Opposite edges are joined; patterns leaving one side enter the other and the image tiles seamlessly",
+            BoundaryMode::Neumann => "Edges act as walls: nothing diffuses or flows across them",
+            BoundaryMode::Dirichlet => "Beyond the edges is fresh A with no B, so B is drained where patterns touch the edges",
+        }
+    }
+}
+
+/// Most steps `update` takes in one frame: the top rate over a 10 fps frame
+const MAX_STEPS_PER_FRAME: f32 = 200.0;
+
+/// A and B just beyond the edges in Dirichlet mode: the unreacted state
+const DIRICHLET_A: f32 = 1.0;
+const DIRICHLET_B: f32 = 0.0;
 
 pub struct ReactionDiffusion {
     pub feed_rate: f32,
@@ -8,12 +81,25 @@ pub struct ReactionDiffusion {
     pub color_scheme: ColorScheme,
     pub resolution: usize,
     pub show_grid: bool,
+    pub brush_radius: usize,
+    pub steps_per_second: f32,
+    pub boundary: BoundaryMode,
+    pub flow_mode: FlowMode,
+    /// Degrees, counterclockwise from +x on screen
+    pub flow_angle: f32,
+    /// Cells per step. Kept at or below 0.5 so the upwind step stays monotone.
+    pub flow_strength: f32,
+    pub flow_noise_scale: f32,
+    pub flow_seed: u32,
+    /// Per-cell velocity, rebuilt whenever the flow settings or grid change
+    flow_field: Vec<[f32; 2]>,
     grid_a: Vec<f32>,
     grid_b: Vec<f32>,
     next_a: Vec<f32>,
     next_b: Vec<f32>,
     width: usize,
     height: usize,
+    time_accumulator: f32,
 }
 
 impl Default for ReactionDiffusion {
@@ -30,12 +116,22 @@ impl Default for ReactionDiffusion {
             color_scheme: ColorScheme::Viridis,
             resolution: 128,
             show_grid: false,
+            brush_radius: 4,
+            steps_per_second: 300.0,
+            boundary: BoundaryMode::Periodic,
+            flow_mode: FlowMode::None,
+            flow_angle: 0.0,
+            flow_strength: 0.2,
+            flow_noise_scale: 0.05,
+            flow_seed: 7,
+            flow_field: Vec::new(),
             grid_a: vec![1.0; size],
             grid_b: vec![0.0; size],
             next_a: vec![0.0; size],
             next_b: vec![0.0; size],
             width,
             height,
+            time_accumulator: 0.0,
         };
 
         // Seed with some B in the center
@@ -54,22 +150,119 @@ impl ReactionDiffusion {
         Self::default()
     }
 
-    fn laplacian(&self, grid: &[f32], x: usize, y: usize) -> f32 {
-        let mut sum = 0.0;
+    /// Recompute `flow_field` from the flow settings
+    fn rebuild_flow(&mut self) {
+        self.flow_strength = self.flow_strength.clamp(0.0, 0.5);
+        let size = self.width * self.height;
+        self.flow_field.clear();
+
+        match self.flow_mode {
+            FlowMode::None => {}
+            FlowMode::Uniform => {
+                let angle = self.flow_angle.to_radians();
+                // Screen y points down, so a positive angle flows upward
+                let velocity = [angle.cos() * self.flow_strength, -angle.sin() * self.flow_strength];
+                self.flow_field = vec![velocity; size];
+            }
+            FlowMode::Noise => {
+                let perlin = Perlin::new(self.flow_seed);
+                let scale = self.flow_noise_scale as f64;
+                let offset = self.flow_angle.to_radians();
+                self.flow_field.reserve(size);
+                for y in 0..self.height {
+                    for x in 0..self.width {
+                        let n = perlin.get([x as f64 * scale, y as f64 * scale]) as f32;
+                        let angle = offset + n * std::f32::consts::TAU;
+                        self.flow_field.push([angle.cos() * self.flow_strength, -angle.sin() * self.flow_strength]);
+                    }
+                }
+            }
+        }
+    }
 
-        let left = if x > 0 { x - 1 } else { self.width - 1 };
-        let right = if x < self.width - 1 { x + 1 } else { 0 };
-        let up = if y > 0 { y - 1 } else { self.height - 1 };
-        let down = if y < self.height - 1 { y + 1 } else { 0 };
+    /// Reallocate the grids at `resolution` and re-seed the center
+    fn resize_grid(&mut self) {
+        self.width = self.resolution;
+        self.height = self.resolution;
+        let size = self.width * self.height;
+
+        self.grid_a = vec![1.0; size];
+        self.grid_b = vec![0.0; size];
+        self.next_a = vec![0.0; size];
+        self.next_b = vec![0.0; size];
+
+        // Re-seed
+        for y in (self.height / 2 - 5)..(self.height / 2 + 5) {
+            for x in (self.width / 2 - 5)..(self.width / 2 + 5) {
+                if y < self.height && x < self.width {
+                    self.grid_b[y * self.width + x] = 1.0;
+                }
+            }
+        }
 
-        sum += grid[y * self.width + left];
-        sum += grid[y * self.width + right];
-        sum += grid[up * self.width + x];
-        sum += grid[down * self.width + x];
-        sum += grid[up * self.width + left] * 0.05;
-        sum += grid[up * self.width + right] * 0.05;
-        sum += grid[down * self.width + left] * 0.05;
-        sum += grid[down * self.width + right] * 0.05;
+        self.rebuild_flow();
+    }
+
+    /// Set V (the B chemical) to 1 in a disc around grid cell (gx, gy).
+    /// The disc wraps around the edges in periodic mode and is cut off by
+    /// them otherwise.
+    pub fn seed_at(&mut self, gx: usize, gy: usize, radius: usize) {
+        let r = radius as i32;
+        for dy in -r..=r {
+            for dx in -r..=r {
+                if dx * dx + dy * dy > r * r {
+                    continue;
+                }
+                let (mut x, mut y) = (gx as i32 + dx, gy as i32 + dy);
+                if self.boundary == BoundaryMode::Periodic {
+                    x = x.rem_euclid(self.width as i32);
+                    y = y.rem_euclid(self.height as i32);
+                } else if x < 0 || y < 0 || x >= self.width as i32 || y >= self.height as i32 {
+                    continue;
+                }
+                self.grid_b[y as usize * self.width + x as usize] = 1.0;
+            }
+        }
+    }
+
+    /// Index of the neighbour `delta` cells from `i` along an axis of
+    /// length `len`, or `None` past a Dirichlet edge
+    fn neighbor(&self, i: usize, delta: isize, len: usize) -> Option<usize> {
+        let j = i as isize + delta;
+        if (0..len as isize).contains(&j) {
+            return Some(j as usize);
+        }
+        match self.boundary {
+            BoundaryMode::Periodic => Some(j.rem_euclid(len as isize) as usize),
+            BoundaryMode::Neumann => Some(j.clamp(0, len as isize - 1) as usize),
+            BoundaryMode::Dirichlet => None,
+        }
+    }
+
+    /// Whether the neighbour `delta` cells from `i` is past a zero-flux edge
+    fn walled(&self, i: usize, delta: isize, len: usize) -> bool {
+        self.boundary == BoundaryMode::Neumann && !(0..len as isize).contains(&(i as isize + delta))
+    }
+
+    /// `grid` at (x + dx, y + dy), with `outside` past a Dirichlet edge
+    fn sample(&self, grid: &[f32], x: usize, y: usize, dx: isize, dy: isize, outside: f32) -> f32 {
+        match (self.neighbor(x, dx, self.width), self.neighbor(y, dy, self.height)) {
+            (Some(nx), Some(ny)) => grid[ny * self.width + nx],
+            _ => outside,
+        }
+    }
+
+    fn laplacian(&self, grid: &[f32], x: usize, y: usize, outside: f32) -> f32 {
+        let mut sum = 0.0;
+
+        sum += self.sample(grid, x, y, -1, 0, outside);
+        sum += self.sample(grid, x, y, 1, 0, outside);
+        sum += self.sample(grid, x, y, 0, -1, outside);
+        sum += self.sample(grid, x, y, 0, 1, outside);
+        sum += self.sample(grid, x, y, -1, -1, outside) * 0.05;
+        sum += self.sample(grid, x, y, 1, -1, outside) * 0.05;
+        sum += self.sample(grid, x, y, -1, 1, outside) * 0.05;
+        sum += self.sample(grid, x, y, 1, 1, outside) * 0.05;
 
         sum /= 4.2;
         sum - grid[y * self.width + x]
@@ -82,8 +275,8 @@ impl ReactionDiffusion {
                 let a = self.grid_a[idx];
                 let b = self.grid_b[idx];
 
-                let lap_a = self.laplacian(&self.grid_a, x, y);
-                let lap_b = self.laplacian(&self.grid_b, x, y);
+                let lap_a = self.laplacian(&self.grid_a, x, y, DIRICHLET_A);
+                let lap_b = self.laplacian(&self.grid_b, x, y, DIRICHLET_B);
 
                 let reaction = a * b * b;
 
@@ -97,6 +290,60 @@ impl ReactionDiffusion {
 
         std::mem::swap(&mut self.grid_a, &mut self.next_a);
         std::mem::swap(&mut self.grid_b, &mut self.next_b);
+
+        if self.flow_mode != FlowMode::None && self.flow_strength > 0.0 {
+            self.advect();
+        }
+    }
+
+    /// Move both chemicals along `flow_field` with first-order upwind
+    /// differences. This runs as its own pass after the reaction-diffusion
+    /// update: with |u| + |v| <= 1 every new value is a convex combination of
+    /// old ones, so the step can't overshoot or ring the way a central
+    /// difference (or one folded into the diffusion update) would. The
+    /// exception is a cell against a zero-flux wall, where what flows in
+    /// piles up; the clamp in the next reaction update caps it.
+    fn advect(&mut self) {
+        if self.flow_field.len() != self.width * self.height {
+            self.rebuild_flow();
+        }
+
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let idx = y * self.width + x;
+                let [u, v] = self.flow_field[idx];
+                // Upwind neighbours: the cells the flow is coming from
+                let (dx, mut sx) = if u > 0.0 { (-1, u) } else { (1, -u) };
+                let (dy, mut sy) = if v > 0.0 { (-1, v) } else { (1, -v) };
+                let mut stay = 1.0 - sx - sy;
+
+                // Walls carry no flux: nothing flows in from behind one, and
+                // what would flow out through one stays put
+                if self.walled(x, -dx, self.width) {
+                    stay += sx;
+                }
+                if self.walled(y, -dy, self.height) {
+                    stay += sy;
+                }
+                if self.walled(x, dx, self.width) {
+                    sx = 0.0;
+                }
+                if self.walled(y, dy, self.height) {
+                    sy = 0.0;
+                }
+
+                let upwind = |grid: &[f32], outside: f32| {
+                    sx * self.sample(grid, x, y, dx, 0, outside) + sy * self.sample(grid, x, y, 0, dy, outside)
+                };
+                let a = stay * self.grid_a[idx] + upwind(&self.grid_a, DIRICHLET_A);
+                let b = stay * self.grid_b[idx] + upwind(&self.grid_b, DIRICHLET_B);
+                self.next_a[idx] = a;
+                self.next_b[idx] = b;
+            }
+        }
+
+        std::mem::swap(&mut self.grid_a, &mut self.next_a);
+        std::mem::swap(&mut self.grid_b, &mut self.next_b);
     }
 }
 
@@ -105,7 +352,21 @@ impl Simulation2D for ReactionDiffusion {
         "Reaction-Diffusion"
     }
 
+    fn equations(&self) -> Option<&str> {
+        Some(
+            "Gray-Scott model\n\
+             ∂A/∂t = D_A ∇²A − A B² + F (1 − A)\n\
+             ∂B/∂t = D_B ∇²B + A B² − (k + F) B\n\
+             F = feed rate, k = kill rate",
+        )
+    }
+
     fn compute(&self, width: usize, height: usize) -> Vec<Color> {
+        self.compute_ctx(&RenderCtx::new(width, height))
+    }
+
+    fn compute_ctx(&self, ctx: &RenderCtx) -> Vec<Color> {
+        let (width, height) = (ctx.width, ctx.height);
         let mut pixels = vec![Color::BLACK; width * height];
 
         let cell_width = width / self.width;
@@ -116,7 +377,7 @@ impl Simulation2D for ReactionDiffusion {
                 let idx = gy * self.width + gx;
                 let value = self.grid_b[idx];
 
-                let color = self.color_scheme.map(value, true);
+                let color = self.color_scheme.map(ctx.cycle_palette(value), true);
 
                 // Fill cell
                 for py in 0..cell_height {
@@ -132,7 +393,45 @@ impl Simulation2D for ReactionDiffusion {
             }
         }
 
-        pixels
+        ctx.over_background(pixels)
+    }
+
+    fn scalar_field(&self) -> Option<(Vec<f32>, usize, usize)> {
+        Some((self.grid_b.clone(), self.width, self.height))
+    }
+
+    fn paint_at(&mut self, x: f32, y: f32, width: usize, height: usize, erase: bool) -> bool {
+        if erase {
+            return false;
+        }
+
+        // Same cell size as compute(), so the brush lands on the cell under the cursor
+        let cell_width = (width / self.width).max(1);
+        let cell_height = (height / self.height).max(1);
+
+        if x < 0.0 || y < 0.0 {
+            return false;
+        }
+        let gx = x as usize / cell_width;
+        let gy = y as usize / cell_height;
+        if gx >= self.width || gy >= self.height {
+            return false;
+        }
+
+        self.seed_at(gx, gy, self.brush_radius);
+        true
+    }
+
+    fn reset(&mut self) {
+        let size = self.width * self.height;
+        self.grid_a = vec![1.0; size];
+        self.grid_b = vec![0.0; size];
+
+        for y in (self.height / 2 - 5)..(self.height / 2 + 5) {
+            for x in (self.width / 2 - 5)..(self.width / 2 + 5) {
+                self.grid_b[y * self.width + x] = 1.0;
+            }
+        }
     }
 
     fn ui_parameters(&mut self, ui: &mut egui::Ui) -> bool {
@@ -144,100 +443,182 @@ impl Simulation2D for ReactionDiffusion {
             .default_open(true)
             .show(ui, |ui| {
                 changed |= ui.add(egui::Slider::new(&mut self.feed_rate, 0.01..=0.1)
+                    .nudge(ui, 0.0005)
                     .text("Feed Rate (F)")).changed();
                 changed |= ui.add(egui::Slider::new(&mut self.kill_rate, 0.03..=0.08)
+                    .nudge(ui, 0.0005)
                     .text("Kill Rate (k)")).changed();
 
                 changed |= ui.add(egui::Slider::new(&mut self.diffusion_a, 0.5..=2.0)
+                    .nudge(ui, 0.01)
                     .text("Diffusion A")).changed();
                 changed |= ui.add(egui::Slider::new(&mut self.diffusion_b, 0.1..=1.0)
+                    .nudge(ui, 0.005)
                     .text("Diffusion B")).changed();
-            });
-
-        egui::CollapsingHeader::new("🔍 Display Settings")
-            .default_open(true)
-            .show(ui, |ui| {
-                if ui.add(egui::Slider::new(&mut self.resolution, 32..=256)
-                    .text("Resolution")).changed() {
-                    self.width = self.resolution;
-                    self.height = self.resolution;
-                    let size = self.width * self.height;
-
-                    self.grid_a = vec![1.0; size];
-                    self.grid_b = vec![0.0; size];
-                    self.next_a = vec![0.0; size];
-                    self.next_b = vec![0.0; size];
-
-                    // Re-seed
-                    for y in (self.height / 2 - 5)..(self.height / 2 + 5) {
-                        for x in (self.width / 2 - 5)..(self.width / 2 + 5) {
-                            if y < self.height && x < self.width {
-                                self.grid_b[y * self.width + x] = 1.0;
-                            }
-                        }
-                    }
 
-                    changed = true;
-                }
+                changed |= ui.add(egui::Slider::new(&mut self.steps_per_second, 10.0..=2000.0)
+                    .nudge(ui, 5.0)
+                    .logarithmic(true)
+                    .text("Steps per second")).changed();
 
-                egui::ComboBox::from_label("Color Scheme")
-                    .selected_text(self.color_scheme.name())
+                // Only the sampling changes, so the current pattern carries
+                // over to the new mode
+                egui::ComboBox::from_label("Edges")
+                    .selected_text(self.boundary.name())
                     .show_ui(ui, |ui| {
-                        for scheme in ColorScheme::all() {
-                            if ui.selectable_value(&mut self.color_scheme, scheme, scheme.name()).clicked() {
-                                changed = true;
-                            }
+                        for mode in BoundaryMode::all() {
+                            changed |= ui.selectable_value(&mut self.boundary, mode, mode.name())
+                                .on_hover_text(mode.description())
+                                .clicked();
                         }
                     });
             });
 
-        egui::CollapsingHeader::new("🎯 Presets")
+        egui::CollapsingHeader::new("🌊 Flow")
             .show(ui, |ui| {
-                if ui.button("Coral Growth").clicked() {
-                    self.feed_rate = 0.055;
-                    self.kill_rate = 0.062;
-                    changed = true;
-                }
-                if ui.button("Spots").clicked() {
-                    self.feed_rate = 0.035;
-                    self.kill_rate = 0.065;
-                    changed = true;
+                let mut flow_changed = false;
+
+                egui::ComboBox::from_label("Flow Field")
+                    .selected_text(self.flow_mode.name())
+                    .show_ui(ui, |ui| {
+                        for mode in FlowMode::all() {
+                            flow_changed |= ui.selectable_value(&mut self.flow_mode, mode, mode.name()).clicked();
+                        }
+                    });
+
+                if self.flow_mode != FlowMode::None {
+                    flow_changed |= ui.add(egui::Slider::new(&mut self.flow_angle, 0.0..=360.0)
+                        .nudge(ui, 2.0)
+                        .suffix("°")
+                        .text("Direction")).changed();
+                    flow_changed |= ui.add(egui::Slider::new(&mut self.flow_strength, 0.0..=0.5)
+                        .nudge(ui, 0.005)
+                        .text("Strength")).changed();
                 }
-                if ui.button("Stripes").clicked() {
-                    self.feed_rate = 0.025;
-                    self.kill_rate = 0.055;
-                    changed = true;
+
+                if self.flow_mode == FlowMode::Noise {
+                    flow_changed |= ui.add(egui::Slider::new(&mut self.flow_noise_scale, 0.005..=0.2)
+                        .nudge(ui, 0.002)
+                        .logarithmic(true)
+                        .text("Noise Scale")).changed();
+                    if ui.button("🎲 New Field").clicked() {
+                        self.flow_seed = self.flow_seed.wrapping_add(1);
+                        flow_changed = true;
+                    }
                 }
-                if ui.button("Waves").clicked() {
-                    self.feed_rate = 0.014;
-                    self.kill_rate = 0.054;
+
+                if flow_changed {
+                    self.rebuild_flow();
                     changed = true;
                 }
-                if ui.button("Maze").clicked() {
-                    self.feed_rate = 0.029;
-                    self.kill_rate = 0.057;
+            });
+
+        egui::CollapsingHeader::new("🔍 Display Settings")
+            .default_open(true)
+            .show(ui, |ui| {
+                if ui.add(egui::Slider::new(&mut self.resolution, 32..=256)
+                    .nudge(ui, 1.0)
+                    .text("Resolution")).changed() {
+                    self.resize_grid();
                     changed = true;
                 }
+
+                changed |= color_scheme_combo(ui, &mut self.color_scheme);
             });
 
-        if ui.button("🔄 Reset").clicked() {
-            let size = self.width * self.height;
-            self.grid_a = vec![1.0; size];
-            self.grid_b = vec![0.0; size];
+        egui::CollapsingHeader::new("🖌 Paint Seed")
+            .show(ui, |ui| {
+                ui.add(egui::Slider::new(&mut self.brush_radius, 1..=20)
+                    .nudge(ui, 1.0)
+                    .text("Brush Radius"));
+                ui.label("Click or drag on the canvas to seed new spots");
+            });
 
-            for y in (self.height / 2 - 5)..(self.height / 2 + 5) {
-                for x in (self.width / 2 - 5)..(self.width / 2 + 5) {
-                    self.grid_b[y * self.width + x] = 1.0;
+        egui::CollapsingHeader::new("🎯 Presets")
+            .show(ui, |ui| {
+                for (name, feed, kill) in PRESETS {
+                    if ui.button(name).clicked() {
+                        self.feed_rate = feed;
+                        self.kill_rate = kill;
+                        changed = true;
+                    }
                 }
-            }
+            });
+
+        if ui.button("🔄 Reset").clicked() {
+            self.reset();
         }
 
-        // Run simulation steps
-        for _ in 0..5 {
+        changed
+    }
+
+    fn update(&mut self, dt: f32, _width: usize, _height: usize) -> bool {
+        // Run simulation steps at a fixed rate, independent of frame rate.
+        // After a stall the backlog is dropped rather than run in one frame.
+        self.time_accumulator = (self.time_accumulator + dt * self.steps_per_second).min(MAX_STEPS_PER_FRAME);
+
+        while self.time_accumulator >= 1.0 {
             self.step();
+            self.time_accumulator -= 1.0;
         }
-        changed = true;
+        true
+    }
 
-        changed
+    fn preset_names(&self) -> Vec<&'static str> {
+        PRESETS.iter().map(|p| p.0).collect()
+    }
+
+    fn load_preset(&mut self, name: &str) -> bool {
+        let Some(&(_, feed, kill)) = PRESETS.iter().find(|p| p.0 == name) else {
+            return false;
+        };
+        self.feed_rate = feed;
+        self.kill_rate = kill;
+        true
+    }
+
+    fn save_state(&self) -> Value {
+        json!({
+            "feed_rate": self.feed_rate,
+            "kill_rate": self.kill_rate,
+            "diffusion_a": self.diffusion_a,
+            "diffusion_b": self.diffusion_b,
+            "color_scheme": self.color_scheme,
+            "resolution": self.resolution,
+            "show_grid": self.show_grid,
+            "brush_radius": self.brush_radius,
+            "steps_per_second": self.steps_per_second,
+            "boundary": self.boundary,
+            "flow_mode": self.flow_mode,
+            "flow_angle": self.flow_angle,
+            "flow_strength": self.flow_strength,
+            "flow_noise_scale": self.flow_noise_scale,
+            "flow_seed": self.flow_seed,
+        })
+    }
+
+    fn load_state(&mut self, state: &Value) {
+        read_clamped(state, "feed_rate", &mut self.feed_rate, 0.01..=0.1);
+        read_clamped(state, "kill_rate", &mut self.kill_rate, 0.03..=0.08);
+        read_clamped(state, "diffusion_a", &mut self.diffusion_a, 0.5..=2.0);
+        read_clamped(state, "diffusion_b", &mut self.diffusion_b, 0.1..=1.0);
+        read(state, "color_scheme", &mut self.color_scheme);
+        read(state, "show_grid", &mut self.show_grid);
+        read_clamped(state, "brush_radius", &mut self.brush_radius, 1..=20);
+        read_clamped(state, "steps_per_second", &mut self.steps_per_second, 10.0..=2000.0);
+        read(state, "boundary", &mut self.boundary);
+        read(state, "flow_mode", &mut self.flow_mode);
+        read_clamped(state, "flow_angle", &mut self.flow_angle, 0.0..=360.0);
+        read_clamped(state, "flow_strength", &mut self.flow_strength, 0.0..=0.5);
+        read_clamped(state, "flow_noise_scale", &mut self.flow_noise_scale, 0.005..=0.2);
+        read(state, "flow_seed", &mut self.flow_seed);
+
+        let resolution = self.resolution;
+        read_clamped(state, "resolution", &mut self.resolution, 32..=256);
+        if self.resolution != resolution {
+            self.resize_grid();
+        } else {
+            self.rebuild_flow();
+        }
     }
 }
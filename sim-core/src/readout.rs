@@ -0,0 +1,166 @@
+//! Number formatting for the info readouts under each sim's parameters, so
+//! counts, rates and times read the same everywhere. The style is one
+//! app-wide setting rather than a per-call choice.
+
+use std::sync::RwLock;
+
+/// Counts at or above this are shortened with an SI suffix in compact style
+const COMPACT_FROM: f64 = 10_000.0;
+
+/// SI suffixes for counts, each 1000 times the last
+const SUFFIXES: [&str; 5] = ["", "k", "M", "G", "T"];
+
+#[derive(Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+pub enum Separator {
+    Comma,
+    Space,
+    None,
+}
+
+impl Separator {
+    pub fn all() -> Vec<Separator> {
+        vec![Separator::Comma, Separator::Space, Separator::None]
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            Separator::Comma => "1,234,567",
+            Separator::Space => "1 234 567",
+            Separator::None => "1234567",
+        }
+    }
+
+    fn text(&self) -> &str {
+        match self {
+            Separator::Comma => ",",
+            Separator::Space => " ",
+            Separator::None => "",
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct NumberStyle {
+    /// Shorten large counts to e.g. "1.2M"
+    pub compact: bool,
+    /// Between groups of three digits
+    pub separator: Separator,
+}
+
+const DEFAULT_STYLE: NumberStyle = NumberStyle {
+    compact: true,
+    separator: Separator::Comma,
+};
+
+impl Default for NumberStyle {
+    fn default() -> Self {
+        DEFAULT_STYLE
+    }
+}
+
+static STYLE: RwLock<NumberStyle> = RwLock::new(DEFAULT_STYLE);
+
+impl NumberStyle {
+    /// The style every readout currently uses
+    pub fn current() -> NumberStyle {
+        STYLE.read().map_or_else(|e| *e.into_inner(), |style| *style)
+    }
+
+    pub fn set(style: NumberStyle) {
+        match STYLE.write() {
+            Ok(mut current) => *current = style,
+            Err(e) => *e.into_inner() = style,
+        }
+    }
+
+    /// Settings; returns true if they changed
+    pub fn ui(ui: &mut egui::Ui) -> bool {
+        let mut style = NumberStyle::current();
+        let mut changed = ui.checkbox(&mut style.compact, "Compact Large Numbers")
+            .on_hover_text("Show counts of 10,000 and up as e.g. 12.3k or 1.2M")
+            .changed();
+        egui::ComboBox::from_label("Digit Grouping")
+            .selected_text(style.separator.name())
+            .show_ui(ui, |ui| {
+                for separator in Separator::all() {
+                    changed |= ui.selectable_value(&mut style.separator, separator, separator.name()).changed();
+                }
+            });
+        if changed {
+            NumberStyle::set(style);
+        }
+        changed
+    }
+}
+
+/// A count of things: grouped digits, or an SI suffix once it's large and
+/// the style is compact
+pub fn count<N: TryInto<u64>>(n: N) -> String {
+    let n = n.try_into().unwrap_or(u64::MAX);
+    let style = NumberStyle::current();
+    if !style.compact || (n as f64) < COMPACT_FROM {
+        return group(&n.to_string(), style.separator);
+    }
+
+    let mut value = n as f64;
+    let mut suffix = 0;
+    // Move up a unit when rounding would show 1000 of the current one
+    while suffix + 1 < SUFFIXES.len() && value >= 999.5 {
+        value /= 1000.0;
+        suffix += 1;
+    }
+    if value < 99.95 {
+        format!("{value:.1}{}", SUFFIXES[suffix])
+    } else {
+        format!("{}{}", decimal(value, 0), SUFFIXES[suffix])
+    }
+}
+
+/// `count` of `n` out of `total`, e.g. "1.2k / 5,000"
+pub fn fraction<N: TryInto<u64>, T: TryInto<u64>>(n: N, total: T) -> String {
+    format!("{} / {}", count(n), count(total))
+}
+
+/// `value` with `places` decimals and grouped integer digits
+pub fn decimal(value: impl Into<f64>, places: usize) -> String {
+    let value = value.into();
+    if !value.is_finite() {
+        return value.to_string();
+    }
+    let text = format!("{:.*}", places, value.abs());
+    let (whole, fraction) = text.split_once('.').map_or((text.as_str(), None), |(w, f)| (w, Some(f)));
+    let sign = if value < 0.0 && text.chars().any(|c| c.is_ascii_digit() && c != '0') { "-" } else { "" };
+    let whole = group(whole, NumberStyle::current().separator);
+    match fraction {
+        Some(fraction) => format!("{sign}{whole}.{fraction}"),
+        None => format!("{sign}{whole}"),
+    }
+}
+
+/// A 0..1 `fraction` as a percentage
+pub fn percent(fraction: impl Into<f64>, places: usize) -> String {
+    format!("{}%", decimal(fraction.into() * 100.0, places))
+}
+
+/// A duration in seconds
+pub fn seconds(seconds: impl Into<f64>, places: usize) -> String {
+    format!("{} s", decimal(seconds, places))
+}
+
+/// A duration in milliseconds
+pub fn millis(millis: impl Into<f64>, places: usize) -> String {
+    format!("{} ms", decimal(millis, places))
+}
+
+/// Insert `separator` between groups of three in a string of digits
+fn group(digits: &str, separator: Separator) -> String {
+    let separator = separator.text();
+    let mut grouped = String::with_capacity(digits.len() + digits.len() / 3 * separator.len());
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            grouped.push_str(separator);
+        }
+        grouped.push(digit);
+    }
+    grouped
+}
@@ -0,0 +1,109 @@
+//! Every simulation the app offers, in menu order. The app builds its lists
+//! from here, and the integration tests run each entry headlessly, so a new
+//! sim is covered as soon as it is registered.
+
+use crate::*;
+
+type New2D = fn() -> Simulation2DBox;
+
+/// Each 2D simulation's name, as its `name()` returns it, and constructor
+const SIMULATIONS_2D: &[(&str, New2D)] = &[
+    // Fractals
+    ("Mandelbrot Set", || Box::new(mandelbrot::Mandelbrot::new())),
+    ("Julia Set", || Box::new(julia::Julia::new())),
+    ("Burning Ship Fractal", || Box::new(burning_ship::BurningShip::new())),
+    ("Quaternion Julia", || Box::new(quaternion_julia::QuaternionJulia::new())),
+
+    // Cellular Systems & Emergent Complexity
+    ("Conway's Game of Life", || Box::new(game_of_life::GameOfLife::new())),
+    ("Elementary Cellular Automaton", || Box::new(cellular_automaton::CellularAutomaton::new(30))),
+    ("Langton's Ant", || Box::new(langtons_ant::LangtonsAnt::new())),
+    ("Cyclic Cellular Automaton", || Box::new(cyclic_ca::CyclicCA::new())),
+
+    // Growth & Self-Organization
+    ("Diffusion-Limited Aggregation", || Box::new(dla::DLA::new())),
+    ("Sandpile Model", || Box::new(sandpile::Sandpile::new())),
+
+    // Animated Simulations
+    ("Double Pendulum", || Box::new(double_pendulum::DoublePendulum::new())),
+    ("Reaction-Diffusion", || Box::new(reaction_diffusion::ReactionDiffusion::new())),
+    ("Lissajous Curves", || Box::new(lissajous::LissajousCurves::new())),
+    ("Wave Interference", || Box::new(wave_interference::WaveInterference::new())),
+
+    // Generative Patterns
+    ("Koch Snowflake", || Box::new(generative::KochSnowflake::new())),
+    ("Phyllotaxis Pattern", || Box::new(generative::Phyllotaxis::new())),
+    ("Perlin Flow Field", || Box::new(generative::PerlinFlow::new())),
+    ("Boids Flocking", || Box::new(generative::Boids::new())),
+    ("De Jong Attractor", || Box::new(generative::DeJongAttractor::new())),
+    ("Clifford Attractor", || Box::new(generative::CliffordAttractor::new())),
+    ("Symmetric Icons", || Box::new(generative::SymmetricIcons::new())),
+
+    // Complex Emergent Simulations
+    ("Slime Mold Simulation", || Box::new(slime_mold::SlimeMold::new())),
+    ("Falling Sand", || Box::new(falling_sand::FallingSand::new())),
+];
+
+/// A fresh instance of each 2D simulation
+pub fn simulations_2d() -> Vec<Simulation2DBox> {
+    SIMULATIONS_2D.iter().map(|(_, new)| new()).collect()
+}
+
+/// A fresh instance of the named 2D simulation, without building the others
+pub fn simulation_2d(name: &str) -> Option<Simulation2DBox> {
+    SIMULATIONS_2D.iter().find(|(sim, _)| *sim == name).map(|(_, new)| new())
+}
+
+/// A fresh instance of each 3D simulation
+pub fn simulations_3d() -> Vec<Simulation3DBox> {
+    vec![
+        // Stunning 3D Visualizations
+        Box::new(dna_helix::DNAHelix::new()),
+        Box::new(torus_knot::TorusKnot::new()),
+        Box::new(galaxy_spiral::GalaxySpiral::new()),
+
+        // Enhanced Particle Systems
+        Box::new(particle_attractor_3d::ParticleAttractor3D::new()),
+        Box::new(boids_3d::Boids3D::new()),
+
+        // Classic Attractors
+        Box::new(lorenz::LorenzAttractor::new()),
+        Box::new(rossler::RosslerAttractor::new()),
+
+        // Additional Chaotic Attractors
+        Box::new(aizawa::AizawaAttractor::new()),
+        Box::new(halvorsen::HalvorsenAttractor::new()),
+        Box::new(dadras::DadrasAttractor::new()),
+        Box::new(thomas::ThomasAttractor::new()),
+        Box::new(chen::ChenAttractor::new()),
+        Box::new(cyclic_symmetric::CyclicSymmetricAttractor::new()),
+
+        // Diverse Particle Simulations
+        Box::new(nbody_gravity::NBodyGravity::new()),
+        Box::new(fluid_sph::FluidSPH::new()),
+        Box::new(magnetic_field::MagneticField::new()),
+
+        // Radical 3D Animations
+        Box::new(vortex_turbulence::VortexTurbulence::new()),
+        Box::new(lightning_bolt::LightningBolt::new()),
+        Box::new(fractal_tree_3d::FractalTree3D::new()),
+
+        // 3D Fractals
+        Box::new(mandelbulb::Mandelbulb::new()),
+        Box::new(menger_sponge::MengerSponge::new()),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn each_2d_sim_is_listed_under_its_own_name() {
+        for (name, new) in SIMULATIONS_2D {
+            assert_eq!(new().name(), *name);
+            assert_eq!(simulation_2d(name).map(|sim| sim.name().to_string()).as_deref(), Some(*name));
+        }
+        assert!(simulation_2d("No Such Simulation").is_none());
+    }
+}
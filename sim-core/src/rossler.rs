@@ -1,4 +1,30 @@
-use crate::Simulation3D;
+use crate::color_ui::color_scheme_combo;
+use crate::nudge::Nudge;
+use crate::poincare::{PoincareSection, SectionAxis};
+use crate::seed::{SeedEdit, SeedPoint, SeedPreset};
+use crate::trajectory::{TrailEmitter, TrajectoryColoring};
+use crate::{Color, ColorScheme, Simulation3D};
+
+/// Seed points, with what the orbit does from each under the classic
+/// parameters. Both equilibria are saddle-foci, and orbits started far from
+/// the band can escape.
+const SEEDS: [SeedPreset; 3] = [
+    SeedPreset {
+        name: "Near Origin",
+        point: [0.1, 0.0, 0.0],
+        behavior: "Spirals outward in the x-y plane from the inner equilibrium and grows into the folded band",
+    },
+    SeedPreset {
+        name: "High Above",
+        point: [0.0, 0.0, 15.0],
+        behavior: "Drops almost straight down to the x-y plane, then spirals out into the band",
+    },
+    SeedPreset {
+        name: "Outer Equilibrium",
+        point: [5.6, -28.4, 28.4],
+        behavior: "Lingers beside the outer equilibrium, then makes one long dive down to the band",
+    },
+];
 
 pub struct RosslerAttractor {
     pub a: f32,
@@ -8,6 +34,11 @@ pub struct RosslerAttractor {
     current: [f32; 3],
     max_points: usize,
     pub speed: f32,
+    pub color_scheme: ColorScheme,
+    pub trajectory_coloring: TrajectoryColoring,
+    pub emitter: TrailEmitter,
+    pub section: PoincareSection,
+    pub seed: SeedPoint,
 }
 
 impl Default for RosslerAttractor {
@@ -20,6 +51,11 @@ impl Default for RosslerAttractor {
             current: [0.1, 0.0, 0.0],
             max_points: 5000,
             speed: 1.0,
+            color_scheme: ColorScheme::Rainbow,
+            trajectory_coloring: TrajectoryColoring::Index,
+            emitter: TrailEmitter::new(),
+            section: PoincareSection::new(SectionAxis::Y, 0.0),
+            seed: SeedPoint::new(30.0, &SEEDS),
         }
     }
 }
@@ -49,6 +85,14 @@ impl Simulation3D for RosslerAttractor {
         "Rössler Attractor"
     }
 
+    fn equations(&self) -> Option<&str> {
+        Some(
+            "dx/dt = −y − z\n\
+             dy/dt = x + a y\n\
+             dz/dt = b + z (x − c)",
+        )
+    }
+
     fn step(&mut self, dt: f32) {
         let dt = dt * self.speed * 0.05;
 
@@ -76,21 +120,27 @@ impl Simulation3D for RosslerAttractor {
         ];
         let k4 = self.compute_derivatives(temp);
 
+        let prev = self.current;
         self.current[0] += (k1[0] + 2.0 * k2[0] + 2.0 * k3[0] + k4[0]) * dt / 6.0;
         self.current[1] += (k1[1] + 2.0 * k2[1] + 2.0 * k3[1] + k4[1]) * dt / 6.0;
         self.current[2] += (k1[2] + 2.0 * k2[2] + 2.0 * k3[2] + k4[2]) * dt / 6.0;
 
-        self.points.push(self.current);
-
-        if self.points.len() > self.max_points {
-            self.points.remove(0);
-        }
+        self.section.record(prev, self.current);
+        self.emitter.push(&mut self.points, prev, self.current, self.max_points);
     }
 
     fn get_points(&self) -> Vec<[f32; 3]> {
         self.points.clone()
     }
 
+    fn get_colors(&self) -> Option<Vec<Color>> {
+        self.emitter.colors(&self.points, self.trajectory_coloring, &self.color_scheme)
+    }
+
+    fn poincare_section(&self) -> Option<&PoincareSection> {
+        Some(&self.section)
+    }
+
     fn ui_parameters(&mut self, ui: &mut egui::Ui) -> bool {
         let mut changed = false;
 
@@ -100,12 +150,15 @@ impl Simulation3D for RosslerAttractor {
             .default_open(true)
             .show(ui, |ui| {
                 changed |= ui.add(egui::Slider::new(&mut self.a, 0.0..=0.5)
+                    .nudge(ui, 0.005)
                     .text("a")).changed();
 
                 changed |= ui.add(egui::Slider::new(&mut self.b, 0.0..=2.0)
+                    .nudge(ui, 0.02)
                     .text("b")).changed();
 
                 changed |= ui.add(egui::Slider::new(&mut self.c, 0.0..=10.0)
+                    .nudge(ui, 0.1)
                     .text("c")).changed();
 
                 ui.label("Classic values: a=0.2, b=0.2, c=5.7");
@@ -115,10 +168,25 @@ impl Simulation3D for RosslerAttractor {
             .default_open(true)
             .show(ui, |ui| {
                 ui.add(egui::Slider::new(&mut self.speed, 0.1..=5.0)
+                    .nudge(ui, 0.02)
                     .text("Speed"));
 
                 ui.add(egui::Slider::new(&mut self.max_points, 100..=10000)
+                    .nudge(ui, 1.0)
                     .text("Trail Length"));
+                changed |= self.emitter.ui(ui, &mut self.points, self.max_points);
+
+                changed |= color_scheme_combo(ui, &mut self.color_scheme);
+
+                egui::ComboBox::from_label("Color By")
+                    .selected_text(self.trajectory_coloring.name())
+                    .show_ui(ui, |ui| {
+                        for coloring in TrajectoryColoring::all() {
+                            if ui.selectable_value(&mut self.trajectory_coloring, coloring, coloring.name()).clicked() {
+                                changed = true;
+                            }
+                        }
+                    });
 
                 if ui.button("🔄 Reset").clicked() {
                     self.reset();
@@ -126,6 +194,19 @@ impl Simulation3D for RosslerAttractor {
                 }
             });
 
+        self.section.ui(ui, -20.0..=30.0, &self.points);
+
+        let edit = self.seed.ui(ui);
+        if edit == SeedEdit::Randomize {
+            if let Some(point) = self.seed.random(|p| self.compute_derivatives(p), 0.01) {
+                self.seed.point = point;
+            }
+        }
+        if edit != SeedEdit::None {
+            self.reset();
+            changed = true;
+        }
+
         egui::CollapsingHeader::new("📍 Interesting Configurations")
             .show(ui, |ui| {
                 if ui.button("Classic Rössler").clicked() {
@@ -153,6 +234,8 @@ impl Simulation3D for RosslerAttractor {
 
     fn reset(&mut self) {
         self.points.clear();
-        self.current = [0.1, 0.0, 0.0];
+        self.emitter.clear();
+        self.section.clear();
+        self.current = self.seed.start(|p| self.compute_derivatives(p));
     }
 }
@@ -1,4 +1,7 @@
-use crate::{Color, ColorScheme, Simulation2D};
+use crate::color_ui::color_scheme_combo;
+use crate::nudge::Nudge;
+use crate::readout;
+use crate::{Color, ColorScheme, RenderCtx, Simulation2D};
 use rand::Rng;
 
 pub struct Sandpile {
@@ -138,22 +141,6 @@ impl Sandpile {
             self.total_avalanches += 1;
         }
     }
-
-    pub fn reset(&mut self) {
-        // Resize grids if dimensions changed
-        let required_size = self.grid_width * self.grid_height;
-        if self.grid.len() != required_size {
-            self.grid = vec![0; required_size];
-            self.avalanche_sites = vec![false; required_size];
-        } else {
-            self.grid.fill(0);
-            self.avalanche_sites.fill(false);
-        }
-
-        self.time_accumulator = 0.0;
-        self.total_drops = 0;
-        self.total_avalanches = 0;
-    }
 }
 
 impl Simulation2D for Sandpile {
@@ -162,6 +149,11 @@ impl Simulation2D for Sandpile {
     }
 
     fn compute(&self, width: usize, height: usize) -> Vec<Color> {
+        self.compute_ctx(&RenderCtx::new(width, height))
+    }
+
+    fn compute_ctx(&self, ctx: &RenderCtx) -> Vec<Color> {
+        let (width, height) = (ctx.width, ctx.height);
         let mut colors = Vec::with_capacity(width * height);
 
         let cell_width = width / self.grid_width;
@@ -182,7 +174,7 @@ impl Simulation2D for Sandpile {
                     } else {
                         // Color by sand amount
                         let t = (sand as f32 / self.critical_mass as f32).clamp(0.0, 1.0);
-                        colors.push(self.color_scheme.map(t, true));
+                        colors.push(self.color_scheme.map(ctx.cycle_palette(t), true));
                     }
                 } else {
                     colors.push(Color::BLACK);
@@ -190,7 +182,28 @@ impl Simulation2D for Sandpile {
             }
         }
 
-        colors
+        ctx.over_background(colors)
+    }
+
+    fn scalar_field(&self) -> Option<(Vec<f32>, usize, usize)> {
+        let heights = self.grid.iter().map(|&h| h as f32).collect();
+        Some((heights, self.grid_width, self.grid_height))
+    }
+
+    fn reset(&mut self) {
+        // Resize grids if dimensions changed
+        let required_size = self.grid_width * self.grid_height;
+        if self.grid.len() != required_size {
+            self.grid = vec![0; required_size];
+            self.avalanche_sites = vec![false; required_size];
+        } else {
+            self.grid.fill(0);
+            self.avalanche_sites.fill(false);
+        }
+
+        self.time_accumulator = 0.0;
+        self.total_drops = 0;
+        self.total_avalanches = 0;
     }
 
     fn ui_parameters(&mut self, ui: &mut egui::Ui) -> bool {
@@ -198,12 +211,12 @@ impl Simulation2D for Sandpile {
 
         ui.heading("Sandpile Model");
 
-        ui.label(format!("Drops: {}", self.total_drops));
-        ui.label(format!("Avalanches: {}", self.total_avalanches));
+        ui.label(format!("Drops: {}", readout::count(self.total_drops)));
+        ui.label(format!("Avalanches: {}", readout::count(self.total_avalanches)));
 
         if self.total_drops > 0 {
             let avalanche_rate = self.total_avalanches as f32 / self.total_drops as f32;
-            ui.label(format!("Avalanche rate: {:.1}%", avalanche_rate * 100.0));
+            ui.label(format!("Avalanche rate: {}", readout::percent(avalanche_rate, 1)));
         }
 
         ui.separator();
@@ -212,10 +225,12 @@ impl Simulation2D for Sandpile {
             .default_open(true)
             .show(ui, |ui| {
                 ui.add(egui::Slider::new(&mut self.drop_rate, 0.1..=100.0)
+                    .nudge(ui, 0.05)
                     .logarithmic(true)
                     .text("Drops per second"));
 
                 if ui.add(egui::Slider::new(&mut self.critical_mass, 3..=8)
+                    .nudge(ui, 1.0)
                     .text("Critical Mass")).changed() {
                     changed = true;
                 }
@@ -237,12 +252,14 @@ impl Simulation2D for Sandpile {
         egui::CollapsingHeader::new("🔍 Grid Settings")
             .show(ui, |ui| {
                 if ui.add(egui::Slider::new(&mut self.grid_width, 50..=250)
+                    .nudge(ui, 1.0)
                     .text("Grid Width")).changed() {
                     self.reset();
                     changed = true;
                 }
 
                 if ui.add(egui::Slider::new(&mut self.grid_height, 50..=250)
+                    .nudge(ui, 1.0)
                     .text("Grid Height")).changed() {
                     self.reset();
                     changed = true;
@@ -254,15 +271,7 @@ impl Simulation2D for Sandpile {
             .show(ui, |ui| {
                 changed |= ui.checkbox(&mut self.show_avalanches, "Highlight Avalanches").changed();
 
-                egui::ComboBox::from_label("Color Scheme")
-                    .selected_text(self.color_scheme.name())
-                    .show_ui(ui, |ui| {
-                        for scheme in ColorScheme::all() {
-                            if ui.selectable_value(&mut self.color_scheme, scheme, scheme.name()).clicked() {
-                                changed = true;
-                            }
-                        }
-                    });
+                changed |= color_scheme_combo(ui, &mut self.color_scheme);
             });
 
         if ui.button("🔄 Reset").clicked() {
@@ -281,8 +290,11 @@ impl Simulation2D for Sandpile {
                 ui.label("of all sizes - a power law!");
             });
 
-        // Auto-dropping
-        let dt = ui.input(|i| i.stable_dt);
+        changed
+    }
+
+    fn update(&mut self, dt: f32, _width: usize, _height: usize) -> bool {
+        let mut changed = false;
         self.time_accumulator += dt * self.drop_rate;
 
         while self.time_accumulator >= 1.0 {
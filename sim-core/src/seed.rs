@@ -0,0 +1,154 @@
+//! Starting points for the trajectory attractors. Each attractor owns a
+//! `SeedPoint` with its own presets; the seed only changes the transient
+//! (how the orbit reaches the attractor, and which lobe it visits first),
+//! not the attractor itself, so changing it restarts the trail.
+
+use crate::nudge::Nudge;
+use rand::Rng;
+
+/// Below this speed the seed counts as sitting on a fixed point
+const FIXED_POINT_SPEED: f32 = 1e-4;
+
+/// How far a seed on a fixed point is pushed off it
+const FIXED_POINT_NUDGE: f32 = 1e-2;
+
+/// Random candidates tried before giving up
+const RANDOM_TRIES: usize = 32;
+
+/// Steps a random candidate is integrated for to check it stays bounded and
+/// keeps moving
+const TRIAL_STEPS: usize = 4000;
+
+/// Coordinates beyond this count as escaping to infinity
+const ESCAPE: f32 = 1e4;
+
+/// A named seed and what the orbit does from it under the default
+/// parameters
+pub struct SeedPreset {
+    pub name: &'static str,
+    pub point: [f32; 3],
+    pub behavior: &'static str,
+}
+
+/// What the seed controls asked for
+#[derive(PartialEq)]
+pub enum SeedEdit {
+    None,
+    /// The seed was edited or a preset picked; restart the trail
+    Moved,
+    /// Pick a seed with `random`, then restart the trail
+    Randomize,
+}
+
+pub struct SeedPoint {
+    pub point: [f32; 3],
+    /// Each coordinate's slider and random range is `-range..=range`
+    range: f32,
+    presets: &'static [SeedPreset],
+}
+
+impl SeedPoint {
+    /// Starts at the first preset
+    pub fn new(range: f32, presets: &'static [SeedPreset]) -> Self {
+        Self {
+            point: presets.first().map_or([0.1, 0.0, 0.0], |p| p.point),
+            range,
+            presets,
+        }
+    }
+
+    /// Where to start integrating. A seed exactly on a fixed point of
+    /// `derivatives` would never move, so it is pushed a little off it.
+    pub fn start(&self, derivatives: impl Fn([f32; 3]) -> [f32; 3]) -> [f32; 3] {
+        let [x, y, z] = self.point;
+        let offsets = [
+            [0.0, 0.0, 0.0],
+            [FIXED_POINT_NUDGE, 0.0, 0.0],
+            [0.0, FIXED_POINT_NUDGE, 0.0],
+            [0.0, 0.0, FIXED_POINT_NUDGE],
+        ];
+        for [dx, dy, dz] in offsets {
+            let point = [x + dx, y + dy, z + dz];
+            if speed(derivatives(point)) > FIXED_POINT_SPEED {
+                return point;
+            }
+        }
+        [x + FIXED_POINT_NUDGE, y + FIXED_POINT_NUDGE, z + FIXED_POINT_NUDGE]
+    }
+
+    /// A random seed whose orbit, integrated with step `h`, neither escapes
+    /// nor comes to rest on a stable fixed point, or `None` if none turns up
+    pub fn random(&self, derivatives: impl Fn([f32; 3]) -> [f32; 3], h: f32) -> Option<[f32; 3]> {
+        let mut rng = rand::thread_rng();
+        (0..RANDOM_TRIES)
+            .map(|_| [
+                rng.gen_range(-self.range..=self.range),
+                rng.gen_range(-self.range..=self.range),
+                rng.gen_range(-self.range..=self.range),
+            ])
+            .find(|&candidate| keeps_moving(candidate, &derivatives, h))
+    }
+
+    /// The preset the seed is currently at, if any
+    pub fn preset(&self) -> Option<&'static SeedPreset> {
+        self.presets.iter().find(|p| p.point == self.point)
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui) -> SeedEdit {
+        let mut edit = SeedEdit::None;
+
+        egui::CollapsingHeader::new("🌱 Seed Point")
+            .show(ui, |ui| {
+                let range = self.range;
+                for (value, label) in self.point.iter_mut().zip(["x0", "y0", "z0"]) {
+                    if ui.add(egui::Slider::new(value, -range..=range)
+                        .nudge(ui, range as f64 * 0.005)
+                        .text(label)).changed() {
+                        edit = SeedEdit::Moved;
+                    }
+                }
+
+                ui.label("Presets:");
+                for preset in self.presets {
+                    if ui.selectable_label(self.point == preset.point, preset.name)
+                        .on_hover_text(preset.behavior)
+                        .clicked() {
+                        self.point = preset.point;
+                        edit = SeedEdit::Moved;
+                    }
+                }
+                if let Some(preset) = self.preset() {
+                    ui.label(egui::RichText::new(preset.behavior).weak());
+                }
+
+                if ui.button("🎲 Random Seed").clicked() {
+                    edit = SeedEdit::Randomize;
+                }
+            });
+
+        edit
+    }
+}
+
+fn speed(v: [f32; 3]) -> f32 {
+    (v[0] * v[0] + v[1] * v[1] + v[2] * v[2]).sqrt()
+}
+
+/// RK4 from `start` for `TRIAL_STEPS` steps: true if the orbit stays finite
+/// and is still moving at the end
+fn keeps_moving(start: [f32; 3], derivatives: &impl Fn([f32; 3]) -> [f32; 3], h: f32) -> bool {
+    let offset = |p: [f32; 3], k: [f32; 3], s: f32| [p[0] + k[0] * s, p[1] + k[1] * s, p[2] + k[2] * s];
+
+    let mut p = start;
+    for _ in 0..TRIAL_STEPS {
+        let k1 = derivatives(p);
+        let k2 = derivatives(offset(p, k1, h * 0.5));
+        let k3 = derivatives(offset(p, k2, h * 0.5));
+        let k4 = derivatives(offset(p, k3, h));
+        p = std::array::from_fn(|i| p[i] + (k1[i] + 2.0 * k2[i] + 2.0 * k3[i] + k4[i]) * h / 6.0);
+        if !p.iter().all(|v| v.is_finite() && v.abs() < ESCAPE) {
+            return false;
+        }
+    }
+    speed(derivatives(p)) > FIXED_POINT_SPEED * 100.0
+}
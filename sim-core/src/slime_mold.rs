@@ -1,6 +1,40 @@
-use crate::{Color, ColorScheme, Simulation2D};
+use crate::color_ui::color_scheme_combo;
+use crate::nudge::Nudge;
+use crate::{Color, ColorScheme, RenderCtx, Simulation2D};
 use rand::Rng;
 
+/// Blur applied to the trail map every step, before decay
+#[derive(Clone, Copy, PartialEq)]
+pub enum DiffusionKernel {
+    None,     // Trails only decay, so filaments stay one agent wide
+    Mean,     // 3x3 box
+    Gaussian, // 5x5 binomial, sigma = 1
+}
+
+impl DiffusionKernel {
+    pub fn all() -> Vec<DiffusionKernel> {
+        vec![DiffusionKernel::None, DiffusionKernel::Mean, DiffusionKernel::Gaussian]
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            DiffusionKernel::None => "None",
+            DiffusionKernel::Mean => "3x3 Mean",
+            DiffusionKernel::Gaussian => "Gaussian",
+        }
+    }
+
+    /// One axis of the kernel; both kernels are separable, so the 2D blur
+    /// is this applied across rows, then down columns
+    fn taps(&self) -> &'static [f32] {
+        match self {
+            DiffusionKernel::None => &[1.0],
+            DiffusionKernel::Mean => &[1.0 / 3.0, 1.0 / 3.0, 1.0 / 3.0],
+            DiffusionKernel::Gaussian => &[1.0 / 16.0, 4.0 / 16.0, 6.0 / 16.0, 4.0 / 16.0, 1.0 / 16.0],
+        }
+    }
+}
+
 struct Agent {
     x: f32,
     y: f32,
@@ -14,14 +48,19 @@ pub struct SlimeMold {
     pub turn_angle: f32,
     pub move_speed: f32,
     pub deposit_amount: f32,
+    /// Fraction of the trail lost each step
     pub decay_rate: f32,
+    pub diffusion_kernel: DiffusionKernel,
+    /// How far each step moves the trail toward its blurred copy, 0..1
     pub diffuse_rate: f32,
     pub color_scheme: ColorScheme,
     pub trail_brightness: f32,
+    pub steps_per_second: f32,
     agents: Vec<Agent>,
     trail_map: Vec<f32>,
     width: usize,
     height: usize,
+    time_accumulator: f32,
 }
 
 impl Default for SlimeMold {
@@ -34,13 +73,16 @@ impl Default for SlimeMold {
             move_speed: 1.0,
             deposit_amount: 5.0,
             decay_rate: 0.1,
-            diffuse_rate: 0.5,
+            diffusion_kernel: DiffusionKernel::Gaussian,
+            diffuse_rate: 0.25,
             color_scheme: ColorScheme::Viridis,
             trail_brightness: 1.0,
+            steps_per_second: 60.0,
             agents: Vec::new(),
             trail_map: Vec::new(),
             width: 800,
             height: 600,
+            time_accumulator: 0.0,
         }
     }
 }
@@ -154,25 +196,48 @@ impl SlimeMold {
     }
 
     fn diffuse_and_decay(&mut self) {
-        let mut new_map = vec![0.0; self.width * self.height];
+        let keep = 1.0 - self.decay_rate;
+        let taps = self.diffusion_kernel.taps();
 
-        for y in 1..self.height - 1 {
-            for x in 1..self.width - 1 {
-                let idx = y * self.width + x;
+        if taps.len() == 1 {
+            for value in &mut self.trail_map {
+                *value *= keep;
+            }
+            return;
+        }
 
-                // Simple box blur for diffusion
-                let sum = self.trail_map[idx - 1] +
-                          self.trail_map[idx + 1] +
-                          self.trail_map[idx - self.width] +
-                          self.trail_map[idx + self.width] +
-                          self.trail_map[idx] * 4.0;
+        let mut rows = vec![0.0; self.trail_map.len()];
+        let mut blurred = vec![0.0; self.trail_map.len()];
+        blur_pass(&self.trail_map, &mut rows, self.width, self.height, taps, true);
+        blur_pass(&rows, &mut blurred, self.width, self.height, taps, false);
 
-                let blurred = sum / 8.0;
-                new_map[idx] = (blurred * (1.0 - self.decay_rate)).max(0.0);
-            }
+        for (value, blurred) in self.trail_map.iter_mut().zip(blurred) {
+            let diffused = *value + (blurred - *value) * self.diffuse_rate;
+            *value = (diffused * keep).max(0.0);
         }
+    }
+}
 
-        self.trail_map = new_map;
+/// Convolve `src` with the centered 1D kernel `taps` along rows
+/// (`horizontal`) or columns into `dst`. Past the edges the nearest edge
+/// cell is repeated, so the blur neither gains nor loses trail at the
+/// walls.
+fn blur_pass(src: &[f32], dst: &mut [f32], width: usize, height: usize, taps: &[f32], horizontal: bool) {
+    let half = (taps.len() / 2) as isize;
+    for y in 0..height {
+        for x in 0..width {
+            let mut sum = 0.0;
+            for (k, weight) in taps.iter().enumerate() {
+                let offset = k as isize - half;
+                let idx = if horizontal {
+                    y * width + x.saturating_add_signed(offset).min(width - 1)
+                } else {
+                    y.saturating_add_signed(offset).min(height - 1) * width + x
+                };
+                sum += src[idx] * weight;
+            }
+            dst[y * width + x] = sum;
+        }
     }
 }
 
@@ -182,6 +247,11 @@ impl Simulation2D for SlimeMold {
     }
 
     fn compute(&self, width: usize, height: usize) -> Vec<Color> {
+        self.compute_ctx(&RenderCtx::new(width, height))
+    }
+
+    fn compute_ctx(&self, ctx: &RenderCtx) -> Vec<Color> {
+        let (width, height) = (ctx.width, ctx.height);
         let mut pixels = vec![Color::BLACK; width * height];
 
         // Scale trail map to requested size
@@ -193,12 +263,20 @@ impl Simulation2D for SlimeMold {
 
                 if src_idx < self.trail_map.len() {
                     let intensity = (self.trail_map[src_idx] / 255.0 * self.trail_brightness).min(1.0);
-                    pixels[y * width + x] = self.color_scheme.map(intensity, true);
+                    pixels[y * width + x] = self.color_scheme.map(ctx.cycle_palette(intensity), true);
                 }
             }
         }
 
-        pixels
+        ctx.over_background(pixels)
+    }
+
+    fn scalar_field(&self) -> Option<(Vec<f32>, usize, usize)> {
+        Some((self.trail_map.clone(), self.width, self.height))
+    }
+
+    fn reset(&mut self) {
+        self.init_agents(self.width, self.height);
     }
 
     fn ui_parameters(&mut self, ui: &mut egui::Ui) -> bool {
@@ -210,55 +288,81 @@ impl Simulation2D for SlimeMold {
             .default_open(true)
             .show(ui, |ui| {
                 if ui.add(egui::Slider::new(&mut self.agent_count, 100..=10000)
+                    .nudge(ui, 1.0)
                     .text("Agent Count")).changed() {
                     self.init_agents(self.width, self.height);
                     changed = true;
                 }
 
                 ui.add(egui::Slider::new(&mut self.move_speed, 0.1..=5.0)
+                    .nudge(ui, 0.02)
                     .text("Move Speed"));
 
                 ui.add(egui::Slider::new(&mut self.sensor_distance, 1.0..=20.0)
+                    .nudge(ui, 0.1)
                     .text("Sensor Distance"));
 
                 ui.add(egui::Slider::new(&mut self.sensor_angle, 0.1..=1.5)
+                    .nudge(ui, 0.01)
                     .text("Sensor Angle"));
 
                 ui.add(egui::Slider::new(&mut self.turn_angle, 0.1..=1.5)
+                    .nudge(ui, 0.01)
                     .text("Turn Angle"));
+
+                ui.add(egui::Slider::new(&mut self.steps_per_second, 5.0..=240.0)
+                    .nudge(ui, 2.0)
+                    .logarithmic(true)
+                    .text("Steps per second"));
             });
 
         egui::CollapsingHeader::new("🎨 Trail Settings")
             .default_open(true)
             .show(ui, |ui| {
                 ui.add(egui::Slider::new(&mut self.deposit_amount, 1.0..=20.0)
+                    .nudge(ui, 0.1)
                     .text("Deposit Amount"));
 
                 ui.add(egui::Slider::new(&mut self.decay_rate, 0.01..=0.5)
-                    .text("Decay Rate"));
+                    .nudge(ui, 0.002)
+                    .text("Decay Rate"))
+                    .on_hover_text("Higher decay clears old trail faster, leaving sharper filaments");
 
-                ui.add(egui::Slider::new(&mut self.trail_brightness, 0.5..=3.0)
-                    .text("Brightness"));
-
-                egui::ComboBox::from_label("Color Scheme")
-                    .selected_text(self.color_scheme.name())
+                egui::ComboBox::from_label("Diffusion Kernel")
+                    .selected_text(self.diffusion_kernel.name())
                     .show_ui(ui, |ui| {
-                        for scheme in ColorScheme::all() {
-                            if ui.selectable_value(&mut self.color_scheme, scheme, scheme.name()).clicked() {
-                                changed = true;
-                            }
+                        for kernel in DiffusionKernel::all() {
+                            ui.selectable_value(&mut self.diffusion_kernel, kernel, kernel.name());
                         }
                     });
+                ui.add_enabled(self.diffusion_kernel != DiffusionKernel::None, egui::Slider::new(&mut self.diffuse_rate, 0.0..=1.0)
+                    .nudge(ui, 0.01)
+                    .text("Diffusion"))
+                    .on_hover_text("How much of the blur is applied each step; more gives a softer, smoother network");
+
+                ui.add(egui::Slider::new(&mut self.trail_brightness, 0.5..=3.0)
+                    .nudge(ui, 0.02)
+                    .text("Brightness"));
+
+                changed |= color_scheme_combo(ui, &mut self.color_scheme);
             });
 
         if ui.button("🔄 Reset").clicked() {
-            self.init_agents(self.width, self.height);
+            self.reset();
         }
 
-        // Update simulation every frame
-        self.update_agents();
-        self.diffuse_and_decay();
+        true
+    }
 
+    fn update(&mut self, dt: f32, _width: usize, _height: usize) -> bool {
+        // Run simulation steps at a fixed rate, independent of frame rate
+        self.time_accumulator += dt * self.steps_per_second;
+
+        while self.time_accumulator >= 1.0 {
+            self.update_agents();
+            self.diffuse_and_decay();
+            self.time_accumulator -= 1.0;
+        }
         true
     }
 }
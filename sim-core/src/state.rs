@@ -0,0 +1,29 @@
+//! Helpers for `save_state`/`load_state`. Sims write their public parameters
+//! into a flat JSON object and read back whichever keys are present, so files
+//! saved by older versions still load what they can.
+
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::ops::RangeInclusive;
+
+/// Overwrite `target` with `state[key]` if it is present and has the right type
+pub fn read<T: DeserializeOwned>(state: &Value, key: &str, target: &mut T) {
+    if let Some(value) = state.get(key).and_then(|v| T::deserialize(v).ok()) {
+        *target = value;
+    }
+}
+
+/// Like `read`, but pulls the value into `range`, normally the range of the
+/// field's slider. Scene files and links come from outside, and a huge
+/// iteration count or zoom would freeze the app. NaN lands on the low end.
+pub fn read_clamped<T: DeserializeOwned + PartialOrd + Copy>(state: &Value, key: &str, target: &mut T, range: RangeInclusive<T>) {
+    if let Some(value) = state.get(key).and_then(|v| T::deserialize(v).ok()) {
+        *target = if range.contains(&value) {
+            value
+        } else if value > *range.end() {
+            *range.end()
+        } else {
+            *range.start()
+        };
+    }
+}
@@ -0,0 +1,74 @@
+//! "Run N steps" for stepped sims. The run queues an exact number of steps
+//! that `update` works off a chunk per frame, apart from the sim's real-time
+//! stepping, so the UI stays responsive and the same starting state always
+//! ends up in the same place however fast the frames come.
+
+use crate::readout;
+
+pub struct StepRunner {
+    /// Steps the next run queues
+    pub count: u64,
+    /// Steps of the current run still to take, and its length
+    pending: u64,
+    total: u64,
+    /// Most steps taken in one frame
+    per_frame: u64,
+}
+
+impl StepRunner {
+    /// A runner for a sim that can afford `per_frame` steps in one frame
+    pub fn new(per_frame: u64) -> Self {
+        Self {
+            count: 1000,
+            pending: 0,
+            total: 0,
+            per_frame: per_frame.max(1),
+        }
+    }
+
+    pub fn running(&self) -> bool {
+        self.pending > 0
+    }
+
+    /// Steps to take this frame; they count as taken
+    pub fn take(&mut self) -> u64 {
+        let steps = self.pending.min(self.per_frame);
+        self.pending -= steps;
+        steps
+    }
+
+    /// Drop the rest of the run, e.g. when the sim is reset under it
+    pub fn cancel(&mut self) {
+        self.pending = 0;
+    }
+
+    /// Step count, Run button and progress. Returns true when a run starts;
+    /// the sim should then hold its real-time stepping so the run lands on
+    /// exactly the requested step.
+    pub fn ui(&mut self, ui: &mut egui::Ui) -> bool {
+        let mut started = false;
+
+        ui.horizontal(|ui| {
+            ui.add(egui::DragValue::new(&mut self.count).range(1..=1_000_000).speed(10.0).suffix(" steps"));
+            if self.running() {
+                if ui.button("⏹ Cancel").clicked() {
+                    self.cancel();
+                }
+            } else if ui.button(format!("⏩ Run {}", readout::count(self.count)))
+                .on_hover_text("Take exactly this many steps, a batch per frame, then stay paused on the last one")
+                .clicked() {
+                self.pending = self.count.max(1);
+                self.total = self.pending;
+                started = true;
+            }
+        });
+
+        if self.running() {
+            let done = self.total - self.pending;
+            ui.add(egui::ProgressBar::new(done as f32 / self.total as f32)
+                .text(format!("{} / {}", readout::count(done), readout::count(self.total))));
+        }
+
+        started
+    }
+}
@@ -1,10 +1,40 @@
-use crate::{ColorScheme, Simulation3D};
+use crate::color_ui::color_scheme_combo;
+use crate::nudge::Nudge;
+use crate::poincare::{PoincareSection, SectionAxis};
+use crate::seed::{SeedEdit, SeedPoint, SeedPreset};
+use crate::trajectory::{TrailEmitter, TrajectoryColoring};
+use crate::{Color, ColorScheme, Simulation3D};
+
+/// Seed points, with what the orbit does from each under the default
+/// parameters. The x = y = z diagonal is invariant and ends on a fixed
+/// point, so none of them sit on it.
+const SEEDS: [SeedPreset; 3] = [
+    SeedPreset {
+        name: "Near Origin",
+        point: [0.1, 0.0, 0.0],
+        behavior: "Drifts out of the unstable origin for a few hundred steps before the tangle forms",
+    },
+    SeedPreset {
+        name: "Inside the Tangle",
+        point: [-4.0, -1.0, 2.0],
+        behavior: "Lands in the tangle almost immediately",
+    },
+    SeedPreset {
+        name: "Positive Corner",
+        point: [5.0, 1.0, -3.0],
+        behavior: "Joins on the positive-x side and stays there for over a thousand steps before first crossing x = 0",
+    },
+];
 
 pub struct ThomasAttractor {
     pub b: f32,
     pub dt: f32,
     pub trail_length: usize,
     pub color_scheme: ColorScheme,
+    pub trajectory_coloring: TrajectoryColoring,
+    pub emitter: TrailEmitter,
+    pub section: PoincareSection,
+    pub seed: SeedPoint,
     pub scale: f32,
     points: Vec<[f32; 3]>,
     x: f32,
@@ -19,6 +49,10 @@ impl Default for ThomasAttractor {
             dt: 0.1,
             trail_length: 5000,
             color_scheme: ColorScheme::Viridis,
+            trajectory_coloring: TrajectoryColoring::Index,
+            emitter: TrailEmitter::new(),
+            section: PoincareSection::new(SectionAxis::X, 0.0),
+            seed: SeedPoint::new(5.0, &SEEDS),
             scale: 80.0,
             points: Vec::new(),
             x: 0.1,
@@ -32,6 +66,15 @@ impl ThomasAttractor {
     pub fn new() -> Self {
         Self::default()
     }
+
+    /// Thomas cyclically symmetric attractor
+    fn derivatives(&self, [x, y, z]: [f32; 3]) -> [f32; 3] {
+        [
+            -self.b * x + y.sin(),
+            -self.b * y + z.sin(),
+            -self.b * z + x.sin(),
+        ]
+    }
 }
 
 impl Simulation3D for ThomasAttractor {
@@ -39,26 +82,27 @@ impl Simulation3D for ThomasAttractor {
         "Thomas Attractor"
     }
 
+    fn equations(&self) -> Option<&str> {
+        Some(
+            "dx/dt = sin y − b x\n\
+             dy/dt = sin z − b y\n\
+             dz/dt = sin x − b z",
+        )
+    }
+
     fn step(&mut self, _dt: f32) {
         for _ in 0..10 {
-            // Thomas cyclically symmetric attractor
-            let dx = -self.b * self.x + self.y.sin();
-            let dy = -self.b * self.y + self.z.sin();
-            let dz = -self.b * self.z + self.x.sin();
+            let [dx, dy, dz] = self.derivatives([self.x, self.y, self.z]);
 
+            let prev = [self.x, self.y, self.z];
             self.x += dx * self.dt;
             self.y += dy * self.dt;
             self.z += dz * self.dt;
+            self.section.record(prev, [self.x, self.y, self.z]);
 
-            self.points.push([
-                self.x * self.scale,
-                self.y * self.scale,
-                self.z * self.scale,
-            ]);
-
-            if self.points.len() > self.trail_length {
-                self.points.remove(0);
-            }
+            let scale = self.scale;
+            let next = [self.x * scale, self.y * scale, self.z * scale];
+            self.emitter.push(&mut self.points, prev.map(|v| v * scale), next, self.trail_length);
         }
     }
 
@@ -66,6 +110,14 @@ impl Simulation3D for ThomasAttractor {
         self.points.clone()
     }
 
+    fn get_colors(&self) -> Option<Vec<Color>> {
+        self.emitter.colors(&self.points, self.trajectory_coloring, &self.color_scheme)
+    }
+
+    fn poincare_section(&self) -> Option<&PoincareSection> {
+        Some(&self.section)
+    }
+
     fn ui_parameters(&mut self, ui: &mut egui::Ui) -> bool {
         let mut changed = false;
 
@@ -75,8 +127,10 @@ impl Simulation3D for ThomasAttractor {
             .default_open(true)
             .show(ui, |ui| {
                 changed |= ui.add(egui::Slider::new(&mut self.b, 0.1..=0.4)
+                    .nudge(ui, 0.002)
                     .text("b (Dissipation)")).changed();
                 changed |= ui.add(egui::Slider::new(&mut self.dt, 0.01..=0.2)
+                    .nudge(ui, 0.001)
                     .text("Time Step")).changed();
             });
 
@@ -84,24 +138,31 @@ impl Simulation3D for ThomasAttractor {
             .default_open(true)
             .show(ui, |ui| {
                 changed |= ui.add(egui::Slider::new(&mut self.trail_length, 100..=10000)
+                    .nudge(ui, 1.0)
                     .text("Trail Length")).changed();
+                changed |= self.emitter.ui(ui, &mut self.points, self.trail_length);
                 changed |= ui.add(egui::Slider::new(&mut self.scale, 20.0..=150.0)
+                    .nudge(ui, 1.0)
                     .text("Scale")).changed();
             });
 
         egui::CollapsingHeader::new("🎨 Color Settings")
             .show(ui, |ui| {
-                egui::ComboBox::from_label("Color Scheme")
-                    .selected_text(self.color_scheme.name())
+                changed |= color_scheme_combo(ui, &mut self.color_scheme);
+
+                egui::ComboBox::from_label("Color By")
+                    .selected_text(self.trajectory_coloring.name())
                     .show_ui(ui, |ui| {
-                        for scheme in ColorScheme::all() {
-                            if ui.selectable_value(&mut self.color_scheme, scheme, scheme.name()).clicked() {
+                        for coloring in TrajectoryColoring::all() {
+                            if ui.selectable_value(&mut self.trajectory_coloring, coloring, coloring.name()).clicked() {
                                 changed = true;
                             }
                         }
                     });
             });
 
+        self.section.ui(ui, -5.0..=5.0, &self.points);
+
         egui::CollapsingHeader::new("🎯 Presets")
             .show(ui, |ui| {
                 if ui.button("Classic").clicked() {
@@ -120,6 +181,17 @@ impl Simulation3D for ThomasAttractor {
                 }
             });
 
+        let edit = self.seed.ui(ui);
+        if edit == SeedEdit::Randomize {
+            if let Some(point) = self.seed.random(|p| self.derivatives(p), self.dt) {
+                self.seed.point = point;
+            }
+        }
+        if edit != SeedEdit::None {
+            self.reset();
+            changed = true;
+        }
+
         if ui.button("🔄 Reset").clicked() {
             self.reset();
             changed = true;
@@ -130,8 +202,8 @@ impl Simulation3D for ThomasAttractor {
 
     fn reset(&mut self) {
         self.points.clear();
-        self.x = 0.1;
-        self.y = 0.0;
-        self.z = 0.0;
+        self.emitter.clear();
+        self.section.clear();
+        [self.x, self.y, self.z] = self.seed.start(|p| self.derivatives(p));
     }
 }
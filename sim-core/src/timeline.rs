@@ -0,0 +1,72 @@
+//! Step history for deterministic sims, so they can seek to any step by
+//! replaying from a saved state. Snapshots are taken every `interval` steps,
+//! which keeps seeking backwards cheap without storing every step.
+
+/// Snapshots kept before they're thinned out
+const MAX_CHECKPOINTS: usize = 128;
+
+/// Steps between snapshots to start with; doubles each time the snapshot
+/// list is thinned
+const INITIAL_INTERVAL: u64 = 16;
+
+pub struct Timeline<S> {
+    step: u64,
+    interval: u64,
+    /// `(step, state)` in step order; the first is always step 0
+    checkpoints: Vec<(u64, S)>,
+}
+
+impl<S: Clone> Timeline<S> {
+    pub fn new(initial: S) -> Self {
+        Self {
+            step: 0,
+            interval: INITIAL_INTERVAL,
+            checkpoints: vec![(0, initial)],
+        }
+    }
+
+    /// Forget the history and start again from `initial` at step 0
+    pub fn restart(&mut self, initial: S) {
+        *self = Self::new(initial);
+    }
+
+    /// Steps taken since the initial state
+    pub fn step(&self) -> u64 {
+        self.step
+    }
+
+    /// Count one step. `state` is only called when a snapshot is due.
+    pub fn record(&mut self, state: impl FnOnce() -> S) {
+        self.step += 1;
+        if !self.step.is_multiple_of(self.interval) {
+            return;
+        }
+
+        self.checkpoints.push((self.step, state()));
+        if self.checkpoints.len() > MAX_CHECKPOINTS {
+            // Keep every other snapshot; step 0 is at index 0 so it survives
+            self.interval *= 2;
+            let interval = self.interval;
+            self.checkpoints.retain(|(step, _)| step.is_multiple_of(interval));
+        }
+    }
+
+    /// Prepare to seek backwards to `target`. Returns the latest snapshot at
+    /// or before it to restore, after which the sim steps forward until
+    /// `step()` reaches `target`. Returns `None` when `target` isn't behind
+    /// the current step, in which case the sim just keeps stepping.
+    ///
+    /// Snapshots after the restored one are dropped: they may not match the
+    /// replay if parameters changed since they were taken.
+    pub fn rewind(&mut self, target: u64) -> Option<S> {
+        if target >= self.step {
+            return None;
+        }
+
+        let index = self.checkpoints.iter().rposition(|(step, _)| *step <= target)?;
+        self.checkpoints.truncate(index + 1);
+        let (step, state) = &self.checkpoints[index];
+        self.step = *step;
+        Some(state.clone())
+    }
+}
@@ -0,0 +1,80 @@
+//! Bringing additive light back into displayable range. Glow renders sum
+//! overlapping colors without clipping, and an exposure and tone curve decide
+//! how bright cores show, instead of a hard clip at white. Shared by the 3D
+//! viewer's glow and the 2D sims' glow and density renders, which get the
+//! 2D viewer's settings through `RenderCtx`.
+
+use crate::nudge::Nudge;
+use serde::{Deserialize, Serialize};
+
+/// Maps the unbounded additive accumulation back into displayable range
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum ToneMap {
+    Clamp,    // Hard clip at white (original look)
+    Reinhard, // x / (1 + x)
+    Aces,     // Filmic curve (Narkowicz fit)
+}
+
+impl ToneMap {
+    pub fn all() -> Vec<ToneMap> {
+        vec![ToneMap::Clamp, ToneMap::Reinhard, ToneMap::Aces]
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            ToneMap::Clamp => "Clamp",
+            ToneMap::Reinhard => "Reinhard",
+            ToneMap::Aces => "ACES",
+        }
+    }
+
+    /// `v` is linear intensity where 1.0 is white
+    pub fn apply(&self, v: f32) -> f32 {
+        let mapped = match self {
+            ToneMap::Clamp => v,
+            ToneMap::Reinhard => v / (1.0 + v),
+            ToneMap::Aces => (v * (2.51 * v + 0.03)) / (v * (2.43 * v + 0.59) + 0.14),
+        };
+        mapped.clamp(0.0, 1.0)
+    }
+
+    /// A channel of summed light, 255 per unit of white, scaled by
+    /// `exposure` and mapped to a display byte. Exposure 1 with `Clamp` is
+    /// a plain clip.
+    pub fn byte(&self, v: f32, exposure: f32) -> u8 {
+        (self.apply(v / 255.0 * exposure) * 255.0).round() as u8
+    }
+}
+
+/// Exposure slider and tone curve picker. Returns true if either changed.
+pub fn tone_ui(ui: &mut egui::Ui, exposure: &mut f32, tone_map: &mut ToneMap) -> bool {
+    let mut changed = ui.add(egui::Slider::new(exposure, 0.1..=8.0)
+        .nudge(ui, 0.05)
+        .logarithmic(true)
+        .text("Exposure")).changed();
+
+    egui::ComboBox::from_label("Tone Map")
+        .selected_text(tone_map.name())
+        .show_ui(ui, |ui| {
+            for option in ToneMap::all() {
+                changed |= ui.selectable_value(tone_map, option, option.name()).changed();
+            }
+        });
+    changed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn neutral_exposure_with_clamp_is_a_plain_clip() {
+        for v in [0.0, 17.0, 254.6, 255.0, 300.0, 5000.0] {
+            assert_eq!(ToneMap::Clamp.byte(v, 1.0), v.round().min(255.0) as u8);
+        }
+        // The curves keep detail past white instead of clipping it
+        for tone_map in [ToneMap::Reinhard, ToneMap::Aces] {
+            assert!(tone_map.byte(600.0, 1.0) < tone_map.byte(1200.0, 1.0));
+        }
+    }
+}
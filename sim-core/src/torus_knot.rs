@@ -1,4 +1,5 @@
 use crate::Simulation3D;
+use crate::nudge::Nudge;
 
 pub struct TorusKnot {
     pub p: i32,  // Number of times the knot winds around the torus longitudinally
@@ -122,24 +123,28 @@ impl Simulation3D for TorusKnot {
             .default_open(true)
             .show(ui, |ui| {
                 if ui.add(egui::Slider::new(&mut self.p, 1..=10)
+                    .nudge(ui, 1.0)
                     .text("P (longitudinal)")).changed() {
                     self.regenerate();
                     changed = true;
                 }
 
                 if ui.add(egui::Slider::new(&mut self.q, 1..=10)
+                    .nudge(ui, 1.0)
                     .text("Q (meridional)")).changed() {
                     self.regenerate();
                     changed = true;
                 }
 
                 if ui.add(egui::Slider::new(&mut self.major_radius, 10.0..=40.0)
+                    .nudge(ui, 0.2)
                     .text("Major Radius")).changed() {
                     self.regenerate();
                     changed = true;
                 }
 
                 if ui.add(egui::Slider::new(&mut self.minor_radius, 5.0..=20.0)
+                    .nudge(ui, 0.1)
                     .text("Minor Radius")).changed() {
                     self.regenerate();
                     changed = true;
@@ -156,12 +161,14 @@ impl Simulation3D for TorusKnot {
 
                 if self.show_tube {
                     if ui.add(egui::Slider::new(&mut self.tube_radius, 0.5..=5.0)
+                        .nudge(ui, 0.02)
                         .text("Tube Radius")).changed() {
                         self.regenerate();
                         changed = true;
                     }
 
                     if ui.add(egui::Slider::new(&mut self.tube_segments, 4..=16)
+                        .nudge(ui, 1.0)
                         .text("Tube Detail")).changed() {
                         self.regenerate();
                         changed = true;
@@ -169,12 +176,14 @@ impl Simulation3D for TorusKnot {
                 }
 
                 if ui.add(egui::Slider::new(&mut self.num_points, 100..=1000)
+                    .nudge(ui, 1.0)
                     .text("Resolution")).changed() {
                     self.regenerate();
                     changed = true;
                 }
 
                 ui.add(egui::Slider::new(&mut self.animation_speed, 0.0..=5.0)
+                    .nudge(ui, 0.05)
                     .text("Animation Speed"));
             });
 
@@ -0,0 +1,57 @@
+//! Per-point colors for particle trails that fade from the particle's color
+//! toward a tail color with age.
+
+use crate::Color;
+
+/// Rainbow head color for particle `index` of `count`, matching the spread
+/// the 3D viewer's rainbow mode gives uncolored points
+pub fn particle_color(index: usize, count: usize) -> Color {
+    Color::from_hsv(index as f32 / count.max(1) as f32 * 360.0, 0.9, 1.0)
+}
+
+pub struct TrailStyle {
+    pub fade: bool,
+    /// Color the oldest point of a full-length trail reaches
+    pub tail_color: [u8; 3],
+}
+
+impl Default for TrailStyle {
+    fn default() -> Self {
+        Self {
+            fade: true,
+            tail_color: [10, 10, 30],
+        }
+    }
+}
+
+impl TrailStyle {
+    /// Append colors for a trail stored oldest-first. Age is measured against
+    /// `max_len` rather than the trail's current length, so a trail that is
+    /// still growing hasn't faded as far as a full one.
+    pub fn push_colors(&self, colors: &mut Vec<Color>, head: Color, trail_len: usize, max_len: usize) {
+        let tail = Color::from_rgb(self.tail_color[0], self.tail_color[1], self.tail_color[2]);
+        let span = max_len.saturating_sub(1).max(1) as f32;
+
+        for j in 0..trail_len {
+            if self.fade {
+                let age = (trail_len - 1 - j) as f32 / span;
+                colors.push(Color::lerp(head, tail, age));
+            } else {
+                colors.push(head);
+            }
+        }
+    }
+
+    /// Fade toggle and tail color picker, for a sim's visual settings
+    pub fn ui(&mut self, ui: &mut egui::Ui) {
+        ui.checkbox(&mut self.fade, "Fade Trails")
+            .on_hover_text("Trails fade from the particle's color to the tail color over the trail length");
+
+        if self.fade {
+            ui.horizontal(|ui| {
+                ui.label("Tail Color:");
+                ui.color_edit_button_srgb(&mut self.tail_color);
+            });
+        }
+    }
+}
@@ -0,0 +1,286 @@
+use crate::nudge::Nudge;
+use crate::{Color, ColorScheme};
+
+/// Steps the mean step length is averaged over
+const MEAN_WINDOW: f32 = 500.0;
+
+/// Most points one step may emit, so a sudden long step (after a parameter
+/// change, say) can't flood the trail
+const MAX_PER_STEP: f32 = 64.0;
+
+/// How a trajectory attractor colors its trail
+#[derive(Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+pub enum TrajectoryColoring {
+    Index,     // Leave coloring to the viewer (by point order)
+    Speed,     // Distance travelled per step
+    Curvature, // How sharply the orbit turns
+}
+
+impl TrajectoryColoring {
+    pub fn all() -> Vec<TrajectoryColoring> {
+        vec![
+            TrajectoryColoring::Index,
+            TrajectoryColoring::Speed,
+            TrajectoryColoring::Curvature,
+        ]
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            TrajectoryColoring::Index => "Index",
+            TrajectoryColoring::Speed => "Speed",
+            TrajectoryColoring::Curvature => "Curvature",
+        }
+    }
+}
+
+/// When a trajectory attractor adds a point to its trail
+#[derive(Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+pub enum Emission {
+    TimeStep,  // One point per integration step
+    ArcLength, // One point per fixed distance along the curve
+}
+
+impl Emission {
+    pub fn all() -> Vec<Emission> {
+        vec![Emission::TimeStep, Emission::ArcLength]
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            Emission::TimeStep => "Per Time Step",
+            Emission::ArcLength => "By Arc Length",
+        }
+    }
+}
+
+/// Adds integration steps to a trail. Per time step, points bunch up where
+/// the orbit is slow and thin out where it is fast; by arc length they are
+/// placed along each step at an even spacing, so the ribbon has the same
+/// density everywhere. The spacing is a multiple of the mean step length,
+/// which keeps it sensible across attractors of very different scales.
+pub struct TrailEmitter {
+    pub mode: Emission,
+    /// Point spacing by arc length, in mean step lengths
+    pub spacing: f32,
+    /// Curve length since the last point placed
+    carry: f32,
+    /// Running mean of the step length, over the last `MEAN_WINDOW` steps
+    mean_step: f32,
+    steps: f32,
+    /// Step length at each trail point, oldest first, while the trail is
+    /// resampled; speed coloring uses it since the spacing no longer shows it
+    speeds: Vec<f32>,
+}
+
+impl Default for TrailEmitter {
+    fn default() -> Self {
+        Self {
+            mode: Emission::TimeStep,
+            spacing: 1.0,
+            carry: 0.0,
+            mean_step: 0.0,
+            steps: 0.0,
+            speeds: Vec::new(),
+        }
+    }
+}
+
+impl TrailEmitter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add the step `prev` → `next` to `trail`, keeping at most `max_len`
+    /// points
+    pub fn push(&mut self, trail: &mut Vec<[f32; 3]>, prev: [f32; 3], next: [f32; 3], max_len: usize) {
+        let step = length(sub(next, prev));
+        self.emit(trail, prev, next, step);
+
+        let excess = trail.len().saturating_sub(max_len);
+        if excess > 0 {
+            trail.drain(..excess);
+            self.speeds.drain(..excess.min(self.speeds.len()));
+        }
+    }
+
+    fn emit(&mut self, trail: &mut Vec<[f32; 3]>, prev: [f32; 3], next: [f32; 3], speed: f32) {
+        let step = length(sub(next, prev));
+        if self.mode == Emission::TimeStep || trail.is_empty() || !step.is_finite() {
+            trail.push(next);
+            self.speeds.push(speed);
+            return;
+        }
+
+        self.steps = (self.steps + 1.0).min(MEAN_WINDOW);
+        self.mean_step += (step - self.mean_step) / self.steps;
+        let spacing = (self.mean_step * self.spacing).max(step / MAX_PER_STEP);
+        if spacing <= 0.0 {
+            return;
+        }
+
+        // Distance along this step to the next point
+        let mut at = spacing - self.carry;
+        while at <= step {
+            let t = at / step;
+            trail.push([
+                prev[0] + (next[0] - prev[0]) * t,
+                prev[1] + (next[1] - prev[1]) * t,
+                prev[2] + (next[2] - prev[2]) * t,
+            ]);
+            self.speeds.push(speed);
+            at += spacing;
+        }
+        self.carry = step - (at - spacing);
+    }
+
+    /// Forget the trail, for when the owner clears it
+    pub fn clear(&mut self) {
+        self.carry = 0.0;
+        self.steps = 0.0;
+        self.mean_step = 0.0;
+        self.speeds.clear();
+    }
+
+    /// Rebuild `trail` under the current mode and spacing by replaying it
+    /// step by step, so a change shows at once instead of as the trail rolls
+    /// over. Going back to time steps keeps the resampled points.
+    fn resample(&mut self, trail: &mut Vec<[f32; 3]>, max_len: usize) {
+        let old = std::mem::take(trail);
+        let speeds = std::mem::take(&mut self.speeds);
+        self.clear();
+        for (i, &point) in old.iter().enumerate() {
+            let prev = if i > 0 { old[i - 1] } else { point };
+            let speed = match speeds.get(i) {
+                Some(&speed) if speeds.len() == old.len() => speed,
+                _ => length(sub(point, prev)),
+            };
+            self.emit(trail, prev, point, speed);
+        }
+        let excess = trail.len().saturating_sub(max_len);
+        trail.drain(..excess);
+        self.speeds.drain(..excess.min(self.speeds.len()));
+    }
+
+    /// Colors for `trail` like `trajectory_colors`. By arc length, speed is
+    /// taken from the recorded step lengths.
+    pub fn colors(&self, trail: &[[f32; 3]], coloring: TrajectoryColoring, scheme: &ColorScheme) -> Option<Vec<Color>> {
+        let recorded = self.mode == Emission::ArcLength && self.speeds.len() == trail.len();
+        if coloring == TrajectoryColoring::Speed && recorded && !trail.is_empty() {
+            return Some(normalized_colors(&self.speeds, scheme));
+        }
+        trajectory_colors(trail, coloring, scheme)
+    }
+
+    /// Emission mode and spacing, for a sim's display settings. Resamples
+    /// `trail` and returns true on a change.
+    pub fn ui(&mut self, ui: &mut egui::Ui, trail: &mut Vec<[f32; 3]>, max_len: usize) -> bool {
+        let mut changed = false;
+
+        egui::ComboBox::from_label("Emit Points")
+            .selected_text(self.mode.name())
+            .show_ui(ui, |ui| {
+                for mode in Emission::all() {
+                    changed |= ui.selectable_value(&mut self.mode, mode, mode.name()).changed();
+                }
+            })
+            .response
+            .on_hover_text("By arc length places points evenly along the curve, so fast stretches are as dense as slow ones");
+
+        if self.mode == Emission::ArcLength {
+            changed |= ui.add(egui::Slider::new(&mut self.spacing, 0.25..=4.0)
+                .nudge(ui, 0.05)
+                .logarithmic(true)
+                .suffix("×")
+                .text("Point Spacing"))
+                .on_hover_text("Distance between points, in average steps")
+                .changed();
+        }
+
+        if changed {
+            self.resample(trail, max_len);
+        }
+        changed
+    }
+}
+
+fn sub(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+fn length(v: [f32; 3]) -> f32 {
+    (v[0] * v[0] + v[1] * v[1] + v[2] * v[2]).sqrt()
+}
+
+fn cross(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+/// Per-point speed or curvature along a trajectory, using central finite
+/// differences. Endpoints reuse their neighbour's value.
+fn trajectory_measure(points: &[[f32; 3]], coloring: TrajectoryColoring) -> Vec<f32> {
+    let n = points.len();
+    let mut values = vec![0.0; n];
+    if n < 3 {
+        return values;
+    }
+
+    for i in 1..n - 1 {
+        let a = sub(points[i], points[i - 1]);
+        let b = sub(points[i + 1], points[i]);
+        let c = sub(points[i + 1], points[i - 1]);
+
+        values[i] = match coloring {
+            TrajectoryColoring::Speed => length(c) * 0.5,
+            TrajectoryColoring::Curvature => {
+                // Menger curvature of the three consecutive points
+                let denom = length(a) * length(b) * length(c);
+                if denom > 1e-12 {
+                    2.0 * length(cross(a, b)) / denom
+                } else {
+                    0.0
+                }
+            }
+            TrajectoryColoring::Index => i as f32,
+        };
+    }
+
+    values[0] = values[1];
+    values[n - 1] = values[n - 2];
+    values
+}
+
+/// Colors for a trajectory mapped through `scheme`, or `None` for index
+/// coloring. Values are normalized by the 95th percentile rather than the
+/// max so a single sharp corner doesn't make the rest of the frame flicker.
+pub fn trajectory_colors(points: &[[f32; 3]], coloring: TrajectoryColoring, scheme: &ColorScheme) -> Option<Vec<Color>> {
+    if coloring == TrajectoryColoring::Index || points.is_empty() {
+        return None;
+    }
+
+    Some(normalized_colors(&trajectory_measure(points, coloring), scheme))
+}
+
+/// `values` mapped through `scheme`, normalized by their 95th percentile
+fn normalized_colors(values: &[f32], scheme: &ColorScheme) -> Vec<Color> {
+    let mut sorted: Vec<f32> = values.iter().copied().filter(|v| v.is_finite()).collect();
+    let scale = if sorted.is_empty() {
+        0.0
+    } else {
+        let k = (sorted.len() - 1) * 95 / 100;
+        let (_, p95, _) = sorted.select_nth_unstable_by(k, |a, b| a.total_cmp(b));
+        *p95
+    };
+
+    values
+        .iter()
+        .map(|&v| {
+            let t = if scale > 0.0 && v.is_finite() { v / scale } else { 0.0 };
+            scheme.map(t.clamp(0.0, 1.0), true)
+        })
+        .collect()
+}
@@ -1,4 +1,8 @@
-use crate::Simulation3D;
+use crate::emitter::{Emitter, EmitterShape};
+use crate::nudge::Nudge;
+use crate::readout;
+use crate::trail::{particle_color, TrailStyle};
+use crate::{Color, Simulation3D};
 use rand::Rng;
 
 struct Particle {
@@ -17,8 +21,9 @@ pub struct VortexTurbulence {
     pub show_trails: bool,
     pub vortex_count: usize,
     pub speed: f32,
-    pub spawn_rate: f32,
     pub particle_life: f32,
+    pub emitter: Emitter,
+    pub trail_style: TrailStyle,
     particles: Vec<Particle>,
     vortices: Vec<[f32; 3]>,
 }
@@ -34,8 +39,10 @@ impl Default for VortexTurbulence {
             show_trails: true,
             vortex_count: 3,
             speed: 1.0,
-            spawn_rate: 0.3,
             particle_life: 10.0,
+            // A band around the vortices, matching the original spawn region
+            emitter: Emitter::new(EmitterShape::Ring, 40.0, 10.0, 20.0),
+            trail_style: TrailStyle::default(),
             particles: Vec::new(),
             vortices: Vec::new(),
         }
@@ -93,15 +100,8 @@ impl VortexTurbulence {
         let mut rng = rand::thread_rng();
 
         for _ in 0..self.particle_count {
-            let theta = rng.gen_range(0.0..std::f32::consts::TAU);
-            let radius = rng.gen_range(30.0..50.0);
-
             self.particles.push(Particle {
-                position: [
-                    radius * theta.cos(),
-                    rng.gen_range(-20.0..20.0),
-                    radius * theta.sin(),
-                ],
+                position: self.emitter.sample(&mut rng),
                 velocity: [0.0, 0.0, 0.0],
                 trail: Vec::new(),
                 life: rng.gen_range(0.0..self.particle_life),
@@ -147,15 +147,9 @@ impl VortexTurbulence {
 
     fn spawn_particle(&mut self) {
         let mut rng = rand::thread_rng();
-        let theta = rng.gen_range(0.0..std::f32::consts::TAU);
-        let radius = rng.gen_range(30.0..50.0);
 
         self.particles.push(Particle {
-            position: [
-                radius * theta.cos(),
-                rng.gen_range(-20.0..20.0),
-                radius * theta.sin(),
-            ],
+            position: self.emitter.sample(&mut rng),
             velocity: [0.0, 0.0, 0.0],
             trail: Vec::new(),
             life: self.particle_life,
@@ -169,6 +163,8 @@ impl Simulation3D for VortexTurbulence {
     }
 
     fn step(&mut self, dt: f32) {
+        // The emitter rate is per wall-clock second (scaled by Speed)
+        let emit_dt = dt * self.speed;
         let dt = dt * self.speed * 0.02;
         let mut rng = rand::thread_rng();
 
@@ -224,12 +220,9 @@ impl Simulation3D for VortexTurbulence {
         self.particles.retain(|p| p.life > 0.0);
 
         // Spawn new particles
-        while self.particles.len() < self.particle_count {
-            if rng.gen_bool(self.spawn_rate as f64) {
-                self.spawn_particle();
-            } else {
-                break;
-            }
+        let room = self.particle_count.saturating_sub(self.particles.len());
+        for _ in 0..self.emitter.emit_count(emit_dt, room) {
+            self.spawn_particle();
         }
     }
 
@@ -256,23 +249,59 @@ impl Simulation3D for VortexTurbulence {
         points
     }
 
+    fn sources(&self) -> Vec<[f32; 3]> {
+        self.vortices.clone()
+    }
+
+    fn move_source(&mut self, index: usize, delta: [f32; 3]) {
+        if let Some(vortex) = self.vortices.get_mut(index) {
+            for (p, d) in vortex.iter_mut().zip(delta) {
+                *p += d;
+            }
+        }
+    }
+
+    fn get_colors(&self) -> Option<Vec<Color>> {
+        let mut colors = Vec::new();
+
+        // Same order as get_points
+        for _ in &self.vortices {
+            colors.extend([Color::WHITE; 5]);
+        }
+
+        let count = self.particles.len();
+        for (i, particle) in self.particles.iter().enumerate() {
+            let head = particle_color(i, count);
+            colors.push(head);
+
+            if self.show_trails {
+                self.trail_style.push_colors(&mut colors, head, particle.trail.len(), self.trail_length);
+            }
+        }
+
+        Some(colors)
+    }
+
     fn ui_parameters(&mut self, ui: &mut egui::Ui) -> bool {
         let mut changed = false;
 
         ui.heading("Vortex Turbulence");
 
-        ui.label(format!("Vortices: {} | Particles: {}", self.vortices.len(), self.particles.len()));
+        ui.label(format!("Vortices: {} | Particles: {}", readout::count(self.vortices.len()), readout::count(self.particles.len())));
 
         egui::CollapsingHeader::new("🌀 Vortex Settings")
             .default_open(true)
             .show(ui, |ui| {
                 if ui.add(egui::Slider::new(&mut self.vortex_count, 1..=4)
+                    .nudge(ui, 1.0)
                     .text("Vortex Count")).changed() {
                     self.init_vortices();
                     changed = true;
                 }
+                ui.label(egui::RichText::new("Drag a vortex center in the view to move it").weak());
 
                 ui.add(egui::Slider::new(&mut self.vortex_strength, 5.0..=50.0)
+                    .nudge(ui, 0.2)
                     .text("Vortex Strength"));
 
                 ui.label("Configurations:");
@@ -283,23 +312,26 @@ impl Simulation3D for VortexTurbulence {
             .default_open(true)
             .show(ui, |ui| {
                 if ui.add(egui::Slider::new(&mut self.particle_count, 100..=1000)
+                    .nudge(ui, 1.0)
                     .text("Particle Count")).changed() {
                     changed = true;
                 }
 
                 ui.add(egui::Slider::new(&mut self.flow_speed, 0.5..=10.0)
+                    .nudge(ui, 0.05)
                     .text("Flow Speed"));
 
                 ui.add(egui::Slider::new(&mut self.turbulence, 0.0..=15.0)
+                    .nudge(ui, 0.1)
                     .text("Turbulence"));
 
                 ui.add(egui::Slider::new(&mut self.particle_life, 2.0..=20.0)
+                    .nudge(ui, 0.1)
                     .text("Particle Life"));
-
-                ui.add(egui::Slider::new(&mut self.spawn_rate, 0.1..=1.0)
-                    .text("Spawn Rate"));
             });
 
+        self.emitter.ui(ui);
+
         egui::CollapsingHeader::new("🎨 Visual")
             .default_open(true)
             .show(ui, |ui| {
@@ -307,10 +339,13 @@ impl Simulation3D for VortexTurbulence {
 
                 if self.show_trails {
                     ui.add(egui::Slider::new(&mut self.trail_length, 10..=100)
+                        .nudge(ui, 1.0)
                         .text("Trail Length"));
+                    self.trail_style.ui(ui);
                 }
 
                 ui.add(egui::Slider::new(&mut self.speed, 0.1..=3.0)
+                    .nudge(ui, 0.02)
                     .text("Speed"));
             });
 
@@ -1,4 +1,6 @@
-use crate::{Color, ColorScheme, Simulation2D};
+use crate::color_ui::color_scheme_combo;
+use crate::nudge::Nudge;
+use crate::{Color, ColorScheme, RenderCtx, Simulation2D};
 
 pub struct WaveInterference {
     pub wave_count: usize,
@@ -65,6 +67,11 @@ impl Simulation2D for WaveInterference {
     }
 
     fn compute(&self, width: usize, height: usize) -> Vec<Color> {
+        self.compute_ctx(&RenderCtx::new(width, height))
+    }
+
+    fn compute_ctx(&self, ctx: &RenderCtx) -> Vec<Color> {
+        let (width, height) = (ctx.width, ctx.height);
         let mut pixels = vec![Color::BLACK; width * height];
 
         let active_sources = self.wave_count.min(self.sources.len());
@@ -100,7 +107,7 @@ impl Simulation2D for WaveInterference {
                 wave_sum /= active_sources as f32;
                 let intensity = (wave_sum + 1.0) / 2.0;
 
-                let color = self.color_scheme.map(intensity, true);
+                let color = self.color_scheme.map(ctx.cycle_palette(intensity), true);
                 pixels[py * width + px] = color;
             }
         }
@@ -117,7 +124,7 @@ impl Simulation2D for WaveInterference {
             }
         }
 
-        pixels
+        ctx.over_background(pixels)
     }
 
     fn ui_parameters(&mut self, ui: &mut egui::Ui) -> bool {
@@ -129,6 +136,7 @@ impl Simulation2D for WaveInterference {
             .default_open(true)
             .show(ui, |ui| {
                 if ui.add(egui::Slider::new(&mut self.wave_count, 1..=6)
+                    .nudge(ui, 1.0)
                     .text("Wave Sources")).changed() {
                     // Ensure we have enough sources
                     while self.sources.len() < self.wave_count {
@@ -140,12 +148,16 @@ impl Simulation2D for WaveInterference {
                 }
 
                 changed |= ui.add(egui::Slider::new(&mut self.wavelength, 10.0..=150.0)
+                    .nudge(ui, 1.0)
                     .text("Wavelength")).changed();
                 changed |= ui.add(egui::Slider::new(&mut self.amplitude, 10.0..=200.0)
+                    .nudge(ui, 1.0)
                     .text("Amplitude")).changed();
                 changed |= ui.add(egui::Slider::new(&mut self.speed, 0.1..=10.0)
+                    .nudge(ui, 0.05)
                     .text("Speed")).changed();
                 changed |= ui.add(egui::Slider::new(&mut self.damping, 0.0..=2.0)
+                    .nudge(ui, 0.02)
                     .text("Damping")).changed();
             });
 
@@ -155,8 +167,10 @@ impl Simulation2D for WaveInterference {
                     ui.horizontal(|ui| {
                         ui.label(format!("Source {}", i + 1));
                         changed |= ui.add(egui::Slider::new(&mut self.sources[i].0, 0.0..=1.0)
+                            .nudge(ui, 0.01)
                             .text("X")).changed();
                         changed |= ui.add(egui::Slider::new(&mut self.sources[i].1, 0.0..=1.0)
+                            .nudge(ui, 0.01)
                             .text("Y")).changed();
                     });
                 }
@@ -167,15 +181,7 @@ impl Simulation2D for WaveInterference {
             .show(ui, |ui| {
                 changed |= ui.checkbox(&mut self.show_sources, "Show Sources").changed();
 
-                egui::ComboBox::from_label("Color Scheme")
-                    .selected_text(self.color_scheme.name())
-                    .show_ui(ui, |ui| {
-                        for scheme in ColorScheme::all() {
-                            if ui.selectable_value(&mut self.color_scheme, scheme, scheme.name()).clicked() {
-                                changed = true;
-                            }
-                        }
-                    });
+                changed |= color_scheme_combo(ui, &mut self.color_scheme);
             });
 
         egui::CollapsingHeader::new("🎯 Presets")
@@ -214,11 +220,11 @@ impl Simulation2D for WaveInterference {
                 }
             });
 
-        // Update animation
-        let dt = ui.input(|i| i.stable_dt);
-        self.animation_time += dt;
-        changed = true;
-
         changed
     }
+
+    fn update(&mut self, dt: f32, _width: usize, _height: usize) -> bool {
+        self.animation_time += dt;
+        true
+    }
 }
@@ -0,0 +1,169 @@
+//! Every registered simulation constructs and runs headlessly: a few frames
+//! of `update` and `compute` for 2D, `step`, `get_points` and
+//! `get_segments` for 3D, with no egui context.
+
+use sim_core::registry::{simulations_2d, simulations_3d};
+use sim_core::Simulation2D;
+use std::collections::HashSet;
+
+const WIDTH: usize = 64;
+const HEIGHT: usize = 48;
+const DT: f32 = 0.016;
+const FRAMES: usize = 3;
+
+#[test]
+fn every_2d_sim_renders_full_frames() {
+    for mut sim in simulations_2d() {
+        for frame in 0..FRAMES {
+            sim.update(DT, WIDTH, HEIGHT);
+            let pixels = sim.compute(WIDTH, HEIGHT);
+            assert_eq!(pixels.len(), WIDTH * HEIGHT, "{} frame {frame}", sim.name());
+        }
+    }
+}
+
+#[test]
+fn every_3d_sim_produces_finite_points() {
+    for mut sim in simulations_3d() {
+        for frame in 0..FRAMES {
+            sim.step(DT);
+            let points = sim.get_points();
+            assert!(
+                points.iter().all(|p| p.iter().all(|v| v.is_finite())),
+                "{} frame {frame} has a non-finite point",
+                sim.name(),
+            );
+            let segments = sim.get_segments();
+            assert!(
+                segments.iter().all(|(a, b)| a.iter().chain(b).all(|v| v.is_finite())),
+                "{} frame {frame} has a non-finite segment",
+                sim.name(),
+            );
+            if let Some(widths) = sim.get_segment_widths() {
+                assert_eq!(widths.len(), segments.len(), "{} frame {frame}", sim.name());
+            }
+        }
+    }
+}
+
+#[test]
+fn every_2d_sim_runs_after_a_reset() {
+    for mut sim in simulations_2d() {
+        sim.update(DT, WIDTH, HEIGHT);
+        sim.reset();
+        for _ in 0..FRAMES {
+            sim.update(DT, WIDTH, HEIGHT);
+        }
+        let pixels = sim.compute(WIDTH, HEIGHT);
+        assert_eq!(pixels.len(), WIDTH * HEIGHT, "{} after reset", sim.name());
+    }
+}
+
+#[test]
+fn sim_names_are_unique() {
+    // Scenes, bookmarks and the screensaver find sims by name
+    let mut seen = HashSet::new();
+    for name in simulations_2d().iter().map(|s| s.name().to_string())
+        .chain(simulations_3d().iter().map(|s| s.name().to_string())) {
+        assert!(seen.insert(name.clone()), "duplicate sim name {name}");
+    }
+}
+
+#[test]
+fn reloading_saved_state_leaves_output_unchanged() {
+    // Bookmarks and copied settings are applied to a sim that may already
+    // hold them; doing so must not restart or redraw it differently
+    for mut sim in simulations_2d() {
+        sim.update(DT, WIDTH, HEIGHT);
+        let before = sim.compute(WIDTH, HEIGHT);
+        sim.load_state(&sim.save_state());
+        assert!(sim.compute(WIDTH, HEIGHT) == before, "{} changed after reloading its state", sim.name());
+    }
+    for mut sim in simulations_3d() {
+        sim.step(DT);
+        let before = sim.get_points();
+        sim.load_state(&sim.save_state());
+        assert!(sim.get_points() == before, "{} changed after reloading its state", sim.name());
+    }
+}
+
+#[test]
+fn mandelbrot_click_opens_the_julia_set_for_that_point() {
+    let mut mandelbrot = sim_core::mandelbrot::Mandelbrot::new();
+    assert!(mandelbrot.plane_click(-0.75, 0.1).is_none());
+
+    // The app switches by name, so the answer must name a registered sim
+    mandelbrot.pick_julia = true;
+    let (name, state) = mandelbrot.plane_click(-0.75, 0.1).unwrap();
+    let mut julia = simulations_2d().into_iter().find(|s| s.name() == name).unwrap();
+    julia.load_state(&state);
+
+    let loaded = julia.save_state();
+    assert_eq!((loaded["c_real"].as_f64(), loaded["c_imag"].as_f64()), (Some(-0.75), Some(0.1)));
+    assert_eq!(loaded["color_scheme"], mandelbrot.save_state()["color_scheme"]);
+}
+
+#[test]
+fn panning_moves_the_complex_plane_with_the_pointer() {
+    // Drag-panning and zooming about the cursor both rely on adjust_center
+    // agreeing with plane_view, on a canvas that isn't square
+    for mut sim in simulations_2d() {
+        let Some(view) = sim.plane_view(WIDTH, HEIGHT).filter(|v| v.complex) else {
+            continue;
+        };
+        let (u, v) = view.to_plane(10.0, 20.0, WIDTH, HEIGHT);
+        sim.adjust_center(7.0, -3.0, WIDTH, HEIGHT);
+        let (x, y) = sim.plane_view(WIDTH, HEIGHT).unwrap().to_pixel(u, v, WIDTH, HEIGHT);
+        assert!((x - 17.0).abs() < 1e-6 && (y - 17.0).abs() < 1e-6, "{} moved the point to ({x}, {y})", sim.name());
+    }
+}
+
+#[test]
+fn out_of_range_state_is_pulled_into_the_slider_ranges() {
+    // Scenes and links come from outside; values like these would freeze the app
+    let hostile = serde_json::json!({
+        "max_iterations": 4_000_000_000u32,
+        "zoom": 1e300,
+        "center_x": -1e300,
+        "power": 1e9,
+        "steps_per_second": 1e9,
+        "channel_offsets": [1e9, -1e9, 0.5],
+    });
+    for name in ["Mandelbrot Set", "Julia Set", "Quaternion Julia", "Reaction-Diffusion"] {
+        let mut sim = simulations_2d().into_iter().find(|s| s.name() == name).unwrap();
+        sim.load_state(&hostile);
+        let state = sim.save_state();
+        for (key, max) in [("max_iterations", 1000.0), ("zoom", 1e12), ("power", 8.0), ("steps_per_second", 2000.0)] {
+            if let Some(value) = state[key].as_f64() {
+                assert!(value <= max, "{name} loaded {key} = {value}");
+            }
+        }
+        if let Some(x) = state["center_x"].as_f64() {
+            assert!(x >= -4.0, "{name} loaded center_x = {x}");
+        }
+        if state["channel_offsets"].is_array() {
+            assert_eq!(state["channel_offsets"], serde_json::json!([8.0, -8.0, 0.5]), "{name}");
+        }
+
+        // A long stall doesn't turn into a frame of millions of steps
+        sim.update(1e6, WIDTH, HEIGHT);
+        assert_eq!(sim.compute(WIDTH, HEIGHT).len(), WIDTH * HEIGHT);
+    }
+}
+
+#[test]
+fn oversized_lorenz_trail_is_clamped_before_retracing() {
+    // Loading re-traces the whole trail at once, so an unclamped length
+    // would freeze the app right here
+    let mut sim = simulations_3d().into_iter().find(|s| s.name() == "Lorenz Attractor").unwrap();
+    sim.load_state(&serde_json::json!({
+        "max_points": 1_000_000_000_000u64,
+        "spacing": 0.0,
+        "speed": 1e9,
+    }));
+    let state = sim.save_state();
+    assert_eq!(state["max_points"], 10000);
+    assert_eq!(state["spacing"], 0.25);
+    assert_eq!(state["speed"], 5.0);
+    assert!(sim.get_points().len() <= 10000);
+}
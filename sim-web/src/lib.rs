@@ -1,6 +1,7 @@
 //! WebAssembly entry point for the Complex Systems Visualizer
 
 use log::LevelFilter;
+use sim_app::scene::Scene;
 use wasm_bindgen::{prelude::*, JsCast};
 
 /// Entry point for WASM - called from JavaScript
@@ -26,6 +27,17 @@ pub async fn start() -> Result<(), JsValue> {
         .get_element_by_id("loading")
         .and_then(|el| el.dyn_into::<web_sys::HtmlElement>().ok());
 
+    // A shared link carries its scene in the fragment; anything unreadable
+    // just starts with the defaults
+    let hash = window.location().hash().unwrap_or_default();
+    let scene = if hash.is_empty() {
+        None
+    } else {
+        Scene::from_fragment(&hash)
+            .map_err(|e| log::warn!("Ignoring URL scene: {e}"))
+            .ok()
+    };
+
     let web_options = eframe::WebOptions::default();
 
     eframe::WebRunner::new()
@@ -36,7 +48,13 @@ pub async fn start() -> Result<(), JsValue> {
                 if let Some(loading) = loading {
                     let _ = loading.style().set_property("display", "none");
                 }
-                Ok(Box::new(sim_app::ComplexSystemsApp::new(cc)))
+                let mut app = sim_app::ComplexSystemsApp::new(cc);
+                if let Some(scene) = scene {
+                    if let Err(e) = app.apply_scene(&scene) {
+                        log::warn!("Ignoring URL scene: {e}");
+                    }
+                }
+                Ok(Box::new(app))
             }),
         )
         .await